@@ -0,0 +1,161 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::store::RecordStore;
+use crate::tree::GedcomData;
+use crate::types::{HasEvents, RepoReference};
+
+/// Extracts the branch of the tree around an individual, for sharing part of a tree.
+///
+/// The branch holds the root individual, its ancestors up to `ancestors` generations
+/// back and its descendants up to `descendants` generations down, along with the
+/// partners of the root & its descendants. The families linking them, the sources they
+/// cite, the repositories of those sources and the header & submitters are carried
+/// over; links to records outside of the branch are removed.
+#[must_use]
+pub fn extract_branch(
+    data: &GedcomData,
+    root: &str,
+    ancestors: u32,
+    descendants: u32,
+) -> GedcomData {
+    let mut individuals = HashSet::new();
+    if data.individual(root).is_none() {
+        return GedcomData {
+            header: data.header.clone(),
+            submitters: data.submitters.clone(),
+            ..GedcomData::default()
+        };
+    }
+    individuals.insert(root.to_string());
+
+    // ancestors, walking up through the families each individual is a child of
+    let mut queue = VecDeque::from([(root.to_string(), 0)]);
+    while let Some((xref, generation)) = queue.pop_front() {
+        if generation >= ancestors {
+            continue;
+        }
+        let Some(individual) = data.individual(&xref) else {
+            continue;
+        };
+        for family in data.child_families(&individual) {
+            for (parent, _) in family.parents() {
+                if individuals.insert(parent.to_string()) {
+                    queue.push_back((parent.to_string(), generation + 1));
+                }
+            }
+        }
+    }
+
+    // descendants & their partners, walking down through the families they are spouses in
+    let mut queue = VecDeque::from([(root.to_string(), 0)]);
+    while let Some((xref, generation)) = queue.pop_front() {
+        let Some(individual) = data.individual(&xref) else {
+            continue;
+        };
+        for family in data.spouse_families(&individual) {
+            if let Some(partner) = family.partner_of(&xref) {
+                individuals.insert(partner.to_string());
+            }
+            if generation >= descendants {
+                continue;
+            }
+            for child in &family.children {
+                if individuals.insert(child.clone()) {
+                    queue.push_back((child.clone(), generation + 1));
+                }
+            }
+        }
+    }
+
+    let mut branch = GedcomData {
+        header: data.header.clone(),
+        submitters: data.submitters.clone(),
+        ..GedcomData::default()
+    };
+
+    // families with a parent and a child, or both parents, in the branch
+    for family in &data.families {
+        let parents: Vec<&str> = family
+            .parents()
+            .into_iter()
+            .map(|(xref, _)| xref)
+            .filter(|xref| individuals.contains(*xref))
+            .collect();
+        let children: Vec<&String> = family
+            .children
+            .iter()
+            .filter(|xref| individuals.contains(*xref))
+            .collect();
+        if parents.is_empty() || parents.len() + children.len() < 2 {
+            continue;
+        }
+        let mut family = family.clone();
+        family.individual1 = family.individual1.filter(|xref| individuals.contains(xref));
+        family.individual2 = family.individual2.filter(|xref| individuals.contains(xref));
+        family.children.retain(|xref| individuals.contains(xref));
+        branch.add_family(family);
+    }
+
+    let families: HashSet<String> = branch
+        .families
+        .iter()
+        .filter_map(|family| family.xref.clone())
+        .collect();
+    for individual in &data.individuals {
+        if !individual
+            .xref
+            .as_ref()
+            .is_some_and(|xref| individuals.contains(xref))
+        {
+            continue;
+        }
+        let mut individual = individual.clone();
+        individual
+            .families
+            .retain(|link| families.contains(&link.xref));
+        branch.add_individual(individual);
+    }
+
+    add_cited_sources(data, &mut branch);
+    branch
+}
+
+/// Copies the sources cited by the records of the branch, and their repositories.
+fn add_cited_sources(data: &GedcomData, branch: &mut GedcomData) {
+    let mut sources = HashSet::new();
+    let events = branch
+        .individuals
+        .iter()
+        .flat_map(HasEvents::events)
+        .chain(branch.families.iter().flat_map(HasEvents::events));
+    for event in events {
+        for citation in event.citations {
+            sources.insert(citation.xref);
+        }
+    }
+
+    let mut repositories = HashSet::new();
+    for source in &data.sources {
+        if source
+            .xref
+            .as_ref()
+            .is_some_and(|xref| sources.contains(xref))
+        {
+            for citation in source.repo_citations() {
+                if let RepoReference::Pointer(xref) = &citation.repository {
+                    repositories.insert(xref.clone());
+                }
+            }
+            branch.add_source(source.clone());
+        }
+    }
+    for repository in &data.repositories {
+        if repository
+            .xref
+            .as_ref()
+            .is_some_and(|xref| repositories.contains(xref))
+        {
+            branch.add_repository(repository.clone());
+        }
+    }
+}
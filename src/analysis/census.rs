@@ -0,0 +1,174 @@
+use crate::tree::GedcomData;
+use crate::types::{Event, HasEvents};
+
+/// Role of a person within a census household
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HouseholdRole {
+    /// Head of the household
+    Head,
+    /// Spouse of the head, the `HUSB`, `WIFE` & `SPOU` roles
+    Spouse,
+    /// Child of the head, the `CHIL` role
+    Child,
+    /// Parent of the head, the `FATH`, `MOTH` & `PARENT` roles
+    Parent,
+    /// Boarder or lodger
+    Boarder,
+    /// Servant of the household
+    Servant,
+    /// Witness of the enumeration, the `WITN` role
+    Witness,
+    /// Any other role, as written without its parentheses, ie. `Visitor`
+    Other(String),
+}
+
+impl HouseholdRole {
+    /// Reads the value of a `ROLE` or `RELA` tag. Both the standard roles & the
+    /// user-defined ones in parentheses, ie. `(boarder)`, are recognized.
+    #[must_use]
+    pub fn parse(value: &str) -> Option<HouseholdRole> {
+        let role = value
+            .trim()
+            .trim_start_matches('(')
+            .trim_end_matches(')')
+            .trim();
+        if role.is_empty() {
+            return None;
+        }
+        Some(match role.to_ascii_uppercase().as_str() {
+            "HEAD" => HouseholdRole::Head,
+            "HUSB" | "WIFE" | "SPOU" | "SPOUSE" | "HUSBAND" => HouseholdRole::Spouse,
+            "CHIL" | "CHILD" | "SON" | "DAUGHTER" | "DAU" => HouseholdRole::Child,
+            "FATH" | "MOTH" | "PARENT" | "FATHER" | "MOTHER" => HouseholdRole::Parent,
+            "BOARDER" | "LODGER" | "ROOMER" => HouseholdRole::Boarder,
+            "SERVANT" | "SERV" => HouseholdRole::Servant,
+            "WITN" | "WITNESS" => HouseholdRole::Witness,
+            _ => HouseholdRole::Other(role.to_string()),
+        })
+    }
+}
+
+/// A person listed in a census household
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HouseholdMember {
+    /// Reference to the `Individual`
+    pub individual: String,
+    /// Role of the person in the household, when one is recorded
+    pub role: Option<HouseholdRole>,
+}
+
+/// The people enumerated together in a census
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CensusHousehold {
+    /// Reference to the `Source` of the census, when the events cite one
+    pub source: Option<String>,
+    /// Page of the census the household is listed on
+    pub page: Option<String>,
+    /// Date of the census, as written in the file
+    pub date: Option<String>,
+    /// Place of the census
+    pub place: Option<String>,
+    /// Members of the household, the head first
+    pub members: Vec<HouseholdMember>,
+}
+
+impl CensusHousehold {
+    /// The head of the household, when one is recorded
+    #[must_use]
+    pub fn head(&self) -> Option<&str> {
+        self.members
+            .iter()
+            .find(|member| member.role == Some(HouseholdRole::Head))
+            .map(|member| member.individual.as_str())
+    }
+
+    fn add(&mut self, individual: &str, role: Option<HouseholdRole>) {
+        match self
+            .members
+            .iter_mut()
+            .find(|member| member.individual == individual)
+        {
+            Some(member) => {
+                if member.role.is_none() {
+                    member.role = role;
+                }
+            }
+            None => self.members.push(HouseholdMember {
+                individual: individual.to_string(),
+                role,
+            }),
+        }
+    }
+}
+
+/// Gathers the census events of the individuals into households.
+///
+/// Census events citing the same page of the same source are read as one household,
+/// the role of each person coming from the `EVEN.ROLE` of their citation. The
+/// individuals linked with `ASSO` to a census event join its household, with the role
+/// of their `ROLE` or `RELA`. A census event without a citation makes a household of
+/// its own. Households are listed in the order they first appear in the tree.
+#[must_use]
+pub fn census_households(data: &GedcomData) -> Vec<CensusHousehold> {
+    let mut households: Vec<CensusHousehold> = Vec::new();
+    for individual in &data.individuals {
+        let Some(xref) = individual.xref.as_deref() else {
+            continue;
+        };
+        for event in individual.events().iter().filter(|event| event.is_census()) {
+            let household = household_of(&mut households, event);
+            let role = event
+                .citations
+                .iter()
+                .find_map(|citation| citation.role.as_deref())
+                .and_then(HouseholdRole::parse);
+            household.add(xref, role);
+            for association in &event.associations {
+                let role = association
+                    .role
+                    .as_deref()
+                    .or(association.relation.as_deref())
+                    .and_then(HouseholdRole::parse);
+                household.add(&association.xref, role);
+            }
+        }
+    }
+    for household in &mut households {
+        household
+            .members
+            .sort_by_key(|member| member.role != Some(HouseholdRole::Head));
+    }
+    households
+}
+
+/// Finds the household of the source & page cited by a census event, adding it when
+/// it is not listed yet
+fn household_of<'a>(
+    households: &'a mut Vec<CensusHousehold>,
+    event: &Event,
+) -> &'a mut CensusHousehold {
+    let citation = event.citations.first();
+    let source = citation.map(|citation| citation.xref.clone());
+    let page = citation.and_then(|citation| citation.page.clone());
+    let index = households.iter().position(|household| {
+        source.is_some() && household.source == source && household.page == page
+    });
+    let index = index.unwrap_or_else(|| {
+        households.push(CensusHousehold {
+            source,
+            page,
+            date: None,
+            place: None,
+            members: Vec::new(),
+        });
+        households.len() - 1
+    });
+    let household = &mut households[index];
+    if household.date.is_none() {
+        household.date.clone_from(&event.date);
+    }
+    if household.place.is_none() {
+        household.place = event.place_name().map(str::to_string);
+    }
+    household
+}
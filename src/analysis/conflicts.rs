@@ -0,0 +1,100 @@
+use crate::tree::GedcomData;
+use crate::types::{Event, EventType, HasEvents};
+
+/// A recorded version of a fact, with the quality of the evidence for it
+#[derive(Clone, Debug)]
+pub struct ConflictingFact {
+    /// The event as recorded
+    pub event: Event,
+    /// Best `QUAY` of the citations of the event, from 0 to 3. `None` when no citation
+    /// gives a quality.
+    pub quality: Option<u8>,
+    /// Number of sources cited for the event
+    pub citations: usize,
+}
+
+/// Events of an individual describing the same once-in-a-life fact, ie. two births,
+/// with a different date or place
+#[derive(Clone, Debug)]
+pub struct EventConflict {
+    /// Reference to the `Individual`
+    pub individual: String,
+    /// The kind of fact recorded more than once
+    pub event_type: EventType,
+    /// The versions of the fact, in the order of the record
+    pub facts: Vec<ConflictingFact>,
+}
+
+impl EventConflict {
+    /// The version best supported by its evidence: the one with the highest citation
+    /// quality, then the one citing the most sources, then the first.
+    #[must_use]
+    pub fn preferred(&self) -> Option<&ConflictingFact> {
+        // `max_by_key` keeps the last of equal elements, so search from the end
+        self.facts
+            .iter()
+            .rev()
+            .max_by_key(|fact| (fact.quality, fact.citations))
+    }
+}
+
+/// Finds the individuals with several births, christenings, deaths or burials that
+/// disagree on their date or place. Dates & places missing from one of the events are
+/// not a disagreement.
+#[must_use]
+pub fn event_conflicts(data: &GedcomData) -> Vec<EventConflict> {
+    let mut conflicts = Vec::new();
+    for individual in &data.individuals {
+        let Some(xref) = individual.xref.as_deref() else {
+            continue;
+        };
+        let events = individual.events();
+        for event_type in ONCE_IN_LIFE {
+            let same: Vec<&Event> = events
+                .iter()
+                .filter(|event| event.event == event_type)
+                .collect();
+            let disagree = same
+                .iter()
+                .enumerate()
+                .any(|(i, a)| same[i + 1..].iter().any(|b| differ(a, b)));
+            if disagree {
+                conflicts.push(EventConflict {
+                    individual: xref.to_string(),
+                    event_type,
+                    facts: same.into_iter().map(fact).collect(),
+                });
+            }
+        }
+    }
+    conflicts
+}
+
+/// Kinds of events that can only happen once to a person
+pub(crate) const ONCE_IN_LIFE: [EventType; 4] = [
+    EventType::Birth,
+    EventType::Christening,
+    EventType::Death,
+    EventType::Burial,
+];
+
+/// Whether two events of the same fact disagree on their date or place
+pub(crate) fn differ(a: &Event, b: &Event) -> bool {
+    let differ = |a: Option<&str>, b: Option<&str>| match (a, b) {
+        (Some(a), Some(b)) => !a.trim().eq_ignore_ascii_case(b.trim()),
+        _ => false,
+    };
+    differ(a.date.as_deref(), b.date.as_deref()) || differ(a.place_name(), b.place_name())
+}
+
+fn fact(event: &Event) -> ConflictingFact {
+    ConflictingFact {
+        quality: event
+            .citations
+            .iter()
+            .filter_map(|citation| citation.quality)
+            .max(),
+        citations: event.citations.len(),
+        event: event.clone(),
+    }
+}
@@ -0,0 +1,108 @@
+use crate::tree::GedcomData;
+use crate::types::RepoReference;
+
+/// A source held by a repository, as cited by the source
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HeldSource {
+    /// Reference to the `Source`
+    pub source: String,
+    /// Call number of the source at the repository, `CALN`
+    pub call_number: Option<String>,
+}
+
+/// A repository with the sources citing it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RepositoryHoldings {
+    /// Reference to the `Repository`
+    pub repository: String,
+    /// The sources held, in the order of the file
+    pub sources: Vec<HeldSource>,
+}
+
+/// Where the sources of a tree can be found, see `repository_coverage`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RepositoryCoverage {
+    /// Each repository record with the sources it holds, including repositories
+    /// holding none
+    pub repositories: Vec<RepositoryHoldings>,
+    /// Sources without any repository citation
+    pub unlocated: Vec<String>,
+    /// Repository citations pointing to a repository missing from the tree, as the
+    /// source & the missing xref
+    pub dangling: Vec<(String, String)>,
+    /// Repository citations without a call number, as the source & the xref of the
+    /// repository, or `None` for a repository described within the citation
+    pub missing_call_numbers: Vec<(String, Option<String>)>,
+    /// Number of repository citations
+    pub citations: usize,
+}
+
+impl RepositoryCoverage {
+    /// Share of the repository citations giving a call number, from 0 to 1. `None`
+    /// without any citation.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // counts of citations are far below 2^52
+    pub fn call_number_completeness(&self) -> Option<f64> {
+        if self.citations == 0 {
+            return None;
+        }
+        let missing = self.missing_call_numbers.len();
+        Some((self.citations - missing) as f64 / self.citations as f64)
+    }
+}
+
+/// Lists each repository with the sources citing it and, inversely, the sources that
+/// cite no repository, so that every cited material can be located. Sources without
+/// an xref are left out.
+#[must_use]
+pub fn repository_coverage(data: &GedcomData) -> RepositoryCoverage {
+    let mut coverage = RepositoryCoverage {
+        repositories: data
+            .repositories
+            .iter()
+            .filter_map(|repository| {
+                Some(RepositoryHoldings {
+                    repository: repository.xref.clone()?,
+                    sources: Vec::new(),
+                })
+            })
+            .collect(),
+        ..RepositoryCoverage::default()
+    };
+
+    for source in &data.sources {
+        let Some(xref) = source.xref.as_deref() else {
+            continue;
+        };
+        if source.repo_citations().is_empty() {
+            coverage.unlocated.push(xref.to_string());
+        }
+        for citation in source.repo_citations() {
+            coverage.citations += 1;
+            let repository = match &citation.repository {
+                RepoReference::Pointer(repository) => Some(repository.clone()),
+                RepoReference::Inline(_) => None,
+            };
+            if citation.call_number.is_none() {
+                coverage
+                    .missing_call_numbers
+                    .push((xref.to_string(), repository.clone()));
+            }
+            let Some(repository) = repository else {
+                continue;
+            };
+            match coverage
+                .repositories
+                .iter_mut()
+                .find(|holdings| holdings.repository == repository)
+            {
+                Some(holdings) => holdings.sources.push(HeldSource {
+                    source: xref.to_string(),
+                    call_number: citation.call_number.clone(),
+                }),
+                None => coverage.dangling.push((xref.to_string(), repository)),
+            }
+        }
+    }
+    coverage
+}
@@ -0,0 +1,99 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::store::RecordStore;
+use crate::types::Event;
+
+/// Where the text of a `SourceExcerpt` was transcribed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExcerptOrigin {
+    /// The `DATA.TEXT` of the citation, quoting the cited entry
+    Citation,
+    /// The `TEXT` of the cited `Source` record
+    Source,
+}
+
+/// An excerpt of a source supporting an event, with where it came from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SourceExcerpt {
+    /// Reference to the cited `Source`
+    pub source: String,
+    /// Title of the cited source, or its abbreviation when it has no title
+    pub title: Option<String>,
+    /// Page of the citation the excerpt was transcribed from, for citation excerpts
+    pub page: Option<String>,
+    /// Whether the text comes from the citation or the source record
+    pub origin: ExcerptOrigin,
+    /// The transcribed text
+    pub text: String,
+}
+
+impl SourceExcerpt {
+    /// Label describing where the excerpt came from, ie. `1881 Census, p. 12`. Falls back
+    /// to the source xref when the source has no title.
+    #[must_use]
+    pub fn label(&self) -> String {
+        let title = self.title.as_deref().unwrap_or(&self.source);
+        match &self.page {
+            Some(page) => format!("{title}, {page}"),
+            None => title.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for SourceExcerpt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.label(), self.text)
+    }
+}
+
+/// Gathers the excerpts of sources supporting an event: the `DATA.TEXT` of each of its
+/// citations, followed by the `TEXT` of each cited source record. Sources cited more
+/// than once contribute their text once.
+#[must_use]
+pub fn source_excerpts<S: RecordStore>(store: &S, event: &Event) -> Vec<SourceExcerpt> {
+    let mut excerpts = Vec::new();
+    let mut cited = Vec::new();
+    let mut seen = HashSet::new();
+
+    for citation in &event.citations {
+        let source = store.source(&citation.xref);
+        let title = source
+            .as_ref()
+            .and_then(|source| source.title.clone().or_else(|| source.abbreviation.clone()));
+        for text in &citation.texts {
+            excerpts.push(SourceExcerpt {
+                source: citation.xref.clone(),
+                title: title.clone(),
+                page: citation.page.clone(),
+                origin: ExcerptOrigin::Citation,
+                text: text.clone(),
+            });
+        }
+        if let Some(text) = source.as_ref().and_then(|source| source.text.clone()) {
+            if seen.insert(citation.xref.clone()) {
+                cited.push(SourceExcerpt {
+                    source: citation.xref.clone(),
+                    title,
+                    page: None,
+                    origin: ExcerptOrigin::Source,
+                    text,
+                });
+            }
+        }
+    }
+
+    excerpts.extend(cited);
+    excerpts
+}
+
+/// The excerpts of `source_excerpts` as a single text, each labeled with its source and
+/// separated by a blank line, for rendering evidence panels.
+#[must_use]
+pub fn source_text<S: RecordStore>(store: &S, event: &Event) -> String {
+    source_excerpts(store, event)
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
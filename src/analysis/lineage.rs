@@ -0,0 +1,77 @@
+use crate::store::RecordStore;
+use crate::types::{FamilyLinkType, Individual, Pedigree};
+use std::collections::HashSet;
+
+/// Which parent to follow when tracing a line of ancestors
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lineage {
+    /// The father's father's... line, mapped from `HUSB`. Relevant for Y-DNA research.
+    Paternal,
+    /// The mother's mother's... line, mapped from `WIFE`. Relevant for mtDNA research.
+    Maternal,
+}
+
+/// An ancestor along a surname line
+#[derive(Clone, Debug, PartialEq)]
+pub struct LineAncestor {
+    /// Reference to the ancestor's `Individual` record
+    pub xref: String,
+    /// Surname of the ancestor, from `SURN` or the slashes of the name value
+    pub surname: Option<String>,
+}
+
+/// Traces the chain of ancestors of an individual along a single lineage, starting
+/// with the individual itself.
+///
+/// Only birth families are followed: `FAMC` links with an adopted, foster or sealing
+/// pedigree are skipped. The line ends at the first missing parent, or when a record
+/// would be revisited in a cyclic tree.
+pub fn surname_line<S: RecordStore>(store: &S, xref: &str, lineage: Lineage) -> Vec<LineAncestor> {
+    let mut line = Vec::new();
+    let mut visited = HashSet::new();
+    let mut current = store.individual(xref);
+
+    while let Some(individual) = current {
+        let Some(xref) = individual.xref.clone() else {
+            break;
+        };
+        if !visited.insert(xref.clone()) {
+            break;
+        }
+        line.push(LineAncestor {
+            xref,
+            surname: surname_of(&individual),
+        });
+
+        current = individual
+            .families
+            .iter()
+            .filter(|link| matches!(link.link_type, FamilyLinkType::Child))
+            .filter(|link| matches!(link.pedigree, None | Some(Pedigree::Birth)))
+            .find_map(|link| {
+                let family = store.family(&link.xref)?;
+                let parent = match lineage {
+                    Lineage::Paternal => family.individual1.as_ref(),
+                    Lineage::Maternal => family.individual2.as_ref(),
+                }?;
+                store.individual(parent)
+            });
+    }
+
+    line
+}
+
+pub(super) fn surname_of(individual: &Individual) -> Option<String> {
+    let name = individual.primary_name()?;
+    if let Some(surname) = &name.surname {
+        return Some(surname.clone());
+    }
+    // NAME values mark the surname with slashes, ie. "John /Doe/"
+    let value = name.value.as_ref()?;
+    let surname = value.split('/').nth(1)?.trim();
+    if surname.is_empty() {
+        None
+    } else {
+        Some(surname.to_string())
+    }
+}
@@ -0,0 +1,105 @@
+use crate::store::RecordStore;
+use crate::tree::GedcomData;
+use crate::types::{CalendarDate, DateValue, EventType, Family, HasEvents};
+
+/// How a marriage ended
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MarriageEnd {
+    /// The couple divorced
+    Divorce,
+    /// A partner died, with the xref of the partner
+    Death(String),
+}
+
+/// The length of the marriage of a family
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MarriageDuration {
+    /// Reference to the `Family`
+    pub family: String,
+    /// Date of the marriage, as written in the file
+    pub married: String,
+    /// How the marriage ended, when an end is recorded
+    pub end: Option<MarriageEnd>,
+    /// Date the marriage ended, as written in the file
+    pub ended: Option<String>,
+    /// Full years the marriage lasted, when it is known to have ended
+    pub years: Option<i32>,
+}
+
+/// Computes the length of each marriage in the tree, from the `MARR` event of a family
+/// to its divorce or the death of the first partner, whichever comes first.
+///
+/// Families without a readable marriage date are left out.
+#[must_use]
+pub fn marriage_durations(data: &GedcomData) -> Vec<MarriageDuration> {
+    data.families
+        .iter()
+        .filter_map(|family| marriage_duration(data, family))
+        .collect()
+}
+
+fn marriage_duration(data: &GedcomData, family: &Family) -> Option<MarriageDuration> {
+    let xref = family.xref.clone()?;
+    let (married, marriage_date) = event_date(family, &EventType::Marriage)?;
+
+    let mut ends = Vec::new();
+    if let Some((written, date)) = event_date(family, &EventType::Divorce) {
+        ends.push((date, written, MarriageEnd::Divorce));
+    }
+    for (partner, _) in family.parents() {
+        if let Some(individual) = data.individual(partner) {
+            if let Some((written, date)) = event_date(individual.as_ref(), &EventType::Death) {
+                ends.push((date, written, MarriageEnd::Death(partner.to_string())));
+            }
+        }
+    }
+    // dates before the marriage can't be its end
+    ends.retain(|(date, _, _)| *date >= marriage_date);
+    ends.sort_by_key(|(date, _, _)| *date);
+
+    let first_end = ends.into_iter().next();
+    Some(MarriageDuration {
+        family: xref,
+        married,
+        years: first_end
+            .as_ref()
+            .map(|(date, _, _)| marriage_date.years_until(*date)),
+        ended: first_end.as_ref().map(|(_, written, _)| written.clone()),
+        end: first_end.map(|(_, _, end)| end),
+    })
+}
+
+/// Finds the first event of a type with a readable date, returning the date as written
+/// along with the date read from it.
+fn event_date<T: HasEvents>(record: &T, event_type: &EventType) -> Option<(String, CalendarDate)> {
+    record
+        .events()
+        .into_iter()
+        .filter(|event| &event.event == event_type)
+        .find_map(|event| {
+            let written = event.date?;
+            let date = DateValue::parse(&written)
+                .date()
+                .filter(|date| date.calendar.is_gregorian_like())?;
+            Some((written, date))
+        })
+}
+
+/// Ages of the partners at their marriages, for those with a known birth date.
+pub(super) fn ages_at_marriage(data: &GedcomData) -> Vec<i32> {
+    let mut ages = Vec::new();
+    for family in &data.families {
+        let Some((_, married)) = event_date(family, &EventType::Marriage) else {
+            continue;
+        };
+        for (partner, _) in family.parents() {
+            let born = data
+                .individual(partner)
+                .and_then(|individual| event_date(individual.as_ref(), &EventType::Birth));
+            if let Some((_, born)) = born {
+                ages.push(born.years_until(married));
+            }
+        }
+    }
+    ages
+}
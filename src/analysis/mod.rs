@@ -0,0 +1,34 @@
+//! Analyses of the relationships and facts within a family tree
+
+mod lineage;
+pub use lineage::*;
+
+mod marriage;
+pub use marriage::{marriage_durations, MarriageDuration, MarriageEnd};
+
+mod statistics;
+pub use statistics::Statistics;
+
+mod branch;
+pub use branch::extract_branch;
+
+mod privacy;
+pub use privacy::LivingFilter;
+
+mod places;
+pub use places::{place_tree, PlaceNode};
+
+mod evidence;
+pub use evidence::{source_excerpts, source_text, ExcerptOrigin, SourceExcerpt};
+
+mod numbering;
+pub use numbering::{number_descendants, sosa_numbers, NumberingSystem};
+
+mod census;
+pub use census::{census_households, CensusHousehold, HouseholdMember, HouseholdRole};
+
+mod coverage;
+pub use coverage::{repository_coverage, HeldSource, RepositoryCoverage, RepositoryHoldings};
+
+pub(crate) mod conflicts;
+pub use conflicts::{event_conflicts, ConflictingFact, EventConflict};
@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+use crate::store::RecordStore;
+
+/// Genealogical numbering systems for the descendants of an individual
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NumberingSystem {
+    /// The generation of each descendant, the root being generation `1`
+    Generation,
+    /// d'Aboville numbering: the children of `1.2` are `1.2.1`, `1.2.2`, ...
+    DAboville,
+    /// Henry numbering: the children of `12` are `121`, `122`, ..., with the tenth child
+    /// onward written in parentheses, ie. `12(10)`, as in the modified Henry system
+    Henry,
+}
+
+/// Numbers the descendants of `root`, including `root` itself, in the given system.
+///
+/// Children are numbered in the order of the families of their parent, then in the
+/// order of each family. A descendant reached through several lines keeps the number
+/// of the first line.
+pub fn number_descendants<S: RecordStore>(
+    store: &S,
+    root: &str,
+    system: NumberingSystem,
+) -> HashMap<String, String> {
+    let mut numbers = HashMap::new();
+    if store.individual(root).is_some() {
+        number(store, root, "1", 1, system, &mut numbers);
+    }
+    numbers
+}
+
+fn number<S: RecordStore>(
+    store: &S,
+    xref: &str,
+    label: &str,
+    generation: u32,
+    system: NumberingSystem,
+    numbers: &mut HashMap<String, String>,
+) {
+    numbers.insert(xref.to_string(), label.to_string());
+    let Some(individual) = store.individual(xref) else {
+        return;
+    };
+    let children: Vec<String> = store
+        .spouse_families(&individual)
+        .iter()
+        .flat_map(|family| family.children.clone())
+        .collect();
+
+    for (i, child) in children.iter().enumerate() {
+        if numbers.contains_key(child) {
+            continue;
+        }
+        let position = i + 1;
+        let child_label = match system {
+            NumberingSystem::Generation => (generation + 1).to_string(),
+            NumberingSystem::DAboville => format!("{label}.{position}"),
+            NumberingSystem::Henry if position < 10 => format!("{label}{position}"),
+            NumberingSystem::Henry => format!("{label}({position})"),
+        };
+        number(store, child, &child_label, generation + 1, system, numbers);
+    }
+}
+
+/// Gives the ancestors of `root` their Sosa-Stradonitz (ahnentafel) number: `root` is
+/// `1`, and the father & mother of the ancestor numbered `n` are `2n` & `2n + 1`.
+///
+/// The first partner of a family (`HUSB`) is taken as the father and the second
+/// (`WIFE`) as the mother, following the first family each individual is a child of.
+/// An ancestor reached through several lines keeps its lowest number.
+pub fn sosa_numbers<S: RecordStore>(store: &S, root: &str) -> HashMap<String, u64> {
+    let mut numbers: HashMap<String, u64> = HashMap::new();
+    let mut queue = vec![(root.to_string(), 1_u64)];
+
+    while let Some((xref, sosa)) = queue.pop() {
+        if numbers.get(&xref).is_some_and(|existing| *existing <= sosa) {
+            continue;
+        }
+        let Some(individual) = store.individual(&xref) else {
+            continue;
+        };
+        numbers.insert(xref, sosa);
+        let Some(father_sosa) = sosa.checked_mul(2) else {
+            continue;
+        };
+        if let Some(family) = store.child_families(&individual).first() {
+            if let Some(father) = &family.individual1 {
+                queue.push((father.clone(), father_sosa));
+            }
+            if let Some(mother) = &family.individual2 {
+                queue.push((mother.clone(), father_sosa + 1));
+            }
+        }
+    }
+    numbers
+}
@@ -0,0 +1,122 @@
+use crate::tree::GedcomData;
+use crate::types::HasEvents;
+
+/// A jurisdiction in the hierarchy of places, ie. a country holding its states
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlaceNode {
+    /// Name of the jurisdiction, ie. `Massachusetts`
+    pub name: String,
+    /// Kind of jurisdiction, ie. `State`, from the place form
+    pub jurisdiction: Option<String>,
+    /// Number of events that took place at this jurisdiction itself
+    pub events: usize,
+    /// Jurisdictions within this one, sorted by name
+    pub children: Vec<PlaceNode>,
+}
+
+impl PlaceNode {
+    fn new(name: &str, jurisdiction: Option<&str>) -> PlaceNode {
+        PlaceNode {
+            name: name.to_string(),
+            jurisdiction: jurisdiction.map(str::to_string),
+            events: 0,
+            children: Vec::new(),
+        }
+    }
+
+    /// Number of events that took place at this jurisdiction or within it
+    #[must_use]
+    pub fn total_events(&self) -> usize {
+        self.events
+            + self
+                .children
+                .iter()
+                .map(PlaceNode::total_events)
+                .sum::<usize>()
+    }
+
+    fn sort(&mut self) {
+        sort_nodes(&mut self.children);
+    }
+}
+
+/// Builds the hierarchy of jurisdictions of the places of all individual & family
+/// events, ie. Country → State → County → City, counting the events of each place.
+///
+/// Place values list their jurisdictions from the smallest to the largest, separated by
+/// commas. The kind of each jurisdiction is read from the `PLAC.FORM` of the event, or
+/// else the `HEAD.PLAC.FORM` of the file. Places with fewer parts than their form are
+/// matched to it from the largest jurisdiction, and empty parts are skipped.
+#[must_use]
+pub fn place_tree(data: &GedcomData) -> Vec<PlaceNode> {
+    let default_form = data.header.place_form.as_deref();
+    let events = data
+        .individuals
+        .iter()
+        .flat_map(HasEvents::events)
+        .chain(data.families.iter().flat_map(HasEvents::events));
+
+    let mut roots = Vec::new();
+    for event in events {
+        let Some(place) = &event.place else {
+            continue;
+        };
+        let form = place.form.as_deref().or(default_form);
+        let jurisdictions: Vec<&str> = form.map(split_parts).unwrap_or_default();
+        let parts = split_parts(&place.value);
+
+        // from the largest jurisdiction to the smallest
+        let path: Vec<(&str, Option<&str>)> = parts
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, name)| !name.is_empty())
+            .map(|(i, name)| {
+                let from_end = parts.len() - i;
+                let jurisdiction = jurisdictions
+                    .len()
+                    .checked_sub(from_end)
+                    .map(|index| jurisdictions[index])
+                    .filter(|jurisdiction| !jurisdiction.is_empty());
+                (*name, jurisdiction)
+            })
+            .collect();
+        insert(&mut roots, &path);
+    }
+
+    sort_nodes(&mut roots);
+    roots
+}
+
+/// Counts an event at the end of the `path` of jurisdictions, adding the missing ones.
+fn insert(nodes: &mut Vec<PlaceNode>, path: &[(&str, Option<&str>)]) {
+    let Some(((name, jurisdiction), rest)) = path.split_first() else {
+        return;
+    };
+    let index = if let Some(index) = nodes.iter().position(|node| node.name == *name) {
+        index
+    } else {
+        nodes.push(PlaceNode::new(name, *jurisdiction));
+        nodes.len() - 1
+    };
+    let node = &mut nodes[index];
+    if node.jurisdiction.is_none() {
+        node.jurisdiction = jurisdiction.map(str::to_string);
+    }
+    if rest.is_empty() {
+        node.events += 1;
+    } else {
+        insert(&mut node.children, rest);
+    }
+}
+
+fn split_parts(value: &str) -> Vec<&str> {
+    value.split(',').map(str::trim).collect()
+}
+
+fn sort_nodes(nodes: &mut [PlaceNode]) {
+    nodes.sort_by(|a, b| a.name.cmp(&b.name));
+    for node in nodes {
+        node.sort();
+    }
+}
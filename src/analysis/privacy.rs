@@ -0,0 +1,81 @@
+use std::convert::TryFrom;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::lineage::surname_of;
+use crate::tree::GedcomData;
+use crate::types::{DateValue, EventType, HasEvents, Individual, Name};
+
+/// Decides which individuals are presumed to be living, to hide their details before
+/// sharing a tree.
+///
+/// An individual is presumed dead when a death or burial is recorded, or when any of
+/// their events is dated more than `max_age` years ago. Individuals without dated events
+/// are presumed living, erring on the side of privacy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LivingFilter {
+    /// The year events are compared against
+    pub current_year: i32,
+    /// Age past which an individual is presumed dead
+    pub max_age: i32,
+}
+
+impl Default for LivingFilter {
+    /// A filter for the current year, presuming individuals dead after 110 years
+    fn default() -> LivingFilter {
+        let days = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs() / 86_400);
+        let (year, _, _) = crate::writer::civil_date(days);
+        LivingFilter {
+            current_year: i32::try_from(year).unwrap_or(i32::MAX),
+            max_age: 110,
+        }
+    }
+}
+
+impl LivingFilter {
+    /// Whether the individual is presumed to be living
+    #[must_use]
+    pub fn is_living(&self, individual: &Individual) -> bool {
+        let events = individual.events();
+        if events
+            .iter()
+            .any(|event| matches!(event.event, EventType::Death | EventType::Burial))
+        {
+            return false;
+        }
+        !events
+            .iter()
+            .filter_map(|event| DateValue::parse(event.date.as_deref()?).date())
+            .filter(|date| date.calendar.is_gregorian_like())
+            .any(|date| self.current_year - date.year > self.max_age)
+    }
+
+    /// Hides the details of the individuals presumed to be living. Their events & custom
+    /// data are removed and their name is replaced by "Living" with the surname kept, so
+    /// the shape of the tree stays intact. Returns the number of individuals privatized.
+    pub fn privatize(&self, data: &mut GedcomData) -> usize {
+        let mut count = 0;
+        for individual in &mut data.individuals {
+            if !self.is_living(individual) {
+                continue;
+            }
+            let mut private = Individual::new(individual.xref.take());
+            private.sex = individual.sex.clone();
+            private.families = std::mem::take(&mut individual.families);
+            let surname = surname_of(individual);
+            private.names = vec![Name {
+                value: Some(format!(
+                    "Living /{}/",
+                    surname.as_deref().unwrap_or_default()
+                )),
+                given: Some("Living".to_string()),
+                surname,
+                ..Name::default()
+            }];
+            *individual = private;
+            count += 1;
+        }
+        count
+    }
+}
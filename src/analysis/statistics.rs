@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+use super::marriage::ages_at_marriage;
+use crate::tree::GedcomData;
+use crate::types::{EventType, HasEvents};
+
+/// Summary of the data contained in a tree
+#[derive(Clone, Debug, PartialEq)]
+pub struct Statistics {
+    /// Number of submitters
+    pub submitters: usize,
+    /// Number of individuals
+    pub individuals: usize,
+    /// Number of families
+    pub families: usize,
+    /// Number of repositories
+    pub repositories: usize,
+    /// Number of sources
+    pub sources: usize,
+    /// Number of multimedia items
+    pub multimedia: usize,
+    /// Median age of the partners at marriage, for those with known birth & marriage dates
+    pub median_age_at_marriage: Option<f64>,
+    /// Share of the married individuals that married more than once
+    pub remarriage_rate: Option<f64>,
+}
+
+impl Statistics {
+    /// Computes the statistics of a tree
+    #[must_use]
+    pub fn of(data: &GedcomData) -> Statistics {
+        Statistics {
+            submitters: data.submitters.len(),
+            individuals: data.individuals.len(),
+            families: data.families.len(),
+            repositories: data.repositories.len(),
+            sources: data.sources.len(),
+            multimedia: data.multimedia.len(),
+            median_age_at_marriage: median(ages_at_marriage(data)),
+            remarriage_rate: remarriage_rate(data),
+        }
+    }
+}
+
+fn median(mut values: Vec<i32>) -> Option<f64> {
+    values.sort_unstable();
+    let middle = values.len() / 2;
+    match values.len() {
+        0 => None,
+        len if len % 2 == 0 => Some(f64::from(values[middle - 1] + values[middle]) / 2.0),
+        _ => Some(f64::from(values[middle])),
+    }
+}
+
+/// Counts the marriages of each partner, a family counting as a marriage when it has
+/// a `MARR` event.
+#[allow(clippy::cast_precision_loss)] // counts of individuals are far below 2^52
+fn remarriage_rate(data: &GedcomData) -> Option<f64> {
+    let mut marriages: HashMap<&str, usize> = HashMap::new();
+    for family in &data.families {
+        let married = family
+            .events()
+            .iter()
+            .any(|event| event.event == EventType::Marriage);
+        if married {
+            for (partner, _) in family.parents() {
+                *marriages.entry(partner).or_default() += 1;
+            }
+        }
+    }
+    if marriages.is_empty() {
+        return None;
+    }
+    let remarried = marriages.values().filter(|count| **count > 1).count();
+    Some(remarried as f64 / marriages.len() as f64)
+}
@@ -0,0 +1,159 @@
+//! Parses a directory of GEDCOM files, for processing collections of submitted files.
+//!
+//! A file that fails to parse doesn't stop the batch: each file gets its own result, and
+//! the summary adds up the records & diagnostics of all files.
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::diagnostics::{Diagnostic, Severity};
+use crate::encoding;
+use crate::parser::{ParseOptions, Parser};
+use crate::tree::GedcomData;
+use crate::GedcomError;
+
+/// The outcome of parsing one file of the batch
+#[derive(Debug)]
+pub struct FileReport {
+    /// Path of the file
+    pub path: PathBuf,
+    /// The parsed data, or why the file could not be parsed
+    pub result: Result<GedcomData, GedcomError>,
+    /// Problems found in the file that did not stop the parse
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl FileReport {
+    /// Number of error diagnostics, counting a failed parse as one
+    #[must_use]
+    pub fn errors(&self) -> usize {
+        let failed = usize::from(self.result.is_err());
+        failed + count(&self.diagnostics, Severity::Error)
+    }
+
+    /// Number of warning diagnostics
+    #[must_use]
+    pub fn warnings(&self) -> usize {
+        count(&self.diagnostics, Severity::Warning)
+    }
+}
+
+/// Totals over all files of a batch
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BatchSummary {
+    /// Number of files in the batch
+    pub files: usize,
+    /// Number of files that could not be parsed
+    pub failed: usize,
+    /// Number of error diagnostics, counting each failed file as one
+    pub errors: usize,
+    /// Number of warning diagnostics
+    pub warnings: usize,
+    /// Number of individuals in the parsed files
+    pub individuals: usize,
+    /// Number of families in the parsed files
+    pub families: usize,
+    /// Number of sources in the parsed files
+    pub sources: usize,
+    /// Number of repositories in the parsed files
+    pub repositories: usize,
+    /// Number of multimedia records in the parsed files
+    pub multimedia: usize,
+}
+
+/// The outcome of parsing a directory
+#[derive(Debug)]
+pub struct BatchReport {
+    /// The files parsed, ordered by path
+    pub files: Vec<FileReport>,
+    /// Totals over all files
+    pub summary: BatchSummary,
+}
+
+/// Parses the `.ged` files of a directory, not descending into subdirectories.
+///
+/// # Errors
+///
+/// Returns an error when the directory can't be read. Files that can't be read or
+/// parsed are reported in their `FileReport` instead.
+pub fn parse_dir(path: impl AsRef<Path>) -> io::Result<BatchReport> {
+    parse_dir_with_options(path, &ParseOptions::default())
+}
+
+/// Like `parse_dir`, parsing each file according to `options`
+///
+/// # Errors
+///
+/// Returns an error when the directory can't be read.
+pub fn parse_dir_with_options(
+    path: impl AsRef<Path>,
+    options: &ParseOptions,
+) -> io::Result<BatchReport> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(path)? {
+        let path = entry?.path();
+        let is_gedcom = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("ged"));
+        if is_gedcom && path.is_file() {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let files: Vec<FileReport> = paths
+        .into_iter()
+        .map(|path| parse_file(path, options))
+        .collect();
+    let mut summary = BatchSummary {
+        files: files.len(),
+        ..BatchSummary::default()
+    };
+    for file in &files {
+        summary.errors += file.errors();
+        summary.warnings += file.warnings();
+        match &file.result {
+            Ok(data) => {
+                summary.individuals += data.individuals.len();
+                summary.families += data.families.len();
+                summary.sources += data.sources.len();
+                summary.repositories += data.repositories.len();
+                summary.multimedia += data.multimedia.len();
+            }
+            Err(_) => summary.failed += 1,
+        }
+    }
+    Ok(BatchReport { files, summary })
+}
+
+fn parse_file(path: PathBuf, options: &ParseOptions) -> FileReport {
+    let content = match fs::read(&path) {
+        Ok(bytes) => encoding::decode_strict(&bytes),
+        Err(error) => Err(GedcomError::from(error)),
+    };
+    let content = match content {
+        Ok(content) => content,
+        Err(error) => {
+            return FileReport {
+                path,
+                result: Err(error),
+                diagnostics: Vec::new(),
+            }
+        }
+    };
+
+    let mut parser = Parser::with_options(content.chars(), options.clone());
+    let result = parser.parse_document();
+    FileReport {
+        path,
+        result,
+        diagnostics: parser.diagnostics().to_vec(),
+    }
+}
+
+fn count(diagnostics: &[Diagnostic], severity: Severity) -> usize {
+    diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == severity)
+        .count()
+}
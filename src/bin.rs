@@ -1,28 +1,47 @@
+use gedcom::analysis::{extract_branch, LivingFilter};
+use gedcom::batch::parse_dir;
+use gedcom::diagnostics::{Diagnostic, Severity};
 use gedcom::parser::Parser;
+use gedcom::store::RecordStore;
+use gedcom::writer::{GedcomWriter, WriteOptions};
 use gedcom::GedcomData;
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Exit code when the GEDCOM file was found to contain errors
+const EXIT_INVALID: i32 = 1;
+/// Exit code when the command line arguments are not understood
+const EXIT_USAGE: i32 = 2;
 
 fn main() {
     let args: Vec<String> = env::args().collect();
-    match args.len() {
-        1 => usage("Missing filename."),
-        s if s > 2 => usage(&format!("Found more args than expected: {:?}", &args[1..])),
-        _ => (),
-    };
-
-    let filename = &args[1];
+    if args.len() == 1 {
+        usage("Missing filename.");
+    }
 
-    if filename == "--help" || filename == "-h" {
-        usage("");
+    match args[1].as_str() {
+        "--help" | "-h" => usage(""),
+        "validate" => validate(&args[2..]),
+        "extract" => extract(&args[2..]),
+        "batch" => batch(&args[2..]),
+        _ if args.len() > 2 => usage(&format!("Found more args than expected: {:?}", &args[1..])),
+        filename => parse(filename),
     }
+}
 
+fn parse(filename: &str) {
     let data: GedcomData;
 
     if let Ok(contents) = read_relative(filename) {
         let mut parser = Parser::new(contents.chars());
-        data = parser.parse_record();
+        data = parser
+            .parse_document()
+            .unwrap_or_else(|error| exit_with_error(&error.to_string()));
+        for diagnostic in parser.diagnostics() {
+            println!("{}", diagnostic);
+        }
 
         println!("Parsing complete!");
         // println!("\n\n{:#?}", data);
@@ -32,21 +51,244 @@ fn main() {
     }
 }
 
+/// Output formats of the `validate` command
+enum Format {
+    Text,
+    Json,
+}
+
+fn validate(args: &[String]) {
+    let mut format = Format::Text;
+    let mut filename: Option<&str> = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                format = match args.next().map(String::as_str) {
+                    Some("text") => Format::Text,
+                    Some("json") => Format::Json,
+                    other => usage(&format!("Unknown format {:?}.", other)),
+                }
+            }
+            _ if filename.is_none() => filename = Some(arg),
+            _ => usage(&format!("Unexpected argument '{}'.", arg)),
+        }
+    }
+
+    let filename = filename.unwrap_or_else(|| usage("Missing filename."));
+    let contents = read_relative(filename)
+        .unwrap_or_else(|_| exit_with_error(&format!("File '{}' not found.", filename)));
+
+    let diagnostics = collect_diagnostics(&contents);
+    for diagnostic in &diagnostics {
+        match format {
+            Format::Text => println!("{}: {}", filename, diagnostic),
+            Format::Json => println!("{}", json_line(filename, diagnostic)),
+        }
+    }
+
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count();
+    if let Format::Text = format {
+        println!(
+            "{}: {} error(s), {} warning(s)",
+            filename,
+            errors,
+            diagnostics.len() - errors
+        );
+    }
+    if errors > 0 {
+        std::process::exit(EXIT_INVALID);
+    }
+}
+
+fn extract(args: &[String]) {
+    let mut filename: Option<&str> = None;
+    let mut root: Option<&str> = None;
+    let mut ancestors = u32::MAX;
+    let mut descendants = u32::MAX;
+    let mut output: Option<&str> = None;
+    let mut privatize = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--root" => root = Some(option_value(arg, args.next())),
+            "--ancestors" => ancestors = generations(arg, args.next()),
+            "--descendants" => descendants = generations(arg, args.next()),
+            "-o" | "--output" => output = Some(option_value(arg, args.next())),
+            "--privatize" => privatize = true,
+            _ if filename.is_none() => filename = Some(arg),
+            _ => usage(&format!("Unexpected argument '{}'.", arg)),
+        }
+    }
+
+    let filename = filename.unwrap_or_else(|| usage("Missing filename."));
+    let root = root.unwrap_or_else(|| usage("Missing --root individual."));
+    let contents = read_relative(filename)
+        .unwrap_or_else(|_| exit_with_error(&format!("File '{}' not found.", filename)));
+    let data = Parser::new(contents.chars())
+        .parse_document()
+        .unwrap_or_else(|error| exit_with_error(&error.to_string()));
+    if data.individual(root).is_none() {
+        exit_with_error(&format!("Individual {} not found in '{}'.", root, filename));
+    }
+
+    let mut branch = extract_branch(&data, root, ancestors, descendants);
+    if privatize {
+        LivingFilter::default().privatize(&mut branch);
+    }
+
+    let writer = GedcomWriter::new(WriteOptions {
+        filename: output
+            .and_then(|path| Path::new(path).file_name())
+            .map(|name| name.to_string_lossy().into_owned()),
+        ..WriteOptions::default()
+    });
+    let result = match output {
+        Some(path) => fs::File::create(path).and_then(|mut file| writer.write(&branch, &mut file)),
+        None => writer.write(&branch, &mut io::stdout().lock()),
+    };
+    if let Err(error) = result {
+        exit_with_error(&format!("Could not write the branch: {}", error));
+    }
+    if output.is_some() {
+        eprintln!(
+            "Extracted {} individual(s) and {} family(ies).",
+            branch.individuals.len(),
+            branch.families.len()
+        );
+    }
+}
+
+fn batch(args: &[String]) {
+    let directory = match args {
+        [directory] => directory,
+        [] => usage("Missing directory."),
+        _ => usage(&format!("Found more args than expected: {:?}", args)),
+    };
+
+    let report = parse_dir(directory).unwrap_or_else(|error| {
+        exit_with_error(&format!(
+            "Could not read directory '{}': {}",
+            directory, error
+        ))
+    });
+
+    for file in &report.files {
+        let path = file.path.display();
+        match &file.result {
+            Ok(data) => println!(
+                "{}: {} individual(s), {} family(ies), {} error(s), {} warning(s)",
+                path,
+                data.individuals.len(),
+                data.families.len(),
+                file.errors(),
+                file.warnings()
+            ),
+            Err(error) => println!("{}: failed: {}", path, error),
+        }
+    }
+
+    let summary = &report.summary;
+    println!(
+        "{} file(s), {} failed: {} individual(s), {} family(ies), {} source(s), \
+         {} error(s), {} warning(s)",
+        summary.files,
+        summary.failed,
+        summary.individuals,
+        summary.families,
+        summary.sources,
+        summary.errors,
+        summary.warnings
+    );
+    if summary.errors > 0 {
+        std::process::exit(EXIT_INVALID);
+    }
+}
+
+fn option_value<'a>(option: &str, value: Option<&'a String>) -> &'a str {
+    value
+        .map(String::as_str)
+        .unwrap_or_else(|| usage(&format!("Missing value for {}.", option)))
+}
+
+fn generations(option: &str, value: Option<&String>) -> u32 {
+    let value = option_value(option, value);
+    value.parse().unwrap_or_else(|_| {
+        usage(&format!(
+            "Invalid number of generations for {}: '{}'.",
+            option, value
+        ))
+    })
+}
+
+/// Parses the content, turning a failed parse into an error diagnostic.
+fn collect_diagnostics(contents: &str) -> Vec<Diagnostic> {
+    let mut parser = Parser::new(contents.chars());
+    let result = parser.parse_document();
+
+    let mut diagnostics = parser.diagnostics().to_vec();
+    if let Err(error) = result {
+        let line = error.line().unwrap_or_else(|| parser.line());
+        diagnostics.push(Diagnostic::error(line, error.to_string()));
+    }
+    diagnostics
+}
+
+/// Formats a diagnostic as a single line of JSON.
+fn json_line(filename: &str, diagnostic: &Diagnostic) -> String {
+    format!(
+        "{{\"file\":\"{}\",\"line\":{},\"severity\":\"{}\",\"message\":\"{}\"}}",
+        json_escape(filename),
+        diagnostic.line,
+        diagnostic.severity,
+        json_escape(&diagnostic.message)
+    )
+}
+
+fn json_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 fn read_relative(path: &str) -> Result<String, std::io::Error> {
     let path_buf: PathBuf = PathBuf::from(path);
     let absolute_path: PathBuf = fs::canonicalize(path_buf)?;
-    fs::read_to_string(absolute_path)
+    let (contents, _, warnings) = gedcom::encoding::decode(&fs::read(absolute_path)?);
+    for warning in warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    Ok(contents)
 }
 
-fn usage(msg: &str) {
+fn usage(msg: &str) -> ! {
     if !msg.is_empty() {
         println!("{}", msg);
     }
     println!("Usage: parse_gedcom ./path/to/gedcom.ged");
-    std::process::exit(0x0100);
+    println!("       parse_gedcom validate [--format text|json] ./path/to/gedcom.ged");
+    println!("       parse_gedcom extract ./path/to/gedcom.ged --root @I1@ [--ancestors N]");
+    println!("                    [--descendants N] [--privatize] [-o branch.ged]");
+    println!("       parse_gedcom batch ./path/to/directory");
+    std::process::exit(if msg.is_empty() { 0 } else { EXIT_USAGE });
 }
 
-fn exit_with_error(msg: &str) {
+fn exit_with_error(msg: &str) -> ! {
     println!("Error! {}", msg);
     std::process::exit(0x1);
 }
@@ -0,0 +1,63 @@
+//! Problems found in the GEDCOM content while parsing it
+#[cfg(feature = "json")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// How severe a `Diagnostic` is
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub enum Severity {
+    /// The content could not be parsed
+    Error,
+    /// The content was parsed, but some of it was skipped or looks suspicious
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A problem found on a line of the GEDCOM content
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct Diagnostic {
+    /// How severe the problem is
+    pub severity: Severity,
+    /// Line number of the GEDCOM content the problem was found on
+    pub line: u32,
+    /// Human-readable description of the problem
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// Shorthand for creating an error `Diagnostic`
+    #[must_use]
+    pub fn error(line: u32, message: String) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Error,
+            line,
+            message,
+        }
+    }
+
+    /// Shorthand for creating a warning `Diagnostic`
+    #[must_use]
+    pub fn warning(line: u32, message: String) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Warning,
+            line,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}: {}", self.line, self.severity, self.message)
+    }
+}
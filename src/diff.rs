@@ -0,0 +1,329 @@
+//! Compares two versions of a tree, ie. two exports of the same file, see `diff`.
+//!
+//! Records are paired by xref. Individuals whose xref is missing from the other tree
+//! are paired by their name & year of birth, and families by their partners, so that
+//! a program renumbering the records on export doesn't make every record look new.
+use std::collections::HashMap;
+
+use crate::tree::GedcomData;
+use crate::types::{DateValue, Event, EventType, Family, Gender, Individual};
+
+/// The differences between two trees, see `diff`
+#[derive(Clone, Debug, Default)]
+pub struct GedcomDiff {
+    /// Individuals added, removed or changed
+    pub individuals: Vec<RecordDiff>,
+    /// Families added, removed or changed
+    pub families: Vec<RecordDiff>,
+}
+
+impl GedcomDiff {
+    /// Whether the trees have the same individuals & families
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.individuals.is_empty() && self.families.is_empty()
+    }
+}
+
+/// How a record differs between two trees
+#[derive(Clone, Debug)]
+pub enum RecordDiff {
+    /// The record is only in the new tree, with its xref
+    Added(String),
+    /// The record is only in the old tree, with its xref
+    Removed(String),
+    /// The record is in both trees, with changes
+    Changed(RecordChanges),
+}
+
+/// The changes to a record present in both trees
+#[derive(Clone, Debug)]
+pub struct RecordChanges {
+    /// Xref of the record in the old tree
+    pub old_xref: String,
+    /// Xref of the record in the new tree, which differs from `old_xref` for records
+    /// paired by their content
+    pub new_xref: String,
+    /// The lines that changed, ie. the `NAME` of an individual
+    pub fields: Vec<FieldChange>,
+    /// The events added, removed or changed
+    pub events: Vec<EventDiff>,
+}
+
+/// A line of a record with a different value in the two trees
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldChange {
+    /// Tag of the line, ie. `NAME`, `SEX`, `HUSB`, `WIFE` or `CHIL`
+    pub tag: &'static str,
+    /// Value in the old tree, `None` when the line was added
+    pub old: Option<String>,
+    /// Value in the new tree, `None` when the line was removed
+    pub new: Option<String>,
+}
+
+/// How an event of a record differs between two trees. The events of a kind are paired
+/// in the order of the record, ie. the first birth with the first birth.
+#[derive(Clone, Debug)]
+pub enum EventDiff {
+    /// The event is only in the new tree
+    Added(Event),
+    /// The event is only in the old tree
+    Removed(Event),
+    /// The event has a different type, date or place in the two trees
+    Changed {
+        /// The event in the old tree
+        old: Box<Event>,
+        /// The event in the new tree
+        new: Box<Event>,
+    },
+}
+
+/// Finds the individuals & families added, removed or changed from the tree `old` to
+/// the tree `new`, along with the changes to their names, sexes, partners, children &
+/// events. Records without an xref are left out.
+#[must_use]
+pub fn diff(old: &GedcomData, new: &GedcomData) -> GedcomDiff {
+    let individuals = pair(
+        &old.individuals,
+        &new.individuals,
+        |individual| individual.xref.as_deref(),
+        |a, b| matches!((person_key(a), person_key(b)), (Some(a), Some(b)) if a == b),
+    );
+    let renamed: HashMap<&str, &str> = individuals
+        .iter()
+        .filter_map(|(old, new)| Some((old.xref.as_deref()?, new.as_ref()?.xref.as_deref()?)))
+        .collect();
+    // the partners of a family, as xrefs of the new tree
+    let partners = |family: &Family, renames: bool| {
+        let mut partners: Vec<String> = family
+            .parents()
+            .into_iter()
+            .map(|(xref, _)| {
+                let xref = if renames {
+                    renamed.get(xref).copied().unwrap_or(xref)
+                } else {
+                    xref
+                };
+                xref.to_string()
+            })
+            .collect();
+        partners.sort_unstable();
+        partners
+    };
+    let families = pair(
+        &old.families,
+        &new.families,
+        |family| family.xref.as_deref(),
+        |a, b| !a.parents().is_empty() && partners(a, true) == partners(b, false),
+    );
+
+    GedcomDiff {
+        individuals: record_diffs(&individuals, &new.individuals, individual_fields),
+        families: record_diffs(&families, &new.families, |old, new| {
+            family_fields(old, new, &renamed)
+        }),
+    }
+}
+
+/// A record of a tree, for comparing records of both kinds alike
+trait Record {
+    fn xref(&self) -> Option<&str>;
+    fn record_events(&self) -> &[Event];
+}
+
+impl Record for Individual {
+    fn xref(&self) -> Option<&str> {
+        self.xref.as_deref()
+    }
+    fn record_events(&self) -> &[Event] {
+        &self.events
+    }
+}
+
+impl Record for Family {
+    fn xref(&self) -> Option<&str> {
+        self.xref.as_deref()
+    }
+    fn record_events(&self) -> &[Event] {
+        &self.events
+    }
+}
+
+/// Pairs each old record with the new record of the same xref, or else the first
+/// unpaired new record `same` finds equivalent
+fn pair<'a, T>(
+    old: &'a [T],
+    new: &'a [T],
+    xref: impl Fn(&T) -> Option<&str>,
+    same: impl Fn(&T, &T) -> bool,
+) -> Vec<(&'a T, Option<&'a T>)> {
+    let by_xref: HashMap<&str, usize> = new
+        .iter()
+        .enumerate()
+        .filter_map(|(i, record)| Some((xref(record)?, i)))
+        .collect();
+    let mut paired = vec![false; new.len()];
+    let mut pairs: Vec<(&T, Option<usize>)> = old
+        .iter()
+        .filter(|record| xref(record).is_some())
+        .map(|record| {
+            let index = xref(record).and_then(|xref| by_xref.get(xref).copied());
+            if let Some(index) = index {
+                paired[index] = true;
+            }
+            (record, index)
+        })
+        .collect();
+    for (record, index) in &mut pairs {
+        if index.is_none() {
+            *index = (0..new.len()).find(|&i| !paired[i] && same(record, &new[i]));
+            if let Some(i) = *index {
+                paired[i] = true;
+            }
+        }
+    }
+    pairs
+        .into_iter()
+        .map(|(record, index)| (record, index.map(|i| &new[i])))
+        .collect()
+}
+
+/// The diffs of the paired records, followed by the new records left unpaired
+fn record_diffs<T: Record>(
+    pairs: &[(&T, Option<&T>)],
+    new: &[T],
+    fields: impl Fn(&T, &T) -> Vec<FieldChange>,
+) -> Vec<RecordDiff> {
+    let mut diffs = Vec::new();
+    for (old, paired) in pairs {
+        let old_xref = old.xref().unwrap_or_default().to_string();
+        let Some(paired) = paired else {
+            diffs.push(RecordDiff::Removed(old_xref));
+            continue;
+        };
+        let changes = RecordChanges {
+            new_xref: paired.xref().unwrap_or_default().to_string(),
+            fields: fields(old, paired),
+            events: event_diffs(old.record_events(), paired.record_events()),
+            old_xref,
+        };
+        if !changes.fields.is_empty() || !changes.events.is_empty() {
+            diffs.push(RecordDiff::Changed(changes));
+        }
+    }
+    for record in new {
+        let Some(xref) = record.xref() else {
+            continue;
+        };
+        let is_paired = pairs
+            .iter()
+            .any(|(_, paired)| paired.is_some_and(|paired| std::ptr::eq(paired, record)));
+        if !is_paired {
+            diffs.push(RecordDiff::Added(xref.to_string()));
+        }
+    }
+    diffs
+}
+
+/// Pairs the events of each kind in order, then compares their date & place
+fn event_diffs(old: &[Event], new: &[Event]) -> Vec<EventDiff> {
+    let kind = |event: &Event| (event.event.clone(), event.classification.clone());
+    let mut diffs = Vec::new();
+    let mut paired = vec![false; new.len()];
+    for event in old {
+        let index = (0..new.len()).find(|&i| !paired[i] && kind(&new[i]) == kind(event));
+        let Some(index) = index else {
+            diffs.push(EventDiff::Removed(event.clone()));
+            continue;
+        };
+        paired[index] = true;
+        let other = &new[index];
+        if event.date != other.date || event.place != other.place {
+            diffs.push(EventDiff::Changed {
+                old: Box::new(event.clone()),
+                new: Box::new(other.clone()),
+            });
+        }
+    }
+    for (event, paired) in new.iter().zip(paired) {
+        if !paired {
+            diffs.push(EventDiff::Added(event.clone()));
+        }
+    }
+    diffs
+}
+
+fn individual_fields(old: &Individual, new: &Individual) -> Vec<FieldChange> {
+    let name = |individual: &Individual| {
+        individual
+            .primary_name()
+            .and_then(|name| name.value.clone())
+    };
+    let mut fields = Vec::new();
+    field(&mut fields, "NAME", name(old), name(new));
+    field(&mut fields, "SEX", sex(&old.sex), sex(&new.sex));
+    fields
+}
+
+fn family_fields(old: &Family, new: &Family, renamed: &HashMap<&str, &str>) -> Vec<FieldChange> {
+    let rename = |xref: &str| renamed.get(xref).copied().unwrap_or(xref).to_string();
+    let mut fields = Vec::new();
+    for (tag, old, new) in [
+        ("HUSB", &old.individual1, &new.individual1),
+        ("WIFE", &old.individual2, &new.individual2),
+    ] {
+        field(&mut fields, tag, old.as_deref().map(rename), new.clone());
+    }
+    let old_children: Vec<String> = old.children.iter().map(|child| rename(child)).collect();
+    for child in &old_children {
+        if !new.children.contains(child) {
+            field(&mut fields, "CHIL", Some(child.clone()), None);
+        }
+    }
+    for child in &new.children {
+        if !old_children.contains(child) {
+            field(&mut fields, "CHIL", None, Some(child.clone()));
+        }
+    }
+    fields
+}
+
+fn field(
+    fields: &mut Vec<FieldChange>,
+    tag: &'static str,
+    old: Option<String>,
+    new: Option<String>,
+) {
+    if old != new {
+        fields.push(FieldChange { tag, old, new });
+    }
+}
+
+fn sex(sex: &Gender) -> Option<String> {
+    let value = match sex {
+        Gender::Male => "M",
+        Gender::Female => "F",
+        Gender::Nonbinary => "X",
+        Gender::Unknown => return None,
+    };
+    Some(value.to_string())
+}
+
+/// The name & year of birth identifying an individual across trees, ie.
+/// `("jane doe", 1990)`
+fn person_key(individual: &Individual) -> Option<(String, i32)> {
+    let name = individual.primary_name()?.value.as_deref()?;
+    let name = name
+        .replace('/', " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    let birth = individual
+        .events
+        .iter()
+        .filter(|event| event.event == EventType::Birth)
+        .filter_map(|event| DateValue::parse(event.date.as_deref()?).date())
+        .find(|date| date.calendar.is_gregorian_like())?;
+    Some((name, birth.year))
+}
@@ -0,0 +1,193 @@
+//! Reads the DNA test data that genealogy programs store in custom tags.
+//!
+//! There is no standard structure for DNA data, so the common vendor patterns are
+//! recognized:
+//! * haplogroups as the value of `_YDNA` & `_MTDNA` (or `_Y_DNA`, `_MT_DNA`, ...)
+//! * matches as `_DNA` tags, with the matched individual as a pointer value or a
+//!   `_MATCH` subtag, and the shared centimorgans & segments either in subtags
+//!   (`_CM`, `_SEGS`, `_LONGEST`) or in free text like `"45.5 cM across 3 segments"`
+//!
+//! The typed values are read from `Individual::custom_data` on demand, so the raw data
+//! is always preserved and written back unchanged.
+use crate::types::{CustomData, Individual};
+
+/// The kind of DNA test a haplogroup was determined by
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HaplogroupKind {
+    /// Y chromosome, passed down the paternal line
+    YDna,
+    /// Mitochondrial DNA, passed down the maternal line
+    MtDna,
+}
+
+/// A haplogroup assigned to an individual
+#[derive(Clone, Debug)]
+pub struct Haplogroup {
+    /// The kind of DNA test that determined the haplogroup
+    pub kind: HaplogroupKind,
+    /// Name of the haplogroup, ie. `R1b-M269`
+    pub name: String,
+    /// The custom tag the haplogroup was read from
+    pub raw: CustomData,
+}
+
+/// An autosomal DNA match between an individual and another person
+#[derive(Clone, Debug)]
+pub struct DnaMatch {
+    /// Reference to the matched `Individual`, when it is in the tree
+    pub matched: Option<String>,
+    /// Total length of the shared DNA in centimorgans
+    pub shared_cm: Option<f64>,
+    /// Number of shared segments
+    pub segments: Option<u32>,
+    /// Length of the longest shared segment in centimorgans
+    pub longest_segment_cm: Option<f64>,
+    /// The custom tag the match was read from
+    pub raw: CustomData,
+}
+
+/// DNA data of a record, read from its custom tags
+pub trait DnaData {
+    /// Haplogroups recognized in the custom data
+    fn haplogroups(&self) -> Vec<Haplogroup>;
+    /// DNA matches recognized in the custom data
+    fn dna_matches(&self) -> Vec<DnaMatch>;
+}
+
+impl DnaData for Individual {
+    fn haplogroups(&self) -> Vec<Haplogroup> {
+        let mut haplogroups = Vec::new();
+        for data in &self.custom_data {
+            collect_haplogroups(data, &mut haplogroups);
+        }
+        haplogroups
+    }
+
+    fn dna_matches(&self) -> Vec<DnaMatch> {
+        self.custom_data
+            .iter()
+            .filter(|data| normalize_tag(&data.tag) == "DNA")
+            .map(parse_match)
+            .collect()
+    }
+}
+
+/// Strips the underscore & separators from a custom tag and uppercases it, so that
+/// `_Y-DNA`, `_Y_DNA` & `_ydna` are all recognized as `YDNA`.
+fn normalize_tag(tag: &str) -> String {
+    tag.chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .collect::<String>()
+        .to_uppercase()
+}
+
+fn haplogroup_kind(tag: &str) -> Option<HaplogroupKind> {
+    match normalize_tag(tag).as_str() {
+        "YDNA" | "YHAPLOGROUP" => Some(HaplogroupKind::YDna),
+        "MTDNA" | "MTHAPLOGROUP" => Some(HaplogroupKind::MtDna),
+        _ => None,
+    }
+}
+
+fn collect_haplogroups(data: &CustomData, haplogroups: &mut Vec<Haplogroup>) {
+    if let Some(kind) = haplogroup_kind(&data.tag) {
+        let name = data.value.trim();
+        if !name.is_empty() {
+            haplogroups.push(Haplogroup {
+                kind,
+                name: name.to_string(),
+                raw: data.clone(),
+            });
+        }
+    }
+    // haplogroups may be grouped under a _DNA tag
+    for child in &data.children {
+        collect_haplogroups(child, haplogroups);
+    }
+}
+
+fn parse_match(data: &CustomData) -> DnaMatch {
+    let mut dna_match = DnaMatch {
+        matched: pointer(&data.value),
+        shared_cm: None,
+        segments: None,
+        longest_segment_cm: None,
+        raw: data.clone(),
+    };
+    scan_text(&data.value, &mut dna_match);
+
+    for child in &data.children {
+        let value = child.value.trim();
+        match normalize_tag(&child.tag).as_str() {
+            "MATCH" | "IND" | "INDI" => dna_match.matched = pointer(value).or(dna_match.matched),
+            "CM" | "SHAREDCM" | "CENTIMORGANS" => dna_match.shared_cm = parse_number(value),
+            "SEGS" | "SEGMENTS" => dna_match.segments = value.parse().ok(),
+            "LONGEST" | "LONGESTSEGMENT" => dna_match.longest_segment_cm = parse_number(value),
+            "NOTE" => scan_text(value, &mut dna_match),
+            _ => (),
+        }
+    }
+    dna_match
+}
+
+fn pointer(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.len() > 2 && value.starts_with('@') && value.ends_with('@') {
+        Some(value.to_string())
+    } else {
+        None
+    }
+}
+
+/// Parses a number of centimorgans, with or without a `cM` unit.
+fn parse_number(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let number = value
+        .strip_suffix("cM")
+        .or_else(|| value.strip_suffix("cm"))
+        .unwrap_or(value);
+    strip_thousands(number.trim()).parse().ok()
+}
+
+/// Removes the commas separating the thousands of numbers, ie. `1,720` to `1720`, leaving
+/// the commas separating words & decimals like `45,5`
+fn strip_thousands(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut stripped = String::with_capacity(text.len());
+    for (i, &c) in chars.iter().enumerate() {
+        let separates_thousands = c == ','
+            && i > 0
+            && chars[i - 1].is_ascii_digit()
+            && chars.len() > i + 3
+            && chars[i + 1..=i + 3].iter().all(char::is_ascii_digit)
+            && !chars.get(i + 4).is_some_and(char::is_ascii_digit);
+        if !separates_thousands {
+            stripped.push(c);
+        }
+    }
+    stripped
+}
+
+/// Fills in values found in free text like `"45.5 cM across 3 segments"`, keeping
+/// values that were already found.
+fn scan_text(text: &str, dna_match: &mut DnaMatch) {
+    let text = strip_thousands(text);
+    let words: Vec<&str> = text
+        .split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    for (i, word) in words.iter().enumerate() {
+        let lowercase = word.to_lowercase();
+        if let Some(number) = lowercase.strip_suffix("cm") {
+            let shared_cm = if number.is_empty() && i > 0 {
+                parse_number(words[i - 1])
+            } else {
+                parse_number(number)
+            };
+            dna_match.shared_cm = dna_match.shared_cm.or(shared_cm);
+        } else if lowercase.starts_with("segment") && i > 0 {
+            dna_match.segments = dna_match.segments.or_else(|| words[i - 1].parse().ok());
+        }
+    }
+}
@@ -0,0 +1,656 @@
+//! Decoding of the character encodings GEDCOM content is written in.
+//!
+//! Content is decoded to Unicode before it is tokenized. A byte order mark selects UTF-8
+//! or UTF-16, otherwise the `CHAR` of the header does, ie. `1 CHAR ANSEL`. Content
+//! without either is read as UTF-8.
+//!
+//! `decode` is usable on its own, for applications reading files themselves:
+//!
+//! ```rust
+//! use gedcom::encoding::{decode, DetectedEncoding};
+//!
+//! let (content, encoding, warnings) = decode(b"0 HEAD\n1 CHAR ANSEL\n0 TRLR\n");
+//! assert_eq!(encoding, DetectedEncoding::Ansel);
+//! assert!(warnings.is_empty());
+//! let data = gedcom::parse_document(&content).unwrap();
+//! ```
+use std::fmt;
+
+use crate::GedcomError;
+
+/// The encoding content was decoded from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DetectedEncoding {
+    /// UTF-8, with or without a byte order mark, the default
+    Utf8,
+    /// UTF-16 little-endian, the `UNICODE` of Windows programs
+    Utf16Le,
+    /// UTF-16 big-endian
+    Utf16Be,
+    /// ANSEL, the `CHAR` of GEDCOM 5.5
+    Ansel,
+}
+
+impl fmt::Display for DetectedEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DetectedEncoding::Utf8 => write!(f, "UTF-8"),
+            DetectedEncoding::Utf16Le => write!(f, "UTF-16LE"),
+            DetectedEncoding::Utf16Be => write!(f, "UTF-16BE"),
+            DetectedEncoding::Ansel => write!(f, "ANSEL"),
+        }
+    }
+}
+
+/// A problem found while decoding content. Decoding goes on regardless, replacing the
+/// bytes it can't read with U+FFFD.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EncodingWarning {
+    /// Bytes that are not valid in the detected encoding, at this offset of the content
+    InvalidBytes(usize),
+    /// UTF-16 content with an odd number of bytes, the last one is dropped
+    TruncatedUtf16,
+    /// The `CHAR` of the header names an encoding that isn't supported, the content
+    /// is read as UTF-8
+    UnsupportedEncoding(String),
+}
+
+impl fmt::Display for EncodingWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodingWarning::InvalidBytes(offset) => {
+                write!(f, "Invalid bytes at offset {offset}, replaced")
+            }
+            EncodingWarning::TruncatedUtf16 => {
+                write!(f, "UTF-16 content with an odd number of bytes")
+            }
+            EncodingWarning::UnsupportedEncoding(encoding) => {
+                write!(f, "Unsupported encoding {encoding}, read as UTF-8")
+            }
+        }
+    }
+}
+
+/// Decodes GEDCOM content to a string, from the encoding given by its byte order mark
+/// or its header. Bytes that can't be decoded are replaced and reported as warnings.
+#[must_use]
+pub fn decode(bytes: &[u8]) -> (String, DetectedEncoding, Vec<EncodingWarning>) {
+    let (encoding, content) = detect(bytes);
+    let mut warnings = Vec::new();
+    let text = match encoding {
+        DetectedEncoding::Utf8 => {
+            if let Some(declared) = declared_encoding(content).filter(|e| !is_utf8_compatible(e)) {
+                warnings.push(EncodingWarning::UnsupportedEncoding(declared));
+            }
+            decode_utf8_lossy(content, &mut warnings)
+        }
+        DetectedEncoding::Utf16Le => decode_utf16_lossy(content, u16::from_le_bytes, &mut warnings),
+        DetectedEncoding::Utf16Be => decode_utf16_lossy(content, u16::from_be_bytes, &mut warnings),
+        DetectedEncoding::Ansel => decode_ansel(content),
+    };
+    (text, encoding, warnings)
+}
+
+/// Decodes GEDCOM content like `decode`, failing on bytes that can't be decoded
+pub(crate) fn decode_strict(bytes: &[u8]) -> Result<String, GedcomError> {
+    match detect(bytes) {
+        (DetectedEncoding::Utf8, content) => Ok(String::from_utf8(content.to_vec())?),
+        (DetectedEncoding::Utf16Le, content) => decode_utf16(content, u16::from_le_bytes),
+        (DetectedEncoding::Utf16Be, content) => decode_utf16(content, u16::from_be_bytes),
+        (DetectedEncoding::Ansel, content) => Ok(decode_ansel(content)),
+    }
+}
+
+/// The encoding of the content, with the content past its byte order mark
+pub(crate) fn detect(bytes: &[u8]) -> (DetectedEncoding, &[u8]) {
+    match bytes {
+        [0xEF, 0xBB, 0xBF, rest @ ..] => (DetectedEncoding::Utf8, rest),
+        [0xFF, 0xFE, rest @ ..] => (DetectedEncoding::Utf16Le, rest),
+        [0xFE, 0xFF, rest @ ..] => (DetectedEncoding::Utf16Be, rest),
+        // UTF-16 without a byte order mark, told apart by the zero byte of the level
+        [b'0', 0, ..] => (DetectedEncoding::Utf16Le, bytes),
+        [0, b'0', ..] => (DetectedEncoding::Utf16Be, bytes),
+        _ => match declared_encoding(bytes) {
+            Some(encoding) if encoding.eq_ignore_ascii_case("ANSEL") => {
+                (DetectedEncoding::Ansel, bytes)
+            }
+            _ => (DetectedEncoding::Utf8, bytes),
+        },
+    }
+}
+
+/// Whether content declared in `encoding` reads the same as UTF-8
+fn is_utf8_compatible(encoding: &str) -> bool {
+    ["UTF-8", "UTF8", "ASCII", "UNICODE"]
+        .iter()
+        .any(|compatible| encoding.eq_ignore_ascii_case(compatible))
+}
+
+/// The value of the `CHAR` line of the header, read from the raw bytes
+fn declared_encoding(bytes: &[u8]) -> Option<String> {
+    let mut lines = bytes
+        .split(|&b| b == b'\n' || b == b'\r')
+        .map(<[u8]>::trim_ascii)
+        .filter(|line| !line.is_empty());
+    // the header is the first record, the lines up to the next level 0 line
+    lines.next()?;
+    lines
+        .take_while(|line| !line.starts_with(b"0 "))
+        .find_map(|line| line.strip_prefix(b"1 CHAR "))
+        .map(|value| String::from_utf8_lossy(value.trim_ascii()).into_owned())
+}
+
+fn decode_utf8_lossy(mut bytes: &[u8], warnings: &mut Vec<EncodingWarning>) -> String {
+    let mut decoded = String::with_capacity(bytes.len());
+    let mut offset = 0;
+    loop {
+        match std::str::from_utf8(bytes) {
+            Ok(valid) => {
+                decoded.push_str(valid);
+                return decoded;
+            }
+            Err(error) => {
+                let (valid, rest) = bytes.split_at(error.valid_up_to());
+                decoded.push_str(std::str::from_utf8(valid).unwrap_or_default());
+                decoded.push(char::REPLACEMENT_CHARACTER);
+                warnings.push(EncodingWarning::InvalidBytes(offset + valid.len()));
+                let invalid = error.error_len().unwrap_or(rest.len());
+                offset += valid.len() + invalid;
+                bytes = &rest[invalid..];
+            }
+        }
+    }
+}
+
+fn decode_utf16(bytes: &[u8], read: fn([u8; 2]) -> u16) -> Result<String, GedcomError> {
+    if !bytes.len().is_multiple_of(2) {
+        let message = "UTF-16 content with an odd number of bytes".to_string();
+        return Err(GedcomError::Encoding(message));
+    }
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| read([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16(&units).map_err(|error| GedcomError::Encoding(error.to_string()))
+}
+
+fn decode_utf16_lossy(
+    bytes: &[u8],
+    read: fn([u8; 2]) -> u16,
+    warnings: &mut Vec<EncodingWarning>,
+) -> String {
+    if !bytes.len().is_multiple_of(2) {
+        warnings.push(EncodingWarning::TruncatedUtf16);
+    }
+    let units = bytes.chunks_exact(2).map(|pair| read([pair[0], pair[1]]));
+    let mut decoded = String::with_capacity(bytes.len() / 2);
+    let mut offset = 0;
+    for c in char::decode_utf16(units) {
+        if let Ok(c) = c {
+            decoded.push(c);
+            offset += c.len_utf16() * 2;
+        } else {
+            decoded.push(char::REPLACEMENT_CHARACTER);
+            warnings.push(EncodingWarning::InvalidBytes(offset));
+            offset += 2;
+        }
+    }
+    decoded
+}
+
+/// Decodes ANSEL (ANSI Z39.47), the encoding of GEDCOM 5.5. ANSEL writes combining
+/// diacritics before the letter they apply to, where Unicode writes them after it, so
+/// they are held until their letter. Letters with a single diacritic are composed into
+/// their precomposed character when there is one, ie. `e` & acute into `é`.
+fn decode_ansel(bytes: &[u8]) -> String {
+    let mut decoded = String::with_capacity(bytes.len());
+    let mut marks = Vec::new();
+    for &byte in bytes {
+        if let Some(mark) = ansel_mark(byte) {
+            marks.push(mark);
+            continue;
+        }
+        let c = ansel_char(byte);
+        if c.is_control() {
+            // diacritics without a letter are kept on their own
+            decoded.extend(marks.drain(..));
+            decoded.push(c);
+            continue;
+        }
+        let mut base = c;
+        let mut remaining = Vec::new();
+        for mark in marks.drain(..) {
+            match compose(base, mark) {
+                Some(composed) if remaining.is_empty() => base = composed,
+                _ => remaining.push(mark),
+            }
+        }
+        decoded.push(base);
+        decoded.extend(remaining);
+    }
+    decoded.extend(marks);
+    decoded
+}
+
+/// The character of an ANSEL byte that isn't a diacritic
+fn ansel_char(byte: u8) -> char {
+    match byte {
+        0x00..=0x7F => char::from(byte),
+        0xA1 => 'Ł',
+        0xA2 => 'Ø',
+        0xA3 => 'Đ',
+        0xA4 => 'Þ',
+        0xA5 => 'Æ',
+        0xA6 => 'Œ',
+        0xA7 => 'ʹ',
+        0xA8 => '·',
+        0xA9 => '♭',
+        0xAA => '®',
+        0xAB => '±',
+        0xAC => 'Ơ',
+        0xAD => 'Ư',
+        0xAE => 'ʼ',
+        0xB0 => 'ʻ',
+        0xB1 => 'ł',
+        0xB2 => 'ø',
+        0xB3 => 'đ',
+        0xB4 => 'þ',
+        0xB5 => 'æ',
+        0xB6 => 'œ',
+        0xB7 => 'ʺ',
+        0xB8 => 'ı',
+        0xB9 => '£',
+        0xBA => 'ð',
+        0xBC => 'ơ',
+        0xBD => 'ư',
+        // the empty & black boxes of GEDCOM 5.5
+        0xBE => '□',
+        0xBF => '■',
+        0xC0 => '°',
+        0xC1 => 'ℓ',
+        0xC2 => '℗',
+        0xC3 => '©',
+        0xC4 => '♯',
+        0xC5 => '¿',
+        0xC6 => '¡',
+        0xC7 | 0xCF => 'ß',
+        0xC8 => '€',
+        0xCD => 'e',
+        0xCE => 'o',
+        _ => char::REPLACEMENT_CHARACTER,
+    }
+}
+
+/// The combining diacritic of an ANSEL byte
+fn ansel_mark(byte: u8) -> Option<char> {
+    Some(match byte {
+        0xE0 => '\u{309}',
+        0xE1 => '\u{300}',
+        0xE2 => '\u{301}',
+        0xE3 => '\u{302}',
+        0xE4 => '\u{303}',
+        0xE5 => '\u{304}',
+        0xE6 => '\u{306}',
+        0xE7 => '\u{307}',
+        0xE8 => '\u{308}',
+        0xE9 => '\u{30c}',
+        0xEA => '\u{30a}',
+        0xEB => '\u{fe20}',
+        0xEC => '\u{fe21}',
+        0xED => '\u{315}',
+        0xEE => '\u{30b}',
+        0xEF => '\u{310}',
+        0xF0 => '\u{327}',
+        0xF1 => '\u{328}',
+        0xF2 => '\u{323}',
+        0xF3 => '\u{324}',
+        0xF4 => '\u{325}',
+        0xF5 => '\u{333}',
+        0xF6 => '\u{332}',
+        0xF7 => '\u{326}',
+        0xF8 => '\u{31c}',
+        0xF9 => '\u{32e}',
+        0xFA => '\u{fe22}',
+        0xFB => '\u{fe23}',
+        0xFE => '\u{313}',
+        _ => return None,
+    })
+}
+
+/// The precomposed character of a letter & a combining diacritic
+fn compose(base: char, mark: char) -> Option<char> {
+    COMPOSITIONS
+        .binary_search_by(|&(b, m, _)| (b, m).cmp(&(base, mark)))
+        .ok()
+        .map(|index| COMPOSITIONS[index].2)
+}
+
+/// Precomposed Latin letters by their letter & diacritic, sorted for binary search
+const COMPOSITIONS: &[(char, char, char)] = &[
+    ('A', '\u{300}', '\u{c0}'),
+    ('A', '\u{301}', '\u{c1}'),
+    ('A', '\u{302}', '\u{c2}'),
+    ('A', '\u{303}', '\u{c3}'),
+    ('A', '\u{304}', '\u{100}'),
+    ('A', '\u{306}', '\u{102}'),
+    ('A', '\u{307}', '\u{226}'),
+    ('A', '\u{308}', '\u{c4}'),
+    ('A', '\u{309}', '\u{1ea2}'),
+    ('A', '\u{30a}', '\u{c5}'),
+    ('A', '\u{30c}', '\u{1cd}'),
+    ('A', '\u{323}', '\u{1ea0}'),
+    ('A', '\u{325}', '\u{1e00}'),
+    ('A', '\u{328}', '\u{104}'),
+    ('B', '\u{307}', '\u{1e02}'),
+    ('B', '\u{323}', '\u{1e04}'),
+    ('B', '\u{331}', '\u{1e06}'),
+    ('C', '\u{301}', '\u{106}'),
+    ('C', '\u{302}', '\u{108}'),
+    ('C', '\u{307}', '\u{10a}'),
+    ('C', '\u{30c}', '\u{10c}'),
+    ('C', '\u{327}', '\u{c7}'),
+    ('D', '\u{307}', '\u{1e0a}'),
+    ('D', '\u{30c}', '\u{10e}'),
+    ('D', '\u{323}', '\u{1e0c}'),
+    ('D', '\u{327}', '\u{1e10}'),
+    ('D', '\u{331}', '\u{1e0e}'),
+    ('E', '\u{300}', '\u{c8}'),
+    ('E', '\u{301}', '\u{c9}'),
+    ('E', '\u{302}', '\u{ca}'),
+    ('E', '\u{303}', '\u{1ebc}'),
+    ('E', '\u{304}', '\u{112}'),
+    ('E', '\u{306}', '\u{114}'),
+    ('E', '\u{307}', '\u{116}'),
+    ('E', '\u{308}', '\u{cb}'),
+    ('E', '\u{309}', '\u{1eba}'),
+    ('E', '\u{30c}', '\u{11a}'),
+    ('E', '\u{323}', '\u{1eb8}'),
+    ('E', '\u{327}', '\u{228}'),
+    ('E', '\u{328}', '\u{118}'),
+    ('F', '\u{307}', '\u{1e1e}'),
+    ('G', '\u{301}', '\u{1f4}'),
+    ('G', '\u{302}', '\u{11c}'),
+    ('G', '\u{304}', '\u{1e20}'),
+    ('G', '\u{306}', '\u{11e}'),
+    ('G', '\u{307}', '\u{120}'),
+    ('G', '\u{30c}', '\u{1e6}'),
+    ('G', '\u{327}', '\u{122}'),
+    ('H', '\u{302}', '\u{124}'),
+    ('H', '\u{307}', '\u{1e22}'),
+    ('H', '\u{308}', '\u{1e26}'),
+    ('H', '\u{30c}', '\u{21e}'),
+    ('H', '\u{323}', '\u{1e24}'),
+    ('H', '\u{327}', '\u{1e28}'),
+    ('I', '\u{300}', '\u{cc}'),
+    ('I', '\u{301}', '\u{cd}'),
+    ('I', '\u{302}', '\u{ce}'),
+    ('I', '\u{303}', '\u{128}'),
+    ('I', '\u{304}', '\u{12a}'),
+    ('I', '\u{306}', '\u{12c}'),
+    ('I', '\u{307}', '\u{130}'),
+    ('I', '\u{308}', '\u{cf}'),
+    ('I', '\u{309}', '\u{1ec8}'),
+    ('I', '\u{30c}', '\u{1cf}'),
+    ('I', '\u{323}', '\u{1eca}'),
+    ('I', '\u{328}', '\u{12e}'),
+    ('J', '\u{302}', '\u{134}'),
+    ('K', '\u{301}', '\u{1e30}'),
+    ('K', '\u{30c}', '\u{1e8}'),
+    ('K', '\u{323}', '\u{1e32}'),
+    ('K', '\u{327}', '\u{136}'),
+    ('K', '\u{331}', '\u{1e34}'),
+    ('L', '\u{301}', '\u{139}'),
+    ('L', '\u{30c}', '\u{13d}'),
+    ('L', '\u{323}', '\u{1e36}'),
+    ('L', '\u{327}', '\u{13b}'),
+    ('L', '\u{331}', '\u{1e3a}'),
+    ('M', '\u{301}', '\u{1e3e}'),
+    ('M', '\u{307}', '\u{1e40}'),
+    ('M', '\u{323}', '\u{1e42}'),
+    ('N', '\u{300}', '\u{1f8}'),
+    ('N', '\u{301}', '\u{143}'),
+    ('N', '\u{303}', '\u{d1}'),
+    ('N', '\u{307}', '\u{1e44}'),
+    ('N', '\u{30c}', '\u{147}'),
+    ('N', '\u{323}', '\u{1e46}'),
+    ('N', '\u{327}', '\u{145}'),
+    ('N', '\u{331}', '\u{1e48}'),
+    ('O', '\u{300}', '\u{d2}'),
+    ('O', '\u{301}', '\u{d3}'),
+    ('O', '\u{302}', '\u{d4}'),
+    ('O', '\u{303}', '\u{d5}'),
+    ('O', '\u{304}', '\u{14c}'),
+    ('O', '\u{306}', '\u{14e}'),
+    ('O', '\u{307}', '\u{22e}'),
+    ('O', '\u{308}', '\u{d6}'),
+    ('O', '\u{309}', '\u{1ece}'),
+    ('O', '\u{30b}', '\u{150}'),
+    ('O', '\u{30c}', '\u{1d1}'),
+    ('O', '\u{323}', '\u{1ecc}'),
+    ('O', '\u{328}', '\u{1ea}'),
+    ('P', '\u{301}', '\u{1e54}'),
+    ('P', '\u{307}', '\u{1e56}'),
+    ('R', '\u{301}', '\u{154}'),
+    ('R', '\u{307}', '\u{1e58}'),
+    ('R', '\u{30c}', '\u{158}'),
+    ('R', '\u{323}', '\u{1e5a}'),
+    ('R', '\u{327}', '\u{156}'),
+    ('R', '\u{331}', '\u{1e5e}'),
+    ('S', '\u{301}', '\u{15a}'),
+    ('S', '\u{302}', '\u{15c}'),
+    ('S', '\u{307}', '\u{1e60}'),
+    ('S', '\u{30c}', '\u{160}'),
+    ('S', '\u{323}', '\u{1e62}'),
+    ('S', '\u{326}', '\u{218}'),
+    ('S', '\u{327}', '\u{15e}'),
+    ('T', '\u{307}', '\u{1e6a}'),
+    ('T', '\u{30c}', '\u{164}'),
+    ('T', '\u{323}', '\u{1e6c}'),
+    ('T', '\u{326}', '\u{21a}'),
+    ('T', '\u{327}', '\u{162}'),
+    ('T', '\u{331}', '\u{1e6e}'),
+    ('U', '\u{300}', '\u{d9}'),
+    ('U', '\u{301}', '\u{da}'),
+    ('U', '\u{302}', '\u{db}'),
+    ('U', '\u{303}', '\u{168}'),
+    ('U', '\u{304}', '\u{16a}'),
+    ('U', '\u{306}', '\u{16c}'),
+    ('U', '\u{308}', '\u{dc}'),
+    ('U', '\u{309}', '\u{1ee6}'),
+    ('U', '\u{30a}', '\u{16e}'),
+    ('U', '\u{30b}', '\u{170}'),
+    ('U', '\u{30c}', '\u{1d3}'),
+    ('U', '\u{323}', '\u{1ee4}'),
+    ('U', '\u{324}', '\u{1e72}'),
+    ('U', '\u{328}', '\u{172}'),
+    ('V', '\u{303}', '\u{1e7c}'),
+    ('V', '\u{323}', '\u{1e7e}'),
+    ('W', '\u{300}', '\u{1e80}'),
+    ('W', '\u{301}', '\u{1e82}'),
+    ('W', '\u{302}', '\u{174}'),
+    ('W', '\u{307}', '\u{1e86}'),
+    ('W', '\u{308}', '\u{1e84}'),
+    ('W', '\u{323}', '\u{1e88}'),
+    ('X', '\u{307}', '\u{1e8a}'),
+    ('X', '\u{308}', '\u{1e8c}'),
+    ('Y', '\u{300}', '\u{1ef2}'),
+    ('Y', '\u{301}', '\u{dd}'),
+    ('Y', '\u{302}', '\u{176}'),
+    ('Y', '\u{303}', '\u{1ef8}'),
+    ('Y', '\u{304}', '\u{232}'),
+    ('Y', '\u{307}', '\u{1e8e}'),
+    ('Y', '\u{308}', '\u{178}'),
+    ('Y', '\u{309}', '\u{1ef6}'),
+    ('Y', '\u{323}', '\u{1ef4}'),
+    ('Z', '\u{301}', '\u{179}'),
+    ('Z', '\u{302}', '\u{1e90}'),
+    ('Z', '\u{307}', '\u{17b}'),
+    ('Z', '\u{30c}', '\u{17d}'),
+    ('Z', '\u{323}', '\u{1e92}'),
+    ('Z', '\u{331}', '\u{1e94}'),
+    ('a', '\u{300}', '\u{e0}'),
+    ('a', '\u{301}', '\u{e1}'),
+    ('a', '\u{302}', '\u{e2}'),
+    ('a', '\u{303}', '\u{e3}'),
+    ('a', '\u{304}', '\u{101}'),
+    ('a', '\u{306}', '\u{103}'),
+    ('a', '\u{307}', '\u{227}'),
+    ('a', '\u{308}', '\u{e4}'),
+    ('a', '\u{309}', '\u{1ea3}'),
+    ('a', '\u{30a}', '\u{e5}'),
+    ('a', '\u{30c}', '\u{1ce}'),
+    ('a', '\u{323}', '\u{1ea1}'),
+    ('a', '\u{325}', '\u{1e01}'),
+    ('a', '\u{328}', '\u{105}'),
+    ('b', '\u{307}', '\u{1e03}'),
+    ('b', '\u{323}', '\u{1e05}'),
+    ('b', '\u{331}', '\u{1e07}'),
+    ('c', '\u{301}', '\u{107}'),
+    ('c', '\u{302}', '\u{109}'),
+    ('c', '\u{307}', '\u{10b}'),
+    ('c', '\u{30c}', '\u{10d}'),
+    ('c', '\u{327}', '\u{e7}'),
+    ('d', '\u{307}', '\u{1e0b}'),
+    ('d', '\u{30c}', '\u{10f}'),
+    ('d', '\u{323}', '\u{1e0d}'),
+    ('d', '\u{327}', '\u{1e11}'),
+    ('d', '\u{331}', '\u{1e0f}'),
+    ('e', '\u{300}', '\u{e8}'),
+    ('e', '\u{301}', '\u{e9}'),
+    ('e', '\u{302}', '\u{ea}'),
+    ('e', '\u{303}', '\u{1ebd}'),
+    ('e', '\u{304}', '\u{113}'),
+    ('e', '\u{306}', '\u{115}'),
+    ('e', '\u{307}', '\u{117}'),
+    ('e', '\u{308}', '\u{eb}'),
+    ('e', '\u{309}', '\u{1ebb}'),
+    ('e', '\u{30c}', '\u{11b}'),
+    ('e', '\u{323}', '\u{1eb9}'),
+    ('e', '\u{327}', '\u{229}'),
+    ('e', '\u{328}', '\u{119}'),
+    ('f', '\u{307}', '\u{1e1f}'),
+    ('g', '\u{301}', '\u{1f5}'),
+    ('g', '\u{302}', '\u{11d}'),
+    ('g', '\u{304}', '\u{1e21}'),
+    ('g', '\u{306}', '\u{11f}'),
+    ('g', '\u{307}', '\u{121}'),
+    ('g', '\u{30c}', '\u{1e7}'),
+    ('g', '\u{327}', '\u{123}'),
+    ('h', '\u{302}', '\u{125}'),
+    ('h', '\u{307}', '\u{1e23}'),
+    ('h', '\u{308}', '\u{1e27}'),
+    ('h', '\u{30c}', '\u{21f}'),
+    ('h', '\u{323}', '\u{1e25}'),
+    ('h', '\u{327}', '\u{1e29}'),
+    ('h', '\u{331}', '\u{1e96}'),
+    ('i', '\u{300}', '\u{ec}'),
+    ('i', '\u{301}', '\u{ed}'),
+    ('i', '\u{302}', '\u{ee}'),
+    ('i', '\u{303}', '\u{129}'),
+    ('i', '\u{304}', '\u{12b}'),
+    ('i', '\u{306}', '\u{12d}'),
+    ('i', '\u{308}', '\u{ef}'),
+    ('i', '\u{309}', '\u{1ec9}'),
+    ('i', '\u{30c}', '\u{1d0}'),
+    ('i', '\u{323}', '\u{1ecb}'),
+    ('i', '\u{328}', '\u{12f}'),
+    ('j', '\u{302}', '\u{135}'),
+    ('j', '\u{30c}', '\u{1f0}'),
+    ('k', '\u{301}', '\u{1e31}'),
+    ('k', '\u{30c}', '\u{1e9}'),
+    ('k', '\u{323}', '\u{1e33}'),
+    ('k', '\u{327}', '\u{137}'),
+    ('k', '\u{331}', '\u{1e35}'),
+    ('l', '\u{301}', '\u{13a}'),
+    ('l', '\u{30c}', '\u{13e}'),
+    ('l', '\u{323}', '\u{1e37}'),
+    ('l', '\u{327}', '\u{13c}'),
+    ('l', '\u{331}', '\u{1e3b}'),
+    ('m', '\u{301}', '\u{1e3f}'),
+    ('m', '\u{307}', '\u{1e41}'),
+    ('m', '\u{323}', '\u{1e43}'),
+    ('n', '\u{300}', '\u{1f9}'),
+    ('n', '\u{301}', '\u{144}'),
+    ('n', '\u{303}', '\u{f1}'),
+    ('n', '\u{307}', '\u{1e45}'),
+    ('n', '\u{30c}', '\u{148}'),
+    ('n', '\u{323}', '\u{1e47}'),
+    ('n', '\u{327}', '\u{146}'),
+    ('n', '\u{331}', '\u{1e49}'),
+    ('o', '\u{300}', '\u{f2}'),
+    ('o', '\u{301}', '\u{f3}'),
+    ('o', '\u{302}', '\u{f4}'),
+    ('o', '\u{303}', '\u{f5}'),
+    ('o', '\u{304}', '\u{14d}'),
+    ('o', '\u{306}', '\u{14f}'),
+    ('o', '\u{307}', '\u{22f}'),
+    ('o', '\u{308}', '\u{f6}'),
+    ('o', '\u{309}', '\u{1ecf}'),
+    ('o', '\u{30b}', '\u{151}'),
+    ('o', '\u{30c}', '\u{1d2}'),
+    ('o', '\u{323}', '\u{1ecd}'),
+    ('o', '\u{328}', '\u{1eb}'),
+    ('p', '\u{301}', '\u{1e55}'),
+    ('p', '\u{307}', '\u{1e57}'),
+    ('r', '\u{301}', '\u{155}'),
+    ('r', '\u{307}', '\u{1e59}'),
+    ('r', '\u{30c}', '\u{159}'),
+    ('r', '\u{323}', '\u{1e5b}'),
+    ('r', '\u{327}', '\u{157}'),
+    ('r', '\u{331}', '\u{1e5f}'),
+    ('s', '\u{301}', '\u{15b}'),
+    ('s', '\u{302}', '\u{15d}'),
+    ('s', '\u{307}', '\u{1e61}'),
+    ('s', '\u{30c}', '\u{161}'),
+    ('s', '\u{323}', '\u{1e63}'),
+    ('s', '\u{326}', '\u{219}'),
+    ('s', '\u{327}', '\u{15f}'),
+    ('t', '\u{307}', '\u{1e6b}'),
+    ('t', '\u{308}', '\u{1e97}'),
+    ('t', '\u{30c}', '\u{165}'),
+    ('t', '\u{323}', '\u{1e6d}'),
+    ('t', '\u{326}', '\u{21b}'),
+    ('t', '\u{327}', '\u{163}'),
+    ('t', '\u{331}', '\u{1e6f}'),
+    ('u', '\u{300}', '\u{f9}'),
+    ('u', '\u{301}', '\u{fa}'),
+    ('u', '\u{302}', '\u{fb}'),
+    ('u', '\u{303}', '\u{169}'),
+    ('u', '\u{304}', '\u{16b}'),
+    ('u', '\u{306}', '\u{16d}'),
+    ('u', '\u{308}', '\u{fc}'),
+    ('u', '\u{309}', '\u{1ee7}'),
+    ('u', '\u{30a}', '\u{16f}'),
+    ('u', '\u{30b}', '\u{171}'),
+    ('u', '\u{30c}', '\u{1d4}'),
+    ('u', '\u{323}', '\u{1ee5}'),
+    ('u', '\u{324}', '\u{1e73}'),
+    ('u', '\u{328}', '\u{173}'),
+    ('v', '\u{303}', '\u{1e7d}'),
+    ('v', '\u{323}', '\u{1e7f}'),
+    ('w', '\u{300}', '\u{1e81}'),
+    ('w', '\u{301}', '\u{1e83}'),
+    ('w', '\u{302}', '\u{175}'),
+    ('w', '\u{307}', '\u{1e87}'),
+    ('w', '\u{308}', '\u{1e85}'),
+    ('w', '\u{30a}', '\u{1e98}'),
+    ('w', '\u{323}', '\u{1e89}'),
+    ('x', '\u{307}', '\u{1e8b}'),
+    ('x', '\u{308}', '\u{1e8d}'),
+    ('y', '\u{300}', '\u{1ef3}'),
+    ('y', '\u{301}', '\u{fd}'),
+    ('y', '\u{302}', '\u{177}'),
+    ('y', '\u{303}', '\u{1ef9}'),
+    ('y', '\u{304}', '\u{233}'),
+    ('y', '\u{307}', '\u{1e8f}'),
+    ('y', '\u{308}', '\u{ff}'),
+    ('y', '\u{309}', '\u{1ef7}'),
+    ('y', '\u{30a}', '\u{1e99}'),
+    ('y', '\u{323}', '\u{1ef5}'),
+    ('z', '\u{301}', '\u{17a}'),
+    ('z', '\u{302}', '\u{1e91}'),
+    ('z', '\u{307}', '\u{17c}'),
+    ('z', '\u{30c}', '\u{17e}'),
+    ('z', '\u{323}', '\u{1e93}'),
+    ('z', '\u{331}', '\u{1e95}'),
+];
@@ -0,0 +1,201 @@
+//! Checks values as they are entered, for data-entry forms & builders.
+//!
+//! Each check returns a typed error describing what is wrong with the value, so forms
+//! can give feedback on a field as soon as it is filled in, rather than when the file is
+//! written or read back.
+use std::fmt;
+
+use crate::types::{month_number, Gender};
+use crate::writer::{check_line_value, InvalidValue};
+
+/// Longest xref allowed by GEDCOM 5.5.1, including the enclosing `@`
+pub const MAX_XREF_LENGTH: usize = 22;
+
+/// Why an entered value is not valid for its field
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FieldError {
+    /// The xref is not a `@` enclosed identifier, ie. `@I1@`
+    Xref(String),
+    /// The date does not follow the GEDCOM date syntax, ie. `1 JAN 1899` or `ABT 1900`
+    Date(String),
+    /// The value is not one of the `SEX` values `M`, `F`, `X` or `U`
+    Sex(String),
+    /// The value does not look like an email address
+    Email(String),
+    /// The value can't be written on a single line
+    Line(InvalidValue),
+    /// The value is not a latitude or longitude within range, ie. `N42.3601`
+    Coordinate(String),
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldError::Xref(reason) => write!(f, "Invalid xref: {reason}"),
+            FieldError::Date(reason) => write!(f, "Invalid date: {reason}"),
+            FieldError::Sex(value) => write!(f, "Invalid sex {value:?}, expected M, F, X or U"),
+            FieldError::Email(reason) => write!(f, "Invalid email address: {reason}"),
+            FieldError::Line(invalid) => invalid.fmt(f),
+            FieldError::Coordinate(reason) => write!(f, "Invalid coordinate: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for FieldError {}
+
+impl From<InvalidValue> for FieldError {
+    fn from(invalid: InvalidValue) -> FieldError {
+        FieldError::Line(invalid)
+    }
+}
+
+/// Checks a value that must fit on a single line, see `writer::check_line_value`
+///
+/// # Errors
+///
+/// Returns `FieldError::Line` when the value contains a line break or is too long.
+pub fn check_text(value: &str) -> Result<(), FieldError> {
+    Ok(check_line_value(value)?)
+}
+
+/// Checks that an xref is an identifier enclosed in `@`, starting with a letter or digit
+/// and free of spaces & further `@`, like `@I1@`.
+///
+/// # Errors
+///
+/// Returns `FieldError::Xref` describing the problem.
+pub fn check_xref(xref: &str) -> Result<(), FieldError> {
+    let error = |reason: &str| Err(FieldError::Xref(reason.to_string()));
+    let Some(id) = xref
+        .strip_prefix('@')
+        .and_then(|rest| rest.strip_suffix('@'))
+    else {
+        return error("must be enclosed in @");
+    };
+    if id.is_empty() {
+        return error("is empty");
+    }
+    if !id.starts_with(|c: char| c.is_ascii_alphanumeric()) {
+        return error("must start with a letter or digit");
+    }
+    if id.contains(|c: char| c == '@' || c.is_whitespace() || c.is_control()) {
+        return error("must not contain @ or spaces");
+    }
+    if xref.chars().count() > MAX_XREF_LENGTH {
+        return error("is longer than 22 characters");
+    }
+    Ok(())
+}
+
+/// Checks that a date follows the GEDCOM date syntax: a date like `1 JAN 1899`,
+/// `JAN 1899` or `1899`, possibly approximated (`ABT`, `CAL`, `EST`), bounded (`BEF`,
+/// `AFT`), a range (`BET ... AND ...`) or a period (`FROM ... TO ...`). Days must exist
+/// in their month.
+///
+/// # Errors
+///
+/// Returns `FieldError::Date` describing the problem.
+pub fn check_date(value: &str) -> Result<(), FieldError> {
+    let words: Vec<&str> = value.split_whitespace().collect();
+    let error = |reason: String| Err(FieldError::Date(reason));
+    match words.as_slice() {
+        [] => error("is empty".to_string()),
+        ["BET", rest @ ..] => match rest.iter().position(|word| *word == "AND") {
+            Some(and) => {
+                check_calendar_date(&rest[..and])?;
+                check_calendar_date(&rest[and + 1..])
+            }
+            None => error("a range needs BET ... AND ...".to_string()),
+        },
+        ["FROM", rest @ ..] => match rest.iter().position(|word| *word == "TO") {
+            Some(to) => {
+                check_calendar_date(&rest[..to])?;
+                check_calendar_date(&rest[to + 1..])
+            }
+            None => check_calendar_date(rest),
+        },
+        ["ABT" | "CAL" | "EST" | "BEF" | "AFT" | "TO", date @ ..] | date => {
+            check_calendar_date(date)
+        }
+    }
+}
+
+fn check_calendar_date(words: &[&str]) -> Result<(), FieldError> {
+    let error = |reason: String| Err(FieldError::Date(reason));
+    let (day, month, year) = match words {
+        [day, month, year] => (Some(*day), Some(*month), *year),
+        [month, year] => (None, Some(*month), *year),
+        [year] => (None, None, *year),
+        [] => return error("a date is missing".to_string()),
+        _ => return error(format!("unexpected words in {:?}", words.join(" "))),
+    };
+
+    let Ok(year) = year.parse::<u32>() else {
+        return error(format!("{year:?} is not a year"));
+    };
+    let month = match month {
+        Some(month) => match month_number(month) {
+            Some(number) => Some(number),
+            None => return error(format!("{month:?} is not a month, ie. JAN")),
+        },
+        None => None,
+    };
+    if let (Some(day), Some(month)) = (day, month) {
+        let days = days_in_month(year, month);
+        match day.parse::<u8>() {
+            Ok(day) if day >= 1 && day <= days => (),
+            _ => return error(format!("{day:?} is not a day of the month")),
+        }
+    }
+    Ok(())
+}
+
+fn days_in_month(year: u32, month: u8) -> u8 {
+    match month {
+        4 | 6 | 9 | 11 => 30,
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => {
+            29
+        }
+        2 => 28,
+        _ => 31,
+    }
+}
+
+/// Checks a `SEX` value, returning the matching gender
+///
+/// # Errors
+///
+/// Returns `FieldError::Sex` when the value is not `M`, `F`, `X` or `U`.
+pub fn check_sex(value: &str) -> Result<Gender, FieldError> {
+    match value {
+        "M" => Ok(Gender::Male),
+        "F" => Ok(Gender::Female),
+        "X" => Ok(Gender::Nonbinary),
+        "U" => Ok(Gender::Unknown),
+        _ => Err(FieldError::Sex(value.to_string())),
+    }
+}
+
+/// Checks that a value looks like an email address: a local part and a domain with a
+/// dot, separated by a single `@`, without spaces.
+///
+/// # Errors
+///
+/// Returns `FieldError::Email` describing the problem.
+pub fn check_email(value: &str) -> Result<(), FieldError> {
+    let error = |reason: &str| Err(FieldError::Email(reason.to_string()));
+    if value.contains(char::is_whitespace) {
+        return error("must not contain spaces");
+    }
+    let Some((local, domain)) = value.split_once('@') else {
+        return error("must contain @");
+    };
+    if local.is_empty() || domain.contains('@') {
+        return error("must have a single @ after the name");
+    }
+    let labels: Vec<&str> = domain.split('.').collect();
+    if labels.len() < 2 || labels.iter().any(|label| label.is_empty()) {
+        return error("must end with a domain like example.com");
+    }
+    Ok(())
+}
@@ -0,0 +1,105 @@
+//! Errors that stop GEDCOM content from being read
+use std::{error, fmt, io, string::FromUtf8Error};
+
+/// Why GEDCOM content could not be read. Applications can match on the variants to
+/// handle each class of failure, ie. report unreadable files differently from
+/// malformed content.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum GedcomError {
+    /// The content could not be read
+    Io(io::Error),
+    /// The content is not in a character encoding that could be decoded
+    Encoding(String),
+    /// A line could not be split into its level, xref, tag & value
+    Tokenize {
+        /// Line number of the malformed line
+        line: u32,
+    },
+    /// A tag appeared where it isn't allowed or isn't handled
+    UnexpectedTag {
+        /// Line number of the tag
+        line: u32,
+        /// The unexpected tag
+        tag: String,
+        /// The structure the tag appeared in, ie. `Individual`
+        context: String,
+    },
+    /// The value of a line is not one of the values allowed by its tag
+    InvalidValue {
+        /// Line number of the value
+        line: u32,
+        /// The value as written
+        value: String,
+        /// Description of the values that are allowed
+        expected: String,
+    },
+    /// The parser rejected the content, with the parser's description of the problem
+    Malformed {
+        /// Line number the parser stopped at
+        line: u32,
+        /// Description of the problem
+        message: String,
+    },
+    /// The content ended before the trailer, in the middle of a record
+    Truncated,
+    /// The content exceeds a limit set on the parser, ie. the nesting depth
+    LimitExceeded(String),
+}
+
+impl fmt::Display for GedcomError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GedcomError::Io(error) => write!(f, "Could not read the content: {error}"),
+            GedcomError::Encoding(message) => write!(f, "Could not decode the content: {message}"),
+            GedcomError::Tokenize { line } => write!(f, "line {line}: Malformed line"),
+            GedcomError::UnexpectedTag { line, tag, context } => {
+                write!(f, "line {line}: Unhandled {context} Tag: {tag}")
+            }
+            GedcomError::InvalidValue {
+                line,
+                value,
+                expected,
+            } => write!(f, "line {line}: Invalid value {value}, expected {expected}"),
+            // the messages of the parser already start with the line number
+            GedcomError::Malformed { message, .. } => write!(f, "{message}"),
+            GedcomError::Truncated => write!(f, "The content ended before the trailer"),
+            GedcomError::LimitExceeded(limit) => write!(f, "The content exceeds the {limit}"),
+        }
+    }
+}
+
+impl error::Error for GedcomError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            GedcomError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for GedcomError {
+    fn from(error: io::Error) -> GedcomError {
+        GedcomError::Io(error)
+    }
+}
+
+impl From<FromUtf8Error> for GedcomError {
+    fn from(error: FromUtf8Error) -> GedcomError {
+        GedcomError::Encoding(error.to_string())
+    }
+}
+
+impl GedcomError {
+    /// Line number of the content the error was found on, when it is tied to a line
+    #[must_use]
+    pub fn line(&self) -> Option<u32> {
+        match self {
+            GedcomError::Tokenize { line }
+            | GedcomError::UnexpectedTag { line, .. }
+            | GedcomError::InvalidValue { line, .. }
+            | GedcomError::Malformed { line, .. } => Some(*line),
+            _ => None,
+        }
+    }
+}
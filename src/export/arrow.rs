@@ -0,0 +1,207 @@
+//! Columnar export of a tree as Arrow record batches & Parquet files, for analyzing
+//! large trees with dataframe libraries like pandas & polars.
+//!
+//! A tree is exported as three tables, each with the xrefs as keys to join them on:
+//! - `individuals`: one row per individual, with their name, sex, birth & death
+//! - `families`: one row per family, with its partners, children & marriage
+//! - `events`: one row per event of an individual or a family
+//!
+//! Dates are kept as written in the file, along with the year read from them when
+//! there is one.
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow_array::builder::{Int32Builder, ListBuilder, StringBuilder};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_schema::ArrowError;
+use parquet::arrow::ArrowWriter;
+use parquet::errors::ParquetError;
+
+use crate::tree::GedcomData;
+use crate::types::{Event, EventType, Gender, HasEvents, NameOrder, ParentTag};
+
+/// The individuals of a tree: `xref`, `name`, `given`, `surname`, `sex`, `birth_date`,
+/// `birth_place`, `birth_year`, `death_date`, `death_place` & `death_year`
+///
+/// # Errors
+///
+/// Returns an error when Arrow rejects the batch, which doesn't happen in practice.
+pub fn individuals_batch(data: &GedcomData) -> Result<RecordBatch, ArrowError> {
+    let mut xref = StringBuilder::new();
+    let mut name = StringBuilder::new();
+    let mut given = StringBuilder::new();
+    let mut surname = StringBuilder::new();
+    let mut sex = StringBuilder::new();
+    let mut birth = EventColumns::default();
+    let mut death = EventColumns::default();
+    for individual in &data.individuals {
+        xref.append_option(individual.xref.as_deref());
+        name.append_option(individual.display_name(NameOrder::Western));
+        let parts = individual.primary_name();
+        given.append_option(parts.and_then(|name| name.given.as_deref()));
+        surname.append_option(parts.and_then(|name| name.surname.as_deref()));
+        sex.append_option(match individual.sex {
+            Gender::Male => Some("M"),
+            Gender::Female => Some("F"),
+            Gender::Nonbinary => Some("X"),
+            Gender::Unknown => None,
+        });
+        let events = individual.events();
+        birth.append(first_event(&events, &EventType::Birth));
+        death.append(first_event(&events, &EventType::Death));
+    }
+
+    let mut columns: Vec<(&str, ArrayRef)> = vec![
+        ("xref", Arc::new(xref.finish())),
+        ("name", Arc::new(name.finish())),
+        ("given", Arc::new(given.finish())),
+        ("surname", Arc::new(surname.finish())),
+        ("sex", Arc::new(sex.finish())),
+    ];
+    columns.extend(birth.finish(["birth_date", "birth_place", "birth_year"]));
+    columns.extend(death.finish(["death_date", "death_place", "death_year"]));
+    RecordBatch::try_from_iter(columns)
+}
+
+/// The families of a tree: `xref`, `husband`, `wife`, `children` (a list of xrefs),
+/// `marriage_date`, `marriage_place` & `marriage_year`
+///
+/// # Errors
+///
+/// Returns an error when Arrow rejects the batch, which doesn't happen in practice.
+pub fn families_batch(data: &GedcomData) -> Result<RecordBatch, ArrowError> {
+    let mut xref = StringBuilder::new();
+    let mut husband = StringBuilder::new();
+    let mut wife = StringBuilder::new();
+    let mut children = ListBuilder::new(StringBuilder::new());
+    let mut marriage = EventColumns::default();
+    for family in &data.families {
+        xref.append_option(family.xref.as_deref());
+        let parents = family.parents();
+        let parent = |tag: ParentTag| {
+            parents
+                .iter()
+                .find(|(_, parent_tag)| *parent_tag == tag)
+                .map(|(xref, _)| *xref)
+        };
+        husband.append_option(parent(ParentTag::Husband));
+        wife.append_option(parent(ParentTag::Wife));
+        for child in &family.children {
+            children.values().append_value(child);
+        }
+        children.append(true);
+        marriage.append(first_event(&family.events(), &EventType::Marriage));
+    }
+
+    let mut columns: Vec<(&str, ArrayRef)> = vec![
+        ("xref", Arc::new(xref.finish())),
+        ("husband", Arc::new(husband.finish())),
+        ("wife", Arc::new(wife.finish())),
+        ("children", Arc::new(children.finish())),
+    ];
+    columns.extend(marriage.finish(["marriage_date", "marriage_place", "marriage_year"]));
+    RecordBatch::try_from_iter(columns)
+}
+
+/// The events of the individuals & families of a tree: `record` (the xref of the
+/// individual or family), `record_type` (`individual` or `family`), `event`, `date`,
+/// `place` & `year`
+///
+/// # Errors
+///
+/// Returns an error when Arrow rejects the batch, which doesn't happen in practice.
+pub fn events_batch(data: &GedcomData) -> Result<RecordBatch, ArrowError> {
+    let mut record = StringBuilder::new();
+    let mut record_type = StringBuilder::new();
+    let mut event_type = StringBuilder::new();
+    let mut details = EventColumns::default();
+    let individuals = data.individuals.iter().map(|individual| {
+        (
+            individual.xref.as_deref(),
+            "individual",
+            individual.events(),
+        )
+    });
+    let families = data
+        .families
+        .iter()
+        .map(|family| (family.xref.as_deref(), "family", family.events()));
+    for (xref, kind, events) in individuals.chain(families) {
+        for event in &events {
+            record.append_option(xref);
+            record_type.append_value(kind);
+            event_type.append_value(match &event.event {
+                EventType::SourceData(events) => events.clone(),
+                EventType::Other => event
+                    .classification
+                    .clone()
+                    .unwrap_or_else(|| "Event".to_string()),
+                event_type => event_type.to_string(),
+            });
+            details.append(Some(event));
+        }
+    }
+
+    let mut columns: Vec<(&str, ArrayRef)> = vec![
+        ("record", Arc::new(record.finish())),
+        ("record_type", Arc::new(record_type.finish())),
+        ("event", Arc::new(event_type.finish())),
+    ];
+    columns.extend(details.finish(["date", "place", "year"]));
+    RecordBatch::try_from_iter(columns)
+}
+
+/// Writes the tables of a tree as `individuals.parquet`, `families.parquet` &
+/// `events.parquet` in the directory `dir`
+///
+/// # Errors
+///
+/// Returns an error when a file can't be written.
+pub fn write_parquet(data: &GedcomData, dir: &Path) -> Result<(), ParquetError> {
+    let tables = [
+        ("individuals", individuals_batch(data)?),
+        ("families", families_batch(data)?),
+        ("events", events_batch(data)?),
+    ];
+    for (name, batch) in tables {
+        let file = File::create(dir.join(format!("{name}.parquet")))?;
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+    }
+    Ok(())
+}
+
+fn first_event<'a>(events: &'a [Event], event_type: &EventType) -> Option<&'a Event> {
+    events.iter().find(|event| &event.event == event_type)
+}
+
+/// The date, place & year columns of an event
+#[derive(Default)]
+struct EventColumns {
+    date: StringBuilder,
+    place: StringBuilder,
+    year: Int32Builder,
+}
+
+impl EventColumns {
+    fn append(&mut self, event: Option<&Event>) {
+        self.date
+            .append_option(event.and_then(|e| e.date.as_deref()));
+        self.place.append_option(event.and_then(Event::place_name));
+        self.year.append_option(
+            event
+                .and_then(Event::date_value)
+                .and_then(|date| date.year()),
+        );
+    }
+
+    fn finish(mut self, names: [&str; 3]) -> Vec<(&str, ArrayRef)> {
+        vec![
+            (names[0], Arc::new(self.date.finish())),
+            (names[1], Arc::new(self.place.finish())),
+            (names[2], Arc::new(self.year.finish())),
+        ]
+    }
+}
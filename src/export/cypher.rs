@@ -0,0 +1,185 @@
+//! Renders a tree as Cypher statements, for loading it into Neo4j & other graph
+//! databases.
+//!
+//! Individuals become `Person` nodes and families `Family` nodes, each keyed by its
+//! xref. Partners are linked to their family with `SPOUSE_OF` relationships carrying the
+//! marriage & divorce of the family, and children with `CHILD_OF` relationships carrying
+//! their pedigree. The nodes are created first, then each relationship is created by
+//! matching its nodes on their xref, so the statements can be run one at a time, ie.
+//! with `cypher-shell`.
+use std::fmt::Write;
+
+use crate::store::RecordStore;
+use crate::tree::GedcomData;
+use crate::types::{Event, EventType, Family, Gender, HasEvents, Individual, NameOrder, Pedigree};
+
+/// The statements creating the nodes & relationships of a tree, one per line, each
+/// ending with `;`. Individuals & families without an xref are left out.
+#[must_use]
+pub fn create_statements(data: &GedcomData) -> String {
+    let mut out = String::new();
+    for individual in &data.individuals {
+        if let Some(xref) = individual.xref.as_deref() {
+            let _ = writeln!(
+                out,
+                "CREATE (:Person {});",
+                properties(&person(xref, individual))
+            );
+        }
+    }
+    for family in &data.families {
+        if let Some(xref) = family.xref.as_deref() {
+            let mut props = vec![("xref", xref.to_string())];
+            props.extend(union_properties(family));
+            let _ = writeln!(out, "CREATE (:Family {});", properties(&props));
+        }
+    }
+    for family in &data.families {
+        if let Some(xref) = family.xref.as_deref() {
+            relationships(data, xref, family, &mut out);
+        }
+    }
+    out
+}
+
+fn person(xref: &str, individual: &Individual) -> Vec<(&'static str, String)> {
+    let mut props = vec![("xref", xref.to_string())];
+    if let Some(name) = individual.display_name(NameOrder::Western) {
+        props.push(("name", name));
+    }
+    let sex = match individual.sex {
+        Gender::Male => Some("M"),
+        Gender::Female => Some("F"),
+        Gender::Nonbinary => Some("X"),
+        Gender::Unknown => None,
+    };
+    if let Some(sex) = sex {
+        props.push(("sex", sex.to_string()));
+    }
+    props.extend(event_properties(
+        individual,
+        &EventType::Birth,
+        ["birth_date", "birth_place"],
+    ));
+    props.extend(event_properties(
+        individual,
+        &EventType::Death,
+        ["death_date", "death_place"],
+    ));
+    props
+}
+
+/// The marriage & divorce of a family, on its node & on the relationships of its partners
+fn union_properties(family: &Family) -> Vec<(&'static str, String)> {
+    let mut props = event_properties(
+        family,
+        &EventType::Marriage,
+        ["marriage_date", "marriage_place"],
+    );
+    props.extend(event_properties(
+        family,
+        &EventType::Divorce,
+        ["divorce_date", "divorce_place"],
+    ));
+    props
+}
+
+fn relationships(data: &GedcomData, xref: &str, family: &Family, out: &mut String) {
+    let spouse_props = union_properties(family);
+    for (partner, tag) in family.parents() {
+        let mut props = vec![("role", tag.tag().to_string())];
+        props.extend(spouse_props.iter().cloned());
+        relationship(out, partner, "SPOUSE_OF", xref, &props);
+    }
+    for child in &family.children {
+        let pedigree = data.individual(child).and_then(|individual| {
+            individual
+                .families
+                .iter()
+                .find(|link| link.xref == xref)
+                .and_then(|link| link.pedigree.as_ref())
+                .map(pedigree_name)
+        });
+        let props: Vec<_> = pedigree
+            .map(|pedigree| ("pedigree", pedigree.to_string()))
+            .into_iter()
+            .collect();
+        relationship(out, child, "CHILD_OF", xref, &props);
+    }
+}
+
+fn relationship(
+    out: &mut String,
+    person: &str,
+    kind: &str,
+    family: &str,
+    props: &[(&str, String)],
+) {
+    let props = if props.is_empty() {
+        String::new()
+    } else {
+        format!(" {}", properties(props))
+    };
+    let _ = writeln!(
+        out,
+        "MATCH (p:Person {{xref: {}}}), (f:Family {{xref: {}}}) CREATE (p)-[:{kind}{props}]->(f);",
+        string(person),
+        string(family),
+    );
+}
+
+/// The date & place of the first event of a type, under the keys `[date, place]`
+fn event_properties<T: HasEvents>(
+    record: &T,
+    event_type: &EventType,
+    [date_key, place_key]: [&'static str; 2],
+) -> Vec<(&'static str, String)> {
+    let events = record.events();
+    let Some(Event { date, place, .. }) = events.iter().find(|event| &event.event == event_type)
+    else {
+        return Vec::new();
+    };
+    let mut props = Vec::new();
+    if let Some(date) = date {
+        props.push((date_key, date.clone()));
+    }
+    if let Some(place) = place {
+        props.push((place_key, place.value.clone()));
+    }
+    props
+}
+
+fn pedigree_name(pedigree: &Pedigree) -> &'static str {
+    match pedigree {
+        Pedigree::Adopted => "adopted",
+        Pedigree::Birth => "birth",
+        Pedigree::Foster => "foster",
+        Pedigree::Sealing => "sealing",
+    }
+}
+
+/// A map of properties, ie. `{xref: '@I1@', name: 'John Doe'}`
+fn properties(props: &[(&str, String)]) -> String {
+    let props: Vec<String> = props
+        .iter()
+        .map(|(key, value)| format!("{key}: {}", string(value)))
+        .collect();
+    format!("{{{}}}", props.join(", "))
+}
+
+/// A Cypher string literal
+fn string(value: &str) -> String {
+    let mut literal = String::with_capacity(value.len() + 2);
+    literal.push('\'');
+    for c in value.chars() {
+        match c {
+            '\\' => literal.push_str("\\\\"),
+            '\'' => literal.push_str("\\'"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            c => literal.push(c),
+        }
+    }
+    literal.push('\'');
+    literal
+}
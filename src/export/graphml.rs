@@ -0,0 +1,122 @@
+//! Renders a tree as a `GraphML` document, for network analysis tools like Gephi & yEd.
+//!
+//! Individuals become nodes keyed by their xref, with their `name`, `surname`, `sex` &
+//! `birth_year` as attributes. Families aren't nodes: each parent is linked to each of
+//! their children by a directed `parent` edge, and partners to each other by a `spouse`
+//! edge, both carrying the xref of the family.
+use std::fmt::Write;
+
+use crate::tree::GedcomData;
+use crate::types::{Event, EventType, Gender, Individual, NameOrder};
+
+/// The attributes of the nodes & edges: id, element, name & type
+const KEYS: [(&str, &str, &str, &str); 6] = [
+    ("d0", "node", "name", "string"),
+    ("d1", "node", "surname", "string"),
+    ("d2", "node", "sex", "string"),
+    ("d3", "node", "birth_year", "int"),
+    ("d4", "edge", "relation", "string"),
+    ("d5", "edge", "family", "string"),
+];
+
+/// The `GraphML` document of a tree. Individuals without an xref, and the edges to them,
+/// are left out.
+#[must_use]
+pub fn graphml(data: &GedcomData) -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+    for (id, element, name, kind) in KEYS {
+        let _ = writeln!(
+            out,
+            "  <key id=\"{id}\" for=\"{element}\" attr.name=\"{name}\" attr.type=\"{kind}\"/>"
+        );
+    }
+    out.push_str("  <graph id=\"G\" edgedefault=\"directed\">\n");
+
+    let mut xrefs = Vec::new();
+    for individual in &data.individuals {
+        if let Some(xref) = individual.xref.as_deref() {
+            xrefs.push(xref);
+            node(&mut out, xref, individual);
+        }
+    }
+    let mut edges = 0;
+    let mut edge = |out: &mut String, source: &str, target: &str, relation: &str, family| {
+        if !xrefs.contains(&source) || !xrefs.contains(&target) {
+            return;
+        }
+        let _ = write!(
+            out,
+            "    <edge id=\"e{edges}\" source=\"{}\" target=\"{}\">\
+             <data key=\"d4\">{relation}</data>",
+            escape(source),
+            escape(target)
+        );
+        if let Some(family) = family {
+            let _ = write!(out, "<data key=\"d5\">{}</data>", escape(family));
+        }
+        out.push_str("</edge>\n");
+        edges += 1;
+    };
+    for family in &data.families {
+        let xref = family.xref.as_deref();
+        let parents = family.parents();
+        if let [(first, _), (second, _)] = parents.as_slice() {
+            edge(&mut out, first, second, "spouse", xref);
+        }
+        for (parent, _) in &parents {
+            for child in &family.children {
+                edge(&mut out, parent, child, "parent", xref);
+            }
+        }
+    }
+    out.push_str("  </graph>\n</graphml>\n");
+    out
+}
+
+fn node(out: &mut String, xref: &str, individual: &Individual) {
+    let _ = write!(out, "    <node id=\"{}\">", escape(xref));
+    let mut data = |key: &str, value: &str| {
+        let _ = write!(out, "<data key=\"{key}\">{}</data>", escape(value));
+    };
+    if let Some(name) = individual.display_name(NameOrder::Western) {
+        data("d0", &name);
+    }
+    if let Some(surname) = individual.primary_name().and_then(|name| name.pieces().1) {
+        data("d1", &surname);
+    }
+    let sex = match individual.sex {
+        Gender::Male => Some("M"),
+        Gender::Female => Some("F"),
+        Gender::Nonbinary => Some("X"),
+        Gender::Unknown => None,
+    };
+    if let Some(sex) = sex {
+        data("d2", sex);
+    }
+    let birth_year = individual
+        .events
+        .iter()
+        .filter(|event| event.event == EventType::Birth)
+        .find_map(|event| Event::date_value(event)?.year());
+    if let Some(year) = birth_year {
+        data("d3", &year.to_string());
+    }
+    out.push_str("</node>\n");
+}
+
+/// Escapes the characters of a value that XML reserves
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
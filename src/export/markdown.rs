@@ -0,0 +1,197 @@
+//! Renders records as Markdown, for pasting into wikis & static-site genealogy blogs.
+//!
+//! The facts of a record are listed in a table, with their citations as footnotes.
+//! Dates are shown in `DateStyle::Abbreviated`, ie. `abt 1832` or `1830–1835`.
+use std::fmt::Write;
+
+use crate::store::RecordStore;
+use crate::types::{
+    DateStyle, Event, EventType, Family, FamilyLinkType, Gender, HasEvents, Individual, NameOrder,
+    SourceCitation,
+};
+
+/// Renders an individual: their name as heading, their sex, a table of their facts and
+/// the families they belong to.
+#[must_use]
+pub fn individual<S: RecordStore>(store: &S, individual: &Individual) -> String {
+    let mut out = format!("# {}\n", name_of(individual));
+    let sex = match individual.sex {
+        Gender::Male => Some("Male"),
+        Gender::Female => Some("Female"),
+        Gender::Nonbinary => Some("Nonbinary"),
+        Gender::Unknown => None,
+    };
+    if let Some(sex) = sex {
+        let _ = write!(out, "\n**Sex:** {sex}\n");
+    }
+
+    let mut footnotes = Footnotes::default();
+    facts_table(store, &individual.events(), &mut footnotes, &mut out);
+
+    let xref = individual.xref.as_deref().unwrap_or_default();
+    let mut families = Vec::new();
+    for link in &individual.families {
+        let Some(family) = store.family(&link.xref) else {
+            continue;
+        };
+        let line = match link.link_type {
+            FamilyLinkType::Spouse => match family.partner_of(xref) {
+                Some(partner) => format!("Spouse of {}", individual_name(store, partner)),
+                None => "Spouse".to_string(),
+            },
+            FamilyLinkType::Child => {
+                let parents: Vec<String> = family
+                    .parents()
+                    .iter()
+                    .map(|(parent, _)| individual_name(store, parent))
+                    .collect();
+                format!("Child of {}", parents.join(" & "))
+            }
+        };
+        families.push(format!("- {} ({})", line, link.xref));
+    }
+    if !families.is_empty() {
+        out.push_str("\n## Families\n\n");
+        out.push_str(&families.join("\n"));
+        out.push('\n');
+    }
+
+    footnotes.write(&mut out);
+    out
+}
+
+/// Renders a family: its partners as heading, a table of its facts and its children.
+#[must_use]
+pub fn family<S: RecordStore>(store: &S, family: &Family) -> String {
+    let partners: Vec<String> = family
+        .parents()
+        .iter()
+        .map(|(parent, _)| individual_name(store, parent))
+        .collect();
+    let title = if partners.is_empty() {
+        family.xref.clone().unwrap_or_else(|| "Unknown".to_string())
+    } else {
+        partners.join(" & ")
+    };
+    let mut out = format!("# Family of {title}\n");
+
+    let mut footnotes = Footnotes::default();
+    facts_table(store, &family.events(), &mut footnotes, &mut out);
+
+    if !family.children.is_empty() {
+        out.push_str("\n## Children\n\n");
+        for child in &family.children {
+            let _ = writeln!(out, "- {} ({})", individual_name(store, child), child);
+        }
+    }
+
+    footnotes.write(&mut out);
+    out
+}
+
+/// Citations collected while rendering, written as footnotes at the end of the record
+#[derive(Default)]
+struct Footnotes {
+    notes: Vec<String>,
+}
+
+impl Footnotes {
+    /// The footnote reference of a citation, reusing the footnote of an identical one
+    fn reference<S: RecordStore>(&mut self, store: &S, citation: &SourceCitation) -> String {
+        let title = store
+            .source(&citation.xref)
+            .and_then(|source| source.title.clone().or_else(|| source.abbreviation.clone()))
+            .unwrap_or_else(|| citation.xref.clone());
+        let note = match &citation.page {
+            Some(page) => format!("{title}, {page}"),
+            None => title,
+        };
+        let index = if let Some(index) = self.notes.iter().position(|existing| *existing == note) {
+            index
+        } else {
+            self.notes.push(note);
+            self.notes.len() - 1
+        };
+        format!("[^{}]", index + 1)
+    }
+
+    fn write(&self, out: &mut String) {
+        if self.notes.is_empty() {
+            return;
+        }
+        out.push('\n');
+        for (i, note) in self.notes.iter().enumerate() {
+            let _ = writeln!(out, "[^{}]: {}", i + 1, escape(note));
+        }
+    }
+}
+
+fn facts_table<S: RecordStore>(
+    store: &S,
+    events: &[Event],
+    footnotes: &mut Footnotes,
+    out: &mut String,
+) {
+    if events.is_empty() {
+        return;
+    }
+    out.push_str("\n| Fact | Date | Place |\n| --- | --- | --- |\n");
+    for event in events {
+        let references: String = event
+            .citations
+            .iter()
+            .map(|citation| footnotes.reference(store, citation))
+            .collect();
+        let _ = writeln!(
+            out,
+            "| {}{} | {} | {} |",
+            fact_name(&event.event),
+            references,
+            escape(
+                &event
+                    .date_value()
+                    .map(|date| date.format(DateStyle::Abbreviated))
+                    .unwrap_or_default()
+            ),
+            escape(event.place_name().unwrap_or_default()),
+        );
+    }
+}
+
+fn fact_name(event_type: &EventType) -> String {
+    match event_type {
+        EventType::SourceData(events) => escape(events),
+        EventType::Other => "Event".to_string(),
+        event_type => event_type.to_string(),
+    }
+}
+
+fn name_of(individual: &Individual) -> String {
+    individual
+        .display_name(NameOrder::Western)
+        .filter(|name| !name.is_empty())
+        .or_else(|| individual.xref.clone())
+        .map_or_else(|| "Unknown".to_string(), |name| escape(&name))
+}
+
+fn individual_name<S: RecordStore>(store: &S, xref: &str) -> String {
+    store
+        .individual(xref)
+        .map_or_else(|| xref.to_string(), |individual| name_of(&individual))
+}
+
+/// Escapes the characters that would break a table cell or start Markdown formatting
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '|' | '*' | '_' | '[' | ']' | '\\' | '`' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push(' '),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,10 @@
+//! Renders records in formats meant for other tools than genealogy programs
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod cypher;
+pub mod graphml;
+pub mod markdown;
+#[cfg(feature = "json")]
+mod tree_json;
+#[cfg(feature = "json")]
+pub use tree_json::{tree_json, EdgeKind, TreeEdge, TreeGraph, TreeNode};
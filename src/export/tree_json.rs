@@ -0,0 +1,125 @@
+//! A compact JSON graph of a tree, in the shape JavaScript tree viewers expect.
+//!
+//! Rather than the full serde output of `GedcomData`, the tree is written as an array of
+//! people and an array of the parent & spouse relations between them. Ids are the xrefs
+//! of the individuals without their `@`, so they stay stable across exports of the same
+//! file and can be used as keys by the viewer.
+use serde::Serialize;
+
+use crate::tree::GedcomData;
+use crate::types::{EventType, Family, Gender, HasEvents, Individual, NameOrder};
+
+/// A person of the graph
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct TreeNode {
+    /// The xref of the individual, without `@`
+    pub id: String,
+    /// The name, given names first
+    pub name: Option<String>,
+    /// `M`, `F`, `X` or `U`
+    pub sex: &'static str,
+    /// Year of birth
+    pub birth: Option<i32>,
+    /// Year of death
+    pub death: Option<i32>,
+}
+
+/// How the people of a `TreeEdge` relate
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EdgeKind {
+    /// `from` is a parent of `to`
+    Parent,
+    /// `from` & `to` are the partners of a family
+    Spouse,
+}
+
+/// A relation between two people of the graph
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct TreeEdge {
+    /// Id of the parent, or of the first partner
+    pub from: String,
+    /// Id of the child, or of the second partner
+    pub to: String,
+    /// How the people relate
+    pub kind: EdgeKind,
+    /// The xref of the family the relation comes from, without `@`
+    pub family: String,
+}
+
+/// The people of a tree and their relations
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct TreeGraph {
+    /// The individuals, in the order of the file
+    pub nodes: Vec<TreeNode>,
+    /// The relations, by family in the order of the file
+    pub edges: Vec<TreeEdge>,
+}
+
+impl TreeGraph {
+    /// Builds the graph of a tree. Individuals & families without an xref are left out.
+    #[must_use]
+    pub fn of(data: &GedcomData) -> TreeGraph {
+        let nodes = data.individuals.iter().filter_map(node).collect();
+        let edges = data.families.iter().flat_map(edges).collect();
+        TreeGraph { nodes, edges }
+    }
+}
+
+/// The graph of a tree as JSON, `{"nodes": [...], "edges": [...]}`
+///
+/// # Panics
+///
+/// Never panics in practice, the graph always serializes.
+#[must_use]
+pub fn tree_json(data: &GedcomData) -> String {
+    serde_json::to_string(&TreeGraph::of(data)).expect("the tree graph serializes")
+}
+
+fn node(individual: &Individual) -> Option<TreeNode> {
+    let year = |event_type: EventType| {
+        let events = individual.events();
+        let event = events.iter().find(|event| event.event == event_type)?;
+        Some(event.date_value()?.date()?.year)
+    };
+    Some(TreeNode {
+        id: id(individual.xref.as_deref()?),
+        name: individual.display_name(NameOrder::Western),
+        sex: match individual.sex {
+            Gender::Male => "M",
+            Gender::Female => "F",
+            Gender::Nonbinary => "X",
+            Gender::Unknown => "U",
+        },
+        birth: year(EventType::Birth),
+        death: year(EventType::Death),
+    })
+}
+
+fn edges(family: &Family) -> Vec<TreeEdge> {
+    let Some(family_id) = family.xref.as_deref().map(id) else {
+        return Vec::new();
+    };
+    let edge = |from: &str, to: &str, kind| TreeEdge {
+        from: id(from),
+        to: id(to),
+        kind,
+        family: family_id.clone(),
+    };
+
+    let parents: Vec<&str> = family.parents().into_iter().map(|(xref, _)| xref).collect();
+    let mut edges = Vec::new();
+    if let [first, second] = parents.as_slice() {
+        edges.push(edge(first, second, EdgeKind::Spouse));
+    }
+    for parent in &parents {
+        for child in &family.children {
+            edges.push(edge(parent, child, EdgeKind::Parent));
+        }
+    }
+    edges
+}
+
+fn id(xref: &str) -> String {
+    xref.trim_matches('@').to_string()
+}
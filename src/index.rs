@@ -0,0 +1,421 @@
+//! Lookup tables over a tree, built in a single pass over its records, see
+//! `GedcomIndex::build`, and a full-text search of its notes, titles & source texts, see
+//! `GedcomIndex::search`.
+//!
+//! With the `rayon` feature, the records are indexed in parallel: each thread indexes a
+//! share of them, then the partial indexes are merged in the order of the records.
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::tree::GedcomData;
+use crate::types::{Event, Family, Individual, Note, NoteRecord, Source, SourceCitation};
+
+/// Xrefs of records by xref, names, places & cited sources, to look records up without
+/// scanning the tree. Names are matched ignoring case, places as written.
+#[derive(Clone, Debug, Default)]
+pub struct GedcomIndex<'a> {
+    tables: Tables<'a>,
+    stats: IndexStats,
+}
+
+/// Statistics of the building of a `GedcomIndex`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct IndexStats {
+    /// Number of individuals, families & sources indexed
+    pub records: usize,
+    /// Time spent building the index
+    pub elapsed: Duration,
+}
+
+/// The tables of the index, or the share of them built by a thread
+#[derive(Clone, Debug, Default)]
+struct Tables<'a> {
+    individuals: HashMap<&'a str, &'a Individual>,
+    families: HashMap<&'a str, &'a Family>,
+    sources: HashMap<&'a str, &'a Source>,
+    surnames: HashMap<String, Vec<&'a str>>,
+    given_names: HashMap<String, Vec<&'a str>>,
+    places: HashMap<&'a str, Vec<&'a str>>,
+    citations: HashMap<&'a str, Vec<&'a str>>,
+    texts: Vec<IndexedText<'a>>,
+}
+
+/// A text of a record, as assembled from its `CONT` & `CONC` lines
+#[derive(Clone, Debug)]
+struct IndexedText<'a> {
+    record: &'a str,
+    field: TextField,
+    text: Cow<'a, str>,
+    /// The text folded for matching, see `fold`
+    folded: String,
+}
+
+/// The field of a record a text was found in, see `GedcomIndex::search`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextField {
+    /// A note of the record, `NOTE`, or the text of a note record
+    Note,
+    /// A note of an event of the record, ie. `BIRT.NOTE`
+    EventNote,
+    /// An excerpt of a source cited by the record, `SOUR.DATA.TEXT`
+    CitationText,
+    /// A note of a source citation of the record, `SOUR.NOTE`
+    CitationNote,
+    /// Title of a source, `TITL`
+    Title,
+    /// Short title of a source, `ABBR`
+    Abbreviation,
+    /// Text of a source, `TEXT`
+    SourceText,
+}
+
+/// A text matching the query of `GedcomIndex::search`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TextMatch<'i> {
+    /// Xref of the record holding the text
+    pub record: &'i str,
+    /// Field of the record holding the text
+    pub field: TextField,
+    /// The whole text, with its `CONT` lines as line breaks
+    pub text: &'i str,
+}
+
+impl<'a> GedcomIndex<'a> {
+    /// Indexes the individuals, families & sources of the tree. Records without an xref
+    /// are left out.
+    #[must_use]
+    pub fn build(data: &'a GedcomData) -> GedcomIndex<'a> {
+        let start = Instant::now();
+        let tables = Tables::build(data);
+        let records = tables.individuals.len() + tables.families.len() + tables.sources.len();
+        GedcomIndex {
+            tables,
+            stats: IndexStats {
+                records,
+                elapsed: start.elapsed(),
+            },
+        }
+    }
+
+    /// Statistics of the building of the index
+    #[must_use]
+    pub fn stats(&self) -> IndexStats {
+        self.stats
+    }
+
+    /// The individual with the xref
+    #[must_use]
+    pub fn individual(&self, xref: &str) -> Option<&'a Individual> {
+        self.tables.individuals.get(xref).copied()
+    }
+
+    /// The family with the xref
+    #[must_use]
+    pub fn family(&self, xref: &str) -> Option<&'a Family> {
+        self.tables.families.get(xref).copied()
+    }
+
+    /// The source with the xref
+    #[must_use]
+    pub fn source(&self, xref: &str) -> Option<&'a Source> {
+        self.tables.sources.get(xref).copied()
+    }
+
+    /// Xrefs of the individuals with a name having the surname, in the order of the tree
+    #[must_use]
+    pub fn with_surname(&self, surname: &str) -> &[&'a str] {
+        lookup(&self.tables.surnames, &surname.to_lowercase())
+    }
+
+    /// Xrefs of the individuals with a name having the given name, ie. `Mary` for
+    /// `Anna Mary /Doe/`
+    #[must_use]
+    pub fn with_given_name(&self, given_name: &str) -> &[&'a str] {
+        lookup(&self.tables.given_names, &given_name.to_lowercase())
+    }
+
+    /// Xrefs of the individuals & families with an event at the place
+    #[must_use]
+    pub fn at_place(&self, place: &str) -> &[&'a str] {
+        lookup(&self.tables.places, place)
+    }
+
+    /// Xrefs of the individuals & families citing the source, the reverse of their
+    /// citations
+    #[must_use]
+    pub fn citing(&self, source: &str) -> &[&'a str] {
+        lookup(&self.tables.citations, source)
+    }
+
+    /// The notes, source titles & source texts containing the query, in the order of the
+    /// tree. Case is ignored, and any run of whitespace matches any other, so that a
+    /// query matches across the `CONC` & `CONT` lines a text was split over.
+    #[must_use]
+    pub fn search(&self, query: &str) -> Vec<TextMatch<'_>> {
+        let query = fold(query);
+        if query.is_empty() {
+            return Vec::new();
+        }
+        self.tables
+            .texts
+            .iter()
+            .filter(|text| text.folded.contains(&query))
+            .map(|text| TextMatch {
+                record: text.record,
+                field: text.field,
+                text: &text.text,
+            })
+            .collect()
+    }
+}
+
+/// Lowercases the text & turns its runs of whitespace into single spaces
+fn fold(text: &str) -> String {
+    text.split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn lookup<'t, 'a, K, Q>(table: &'t HashMap<K, Vec<&'a str>>, key: &Q) -> &'t [&'a str]
+where
+    K: std::borrow::Borrow<Q> + std::hash::Hash + Eq,
+    Q: std::hash::Hash + Eq + ?Sized,
+{
+    table.get(key).map_or(&[], Vec::as_slice)
+}
+
+impl<'a> Tables<'a> {
+    #[cfg(not(feature = "rayon"))]
+    fn build(data: &'a GedcomData) -> Tables<'a> {
+        let mut tables = Tables::default();
+        data.individuals
+            .iter()
+            .for_each(|individual| tables.add_individual(individual));
+        data.families
+            .iter()
+            .for_each(|family| tables.add_family(family));
+        data.sources
+            .iter()
+            .for_each(|source| tables.add_source(source));
+        data.notes
+            .iter()
+            .for_each(|note| tables.add_note_record(note));
+        tables
+    }
+
+    #[cfg(feature = "rayon")]
+    fn build(data: &'a GedcomData) -> Tables<'a> {
+        fn index<'a, T: Sync>(records: &'a [T], add: fn(&mut Tables<'a>, &'a T)) -> Tables<'a> {
+            records
+                .par_iter()
+                .fold(Tables::default, |mut tables, record| {
+                    add(&mut tables, record);
+                    tables
+                })
+                .reduce(Tables::default, Tables::merge)
+        }
+        let ((individuals, families), (sources, notes)) = rayon::join(
+            || {
+                rayon::join(
+                    || index(&data.individuals, Tables::add_individual),
+                    || index(&data.families, Tables::add_family),
+                )
+            },
+            || {
+                rayon::join(
+                    || index(&data.sources, Tables::add_source),
+                    || index(&data.notes, Tables::add_note_record),
+                )
+            },
+        );
+        individuals.merge(families).merge(sources).merge(notes)
+    }
+
+    /// Appends the tables built from the records following those of `self`
+    #[cfg(feature = "rayon")]
+    fn merge(mut self, other: Tables<'a>) -> Tables<'a> {
+        fn append<K: Eq + std::hash::Hash, V>(
+            table: &mut HashMap<K, Vec<V>>,
+            other: HashMap<K, Vec<V>>,
+        ) {
+            for (key, values) in other {
+                table.entry(key).or_default().extend(values);
+            }
+        }
+        // the first record of an xref wins, like in a sequential pass
+        for (xref, individual) in other.individuals {
+            self.individuals.entry(xref).or_insert(individual);
+        }
+        for (xref, family) in other.families {
+            self.families.entry(xref).or_insert(family);
+        }
+        for (xref, source) in other.sources {
+            self.sources.entry(xref).or_insert(source);
+        }
+        append(&mut self.surnames, other.surnames);
+        append(&mut self.given_names, other.given_names);
+        append(&mut self.places, other.places);
+        append(&mut self.citations, other.citations);
+        self.texts.extend(other.texts);
+        self
+    }
+
+    fn add_individual(&mut self, individual: &'a Individual) {
+        let Some(xref) = individual.xref.as_deref() else {
+            return;
+        };
+        self.individuals.entry(xref).or_insert(individual);
+        let mut surnames = Vec::new();
+        let mut given_names = Vec::new();
+        for name in &individual.names {
+            let (given, surname, _) = name.pieces();
+            let mut name_surnames = name.surnames();
+            if name_surnames.is_empty() {
+                name_surnames.extend(surname);
+            }
+            let mut name_given_names = name.given_names();
+            if name_given_names.is_empty() {
+                name_given_names.extend(given);
+            }
+            surnames.extend(name_surnames.iter().map(|surname| surname.to_lowercase()));
+            // each of the given names, ie. `Mary` of `Anna Mary`
+            given_names.extend(
+                name_given_names
+                    .iter()
+                    .flat_map(|given| given.split_whitespace())
+                    .map(str::to_lowercase),
+            );
+        }
+        add_keys(&mut self.surnames, surnames, xref);
+        add_keys(&mut self.given_names, given_names, xref);
+        self.add_notes(xref, TextField::Note, &individual.notes);
+        self.add_events(xref, &individual.events);
+
+        let mut citations: Vec<&SourceCitation> = Vec::new();
+        for link in &individual.families {
+            self.add_notes(xref, TextField::Note, &link.notes);
+            citations.extend(&link.citations);
+        }
+        for association in &individual.associations {
+            self.add_notes(xref, TextField::Note, &association.notes);
+            citations.extend(&association.citations);
+        }
+        for ordinance in &individual.lds_ordinances {
+            self.add_notes(xref, TextField::Note, &ordinance.notes);
+            citations.extend(&ordinance.citations);
+        }
+        self.add_citations(xref, citations);
+    }
+
+    fn add_family(&mut self, family: &'a Family) {
+        let Some(xref) = family.xref.as_deref() else {
+            return;
+        };
+        self.families.entry(xref).or_insert(family);
+        self.add_notes(xref, TextField::Note, &family.notes);
+        self.add_events(xref, &family.events);
+        for ordinance in &family.lds_ordinances {
+            self.add_notes(xref, TextField::Note, &ordinance.notes);
+        }
+        let citations = family
+            .lds_ordinances
+            .iter()
+            .flat_map(|ordinance| &ordinance.citations)
+            .collect();
+        self.add_citations(xref, citations);
+    }
+
+    fn add_source(&mut self, source: &'a Source) {
+        let Some(xref) = source.xref.as_deref() else {
+            return;
+        };
+        self.sources.entry(xref).or_insert(source);
+        for (field, text) in [
+            (TextField::Title, &source.title),
+            (TextField::Abbreviation, &source.abbreviation),
+            (TextField::SourceText, &source.text),
+        ] {
+            if let Some(text) = text {
+                self.add_text(xref, field, Cow::Borrowed(text));
+            }
+        }
+        self.add_notes(xref, TextField::Note, &source.data.notes);
+        self.add_notes(xref, TextField::Note, &source.notes);
+    }
+
+    fn add_note_record(&mut self, note: &'a NoteRecord) {
+        if let Some(xref) = note.xref.as_deref() {
+            self.add_text(xref, TextField::Note, note.text.text());
+        }
+    }
+
+    /// Indexes the places & citations of the events of a record
+    fn add_events(&mut self, xref: &'a str, events: &'a [Event]) {
+        let places = events
+            .iter()
+            .filter_map(|event| event.place_name().map(str::trim))
+            .filter(|place| !place.is_empty());
+        add_keys(&mut self.places, places, xref);
+        let mut citations = Vec::new();
+        for event in events {
+            self.add_notes(xref, TextField::EventNote, &event.notes);
+            citations.extend(&event.citations);
+            for association in &event.associations {
+                citations.extend(&association.citations);
+            }
+        }
+        self.add_citations(xref, citations);
+    }
+
+    /// Indexes the sources cited, and the texts & notes of the citations
+    fn add_citations(&mut self, xref: &'a str, citations: Vec<&'a SourceCitation>) {
+        for citation in &citations {
+            for text in &citation.texts {
+                self.add_text(xref, TextField::CitationText, Cow::Borrowed(text));
+            }
+            self.add_notes(xref, TextField::CitationNote, &citation.notes);
+        }
+        let sources = citations
+            .into_iter()
+            .map(|citation| citation.xref.as_str())
+            .filter(|source| source.starts_with('@'));
+        add_keys(&mut self.citations, sources, xref);
+    }
+
+    fn add_notes(&mut self, xref: &'a str, field: TextField, notes: &'a [Note]) {
+        for note in notes {
+            self.add_text(xref, field, note.text());
+        }
+    }
+
+    fn add_text(&mut self, record: &'a str, field: TextField, text: Cow<'a, str>) {
+        let folded = fold(&text);
+        if !folded.is_empty() {
+            self.texts.push(IndexedText {
+                record,
+                field,
+                text,
+                folded,
+            });
+        }
+    }
+}
+
+/// Adds the record to the entries of the keys, once per key
+fn add_keys<'a, K: Eq + std::hash::Hash>(
+    table: &mut HashMap<K, Vec<&'a str>>,
+    keys: impl IntoIterator<Item = K>,
+    xref: &'a str,
+) {
+    for key in keys {
+        let entry = table.entry(key).or_default();
+        if entry.last() != Some(&xref) {
+            entry.push(xref);
+        }
+    }
+}
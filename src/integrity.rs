@@ -0,0 +1,381 @@
+//! Edits a tree without leaving pointers to missing records behind, see
+//! `GedcomData::guarded`.
+//!
+//! The guard covers the links between individuals & families: the `FAMC` & `FAMS` of
+//! individuals, along with the `FAMC` of their events & ordinances, the `HUSB`, `WIFE`
+//! & `CHIL` of families, and the `ASSO` between individuals.
+use std::collections::HashSet;
+use std::error;
+use std::fmt;
+
+use crate::tree::{stamp, GedcomData};
+use crate::types::{Family, Individual};
+
+/// What a guarded edit does when it would leave a pointer to a missing record
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IntegrityMode {
+    /// Refuses the edit, leaving the tree as it was
+    #[default]
+    Strict,
+    /// Removes the pointers to missing records, ie. the `HUSB` of a removed individual
+    Fix,
+    /// Makes the edit anyway, as the unguarded methods of `GedcomData` do
+    Ignore,
+}
+
+/// Why a guarded edit was refused
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IntegrityError {
+    /// The record to remove is still pointed to by other records
+    Referenced {
+        /// Reference to the record to remove
+        xref: String,
+        /// References to the records pointing to it, in the order of the tree
+        by: Vec<String>,
+    },
+    /// The record added or edited points to records missing from the tree
+    Dangling {
+        /// Reference to the record added or edited
+        record: Option<String>,
+        /// References to the missing records
+        missing: Vec<String>,
+    },
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityError::Referenced { xref, by } => {
+                write!(f, "{xref} is still linked from {}", by.join(", "))
+            }
+            IntegrityError::Dangling { record, missing } => write!(
+                f,
+                "{} links to missing records {}",
+                record.as_deref().unwrap_or("The record"),
+                missing.join(", ")
+            ),
+        }
+    }
+}
+
+impl error::Error for IntegrityError {}
+
+/// Edits of a tree enforcing its referential integrity, see `GedcomData::guarded`
+#[derive(Debug)]
+pub struct GuardedTree<'a> {
+    data: &'a mut GedcomData,
+    mode: IntegrityMode,
+}
+
+impl GedcomData {
+    /// Edits the tree through a guard checking that no edit leaves a pointer to a
+    /// missing record, ie. removing an individual still listed as the `HUSB` of a
+    /// family. `mode` decides whether such edits are refused, fixed or let through.
+    pub fn guarded(&mut self, mode: IntegrityMode) -> GuardedTree<'_> {
+        GuardedTree { data: self, mode }
+    }
+}
+
+impl GuardedTree<'_> {
+    /// Adds an `Individual` whose links point to records of the tree, see
+    /// `GedcomData::add_individual`
+    ///
+    /// # Errors
+    ///
+    /// In `IntegrityMode::Strict`, returns `IntegrityError::Dangling` without adding the
+    /// individual when it links to missing records.
+    pub fn add_individual(&mut self, mut individual: Individual) -> Result<(), IntegrityError> {
+        self.check_individual(&mut individual)?;
+        self.data.add_individual(individual);
+        Ok(())
+    }
+
+    /// Adds a `Family` whose members are individuals of the tree, see
+    /// `GedcomData::add_family`
+    ///
+    /// # Errors
+    ///
+    /// In `IntegrityMode::Strict`, returns `IntegrityError::Dangling` without adding the
+    /// family when it links to missing individuals.
+    pub fn add_family(&mut self, mut family: Family) -> Result<(), IntegrityError> {
+        self.check_family(&mut family)?;
+        self.data.add_family(family);
+        Ok(())
+    }
+
+    /// Edits the `Individual` with the given xref, see `GedcomData::edit_individual`.
+    /// Returns the result of `edit`, or `None` when there is no such individual.
+    ///
+    /// # Errors
+    ///
+    /// In `IntegrityMode::Strict`, returns `IntegrityError::Dangling` and restores the
+    /// individual when the edit links it to missing records.
+    pub fn edit_individual<R>(
+        &mut self,
+        xref: &str,
+        edit: impl FnOnce(&mut Individual) -> R,
+    ) -> Result<Option<R>, IntegrityError> {
+        let Some(index) = position(&self.data.individuals, |i| i.xref.as_deref(), xref) else {
+            return Ok(None);
+        };
+        let mut individual = self.data.individuals[index].clone();
+        let result = edit(&mut individual);
+        self.check_individual(&mut individual)?;
+        self.data.individuals[index] = individual;
+        stamp(
+            self.data.change_dates,
+            None,
+            &mut self.data.individuals[index].last_updated,
+        );
+        Ok(Some(result))
+    }
+
+    /// Edits the `Family` with the given xref, see `GedcomData::edit_family`. Returns
+    /// the result of `edit`, or `None` when there is no such family.
+    ///
+    /// # Errors
+    ///
+    /// In `IntegrityMode::Strict`, returns `IntegrityError::Dangling` and restores the
+    /// family when the edit links it to missing individuals.
+    pub fn edit_family<R>(
+        &mut self,
+        xref: &str,
+        edit: impl FnOnce(&mut Family) -> R,
+    ) -> Result<Option<R>, IntegrityError> {
+        let Some(index) = position(&self.data.families, |f| f.xref.as_deref(), xref) else {
+            return Ok(None);
+        };
+        let mut family = self.data.families[index].clone();
+        let result = edit(&mut family);
+        self.check_family(&mut family)?;
+        self.data.families[index] = family;
+        stamp(
+            self.data.change_dates,
+            None,
+            &mut self.data.families[index].last_updated,
+        );
+        Ok(Some(result))
+    }
+
+    /// Removes the `Individual` with the given xref from the tree. In
+    /// `IntegrityMode::Fix` the families & associations pointing to it are updated,
+    /// their change date stamped according to the policy of the tree. Returns the
+    /// individual removed, or `None` when there is no such individual.
+    ///
+    /// # Errors
+    ///
+    /// In `IntegrityMode::Strict`, returns `IntegrityError::Referenced` without removing
+    /// the individual when other records still point to it.
+    pub fn remove_individual(&mut self, xref: &str) -> Result<Option<Individual>, IntegrityError> {
+        let Some(index) = position(&self.data.individuals, |i| i.xref.as_deref(), xref) else {
+            return Ok(None);
+        };
+        let keep = |pointer: &str| pointer != xref;
+        let by: Vec<String> = self
+            .data
+            .families
+            .iter()
+            .filter(|family| family_pointers(family).any(|pointer| !keep(pointer)))
+            .filter_map(|family| family.xref.clone())
+            .chain(
+                self.data
+                    .individuals
+                    .iter()
+                    .filter(|i| i.xref.as_deref() != Some(xref))
+                    .filter(|i| association_pointers(i).any(|pointer| !keep(pointer)))
+                    .filter_map(|i| i.xref.clone()),
+            )
+            .collect();
+        match self.mode {
+            IntegrityMode::Strict if !by.is_empty() => {
+                return Err(IntegrityError::Referenced {
+                    xref: xref.to_string(),
+                    by,
+                });
+            }
+            IntegrityMode::Fix => {
+                let policy = self.data.change_dates;
+                for family in &mut self.data.families {
+                    if retain_family_pointers(family, keep) {
+                        stamp(policy, None, &mut family.last_updated);
+                    }
+                }
+                for individual in &mut self.data.individuals {
+                    if retain_associations(individual, keep) {
+                        stamp(policy, None, &mut individual.last_updated);
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(Some(self.data.individuals.remove(index)))
+    }
+
+    /// Removes the `Family` with the given xref from the tree. In `IntegrityMode::Fix`
+    /// the links of its members to it are removed, their change date stamped according
+    /// to the policy of the tree. Returns the family removed, or `None` when there is
+    /// no such family.
+    ///
+    /// # Errors
+    ///
+    /// In `IntegrityMode::Strict`, returns `IntegrityError::Referenced` without removing
+    /// the family when individuals still link to it.
+    pub fn remove_family(&mut self, xref: &str) -> Result<Option<Family>, IntegrityError> {
+        let Some(index) = position(&self.data.families, |f| f.xref.as_deref(), xref) else {
+            return Ok(None);
+        };
+        let keep = |pointer: &str| pointer != xref;
+        let by: Vec<String> = self
+            .data
+            .individuals
+            .iter()
+            .filter(|i| family_links(i).any(|pointer| !keep(pointer)))
+            .filter_map(|i| i.xref.clone())
+            .collect();
+        match self.mode {
+            IntegrityMode::Strict if !by.is_empty() => {
+                return Err(IntegrityError::Referenced {
+                    xref: xref.to_string(),
+                    by,
+                });
+            }
+            IntegrityMode::Fix => {
+                let policy = self.data.change_dates;
+                for individual in &mut self.data.individuals {
+                    if retain_family_links(individual, keep) {
+                        stamp(policy, None, &mut individual.last_updated);
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(Some(self.data.families.remove(index)))
+    }
+
+    /// Checks the links of an individual about to be added or replaced, removing those
+    /// to missing records in `IntegrityMode::Fix`
+    fn check_individual(&self, individual: &mut Individual) -> Result<(), IntegrityError> {
+        let families = xrefs(&self.data.families, |f| f.xref.as_deref());
+        let mut individuals = xrefs(&self.data.individuals, |i| i.xref.as_deref());
+        // an individual may be associated with themselves, ie. before being added
+        individuals.extend(individual.xref.clone());
+        let missing: Vec<String> = family_links(individual)
+            .filter(|pointer| !families.contains(*pointer))
+            .chain(association_pointers(individual).filter(|p| !individuals.contains(*p)))
+            .map(String::from)
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        match self.mode {
+            IntegrityMode::Strict => Err(IntegrityError::Dangling {
+                record: individual.xref.clone(),
+                missing,
+            }),
+            IntegrityMode::Fix => {
+                retain_family_links(individual, |pointer| families.contains(pointer));
+                retain_associations(individual, |pointer| individuals.contains(pointer));
+                Ok(())
+            }
+            IntegrityMode::Ignore => Ok(()),
+        }
+    }
+
+    /// Checks the members of a family about to be added or replaced, removing the
+    /// missing ones in `IntegrityMode::Fix`
+    fn check_family(&self, family: &mut Family) -> Result<(), IntegrityError> {
+        let individuals = xrefs(&self.data.individuals, |i| i.xref.as_deref());
+        let missing: Vec<String> = family_pointers(family)
+            .filter(|pointer| !individuals.contains(*pointer))
+            .map(String::from)
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        match self.mode {
+            IntegrityMode::Strict => Err(IntegrityError::Dangling {
+                record: family.xref.clone(),
+                missing,
+            }),
+            IntegrityMode::Fix => {
+                retain_family_pointers(family, |pointer| individuals.contains(pointer));
+                Ok(())
+            }
+            IntegrityMode::Ignore => Ok(()),
+        }
+    }
+}
+
+fn position<T>(records: &[T], xref_of: impl Fn(&T) -> Option<&str>, xref: &str) -> Option<usize> {
+    records
+        .iter()
+        .position(|record| xref_of(record) == Some(xref))
+}
+
+fn xrefs<T>(records: &[T], xref_of: impl Fn(&T) -> Option<&str>) -> HashSet<String> {
+    records
+        .iter()
+        .filter_map(|record| xref_of(record).map(String::from))
+        .collect()
+}
+
+/// The families an individual links to, from its `FAMC` & `FAMS` and those of its
+/// events & ordinances
+fn family_links(individual: &Individual) -> impl Iterator<Item = &str> {
+    let links = individual.families.iter().map(|link| link.xref.as_str());
+    let events = individual.events.iter().filter_map(|e| e.family.as_deref());
+    let ordinances = (individual.lds_ordinances.iter()).filter_map(|o| o.family.as_deref());
+    links.chain(events).chain(ordinances)
+}
+
+/// The individuals an individual is associated with, directly or in its events
+fn association_pointers(individual: &Individual) -> impl Iterator<Item = &str> {
+    let events = individual.events.iter().flat_map(|e| &e.associations);
+    (individual.associations.iter())
+        .chain(events)
+        .map(|association| association.xref.as_str())
+}
+
+/// The individuals of a family: its partners, children & the associations of its events
+fn family_pointers(family: &Family) -> impl Iterator<Item = &str> {
+    let events = family.events.iter().flat_map(|event| &event.associations);
+    (family.individual1.iter().chain(&family.individual2))
+        .chain(&family.children)
+        .map(String::as_str)
+        .chain(events.map(|association| association.xref.as_str()))
+}
+
+/// Removes the links to families not kept, returning whether any was removed
+fn retain_family_links(individual: &mut Individual, keep: impl Fn(&str) -> bool) -> bool {
+    let count = family_links(individual).count();
+    individual.families.retain(|link| keep(&link.xref));
+    for event in &mut individual.events {
+        event.family = event.family.take().filter(|family| keep(family));
+    }
+    for ordinance in &mut individual.lds_ordinances {
+        ordinance.family = ordinance.family.take().filter(|family| keep(family));
+    }
+    family_links(individual).count() != count
+}
+
+/// Removes the associations with individuals not kept, returning whether any was removed
+fn retain_associations(individual: &mut Individual, keep: impl Fn(&str) -> bool) -> bool {
+    let count = association_pointers(individual).count();
+    individual.associations.retain(|a| keep(&a.xref));
+    for event in &mut individual.events {
+        event.associations.retain(|a| keep(&a.xref));
+    }
+    association_pointers(individual).count() != count
+}
+
+/// Removes the members of a family not kept, returning whether any was removed
+fn retain_family_pointers(family: &mut Family, keep: impl Fn(&str) -> bool) -> bool {
+    let count = family_pointers(family).count();
+    family.individual1 = family.individual1.take().filter(|xref| keep(xref));
+    family.individual2 = family.individual2.take().filter(|xref| keep(xref));
+    family.children.retain(|child| keep(child));
+    for event in &mut family.events {
+        event.associations.retain(|a| keep(&a.xref));
+    }
+    family_pointers(family).count() != count
+}
@@ -1,19 +1,25 @@
 /*! A parser for GEDCOM files
 
 ```rust
-use gedcom::parser::Parser;
-
-// the parser takes the gedcom file contents as a chars iterator
 let gedcom_source = std::fs::read_to_string("./tests/fixtures/sample.ged").unwrap();
 
-let mut parser = Parser::new(gedcom_source.chars());
-let gedcom_data = parser.parse_record();
-
-// output some stats on the gedcom contents
-gedcom_data.stats();
+// malformed content gives a `GedcomError` with the line it was found on
+match gedcom::parse_document(&gedcom_source) {
+    // output some stats on the gedcom contents
+    Ok(gedcom_data) => gedcom_data.stats(),
+    Err(error) => eprintln!("{error}"),
+}
 ```
 
 This crate contains an optional `"json"` feature that implements serialization & deserialization to json with [`serde`](https://serde.rs).
+
+The optional `"dna"` feature adds the `dna` module, which reads DNA test data that genealogy programs store in custom tags.
+
+The optional `"compression"` feature allows long notes to be stored compressed in memory, see `parser::ParseOptions`.
+
+The optional `"chrono"` feature converts dates to [`chrono`](https://docs.rs/chrono) dates, see `types::DateValue::to_chrono`.
+
+The optional `"watch"` feature adds the `watch` module, which reloads a file when it changes on disk and reports the records that changed, along with a `diff` of its individuals & families.
 */
 
 #![deny(clippy::pedantic)]
@@ -22,16 +28,91 @@ This crate contains an optional `"json"` feature that implements serialization &
 #[macro_use]
 mod util;
 
+pub mod analysis;
+pub mod batch;
+pub mod diagnostics;
+pub mod diff;
+pub use diff::{diff, GedcomDiff};
+#[cfg(feature = "dna")]
+pub mod dna;
+pub mod encoding;
+pub mod entry;
+mod error;
+pub use error::GedcomError;
+pub mod export;
+pub mod index;
+pub mod integrity;
+pub mod media;
+pub mod merge;
 pub mod parser;
+pub mod pipeline;
+pub mod prelude;
+pub mod repair;
+pub mod reports;
+pub mod store;
+pub mod survey;
+pub use survey::survey;
+pub mod syntax;
 pub mod tokenizer;
 pub mod types;
+pub mod validate;
+#[cfg(feature = "watch")]
+pub mod watch;
+pub mod writer;
 
 mod tree;
-pub use tree::GedcomData;
+pub use tree::{ChangeDatePolicy, ChangedRecord, GedcomData, MultimediaPlacement, NoteOwner};
 
 #[must_use]
-/// Helper function for converting GEDCOM file content stream to parsed data.
+/// Helper function for converting GEDCOM file content stream to parsed data, like
+/// `parse_document` which returns an error instead of panicking.
+///
+/// # Panics
+///
+/// Panics when the GEDCOM content is malformed or contains unhandled tags.
 pub fn parse(content: std::str::Chars) -> GedcomData {
     let mut p = parser::Parser::new(content);
     p.parse_record()
 }
+
+/// Helper function for converting GEDCOM file content to parsed data, returning an error
+/// rather than panicking when the content can't be parsed.
+///
+/// # Errors
+///
+/// Returns an error when the GEDCOM content is malformed or contains unhandled tags.
+pub fn parse_document(content: &str) -> Result<GedcomData, GedcomError> {
+    parser::Parser::new(content.chars()).parse_document()
+}
+
+/// Helper function for converting the raw bytes of a GEDCOM file to parsed data. The
+/// bytes are decoded from the encoding of their byte order mark or of the `CHAR` of
+/// their header, so that ANSEL & UTF-16 files read as well as UTF-8 ones.
+///
+/// # Errors
+///
+/// Returns an error when the content can't be decoded, or when it is malformed or
+/// contains unhandled tags.
+pub fn parse_bytes(bytes: &[u8]) -> Result<GedcomData, GedcomError> {
+    parse_document(&encoding::decode_strict(bytes)?)
+}
+
+/// Helper function for converting the raw bytes of a GEDCOM file to parsed data like
+/// `parse_bytes`, along with statistics of the parse: its size, its records, the time
+/// spent decoding & parsing it and the number of problems found.
+///
+/// # Errors
+///
+/// Returns an error when the content can't be decoded, or when it is malformed or
+/// contains unhandled tags.
+pub fn parse_bytes_with_report(
+    bytes: &[u8],
+) -> Result<(GedcomData, parser::ParseReport), GedcomError> {
+    let start = std::time::Instant::now();
+    let content = encoding::decode_strict(bytes)?;
+    let decoding = start.elapsed();
+    let (data, mut report) = parser::Parser::new(content.chars()).parse_document_with_report()?;
+    report.bytes = bytes.len();
+    report.decoding = decoding;
+    Ok((data, report))
+}
@@ -0,0 +1,168 @@
+//! Extracts the media files that multimedia records refer to, safely.
+//!
+//! The `FILE` references of a tree are written by whoever made the file, so a service
+//! extracting the media of user-uploaded trees can't trust them: a reference like
+//! `../../etc/passwd` or `/home/user/.ssh/id_rsa` must not lead to writing outside the
+//! directory the media is extracted to. `MediaExtractor` only writes below the output
+//! roots it allows, and only to references that are relative & stay inside the root.
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// Why a media file can't be extracted
+#[derive(Debug)]
+pub enum MediaError {
+    /// The reference is empty or contains no file name
+    Empty,
+    /// The reference is an absolute path or a URL, ie. `/etc/passwd` or `C:\photo.jpg`
+    Absolute(String),
+    /// The reference climbs out of its directory with `..`
+    Traversal(String),
+    /// The output root is not one of the allowed roots
+    RootNotAllowed(PathBuf),
+    /// The destination resolves outside the output root, ie. through a symbolic link
+    OutsideRoot(PathBuf),
+    /// The file could not be written
+    Io(io::Error),
+}
+
+impl fmt::Display for MediaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MediaError::Empty => write!(f, "Media reference is empty"),
+            MediaError::Absolute(reference) => {
+                write!(f, "Media reference {reference:?} is not a relative path")
+            }
+            MediaError::Traversal(reference) => {
+                write!(f, "Media reference {reference:?} leaves its directory")
+            }
+            MediaError::RootNotAllowed(root) => {
+                write!(f, "Output root {} is not allowed", root.display())
+            }
+            MediaError::OutsideRoot(path) => {
+                write!(
+                    f,
+                    "Destination {} is outside the output root",
+                    path.display()
+                )
+            }
+            MediaError::Io(error) => write!(f, "Could not write media file: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for MediaError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MediaError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for MediaError {
+    fn from(error: io::Error) -> MediaError {
+        MediaError::Io(error)
+    }
+}
+
+/// Turns a media reference into a relative path that stays within its directory.
+/// Both `/` & `\` separate directories, as references are often written on Windows, and
+/// `.` components are dropped.
+///
+/// # Errors
+///
+/// Returns an error when the reference is empty, absolute, a URL or contains `..`.
+pub fn safe_relative_path(reference: &str) -> Result<PathBuf, MediaError> {
+    let normalized = reference.trim().replace('\\', "/");
+    let is_drive = normalized
+        .split_once(':')
+        .is_some_and(|(prefix, _)| !prefix.contains('/'));
+    if normalized.starts_with('/') || is_drive {
+        return Err(MediaError::Absolute(reference.to_string()));
+    }
+
+    let mut path = PathBuf::new();
+    for part in normalized.split('/') {
+        match part {
+            "" | "." => (),
+            ".." => return Err(MediaError::Traversal(reference.to_string())),
+            _ if part.contains('\0') => return Err(MediaError::Absolute(reference.to_string())),
+            _ => path.push(part),
+        }
+    }
+    // the platform may still see a component as something else, ie. a prefix on Windows
+    if !path.components().all(|c| matches!(c, Component::Normal(_))) {
+        return Err(MediaError::Absolute(reference.to_string()));
+    }
+    if path.as_os_str().is_empty() {
+        return Err(MediaError::Empty);
+    }
+    Ok(path)
+}
+
+/// Writes media files below an allowlist of output roots
+#[derive(Clone, Debug, Default)]
+pub struct MediaExtractor {
+    roots: Vec<PathBuf>,
+}
+
+impl MediaExtractor {
+    /// Creates an extractor that writes below any of `roots`
+    #[must_use]
+    pub fn new(roots: Vec<PathBuf>) -> MediaExtractor {
+        MediaExtractor { roots }
+    }
+
+    /// Where the media file of `reference` is extracted to below `root`, without touching
+    /// the file system.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when `root` is not allowed or the reference is not safe, see
+    /// `safe_relative_path`.
+    pub fn destination(&self, root: &Path, reference: &str) -> Result<PathBuf, MediaError> {
+        let allowed = root.components().all(|c| c != Component::ParentDir)
+            && self.roots.iter().any(|allowed| root.starts_with(allowed));
+        if !allowed {
+            return Err(MediaError::RootNotAllowed(root.to_path_buf()));
+        }
+        Ok(root.join(safe_relative_path(reference)?))
+    }
+
+    /// Writes `contents` as the media file of `reference` below `root`, creating the
+    /// directories it is in, and returns the path written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the destination is not safe, see `destination`, when it
+    /// resolves outside `root` through symbolic links, or when writing fails.
+    pub fn extract(
+        &self,
+        root: &Path,
+        reference: &str,
+        contents: &[u8],
+    ) -> Result<PathBuf, MediaError> {
+        let destination = self.destination(root, reference)?;
+        // symbolic links below the root could lead anywhere, so none are followed
+        let mut path = root.to_path_buf();
+        for component in safe_relative_path(reference)?.components() {
+            path.push(component);
+            let is_symlink =
+                fs::symlink_metadata(&path).is_ok_and(|metadata| metadata.file_type().is_symlink());
+            if is_symlink {
+                return Err(MediaError::OutsideRoot(destination));
+            }
+        }
+
+        let directory = destination.parent().unwrap_or(root);
+        fs::create_dir_all(directory)?;
+        if !fs::canonicalize(directory)?.starts_with(fs::canonicalize(root)?) {
+            return Err(MediaError::OutsideRoot(destination));
+        }
+
+        fs::write(&destination, contents)?;
+        Ok(destination)
+    }
+}
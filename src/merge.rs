@@ -0,0 +1,751 @@
+//! Merges duplicate records of the same person into one, and whole documents into one
+//! tree while recording the document each record came from
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::analysis::conflicts::{differ, ONCE_IN_LIFE};
+use crate::tree::{stamp, GedcomData};
+use crate::types::{
+    Association, ChangeDate, CustomData, Event, Gender, HasCustomData, HasEvents, Individual,
+    LdsOrdinance, Name, Note, Place, RepoReference, SourceCitation,
+};
+
+/// Tag of the user-defined line recording the document a record came from, ie.
+/// `1 _PROV smith.ged`, see `GedcomData::merge_document`
+pub const PROVENANCE_TAG: &str = "_PROV";
+
+/// Why two individuals could not be merged
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MergeError {
+    /// There is no individual with the xref
+    NotFound(String),
+    /// Both xrefs refer to the same individual
+    SameIndividual,
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeError::NotFound(xref) => write!(f, "No individual {xref}"),
+            MergeError::SameIndividual => write!(f, "Can't merge an individual with itself"),
+        }
+    }
+}
+
+impl std::error::Error for MergeError {}
+
+/// Facts of the two merged individuals that disagree. The kept individual's facts win,
+/// and the others are either kept alongside them or listed here, so nothing is lost
+/// silently.
+#[derive(Clone, Debug)]
+pub enum MergeConflict {
+    /// The primary names differ; the primary name of the removed individual was dropped
+    Name {
+        /// Name of the kept individual
+        kept: Box<Name>,
+        /// Name of the removed individual
+        removed: Box<Name>,
+    },
+    /// The sexes differ; the sex of the removed individual was dropped
+    Sex {
+        /// Sex of the kept individual
+        kept: Gender,
+        /// Sex of the removed individual
+        removed: Gender,
+    },
+    /// A line holding a single value, ie. `RESN`, `RFN` or `RIN`, differs; the value of the
+    /// removed individual was dropped
+    Field {
+        /// Tag of the line
+        tag: &'static str,
+        /// Value of the kept individual
+        kept: String,
+        /// Value of the removed individual
+        removed: String,
+    },
+    /// Both have an event that happens once in a life, with a different date or place,
+    /// as found by `analysis::event_conflicts`. Both events were kept.
+    Event {
+        /// Event of the kept individual
+        kept: Box<Event>,
+        /// Event of the removed individual
+        removed: Box<Event>,
+    },
+}
+
+/// Outcome of `GedcomData::merge_individuals`
+#[derive(Clone, Debug)]
+pub struct MergeReport {
+    /// Reference to the kept individual
+    pub kept: String,
+    /// Reference to the removed individual
+    pub removed: String,
+    /// Number of events moved to the kept individual. Events matching one of the kept
+    /// individual only had their citations moved.
+    pub moved_events: usize,
+    /// Number of family links moved to the kept individual
+    pub moved_links: usize,
+    /// Facts that disagree between the individuals
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// Outcome of `GedcomData::merge_document`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DocumentMerge {
+    /// Number of records added to the tree
+    pub records: usize,
+    /// Xrefs of the merged document that the tree already used, with the xref their
+    /// record was given instead
+    pub renamed: Vec<(String, String)>,
+}
+
+impl GedcomData {
+    /// Merges the individual `remove` into the individual `keep`, for records found to
+    /// describe the same person.
+    ///
+    /// The names, events, family links & every other fact of `remove` move to `keep`,
+    /// and the families & associations referencing `remove` are rewritten to reference
+    /// `keep`. `remove` is then deleted from the tree. Facts that disagree are listed in
+    /// the returned report.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when either individual is missing or both are the same.
+    pub fn merge_individuals(
+        &mut self,
+        keep: &str,
+        remove: &str,
+    ) -> Result<MergeReport, MergeError> {
+        if keep == remove {
+            return Err(MergeError::SameIndividual);
+        }
+        let position = |xref: &str| {
+            self.individuals
+                .iter()
+                .position(|individual| individual.xref.as_deref() == Some(xref))
+                .ok_or_else(|| MergeError::NotFound(xref.to_string()))
+        };
+        let keep_index = position(keep)?;
+        let remove_index = position(remove)?;
+
+        let removed = self.individuals.remove(remove_index);
+        let keep_index = if remove_index < keep_index {
+            keep_index - 1
+        } else {
+            keep_index
+        };
+        let mut report = MergeReport {
+            kept: keep.to_string(),
+            removed: remove.to_string(),
+            moved_events: 0,
+            moved_links: 0,
+            conflicts: Vec::new(),
+        };
+        let policy = self.change_dates;
+        let kept = &mut self.individuals[keep_index];
+        merge_into(kept, removed, &mut report);
+        stamp(policy, None, &mut kept.last_updated);
+
+        for family in &mut self.families {
+            let mut changed = false;
+            for partner in [&mut family.individual1, &mut family.individual2] {
+                if partner.as_deref() == Some(remove) {
+                    *partner = Some(keep.to_string());
+                    changed = true;
+                }
+            }
+            if family.children.iter().any(|child| child == remove) {
+                let mut seen = false;
+                family.children.retain_mut(|child| {
+                    if child == remove {
+                        *child = keep.to_string();
+                    }
+                    let duplicate = child == keep && seen;
+                    seen |= child == keep;
+                    !duplicate
+                });
+                changed = true;
+            }
+            if changed {
+                stamp(policy, None, &mut family.last_updated);
+            }
+        }
+        let repoint = |associations: &mut [Association]| {
+            for association in associations {
+                if association.xref == remove {
+                    keep.clone_into(&mut association.xref);
+                }
+            }
+        };
+        for individual in &mut self.individuals {
+            repoint(&mut individual.associations);
+            for event in individual.events_mut() {
+                repoint(&mut event.associations);
+            }
+        }
+        for family in &mut self.families {
+            for event in family.events_mut() {
+                repoint(&mut event.associations);
+            }
+        }
+        Ok(report)
+    }
+
+    /// Adds the records of another document to the tree, ie. when combining the files
+    /// of several researchers, and records `origin`, ie. the name of the file, as the
+    /// provenance of each of them with a `_PROV` line.
+    ///
+    /// Records already carrying a provenance, from an earlier merge, keep it. The header
+    /// of `other` is dropped, and its records whose xref the tree already uses are given
+    /// a new one, with every pointer to them rewritten. Notes `other` shares between
+    /// structures are stored in each of them. Extension records are merged without a
+    /// provenance.
+    pub fn merge_document(&mut self, mut other: GedcomData, origin: &str) -> DocumentMerge {
+        let shared: HashSet<&str> = other
+            .shared_notes
+            .iter()
+            .filter_map(Note::shared_xref)
+            .collect();
+        let mut taken: HashSet<String> = self
+            .xrefs()
+            .chain(other.xrefs())
+            .map(str::to_string)
+            .collect();
+        let mut renamed = Vec::new();
+        for xref in other.xrefs().filter(|xref| !shared.contains(xref)) {
+            if self.xrefs().any(|existing| existing == xref) {
+                let new = fresh_xref(xref, &taken);
+                taken.insert(new.clone());
+                renamed.push((xref.to_string(), new));
+            }
+        }
+        let new_xrefs: HashMap<String, String> = renamed.iter().cloned().collect();
+        rename_xrefs(&mut other, &Renames(&new_xrefs));
+        other.tag_provenance(origin);
+
+        let records = other.individuals.len()
+            + other.families.len()
+            + other.sources.len()
+            + other.repositories.len()
+            + other.submitters.len()
+            + other.multimedia.len()
+            + other.locations.len()
+            + other.notes.len()
+            + other.extension_records.len();
+        self.submitters.append(&mut other.submitters);
+        self.individuals.append(&mut other.individuals);
+        self.families.append(&mut other.families);
+        self.repositories.append(&mut other.repositories);
+        self.sources.append(&mut other.sources);
+        self.multimedia.append(&mut other.multimedia);
+        self.locations.append(&mut other.locations);
+        self.notes.append(&mut other.notes);
+        self.extension_records.append(&mut other.extension_records);
+        DocumentMerge { records, renamed }
+    }
+
+    /// Records `origin` as the provenance of the records without one, ie. to tag the
+    /// records of a tree before merging other documents into it. Returns the number of
+    /// records tagged.
+    pub fn tag_provenance(&mut self, origin: &str) -> usize {
+        fn tag<T: HasCustomData>(records: &mut [T], origin: &str) -> usize {
+            let mut tagged = 0;
+            for record in records {
+                let custom_data = record.custom_data_mut();
+                if !custom_data.iter().any(|data| data.tag == PROVENANCE_TAG) {
+                    custom_data.push(CustomData {
+                        tag: PROVENANCE_TAG.to_string(),
+                        value: origin.to_string(),
+                        children: Vec::new(),
+                    });
+                    tagged += 1;
+                }
+            }
+            tagged
+        }
+
+        tag(&mut self.submitters, origin)
+            + tag(&mut self.individuals, origin)
+            + tag(&mut self.families, origin)
+            + tag(&mut self.repositories, origin)
+            + tag(&mut self.sources, origin)
+            + tag(&mut self.multimedia, origin)
+            + tag(&mut self.locations, origin)
+            + tag(&mut self.notes, origin)
+    }
+
+    /// The document the record `xref` came from, as recorded by `merge_document`
+    #[must_use]
+    pub fn provenance(&self, xref: &str) -> Option<&str> {
+        self.provenances()
+            .find(|(record, _)| *record == xref)
+            .map(|(_, origin)| origin)
+    }
+
+    /// The xrefs of the records that came from the document `origin`, ie. to audit or
+    /// take apart a merged tree
+    #[must_use]
+    pub fn records_from(&self, origin: &str) -> Vec<&str> {
+        self.provenances()
+            .filter(|(_, record_origin)| *record_origin == origin)
+            .map(|(xref, _)| xref)
+            .collect()
+    }
+
+    /// The documents the records of the tree came from, in the order of the records
+    #[must_use]
+    pub fn origins(&self) -> Vec<&str> {
+        let mut origins = Vec::new();
+        for (_, origin) in self.provenances() {
+            if !origins.contains(&origin) {
+                origins.push(origin);
+            }
+        }
+        origins
+    }
+
+    /// The xref & provenance of each record carrying one
+    fn provenances(&self) -> impl Iterator<Item = (&str, &str)> {
+        fn of<'a, T: HasCustomData>(
+            xref: Option<&'a String>,
+            record: &'a T,
+        ) -> Option<(&'a str, &'a str)> {
+            let origin = record
+                .custom_data()
+                .iter()
+                .find(|data| data.tag == PROVENANCE_TAG)?;
+            Some((xref?.as_str(), origin.value.as_str()))
+        }
+
+        let submitters = self.submitters.iter().map(|r| of(r.xref.as_ref(), r));
+        let individuals = self.individuals.iter().map(|r| of(r.xref.as_ref(), r));
+        let families = self.families.iter().map(|r| of(r.xref.as_ref(), r));
+        let repositories = self.repositories.iter().map(|r| of(r.xref.as_ref(), r));
+        let sources = self.sources.iter().map(|r| of(r.xref.as_ref(), r));
+        let multimedia = self.multimedia.iter().map(|r| of(r.xref.as_ref(), r));
+        let locations = self.locations.iter().map(|r| of(r.xref.as_ref(), r));
+        let notes = self.notes.iter().map(|r| of(r.xref.as_ref(), r));
+        submitters
+            .chain(individuals)
+            .chain(families)
+            .chain(repositories)
+            .chain(sources)
+            .chain(multimedia)
+            .chain(locations)
+            .chain(notes)
+            .flatten()
+    }
+}
+
+/// An xref not `taken`, made from `xref` and a number, ie. `@I1_2@` for `@I1@`
+fn fresh_xref(xref: &str, taken: &HashSet<String>) -> String {
+    let stem = xref.trim_matches('@');
+    let mut number = 1;
+    loop {
+        number += 1;
+        let candidate = format!("@{stem}_{number}@");
+        if !taken.contains(&candidate) {
+            return candidate;
+        }
+    }
+}
+
+/// The new xrefs of renamed records
+struct Renames<'a>(&'a HashMap<String, String>);
+
+impl Renames<'_> {
+    fn xref(&self, xref: &mut String) {
+        if let Some(new) = self.0.get(xref.as_str()) {
+            xref.clone_from(new);
+        }
+    }
+
+    fn optional(&self, xref: &mut Option<String>) {
+        if let Some(xref) = xref {
+            self.xref(xref);
+        }
+    }
+
+    /// Rewrites the pointer notes, ie. `NOTE @N1@`, and stores the text of shared notes
+    /// in the note itself, as the shared note records stay behind
+    fn notes(&self, notes: &mut [Note]) {
+        for note in notes {
+            let text = note.text();
+            let replacement = match self.0.get(text.trim()) {
+                Some(new) => Some(new.clone()),
+                None if note.shared_xref().is_some() => Some(text.into_owned()),
+                None => None,
+            };
+            if let Some(replacement) = replacement {
+                *note = Note::new(replacement);
+            }
+        }
+    }
+
+    fn custom_data(&self, custom_data: &mut [CustomData]) {
+        for data in custom_data {
+            self.xref(&mut data.value);
+            self.custom_data(&mut data.children);
+        }
+    }
+
+    fn citations(&self, citations: &mut [SourceCitation]) {
+        for citation in citations {
+            self.xref(&mut citation.xref);
+            self.notes(&mut citation.notes);
+        }
+    }
+
+    fn associations(&self, associations: &mut [Association]) {
+        for association in associations {
+            self.xref(&mut association.xref);
+            self.notes(&mut association.notes);
+            self.citations(&mut association.citations);
+        }
+    }
+
+    fn events(&self, events: &mut [Event]) {
+        for event in events {
+            if let Some(place) = &mut event.place {
+                self.place(place);
+            }
+            self.optional(&mut event.family);
+            self.citations(&mut event.citations);
+            self.associations(&mut event.associations);
+            self.notes(&mut event.notes);
+            for link in &mut event.multimedia {
+                self.xref(&mut link.xref);
+                self.custom_data(&mut link.custom_data);
+            }
+            self.custom_data(&mut event.custom_data);
+        }
+    }
+
+    fn place(&self, place: &mut Place) {
+        self.optional(&mut place.location);
+        self.notes(&mut place.notes);
+        self.custom_data(&mut place.custom_data);
+    }
+
+    fn ordinances(&self, ordinances: &mut [LdsOrdinance]) {
+        for ordinance in ordinances {
+            if let Some(place) = &mut ordinance.place {
+                self.place(place);
+            }
+            self.optional(&mut ordinance.family);
+            self.citations(&mut ordinance.citations);
+            self.notes(&mut ordinance.notes);
+            self.custom_data(&mut ordinance.custom_data);
+        }
+    }
+}
+
+/// Rewrites the xrefs of the records of a tree & the pointers to them
+fn rename_xrefs(data: &mut GedcomData, renames: &Renames) {
+    for submitter in &mut data.submitters {
+        renames.optional(&mut submitter.xref);
+        renames.notes(&mut submitter.notes);
+        renames.custom_data(&mut submitter.custom_data);
+    }
+    for individual in &mut data.individuals {
+        renames.optional(&mut individual.xref);
+        for name in &mut individual.names {
+            renames.notes(&mut name.notes);
+            renames.citations(&mut name.citations);
+            for variation in name.phonetic.iter_mut().chain(&mut name.romanized) {
+                renames.notes(&mut variation.notes);
+                renames.citations(&mut variation.citations);
+            }
+        }
+        for link in &mut individual.families {
+            renames.xref(&mut link.xref);
+            renames.notes(&mut link.notes);
+            renames.citations(&mut link.citations);
+        }
+        for link in &mut individual.multimedia {
+            renames.xref(&mut link.xref);
+            renames.custom_data(&mut link.custom_data);
+        }
+        renames.associations(&mut individual.associations);
+        renames.events(individual.events_mut());
+        renames.ordinances(&mut individual.lds_ordinances);
+        renames.notes(&mut individual.notes);
+        renames.custom_data(&mut individual.custom_data);
+    }
+    for family in &mut data.families {
+        renames.optional(&mut family.xref);
+        renames.optional(&mut family.individual1);
+        renames.optional(&mut family.individual2);
+        for child in &mut family.children {
+            renames.xref(child);
+        }
+        for submitter in &mut family.submitters {
+            renames.xref(submitter);
+        }
+        renames.events(family.events_mut());
+        renames.ordinances(&mut family.lds_ordinances);
+        renames.notes(&mut family.notes);
+        renames.custom_data(&mut family.custom_data);
+    }
+    for repository in &mut data.repositories {
+        renames.optional(&mut repository.xref);
+        renames.notes(&mut repository.notes);
+        renames.custom_data(&mut repository.custom_data);
+    }
+    for source in &mut data.sources {
+        renames.optional(&mut source.xref);
+        renames.events(source.data.events_mut());
+        renames.notes(&mut source.data.notes);
+        for citation in source.repo_citations_mut() {
+            if let RepoReference::Pointer(xref) = &mut citation.repository {
+                renames.xref(xref);
+            }
+        }
+        renames.notes(&mut source.notes);
+        renames.custom_data(&mut source.custom_data);
+    }
+    for multimedia in &mut data.multimedia {
+        renames.optional(&mut multimedia.xref);
+        renames.notes(&mut multimedia.notes);
+        renames.custom_data(&mut multimedia.custom_data);
+    }
+    for location in &mut data.locations {
+        renames.optional(&mut location.xref);
+        for link in &mut location.within {
+            renames.xref(&mut link.xref);
+        }
+        renames.notes(&mut location.notes);
+        renames.custom_data(&mut location.custom_data);
+    }
+    for record in &mut data.notes {
+        renames.optional(&mut record.xref);
+        renames.citations(&mut record.citations);
+        renames.custom_data(&mut record.custom_data);
+    }
+    for record in &mut data.extension_records {
+        renames.optional(&mut record.xref);
+        renames.custom_data(std::slice::from_mut(&mut record.data));
+    }
+    data.shared_notes.clear();
+}
+
+fn merge_into(kept: &mut Individual, mut removed: Individual, report: &mut MergeReport) {
+    merge_lines(kept, &mut removed, report);
+    let events = removed.events();
+    let mut removed_names = removed.names.into_iter();
+    match (kept.names.first(), removed_names.next()) {
+        (None, Some(name)) => kept.names.push(name),
+        (Some(kept_name), Some(removed_name)) if *kept_name != removed_name => {
+            report.conflicts.push(MergeConflict::Name {
+                kept: Box::new(kept_name.clone()),
+                removed: Box::new(removed_name),
+            });
+        }
+        _ => (),
+    }
+    // the other names of the removed individual are kept as alternate names
+    for name in removed_names {
+        if !kept.names.contains(&name) {
+            kept.names.push(name);
+        }
+    }
+
+    match (&kept.sex, removed.sex) {
+        (Gender::Unknown, sex) => kept.sex = sex,
+        (_, Gender::Unknown) => (),
+        (kept_sex, removed_sex) => {
+            if std::mem::discriminant(kept_sex) != std::mem::discriminant(&removed_sex) {
+                report.conflicts.push(MergeConflict::Sex {
+                    kept: kept_sex.clone(),
+                    removed: removed_sex,
+                });
+            }
+        }
+    }
+
+    merge_events(kept, events, report);
+
+    for link in removed.families {
+        let linked = kept
+            .families
+            .iter()
+            .any(|existing| existing.xref == link.xref);
+        if !linked {
+            kept.families.push(link);
+            report.moved_links += 1;
+        }
+    }
+    for link in removed.multimedia {
+        let linked = link.embedded.is_none()
+            && kept
+                .multimedia
+                .iter()
+                .any(|existing| existing.xref == link.xref);
+        if !linked {
+            kept.multimedia.push(link);
+        }
+    }
+    for association in removed.associations {
+        let associated = kept.associations.iter().any(|existing| {
+            existing.xref == association.xref
+                && existing.relation == association.relation
+                && existing.role == association.role
+        });
+        if !associated {
+            kept.associations.push(association);
+        }
+    }
+    for ordinance in removed.lds_ordinances {
+        let same = kept.lds_ordinances.iter_mut().find(|existing| {
+            existing.ordinance == ordinance.ordinance
+                && existing.date == ordinance.date
+                && existing.temple == ordinance.temple
+                && existing.place == ordinance.place
+        });
+        match same {
+            Some(existing) => merge_citations(&mut existing.citations, ordinance.citations),
+            None => kept.lds_ordinances.push(ordinance),
+        }
+    }
+    for note in removed.notes {
+        if !kept.notes.contains(&note) {
+            kept.notes.push(note);
+        }
+    }
+    kept.custom_data.extend(removed.custom_data);
+}
+
+/// Takes the lines holding a single value, ie. `RIN`, and the `REFN`s of the removed
+/// individual
+fn merge_lines(kept: &mut Individual, removed: &mut Individual, report: &mut MergeReport) {
+    match (kept.num_children, removed.num_children) {
+        (None, removed_count) => kept.num_children = removed_count,
+        (Some(kept_count), Some(removed_count)) if kept_count != removed_count => {
+            report.conflicts.push(MergeConflict::Field {
+                tag: "NCHI",
+                kept: kept_count.to_string(),
+                removed: removed_count.to_string(),
+            });
+        }
+        _ => (),
+    }
+    for (tag, kept_value, removed_value) in [
+        (
+            "RESN",
+            &mut kept.restriction_notice,
+            removed.restriction_notice.take(),
+        ),
+        (
+            "RFN",
+            &mut kept.permanent_record_file_number,
+            removed.permanent_record_file_number.take(),
+        ),
+        (
+            "AFN",
+            &mut kept.ancestral_file_number,
+            removed.ancestral_file_number.take(),
+        ),
+        (
+            "RIN",
+            &mut kept.automated_record_id,
+            removed.automated_record_id.take(),
+        ),
+    ] {
+        merge_field(tag, kept_value, removed_value, report);
+    }
+    for reference in std::mem::take(&mut removed.user_reference_numbers) {
+        if !kept.user_reference_numbers.contains(&reference) {
+            kept.user_reference_numbers.push(reference);
+        }
+    }
+    // the merged record was created with the first of the two, and changed with the last
+    merge_change_date(&mut kept.created, removed.created.take(), Ordering::Less);
+    merge_change_date(
+        &mut kept.last_updated,
+        removed.last_updated.take(),
+        Ordering::Greater,
+    );
+}
+
+/// Takes the change date of the removed record when the kept one has none, or when it
+/// compares to the kept one as `takes`. Dates that can't be read are left as they are.
+fn merge_change_date(kept: &mut Option<String>, removed: Option<String>, takes: Ordering) {
+    let Some(removed) = removed else {
+        return;
+    };
+    let replaces = match kept.as_deref() {
+        None => true,
+        Some(kept) => match (ChangeDate::parse(&removed), ChangeDate::parse(kept)) {
+            (Some(removed), Some(kept)) => removed.cmp(&kept) == takes,
+            _ => false,
+        },
+    };
+    if replaces {
+        *kept = Some(removed);
+    }
+}
+
+/// Moves the events of the removed individual, only moving the citations of the events
+/// matching one of the kept individual
+fn merge_events(kept: &mut Individual, events: Vec<Event>, report: &mut MergeReport) {
+    for event in events {
+        let same = kept.events_mut().iter_mut().find(|existing| {
+            existing.event == event.event
+                && existing.date == event.date
+                && existing.place == event.place
+        });
+        if let Some(existing) = same {
+            merge_citations(&mut existing.citations, event.citations);
+            continue;
+        }
+        if ONCE_IN_LIFE.contains(&event.event) {
+            let events = kept.events();
+            let conflicting = events
+                .into_iter()
+                .find(|e| e.event == event.event && differ(e, &event));
+            if let Some(existing) = conflicting {
+                report.conflicts.push(MergeConflict::Event {
+                    kept: Box::new(existing),
+                    removed: Box::new(event.clone()),
+                });
+            }
+        }
+        kept.add_event(event);
+        report.moved_events += 1;
+    }
+}
+
+/// Adds the citations of a fact of the removed individual not citing the same page as
+/// one of the kept individual's
+fn merge_citations(kept: &mut Vec<SourceCitation>, removed: Vec<SourceCitation>) {
+    for citation in removed {
+        let cited = kept
+            .iter()
+            .any(|c| c.xref == citation.xref && c.page == citation.page);
+        if !cited {
+            kept.push(citation);
+        }
+    }
+}
+
+/// Takes the value of the removed individual for a line the kept one lacks, or reports
+/// the values differing
+fn merge_field(
+    tag: &'static str,
+    kept: &mut Option<String>,
+    removed: Option<String>,
+    report: &mut MergeReport,
+) {
+    match (kept.as_deref(), removed) {
+        (None, removed) => *kept = removed,
+        (Some(kept), Some(removed)) if kept != removed => {
+            report.conflicts.push(MergeConflict::Field {
+                tag,
+                kept: kept.to_string(),
+                removed,
+            });
+        }
+        _ => (),
+    }
+}
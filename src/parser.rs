@@ -1,593 +1,2089 @@
 //! The state machine that parses a char iterator of the gedcom's contents
-use std::{panic, str::Chars};
+use std::{
+    collections::{BTreeMap, HashSet},
+    str::Chars,
+    time::{Duration, Instant},
+};
 
+use crate::diagnostics::{Diagnostic, Severity};
+use crate::pipeline::Pipeline;
 use crate::tokenizer::{Token, Tokenizer};
 use crate::tree::GedcomData;
 use crate::types::{
-    event::HasEvents, Address, CustomData, Event, Family, FamilyLink, Gender, Header, Individual,
-    Name, RepoCitation, Repository, Source, SourceCitation, Submitter,
+    event::HasEvents, Address, Association, CustomData, Event, EventType, ExtensionTag, Family,
+    FamilyLink, Gender, HasCustomData, Header, HeaderSource, Individual, LdsOrdinance,
+    LdsOrdinanceType, Location, LocationLink, LocationName, Map, MultimediaFileRefn,
+    MultimediaLink, MultimediaRecord, Name, NameVariation, Note, NoteInterner, NoteRecord,
+    ParentTag, Pedigree, Place, PlaceVariation, Producer, RepoCitation, RepoReference, Repository,
+    Source, SourceCitation, SourceData, Submitter, Time, UserDefinedDataset, UserReferenceNumber,
 };
+use crate::GedcomError;
+
+/// Options changing how the content is parsed
+#[derive(Clone, Debug, Default)]
+pub struct ParseOptions {
+    /// Notes at least this many bytes long are stored compressed, trading speed of
+    /// access for memory. Only has an effect with the `compression` feature.
+    pub note_compression_threshold: Option<usize>,
+    /// Reports deviations from the specification that are otherwise accepted, like the
+    /// dialects of some vendors, as error diagnostics. Tags are matched as written,
+    /// rather than uppercased.
+    pub strict: bool,
+    /// Stores the text of identical notes once, ie. the source descriptions some vendors
+    /// repeat thousands of times. Notes whose text repeats are gathered as
+    /// `GedcomData::shared_notes` and written as note records they point to, `SNOTE` in
+    /// GEDCOM 7.0. Takes precedence over `note_compression_threshold`.
+    pub deduplicate_notes: bool,
+    /// Whether `CONC` lines that look like the artifacts of buggy exporters are reported,
+    /// or repaired
+    pub conc_audit: ConcAudit,
+    /// How the text of `CONC` lines is joined to the line it continues
+    pub conc_join: ConcJoin,
+    /// Keeps the records of tags the parser doesn't handle, ie. GEDCOM 7.0 extension
+    /// records like `0 @T1@ _TODO`, as `GedcomData::extension_records` rather than
+    /// skipping them with a warning. They are written back as they were read.
+    pub extension_records: bool,
+    /// Deepest level of the lines accepted, ie. `Some(99)`, the deepest the
+    /// specification allows. Deeper lines stop the parse with
+    /// `GedcomError::LimitExceeded`, guarding against content nesting without end.
+    pub max_level: Option<u8>,
+}
+
+/// Handling of suspicious `CONC` lines. Exporters that split text at a space and drop
+/// it, or that expect the reader to add it back, glue words together: `end.Start`.
+/// Splitting a word across `CONC` lines, as the specification asks, is not suspicious.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConcAudit {
+    /// Joins `CONC` lines as they are, without reporting anything
+    #[default]
+    Off,
+    /// Reports a warning for each `CONC` line joining words without a space, ie. after a
+    /// period or between a lowercase and an uppercase letter, and for each `CONC` line
+    /// continuing an empty line
+    Report,
+    /// Reports like `Report`, and puts back the space between the words joined
+    Repair,
+}
+
+/// Joining of `CONC` lines. The specification splits text anywhere, so that the lines
+/// are joined as they are, but some exporters split text at a space and drop it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConcJoin {
+    /// Joins `CONC` lines as they are, as the specification asks
+    #[default]
+    Exact,
+    /// Puts a space between the line and the `CONC` line continuing it, unless the line
+    /// ends with one
+    InsertSpace,
+    /// Puts a space like `InsertSpace` in the files of programs known to drop it, ie.
+    /// Family Tree Maker & Ancestry, detected from `HEAD.SOUR`, and joins the lines of
+    /// other files exactly
+    Auto,
+}
+
+/// A top-level record of the content, as yielded by `Parser::records`
+#[derive(Clone, Debug)]
+pub enum Record {
+    /// The `HEAD` record
+    Header(Header),
+    /// A `SUBM` record
+    Submitter(Submitter),
+    /// An `INDI` record
+    Individual(Individual),
+    /// A `FAM` record
+    Family(Family),
+    /// A `SOUR` record
+    Source(Source),
+    /// A `REPO` record
+    Repository(Repository),
+    /// An `OBJE` record
+    Multimedia(MultimediaRecord),
+    /// A shared location record, `_LOC`
+    Location(Location),
+    /// A `NOTE` record, or `SNOTE` in GEDCOM 7.0
+    Note(NoteRecord),
+    /// A record of a tag the parser doesn't handle, kept with
+    /// `ParseOptions::extension_records`
+    Extension(UserDefinedDataset),
+}
+
+impl Record {
+    /// The tag of the record, ie. `INDI`, or `_LOC` for a shared location
+    #[must_use]
+    pub fn tag(&self) -> &str {
+        match self {
+            Record::Header(_) => "HEAD",
+            Record::Submitter(_) => "SUBM",
+            Record::Individual(_) => "INDI",
+            Record::Family(_) => "FAM",
+            Record::Source(_) => "SOUR",
+            Record::Repository(_) => "REPO",
+            Record::Multimedia(_) => "OBJE",
+            Record::Location(_) => "_LOC",
+            Record::Note(_) => "NOTE",
+            Record::Extension(record) => &record.data.tag,
+        }
+    }
+}
+
+/// Statistics of a parse, see `Parser::parse_document_with_report`, ie. to show a
+/// summary of an import or to track how long imports take
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParseReport {
+    /// Size of the content in bytes
+    pub bytes: usize,
+    /// Number of lines of the content, up to its trailer
+    pub lines: u32,
+    /// Number of top-level records of each tag, ie. `INDI`, counting the records skipped
+    /// with a warning out
+    pub records: BTreeMap<String, usize>,
+    /// Time spent decoding the bytes of the content, zero when the content was given as
+    /// text
+    pub decoding: Duration,
+    /// Time spent parsing the content into a tree
+    pub parsing: Duration,
+    /// Number of error diagnostics
+    pub errors: usize,
+    /// Number of warning diagnostics
+    pub warnings: usize,
+}
+
+impl ParseReport {
+    /// Time spent on every phase of the parse
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        self.decoding + self.parsing
+    }
+}
+
+/// Iterator over the top-level records of the content, see `Parser::records`
+pub struct RecordIter<'p, 'a> {
+    parser: &'p mut Parser<'a>,
+    done: bool,
+}
+
+impl Iterator for RecordIter<'_, '_> {
+    type Item = Result<Record, GedcomError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let record = match self.parser.take_error() {
+            Ok(()) => self.parser.next_record().transpose(),
+            Err(error) => Some(Err(error)),
+        };
+        self.done = !matches!(record, Some(Ok(_)));
+        record
+    }
+}
 
 /// The Gedcom parser that converts the token list into a data structure
 pub struct Parser<'a> {
     tokenizer: Tokenizer<'a>,
+    options: ParseOptions,
+    diagnostics: Vec<Diagnostic>,
+    notes: NoteInterner,
+    /// An error found before parsing began, returned once parsing is asked for
+    error: Option<GedcomError>,
+    /// Size of the content in bytes
+    bytes: usize,
+    /// Whether a space is put between joined `CONC` lines, see `ParseOptions::conc_join`
+    conc_space: bool,
 }
 
 impl<'a> Parser<'a> {
     /// Creates a parser state machine for parsing a gedcom file as a chars iterator
     #[must_use]
-    pub fn new(chars: Chars<'a>) -> Parser {
+    pub fn new(chars: Chars<'a>) -> Parser<'a> {
+        Parser::with_options(chars, ParseOptions::default())
+    }
+
+    /// Creates a parser state machine that parses according to `options`
+    #[must_use]
+    pub fn with_options(chars: Chars<'a>, options: ParseOptions) -> Parser<'a> {
+        let bytes = chars.as_str().len();
         let mut tokenizer = Tokenizer::new(chars);
-        tokenizer.next_token();
-        Parser { tokenizer }
+        tokenizer.uppercase_tags(!options.strict);
+        let error = tokenizer.try_next_token().err();
+        let conc_space = options.conc_join == ConcJoin::InsertSpace;
+        Parser {
+            tokenizer,
+            options,
+            diagnostics: Vec::new(),
+            notes: NoteInterner::default(),
+            conc_space,
+            error,
+            bytes,
+        }
     }
 
-    /// Does the actual parsing of the record.
-    pub fn parse_record(&mut self) -> GedcomData {
+    /// Problems found in the content so far that did not stop the parse
+    #[must_use]
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// The line number of the content the parser is currently on
+    #[must_use]
+    pub fn line(&self) -> u32 {
+        self.tokenizer.line
+    }
+
+    /// Parses the whole content, up to its trailer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the GEDCOM content is malformed or contains unhandled tags,
+    /// along with the line it was found on.
+    pub fn parse_document(&mut self) -> Result<GedcomData, GedcomError> {
+        self.parse_counting(&mut BTreeMap::new())
+    }
+
+    /// Parses the whole content like `parse_document`, along with statistics of the
+    /// parse.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the GEDCOM content is malformed or contains unhandled tags,
+    /// along with the line it was found on.
+    pub fn parse_document_with_report(&mut self) -> Result<(GedcomData, ParseReport), GedcomError> {
+        let start = Instant::now();
+        let mut records = BTreeMap::new();
+        let data = self.parse_counting(&mut records)?;
+        let parsing = start.elapsed();
+        let count = |severity| {
+            self.diagnostics
+                .iter()
+                .filter(|diagnostic| diagnostic.severity == severity)
+                .count()
+        };
+        let report = ParseReport {
+            bytes: self.bytes,
+            lines: self.tokenizer.line,
+            records,
+            decoding: Duration::ZERO,
+            parsing,
+            errors: count(Severity::Error),
+            warnings: count(Severity::Warning),
+        };
+        Ok((data, report))
+    }
+
+    /// Parses the whole content, counting its records by tag
+    fn parse_counting(
+        &mut self,
+        records: &mut BTreeMap<String, usize>,
+    ) -> Result<GedcomData, GedcomError> {
+        self.take_error()?;
         let mut data = GedcomData::default();
-        loop {
-            let level = match self.tokenizer.current_token {
-                Token::Level(n) => n,
-                _ => panic!(
-                    "{} Expected Level, found {:?}",
-                    self.dbg(),
-                    self.tokenizer.current_token
-                ),
-            };
+        while let Some(record) = self.next_record()? {
+            *records.entry(record.tag().to_string()).or_default() += 1;
+            data.add_record(record);
+        }
+
+        if self.options.deduplicate_notes {
+            let xrefs: HashSet<&str> = data.xrefs().collect();
+            let shared_notes =
+                std::mem::take(&mut self.notes).shared_notes(|xref| xrefs.contains(xref));
+            data.shared_notes = shared_notes;
+        }
+        Ok(data)
+    }
 
-            self.tokenizer.next_token();
+    /// Iterates over the top-level records of the content, parsing one record at a time
+    /// rather than building the whole tree, to process large files without holding all
+    /// of their records in memory. The iteration ends at the trailer, or after the first
+    /// error.
+    ///
+    /// Notes are not shared between records with `ParseOptions::deduplicate_notes`,
+    /// which needs the whole tree.
+    ///
+    /// ```rust
+    /// use gedcom::parser::{Parser, Record};
+    ///
+    /// let content = "0 HEAD\n0 @I1@ INDI\n1 NAME Ann /Doe/\n0 @I2@ INDI\n0 TRLR";
+    /// let mut parser = Parser::new(content.chars());
+    /// let mut individuals = 0;
+    /// for record in parser.records() {
+    ///     if let Record::Individual(_) = record.unwrap() {
+    ///         individuals += 1;
+    ///     }
+    /// }
+    /// assert_eq!(individuals, 2);
+    /// ```
+    pub fn records(&mut self) -> RecordIter<'_, 'a> {
+        RecordIter {
+            parser: self,
+            done: false,
+        }
+    }
 
-            let mut pointer: Option<String> = None;
-            if let Token::Pointer(xref) = &self.tokenizer.current_token {
-                pointer = Some(xref.to_string());
-                self.tokenizer.next_token();
-            }
+    /// Parses the next top-level record, skipping those that are not kept. Returns
+    /// `None` at the trailer.
+    fn next_record(&mut self) -> Result<Option<Record>, GedcomError> {
+        loop {
+            let (level, pointer) = self.take_record_start()?;
 
             if let Token::Tag(tag) = &self.tokenizer.current_token {
-                match tag.as_str() {
-                    "HEAD" => data.header = self.parse_header(),
-                    "FAM" => data.add_family(self.parse_family(level, pointer)),
-                    "INDI" => data.add_individual(self.parse_individual(level, pointer)),
-                    "REPO" => data.add_repository(self.parse_repository(level, pointer)),
-                    "SOUR" => data.add_source(self.parse_source(level, pointer)),
-                    "SUBM" => data.add_submitter(self.parse_submitter(level, pointer)),
-                    "TRLR" => break,
+                let record = match tag.as_str() {
+                    "HEAD" => Record::Header(self.parse_header()?),
+                    "FAM" => Record::Family(self.parse_family(level, pointer)?),
+                    "INDI" => Record::Individual(self.parse_individual(level, pointer)?),
+                    "OBJE" => Record::Multimedia(self.parse_multimedia(level, pointer)?),
+                    "REPO" => Record::Repository(self.parse_repository(level, pointer)?),
+                    "SOUR" => Record::Source(self.parse_source(level, pointer)?),
+                    "SUBM" => Record::Submitter(self.parse_submitter(level, pointer)?),
+                    "NOTE" | "SNOTE" => Record::Note(self.parse_note_record(level, pointer)?),
+                    "TRLR" => {
+                        self.take_tokenizer_findings();
+                        return Ok(None);
+                    }
+                    _ if self.options.extension_records => {
+                        let tag_clone = self
+                            .tokenizer
+                            .original_tag()
+                            .map_or_else(|| tag.clone(), str::to_string);
+                        let record = self.parse_custom_tag(tag_clone, level)?;
+                        Record::Extension(UserDefinedDataset {
+                            xref: pointer,
+                            data: record,
+                        })
+                    }
                     _ => {
-                        println!("{} Unhandled tag {}", self.dbg(), tag);
-                        self.tokenizer.next_token();
+                        let message = format!("Unhandled tag {tag}");
+                        self.warn(message);
+                        self.skip_record()?;
+                        continue;
                     }
                 };
+                return Ok(Some(record));
             } else if let Token::CustomTag(tag) = &self.tokenizer.current_token {
-                // TODO
+                if tag == "_LOC" {
+                    return Ok(Some(Record::Location(self.parse_location(level, pointer)?)));
+                }
                 let tag_clone = tag.clone();
-                let custom_data = self.parse_custom_tag(tag_clone);
-                println!(
-                    "{} Skipping top-level custom tag: {:?}",
-                    self.dbg(),
-                    custom_data
-                );
-                while self.tokenizer.current_token != Token::Level(0) {
-                    self.tokenizer.next_token();
+                let custom_data = self.parse_custom_tag(tag_clone, level)?;
+                if self.options.extension_records {
+                    return Ok(Some(Record::Extension(UserDefinedDataset {
+                        xref: pointer,
+                        data: custom_data,
+                    })));
                 }
+                self.warn(format!(
+                    "Skipping top-level custom tag: {}",
+                    custom_data.tag
+                ));
             } else {
-                println!(
-                    "{} Unhandled token {:?}",
-                    self.dbg(),
-                    self.tokenizer.current_token
-                );
-                self.tokenizer.next_token();
-            };
+                let message = format!("Unhandled token {:?}", self.tokenizer.current_token);
+                self.warn(message);
+                self.next_token()?;
+            }
         }
+    }
+
+    /// Does the actual parsing of the record, like `parse_document` which returns the
+    /// error instead of panicking.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the GEDCOM content is malformed or contains unhandled tags.
+    pub fn parse_record(&mut self) -> GedcomData {
+        self.parse_document()
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
 
-        data
+    /// Parses the content like `parse_document`, then runs the passes of `pipeline` over
+    /// the tree. Their findings are added to the `diagnostics` of the parser.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the GEDCOM content is malformed or contains unhandled tags.
+    pub fn parse_document_with(&mut self, pipeline: &Pipeline) -> Result<GedcomData, GedcomError> {
+        let mut data = self.parse_document()?;
+        pipeline.run(&mut data, &mut self.diagnostics);
+        Ok(data)
+    }
+
+    /// Parses the content like `parse_document_with`, panicking on errors.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the GEDCOM content is malformed or contains unhandled tags.
+    pub fn parse_record_with(&mut self, pipeline: &Pipeline) -> GedcomData {
+        self.parse_document_with(pipeline)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Parses a single `HEAD` record starting at the current line.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the current line does not begin a `HEAD` record or the
+    /// record is malformed.
+    pub fn try_parse_header_record(&mut self) -> Result<Header, GedcomError> {
+        self.parse_fragment("HEAD", |parser, _, _| parser.parse_header())
+    }
+
+    /// Parses a single `HEAD` record like `try_parse_header_record`, panicking on errors.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the current line does not begin a `HEAD` record or the record is malformed.
+    pub fn parse_header_record(&mut self) -> Header {
+        self.try_parse_header_record()
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Parses a single `SUBM` record starting at the current line.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the current line does not begin a `SUBM` record or the
+    /// record is malformed.
+    pub fn try_parse_submitter_record(&mut self) -> Result<Submitter, GedcomError> {
+        self.parse_fragment("SUBM", Parser::parse_submitter)
+    }
+
+    /// Parses a single `SUBM` record like `try_parse_submitter_record`, panicking on errors.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the current line does not begin a `SUBM` record or the record is malformed.
+    pub fn parse_submitter_record(&mut self) -> Submitter {
+        self.try_parse_submitter_record()
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Parses a single `INDI` record starting at the current line.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the current line does not begin an `INDI` record or the
+    /// record is malformed.
+    pub fn try_parse_individual_record(&mut self) -> Result<Individual, GedcomError> {
+        self.parse_fragment("INDI", Parser::parse_individual)
+    }
+
+    /// Parses a single `INDI` record like `try_parse_individual_record`, panicking on errors.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the current line does not begin an `INDI` record or the record is malformed.
+    pub fn parse_individual_record(&mut self) -> Individual {
+        self.try_parse_individual_record()
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Parses a single `FAM` record starting at the current line.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the current line does not begin a `FAM` record or the
+    /// record is malformed.
+    pub fn try_parse_family_record(&mut self) -> Result<Family, GedcomError> {
+        self.parse_fragment("FAM", Parser::parse_family)
+    }
+
+    /// Parses a single `FAM` record like `try_parse_family_record`, panicking on errors.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the current line does not begin a `FAM` record or the record is malformed.
+    pub fn parse_family_record(&mut self) -> Family {
+        self.try_parse_family_record()
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Parses a single `SOUR` record starting at the current line.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the current line does not begin a `SOUR` record or the
+    /// record is malformed.
+    pub fn try_parse_source_record(&mut self) -> Result<Source, GedcomError> {
+        self.parse_fragment("SOUR", Parser::parse_source)
+    }
+
+    /// Parses a single `SOUR` record like `try_parse_source_record`, panicking on errors.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the current line does not begin a `SOUR` record or the record is malformed.
+    pub fn parse_source_record(&mut self) -> Source {
+        self.try_parse_source_record()
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Parses a single `REPO` record starting at the current line.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the current line does not begin a `REPO` record or the
+    /// record is malformed.
+    pub fn try_parse_repository_record(&mut self) -> Result<Repository, GedcomError> {
+        self.parse_fragment("REPO", Parser::parse_repository)
+    }
+
+    /// Parses a single `REPO` record like `try_parse_repository_record`, panicking on errors.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the current line does not begin a `REPO` record or the record is malformed.
+    pub fn parse_repository_record(&mut self) -> Repository {
+        self.try_parse_repository_record()
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Parses a single `tag` record with `parse`, given its level & xref
+    fn parse_fragment<T>(
+        &mut self,
+        tag: &str,
+        parse: impl FnOnce(&mut Self, u8, Option<String>) -> Result<T, GedcomError>,
+    ) -> Result<T, GedcomError> {
+        let result = self
+            .take_error()
+            .and_then(|()| self.take_record_start_for(tag))
+            .and_then(|(level, xref)| parse(self, level, xref));
+        self.take_tokenizer_findings();
+        result
+    }
+
+    /// Returns the error found while reading the first line, if there was one
+    fn take_error(&mut self) -> Result<(), GedcomError> {
+        self.error.take().map_or(Ok(()), Err)
     }
 
     /// Parses HEAD top-level tag
-    fn parse_header(&mut self) -> Header {
+    fn parse_header(&mut self) -> Result<Header, GedcomError> {
         // skip over HEAD tag name
-        self.tokenizer.next_token();
+        self.next_token()?;
 
         let mut header = Header::default();
 
         // just skipping the header for now
-        while self.tokenizer.current_token != Token::Level(0) {
-            match &self.tokenizer.current_token {
-                Token::Tag(tag) => match tag.as_str() {
-                    // TODO: CHAR.VERS
-                    "CHAR" => header.encoding = Some(self.take_line_value()),
-                    "CORP" => header.corporation = Some(self.take_line_value()),
-                    "COPR" => header.copyright = Some(self.take_line_value()),
-                    "DATE" => header.date = Some(self.take_line_value()),
-                    "DEST" => header.add_destination(self.take_line_value()),
-                    "LANG" => header.language = Some(self.take_line_value()),
-                    "FILE" => header.filename = Some(self.take_line_value()),
-                    "NOTE" => header.note = Some(self.take_continued_text(1)),
-                    "SUBM" => header.submitter_tag = Some(self.take_line_value()),
-                    "SUBN" => header.submission_tag = Some(self.take_line_value()),
+        while !self.at_end_of(0) {
+            match &self.tokenizer.current_token {
+                Token::Tag(tag) => match tag.as_str() {
+                    "CHAR" => self.parse_character_set(&mut header)?,
+                    "CORP" => header.corporation = Some(self.take_line_value()?),
+                    "COPR" => header.copyright = Some(self.take_line_value()?),
+                    "DATE" => header.date = Some(self.take_line_value()?),
+                    "DEST" => header.add_destination(self.take_line_value()?),
+                    "LANG" => header.languages.push(self.take_line_value()?),
+                    "FILE" => header.filename = Some(self.take_line_value()?),
+                    "NOTE" => header.notes.push(self.parse_note(1)?),
+                    "SUBM" => header.submitter_tag = Some(self.take_line_value()?),
+                    "SUBN" => header.submission_tag = Some(self.take_line_value()?),
+                    "PLAC" => {
+                        self.next_token()?;
+                        header.place_form = self.parse_place_form(1)?;
+                    }
                     "TIME" => {
-                        let time = self.take_line_value();
+                        let time = self.take_line_value()?;
                         // assuming subtag of DATE
                         if let Some(date) = header.date {
                             let mut datetime = String::new();
                             datetime.push_str(&date);
-                            datetime.push_str(" ");
+                            datetime.push(' ');
                             datetime.push_str(&time);
                             header.date = Some(datetime);
                         } else {
-                            panic!("Expected TIME to be under DATE in header.");
+                            return Err(self.malformed("Expected TIME to be under DATE in header."));
                         }
                     }
                     "GEDC" => {
-                        header = self.parse_gedcom_data(header);
+                        header = self.parse_gedcom_data(header)?;
                     }
-                    // TODO: HeaderSource
                     "SOUR" => {
-                        println!("WARNING: Skipping header source.");
-                        while self.tokenizer.current_token != Token::Level(1) {
-                            self.tokenizer.next_token();
+                        let source = self.parse_header_source(1)?;
+                        if self.options.conc_join == ConcJoin::Auto {
+                            self.conc_space = matches!(
+                                source.producer(),
+                                Producer::AncestryFamilyTrees | Producer::FamilyTreeMaker
+                            );
                         }
+                        header.source = Some(source);
+                    }
+                    "SCHMA" => {
+                        self.next_token()?;
+                        header.schema = self.parse_schema(1)?;
                     }
-                    _ => panic!("{} Unhandled Header Tag: {}", self.dbg(), tag),
+                    _ => return Err(self.unexpected_tag("Header")),
                 },
-                Token::Level(_) => self.tokenizer.next_token(),
-                _ => panic!("Unhandled Header Token: {:?}", self.tokenizer.current_token),
+                Token::CustomTag(tag) => {
+                    let tag_clone = tag.clone();
+                    header.add_custom_data(self.parse_custom_tag(tag_clone, 1)?);
+                }
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("Header")),
             }
         }
-        header
+        Ok(header)
     }
 
     /// Parses SUBM top-level tag
-    fn parse_submitter(&mut self, level: u8, xref: Option<String>) -> Submitter {
+    fn parse_submitter(
+        &mut self,
+        level: u8,
+        xref: Option<String>,
+    ) -> Result<Submitter, GedcomError> {
         // skip over SUBM tag name
-        self.tokenizer.next_token();
+        self.next_token()?;
 
         let mut submitter = Submitter::new(xref);
-        while self.tokenizer.current_token != Token::Level(level) {
+        while !self.at_end_of(level) {
             match &self.tokenizer.current_token {
                 Token::Tag(tag) => match tag.as_str() {
-                    "NAME" => submitter.name = Some(self.take_line_value()),
+                    "NAME" => submitter.name = Some(self.take_line_value()?),
                     "ADDR" => {
-                        submitter.address = Some(self.parse_address(level + 1));
+                        submitter.address = Some(self.parse_address(level + 1)?);
                     }
-                    "PHON" => submitter.phone = Some(self.take_line_value()),
-                    _ => panic!("{} Unhandled Submitter Tag: {}", self.dbg(), tag),
+                    "PHON" => submitter.phone = Some(self.take_line_value()?),
+                    "LANG" => submitter.languages.push(self.take_line_value()?),
+                    "NOTE" => submitter.notes.push(self.parse_note(level + 1)?),
+                    "CHAN" => submitter.last_updated = self.parse_change_date(level + 1)?,
+                    _ => return Err(self.unexpected_tag("Submitter")),
                 },
-                Token::Level(_) => self.tokenizer.next_token(),
-                _ => panic!(
-                    "Unhandled Submitter Token: {:?}",
-                    self.tokenizer.current_token
-                ),
+                Token::CustomTag(tag) => {
+                    let tag_clone = tag.clone();
+                    submitter.add_custom_data(self.parse_custom_tag(tag_clone, level + 1)?);
+                }
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("Submitter")),
             }
         }
         // println!("found submitter:\n{:#?}", submitter);
-        submitter
+        Ok(submitter)
     }
 
     /// Parses INDI top-level tag
-    fn parse_individual(&mut self, level: u8, xref: Option<String>) -> Individual {
+    fn parse_individual(
+        &mut self,
+        level: u8,
+        xref: Option<String>,
+    ) -> Result<Individual, GedcomError> {
         // skip over INDI tag name
-        self.tokenizer.next_token();
+        self.next_token()?;
         let mut individual = Individual::new(xref);
 
-        while self.tokenizer.current_token != Token::Level(level) {
+        while !self.at_end_of(level) {
             match &self.tokenizer.current_token {
                 Token::Tag(tag) => match tag.as_str() {
-                    "NAME" => individual.name = Some(self.parse_name(level + 1)),
-                    "SEX" => individual.sex = self.parse_gender(),
+                    "NAME" => individual.names.push(self.parse_name(level + 1)?),
+                    "SEX" => individual.sex = self.parse_gender()?,
+                    "NCHI" => {
+                        individual.num_children = self.take_child_count()?;
+                        // the date & sources of the count aren't kept
+                        self.skip_subordinates(level + 1)?;
+                    }
                     "ADOP" | "BIRT" | "BAPM" | "BARM" | "BASM" | "BLES" | "BURI" | "CENS"
                     | "CHR" | "CHRA" | "CONF" | "CREM" | "DEAT" | "EMIG" | "FCOM" | "GRAD"
                     | "IMMI" | "NATU" | "ORDN" | "RETI" | "RESI" | "PROB" | "WILL" | "EVEN" => {
                         let tag_clone = tag.clone();
-                        individual.add_event(self.parse_event(tag_clone.as_str(), level + 1));
+                        individual.add_event(self.parse_event(tag_clone.as_str(), level + 1)?);
                     }
                     "FAMC" | "FAMS" => {
                         let tag_clone = tag.clone();
                         individual
-                            .add_family(self.parse_family_link(tag_clone.as_str(), level + 1));
+                            .add_family(self.parse_family_link(tag_clone.as_str(), level + 1)?);
+                    }
+                    "OBJE" => individual
+                        .multimedia
+                        .push(self.parse_multimedia_link(level + 1)?),
+                    "ASSO" => individual
+                        .associations
+                        .push(self.parse_association(level + 1)?),
+                    "BAPL" | "CONL" | "ENDL" | "SLGC" => {
+                        let tag_clone = tag.clone();
+                        let ordinance = self.parse_lds_ordinance(&tag_clone, level + 1)?;
+                        individual.lds_ordinances.push(ordinance);
                     }
-                    "CHAN" => {
-                        // assuming it always only has a single DATE subtag
-                        self.tokenizer.next_token(); // level
-                        self.tokenizer.next_token(); // DATE tag
-                        individual.last_updated = Some(self.take_line_value());
+                    "CHAN" => individual.last_updated = self.parse_change_date(level + 1)?,
+                    "CREA" => individual.created = self.parse_change_date(level + 1)?,
+                    "NOTE" => individual.notes.push(self.parse_note(level + 1)?),
+                    "RESN" => individual.restriction_notice = Some(self.take_line_value()?),
+                    "RFN" => {
+                        individual.permanent_record_file_number = Some(self.take_line_value()?);
                     }
-                    _ => panic!("{} Unhandled Individual Tag: {}", self.dbg(), tag),
+                    "AFN" => individual.ancestral_file_number = Some(self.take_line_value()?),
+                    "REFN" => individual
+                        .user_reference_numbers
+                        .push(self.parse_user_reference_number(level + 1)?),
+                    "RIN" => individual.automated_record_id = Some(self.take_line_value()?),
+                    _ => return Err(self.unexpected_tag("Individual")),
                 },
                 Token::CustomTag(tag) => {
                     let tag_clone = tag.clone();
-                    individual.add_custom_data(self.parse_custom_tag(tag_clone))
+                    individual.add_custom_data(self.parse_custom_tag(tag_clone, level + 1)?);
                 }
-                Token::Level(_) => self.tokenizer.next_token(),
-                _ => panic!(
-                    "Unhandled Individual Token: {:?}",
-                    self.tokenizer.current_token
-                ),
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("Individual")),
             }
         }
         // println!("found individual:\n{:#?}", individual);
-        individual
+        Ok(individual)
     }
 
     /// Parses FAM top-level tag
-    fn parse_family(&mut self, level: u8, xref: Option<String>) -> Family {
+    fn parse_family(&mut self, level: u8, xref: Option<String>) -> Result<Family, GedcomError> {
         // skip over FAM tag name
-        self.tokenizer.next_token();
+        self.next_token()?;
         let mut family = Family::new(xref);
 
-        while self.tokenizer.current_token != Token::Level(level) {
+        while !self.at_end_of(level) {
             match &self.tokenizer.current_token {
                 Token::Tag(tag) => match tag.as_str() {
-                    "MARR" => family.add_event(self.parse_event("MARR", level + 1)),
-                    "HUSB" => family.set_individual1(self.take_line_value()),
-                    "WIFE" => family.set_individual2(self.take_line_value()),
-                    "CHIL" => family.add_child(self.take_line_value()),
-                    _ => panic!("{} Unhandled Family Tag: {}", self.dbg(), tag),
+                    "MARR" | "DIV" => {
+                        let tag_clone = tag.clone();
+                        let duplicate = family
+                            .events
+                            .iter()
+                            .find(|e| EventType::from_tag(&tag_clone).as_ref() == Some(&e.event));
+                        if let Some(duplicate) = duplicate {
+                            let message = format!(
+                                "Family has more than one {:?} event, recording both",
+                                duplicate.event
+                            );
+                            self.warn(message);
+                        }
+                        family.add_event(self.parse_event(tag_clone.as_str(), level + 1)?);
+                    }
+                    "HUSB" | "WIFE" => {
+                        let parent_tag = if tag == "HUSB" {
+                            ParentTag::Husband
+                        } else {
+                            ParentTag::Wife
+                        };
+                        self.add_parent(&mut family, parent_tag)?;
+                    }
+                    "CHIL" => family.add_child(self.take_line_value()?),
+                    "NCHI" => family.num_children = self.take_child_count()?,
+                    "SUBM" => family.submitters.push(self.take_line_value()?),
+                    "RESN" => family.restriction_notice = Some(self.take_line_value()?),
+                    "REFN" => family
+                        .user_reference_numbers
+                        .push(self.parse_user_reference_number(level + 1)?),
+                    "RIN" => family.automated_record_id = Some(self.take_line_value()?),
+                    "SLGS" => {
+                        let ordinance = self.parse_lds_ordinance("SLGS", level + 1)?;
+                        family.lds_ordinances.push(ordinance);
+                    }
+                    "NOTE" => family.notes.push(self.parse_note(level + 1)?),
+                    "CHAN" => family.last_updated = self.parse_change_date(level + 1)?,
+                    "CREA" => family.created = self.parse_change_date(level + 1)?,
+                    _ => return Err(self.unexpected_tag("Family")),
                 },
-                Token::Level(_) => self.tokenizer.next_token(),
-                _ => panic!("Unhandled Family Token: {:?}", self.tokenizer.current_token),
+                Token::CustomTag(tag) => {
+                    let tag_clone = tag.clone();
+                    family.add_custom_data(self.parse_custom_tag(tag_clone, level + 1)?);
+                }
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("Family")),
             }
         }
 
         // println!("found family:\n{:#?}", family);
-        family
+        Ok(family)
+    }
+
+    /// Adds a parent to the family, flagging duplicate `HUSB` & `WIFE` lines.
+    fn add_parent(
+        &mut self,
+        family: &mut Family,
+        parent_tag: ParentTag,
+    ) -> Result<(), GedcomError> {
+        let line = self.line();
+        let xref = self.take_line_value()?;
+        let tag = parent_tag.tag();
+        let taken = match parent_tag {
+            ParentTag::Husband => family.individual1.is_some(),
+            ParentTag::Wife => family.individual2.is_some(),
+        };
+        match family.add_parent(xref.clone(), parent_tag) {
+            Ok(()) if taken => self.warn_at(
+                line,
+                format!("Family has more than one {tag}, recording {xref} as the other parent"),
+            ),
+            Ok(()) => (),
+            Err(_) => self.warn_at(
+                line,
+                format!("Family already has two parents, ignoring {tag} {xref}"),
+            ),
+        }
+        Ok(())
     }
 
-    fn parse_source(&mut self, level: u8, xref: Option<String>) -> Source {
+    fn parse_source(&mut self, level: u8, xref: Option<String>) -> Result<Source, GedcomError> {
         // skip SOUR tag
-        self.tokenizer.next_token();
+        self.next_token()?;
         let mut source = Source::new(xref);
 
-        loop {
-            if let Token::Level(cur_level) = self.tokenizer.current_token {
-                if cur_level <= level {
-                    break;
+        while !self.at_end_of(level) {
+            match &self.tokenizer.current_token {
+                Token::Tag(tag) => match tag.as_str() {
+                    "DATA" => self.parse_source_data(&mut source.data, level + 1)?,
+                    "ABBR" => source.abbreviation = Some(self.take_continued_text(level + 1)?),
+                    "TITL" => source.title = Some(self.take_continued_text(level + 1)?),
+                    "TEXT" => source.text = Some(self.take_continued_text(level + 1)?),
+                    "REPO" => source.add_repo_citation(self.parse_repo_citation(level + 1)?),
+                    "NOTE" => source.notes.push(self.parse_note(level + 1)?),
+                    _ => return Err(self.unexpected_tag("Source")),
+                },
+                Token::CustomTag(tag) => {
+                    let tag_clone = tag.clone();
+                    source.add_custom_data(self.parse_custom_tag(tag_clone, level + 1)?);
                 }
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("Source")),
             }
+        }
+
+        // println!("found source:\n{:#?}", source);
+        Ok(source)
+    }
+
+    /// Parses the `DATA` of a source record: the events it recorded, the agency
+    /// responsible for them & notes about them
+    fn parse_source_data(&mut self, data: &mut SourceData, level: u8) -> Result<(), GedcomError> {
+        self.next_token()?;
+        while !self.at_end_of(level) {
             match &self.tokenizer.current_token {
                 Token::Tag(tag) => match tag.as_str() {
-                    "DATA" => self.tokenizer.next_token(),
                     "EVEN" => {
-                        let events_recorded = self.take_line_value();
-                        let mut event = self.parse_event("OTHER", level + 2);
+                        // `parse_event` moves past the value, as it does past the tag
+                        // of other events
+                        self.next_token()?;
+                        let Token::LineValue(events_recorded) = &self.tokenizer.current_token
+                        else {
+                            return Err(self.malformed("Expected the events recorded"));
+                        };
+                        let events_recorded = events_recorded.clone();
+                        let mut event = self.parse_event("OTHER", level + 1)?;
                         event.with_source_data(events_recorded);
-                        source.data.add_event(event);
+                        data.add_event(event);
                     }
-                    "AGNC" => source.data.agency = Some(self.take_line_value()),
-                    "ABBR" => source.abbreviation = Some(self.take_continued_text(level + 1)),
-                    "TITL" => source.title = Some(self.take_continued_text(level + 1)),
-                    "REPO" => source.add_repo_citation(self.parse_repo_citation(level + 1)),
-                    _ => panic!("{} Unhandled Source Tag: {}", self.dbg(), tag),
+                    "AGNC" => data.agency = Some(self.take_line_value()?),
+                    "NOTE" => data.notes.push(self.parse_note(level + 1)?),
+                    _ => return Err(self.unexpected_tag("Source Data")),
                 },
-                Token::Level(_) => self.tokenizer.next_token(),
-                _ => panic!("Unhandled Source Token: {:?}", self.tokenizer.current_token),
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("Source Data")),
             }
         }
-
-        // println!("found source:\n{:#?}", source);
-        source
+        Ok(())
     }
 
     /// Parses REPO top-level tag.
-    fn parse_repository(&mut self, level: u8, xref: Option<String>) -> Repository {
+    fn parse_repository(
+        &mut self,
+        level: u8,
+        xref: Option<String>,
+    ) -> Result<Repository, GedcomError> {
         // skip REPO tag
-        self.tokenizer.next_token();
-        let mut repo = Repository {
-            xref,
-            name: None,
-            address: None,
+        self.next_token()?;
+        let mut repo = Repository::new(xref);
+        while !self.at_end_of(level) {
+            match &self.tokenizer.current_token {
+                Token::Tag(tag) => match tag.as_str() {
+                    "NAME" => repo.name = Some(self.take_line_value()?),
+                    "ADDR" => repo.address = Some(self.parse_address(level + 1)?),
+                    "PHON" => repo.phones.push(self.take_line_value()?),
+                    "EMAIL" => repo.emails.push(self.take_line_value()?),
+                    "FAX" => repo.faxes.push(self.take_line_value()?),
+                    "WWW" => repo.websites.push(self.take_line_value()?),
+                    "NOTE" => repo.notes.push(self.parse_note(level + 1)?),
+                    "REFN" => repo
+                        .user_reference_numbers
+                        .push(self.parse_user_reference_number(level + 1)?),
+                    "RIN" => repo.automated_record_id = Some(self.take_line_value()?),
+                    "CHAN" => repo.last_updated = self.parse_change_date(level + 1)?,
+                    _ => return Err(self.unexpected_tag("Repository")),
+                },
+                Token::CustomTag(tag) => {
+                    let tag_clone = tag.clone();
+                    repo.add_custom_data(self.parse_custom_tag(tag_clone, level + 1)?);
+                }
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("Repository")),
+            }
+        }
+        // println!("found repositiory:\n{:#?}", repo);
+        Ok(repo)
+    }
+
+    /// Parses OBJE top-level tag. Tags that aren't modeled yet are skipped with a warning.
+    fn parse_multimedia(
+        &mut self,
+        level: u8,
+        xref: Option<String>,
+    ) -> Result<MultimediaRecord, GedcomError> {
+        // skip OBJE tag
+        self.next_token()?;
+        let mut multimedia = MultimediaRecord::new(xref);
+        self.parse_multimedia_lines(level, &mut multimedia, false)?;
+        Ok(multimedia)
+    }
+
+    /// Parses the lines of a multimedia record, or of an object embedded in a link
+    fn parse_multimedia_lines(
+        &mut self,
+        level: u8,
+        multimedia: &mut MultimediaRecord,
+        embedded: bool,
+    ) -> Result<(), GedcomError> {
+        while !self.at_end_of(level) {
+            match &self.tokenizer.current_token {
+                Token::Tag(tag) => match tag.as_str() {
+                    "FILE" => multimedia.add_file(self.parse_multimedia_file(level + 1)?),
+                    // Ancestry writes FORM & TITL of records as siblings of FILE, where
+                    // embedded objects have them
+                    "FORM" => {
+                        if !embedded {
+                            self.flag_nonstandard(
+                                "FORM of multimedia record should be under its FILE",
+                            );
+                        }
+                        multimedia.form = Some(self.take_line_value()?);
+                    }
+                    "TITL" => {
+                        if !embedded {
+                            self.flag_nonstandard(
+                                "TITL of multimedia record should be under its FILE",
+                            );
+                        }
+                        multimedia.title = Some(self.take_line_value()?);
+                    }
+                    "NOTE" => multimedia.notes.push(self.parse_note(level + 1)?),
+                    "CHAN" => multimedia.last_updated = self.parse_change_date(level + 1)?,
+                    "CREA" => multimedia.created = self.parse_change_date(level + 1)?,
+                    _ => {
+                        let message = format!("Skipping Multimedia Tag: {tag}");
+                        self.warn(message);
+                        self.skip_subordinates(level + 1)?;
+                    }
+                },
+                Token::CustomTag(tag) => {
+                    let tag_clone = tag.clone();
+                    multimedia.add_custom_data(self.parse_custom_tag(tag_clone, level + 1)?);
+                }
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("Multimedia")),
+            }
+        }
+        Ok(())
+    }
+
+    /// Parses the FILE tag of a multimedia record
+    fn parse_multimedia_file(&mut self, level: u8) -> Result<MultimediaFileRefn, GedcomError> {
+        let mut file = MultimediaFileRefn::new(self.take_line_value()?);
+
+        while !self.at_end_of(level) {
+            match &self.tokenizer.current_token {
+                Token::Tag(tag) => match tag.as_str() {
+                    "FORM" => file.form = Some(self.take_line_value()?),
+                    "MEDI" | "TYPE" => file.source_media_type = Some(self.take_line_value()?),
+                    "TITL" => file.title = Some(self.take_line_value()?),
+                    _ => return Err(self.unexpected_tag("Multimedia File")),
+                },
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("Multimedia File")),
+            }
+        }
+        Ok(file)
+    }
+
+    /// Parses a CHAN or CREA tag into its date, joining a TIME onto the DATE value like
+    /// the header does. Notes on the change are skipped.
+    fn parse_change_date(&mut self, level: u8) -> Result<Option<String>, GedcomError> {
+        self.next_token()?;
+        let mut date: Option<String> = None;
+
+        while !self.at_end_of(level) {
+            match &self.tokenizer.current_token {
+                Token::Tag(tag) => match tag.as_str() {
+                    "DATE" => date = Some(self.take_line_value()?),
+                    "TIME" => {
+                        let time = self.take_line_value()?;
+                        if Time::parse(&time).is_none() {
+                            self.warn(format!("Invalid change time: {time}"));
+                        }
+                        date = date.map(|date| format!("{date} {time}"));
+                    }
+                    "NOTE" => self.skip_subordinates(level + 1)?,
+                    _ => return Err(self.unexpected_tag("Change Date")),
+                },
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("Change Date")),
+            }
+        }
+        Ok(date)
+    }
+
+    /// Parses a user-defined tag along with all of its subordinate lines, whether
+    /// they are custom tags or not.
+    fn parse_custom_tag(&mut self, tag: String, level: u8) -> Result<CustomData, GedcomError> {
+        let mut custom_data = CustomData {
+            tag,
+            value: self.take_optional_line_value()?.unwrap_or_default(),
+            children: Vec::new(),
         };
-        loop {
-            if let Token::Level(cur_level) = self.tokenizer.current_token {
-                if cur_level <= level {
-                    break;
+
+        while !self.at_end_of(level) {
+            match &self.tokenizer.current_token {
+                Token::Tag(tag) | Token::CustomTag(tag) => {
+                    // keep the tag as written, so it is written back unchanged
+                    let tag_clone = self
+                        .tokenizer
+                        .original_tag()
+                        .map_or_else(|| tag.clone(), str::to_string);
+                    custom_data
+                        .children
+                        .push(self.parse_custom_tag(tag_clone, level + 1)?);
+                }
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("CustomData")),
+            }
+        }
+        Ok(custom_data)
+    }
+
+    /// Parses an `OBJE` link to a multimedia record, or an object embedded in the record
+    /// rather than pointed to
+    fn parse_multimedia_link(&mut self, level: u8) -> Result<MultimediaLink, GedcomError> {
+        let Some(xref) = self.take_optional_line_value()? else {
+            let mut multimedia = MultimediaRecord::new(None);
+            self.parse_multimedia_lines(level, &mut multimedia, true)?;
+            // the flags marking the preferred object belong to the link
+            let (flags, custom_data) = std::mem::take(&mut multimedia.custom_data)
+                .into_iter()
+                .partition(|data| MultimediaLink::is_primary_tag(&data.tag));
+            multimedia.custom_data = custom_data;
+            let mut link = MultimediaLink::embedded(multimedia);
+            link.primary = flags.iter().any(MultimediaLink::is_primary_flag);
+            link.custom_data = flags;
+            return Ok(link);
+        };
+        let mut link = MultimediaLink::new(xref);
+
+        while !self.at_end_of(level) {
+            match &self.tokenizer.current_token {
+                Token::CustomTag(tag) => {
+                    let tag_clone = tag.clone();
+                    let custom_data = self.parse_custom_tag(tag_clone, level + 1)?;
+                    link.primary |= MultimediaLink::is_primary_flag(&custom_data);
+                    link.add_custom_data(custom_data);
+                }
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_tag("MultimediaLink")),
+            }
+        }
+        Ok(link)
+    }
+
+    /// Parses a `_LOC` shared location record. Lines not read into the fields of the
+    /// location are kept as its custom data.
+    fn parse_location(&mut self, level: u8, xref: Option<String>) -> Result<Location, GedcomError> {
+        // skip _LOC tag
+        self.next_token()?;
+        let mut location = Location::new(xref);
+        while !self.at_end_of(level) {
+            match &self.tokenizer.current_token {
+                Token::Tag(tag) | Token::CustomTag(tag) => match tag.as_str() {
+                    "NAME" => {
+                        let value = self.take_line_value()?;
+                        let date = self.parse_dated_subordinates(level + 1)?;
+                        location.names.push(LocationName { value, date });
+                    }
+                    "_LOC" => {
+                        let xref = self.take_line_value()?;
+                        let date = self.parse_dated_subordinates(level + 1)?;
+                        location.within.push(LocationLink { xref, date });
+                    }
+                    "TYPE" => location.location_type = Some(self.take_line_value()?),
+                    "MAP" => location.map = self.parse_map(level + 1)?,
+                    "NOTE" => location.notes.push(self.parse_note(level + 1)?),
+                    "CHAN" => location.last_updated = self.parse_change_date(level + 1)?,
+                    _ => {
+                        let tag_clone = self
+                            .tokenizer
+                            .original_tag()
+                            .map_or_else(|| tag.clone(), str::to_string);
+                        let custom_data = self.parse_custom_tag(tag_clone, level + 1)?;
+                        location.add_custom_data(custom_data);
+                    }
+                },
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("Location")),
+            }
+        }
+        Ok(location)
+    }
+
+    /// Parses the subordinates of a line that may be dated, returning the value of its
+    /// `DATE`. Other subordinates are skipped with a warning.
+    fn parse_dated_subordinates(&mut self, level: u8) -> Result<Option<String>, GedcomError> {
+        let mut date = None;
+        while !self.at_end_of(level) {
+            match &self.tokenizer.current_token {
+                Token::Tag(tag) if tag == "DATE" => date = Some(self.take_line_value()?),
+                Token::Tag(tag) | Token::CustomTag(tag) => {
+                    let message = format!("Skipping {tag} of a location");
+                    self.warn(message);
+                    self.next_token()?;
+                    self.skip_subordinates(level + 1)?;
                 }
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("Location")),
             }
+        }
+        Ok(date)
+    }
+
+    /// Parses a MAP tag. Coordinates that aren't numbers of degrees within range are
+    /// left out with a warning.
+    fn parse_map(&mut self, level: u8) -> Result<Option<Map>, GedcomError> {
+        let line = self.tokenizer.line;
+        self.next_token()?;
+        let mut latitude = String::new();
+        let mut longitude = String::new();
+        while !self.at_end_of(level) {
             match &self.tokenizer.current_token {
                 Token::Tag(tag) => match tag.as_str() {
-                    "NAME" => repo.name = Some(self.take_line_value()),
-                    "ADDR" => repo.address = Some(self.parse_address(level + 1)),
-                    _ => panic!("{} Unhandled Repository Tag: {}", self.dbg(), tag),
+                    "LATI" => latitude = self.take_line_value()?,
+                    "LONG" => longitude = self.take_line_value()?,
+                    _ => return Err(self.unexpected_tag("Map")),
                 },
-                Token::Level(_) => self.tokenizer.next_token(),
-                _ => panic!(
-                    "Unhandled Repository Token: {:?}",
-                    self.tokenizer.current_token
-                ),
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("Map")),
+            }
+        }
+        match Map::parse(&latitude, &longitude) {
+            Ok(map) => Ok(Some(map)),
+            Err(error) => {
+                self.warn_at(line, format!("{error}, ignoring MAP"));
+                Ok(None)
             }
         }
-        // println!("found repositiory:\n{:#?}", repo);
-        repo
     }
 
-    fn parse_custom_tag(&mut self, tag: String) -> CustomData {
-        let value = self.take_line_value();
-        CustomData { tag, value }
+    /// Parses the SOUR tag of the header
+    fn parse_header_source(&mut self, level: u8) -> Result<HeaderSource, GedcomError> {
+        let mut source = HeaderSource {
+            value: self.take_optional_line_value()?,
+            ..HeaderSource::default()
+        };
+
+        while !self.at_end_of(level) {
+            match &self.tokenizer.current_token {
+                Token::Tag(tag) => match tag.as_str() {
+                    "VERS" => source.version = Some(self.take_line_value()?),
+                    "NAME" => source.name = Some(self.take_line_value()?),
+                    "CORP" => {
+                        source.corporation = Some(self.take_line_value()?);
+                        // TODO: corporation address
+                        self.skip_subordinates(level + 1)?;
+                    }
+                    // TODO: source data
+                    "DATA" => self.skip_subordinates(level + 1)?,
+                    _ => return Err(self.unexpected_tag("HeaderSource")),
+                },
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("HeaderSource")),
+            }
+        }
+        Ok(source)
     }
 
     /// Handle parsing GEDC tag
-    fn parse_gedcom_data(&mut self, mut header: Header) -> Header {
+    fn parse_gedcom_data(&mut self, mut header: Header) -> Result<Header, GedcomError> {
         // skip GEDC tag
-        self.tokenizer.next_token();
+        self.next_token()?;
 
-        while self.tokenizer.current_token != Token::Level(1) {
+        while !self.at_end_of(1) {
             match &self.tokenizer.current_token {
                 Token::Tag(tag) => match tag.as_str() {
-                    "VERS" => header.gedcom_version = Some(self.take_line_value()),
+                    "VERS" => header.gedcom_version = Some(self.take_line_value()?),
                     // this is the only value that makes sense. warn them otherwise.
                     "FORM" => {
-                        let form = self.take_line_value();
+                        let form = self.take_line_value()?;
                         if &form.to_uppercase() != "LINEAGE-LINKED" {
-                            println!(
-                                "WARNING: Unrecognized GEDCOM form. Expected LINEAGE-LINKED, found {}"
-                            , form);
+                            self.warn(format!(
+                                "Unrecognized GEDCOM form. Expected LINEAGE-LINKED, found {form}"
+                            ));
                         }
                     }
-                    _ => panic!("{} Unhandled GEDC Tag: {}", self.dbg(), tag),
+                    _ => return Err(self.unexpected_tag("GEDC")),
                 },
-                Token::Level(_) => self.tokenizer.next_token(),
-                _ => panic!(
-                    "{} Unexpected GEDC Token: {:?}",
-                    self.dbg(),
-                    &self.tokenizer.current_token
-                ),
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("GEDC")),
             }
         }
-        header
+        Ok(header)
     }
 
-    fn parse_family_link(&mut self, tag: &str, level: u8) -> FamilyLink {
-        let xref = self.take_line_value();
-        let mut link = FamilyLink::new(xref, tag);
+    /// Parses the `CHAR` of the header along with its `VERS`
+    fn parse_character_set(&mut self, header: &mut Header) -> Result<(), GedcomError> {
+        header.encoding = Some(self.take_line_value()?);
 
-        loop {
-            if let Token::Level(cur_level) = self.tokenizer.current_token {
-                if cur_level <= level {
-                    break;
-                }
-            }
+        while !self.at_end_of(1) {
             match &self.tokenizer.current_token {
                 Token::Tag(tag) => match tag.as_str() {
-                    "PEDI" => link.set_pedigree(self.take_line_value().as_str()),
-                    _ => panic!("{} Unhandled FamilyLink Tag: {}", self.dbg(), tag),
+                    "VERS" => header.encoding_version = Some(self.take_line_value()?),
+                    _ => return Err(self.unexpected_tag("CHAR")),
                 },
-                Token::Level(_) => self.tokenizer.next_token(),
-                _ => panic!(
-                    "Unhandled FamilyLink Token: {:?}",
-                    self.tokenizer.current_token
-                ),
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("CHAR")),
             }
         }
+        Ok(())
+    }
 
-        link
+    /// Parses the `TAG` declarations of the `SCHMA` of a 7.0 header. Declarations
+    /// without a URI are skipped with a warning.
+    fn parse_schema(&mut self, level: u8) -> Result<Vec<ExtensionTag>, GedcomError> {
+        let mut schema = Vec::new();
+        while !self.at_end_of(level) {
+            match &self.tokenizer.current_token {
+                Token::Tag(tag) => match tag.as_str() {
+                    "TAG" => {
+                        let line = self.line();
+                        let value = self.take_line_value()?;
+                        match value.trim().split_once(' ') {
+                            Some((tag, uri)) => schema.push(ExtensionTag {
+                                tag: tag.to_string(),
+                                uri: uri.trim().to_string(),
+                            }),
+                            None => self.warn_at(
+                                line,
+                                format!("Skipping extension tag without a URI: {value}"),
+                            ),
+                        }
+                    }
+                    _ => return Err(self.unexpected_tag("Schema")),
+                },
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("Schema")),
+            }
+        }
+        Ok(schema)
     }
 
-    fn parse_repo_citation(&mut self, level: u8) -> RepoCitation {
-        let xref = self.take_line_value();
-        let mut citation = RepoCitation {
-            xref,
-            call_number: None,
-        };
-        loop {
-            if let Token::Level(cur_level) = self.tokenizer.current_token {
-                if cur_level <= level {
-                    break;
-                }
+    fn parse_family_link(&mut self, tag: &str, level: u8) -> Result<FamilyLink, GedcomError> {
+        let xref = self.take_line_value()?;
+        let mut link = FamilyLink::new(xref, tag);
+
+        while !self.at_end_of(level) {
+            match &self.tokenizer.current_token {
+                Token::Tag(tag) => match tag.as_str() {
+                    "PEDI" => {
+                        let value_line = self.tokenizer.line;
+                        let value = self.take_line_value()?;
+                        let Some(pedigree) = Pedigree::parse(&value) else {
+                            let expected = "a PEDI of adopted, birth, foster or sealing";
+                            return Err(Parser::invalid_value(value_line, value, expected));
+                        };
+                        link.pedigree = Some(pedigree);
+                    }
+                    "NOTE" => link.notes.push(self.parse_note(level + 1)?),
+                    "SOUR" => link.citations.push(self.parse_citation(level + 1)?),
+                    _ => return Err(self.unexpected_tag("FamilyLink")),
+                },
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("FamilyLink")),
             }
+        }
+
+        Ok(link)
+    }
+
+    fn parse_repo_citation(&mut self, level: u8) -> Result<RepoCitation, GedcomError> {
+        let value = self.take_optional_line_value()?;
+        let mut inline = Repository::new(None);
+        let mut call_number = None;
+
+        while !self.at_end_of(level) {
             match &self.tokenizer.current_token {
                 Token::Tag(tag) => match tag.as_str() {
-                    "CALN" => citation.call_number = Some(self.take_line_value()),
-                    _ => panic!("{} Unhandled RepoCitation Tag: {}", self.dbg(), tag),
+                    "CALN" => call_number = Some(self.take_line_value()?),
+                    "NAME" => inline.name = Some(self.take_line_value()?),
+                    "ADDR" => inline.address = Some(self.parse_address(level + 1)?),
+                    _ => return Err(self.unexpected_tag("RepoCitation")),
                 },
-                Token::Level(_) => self.tokenizer.next_token(),
-                _ => panic!(
-                    "Unhandled RepoCitation Token: {:?}",
-                    self.tokenizer.current_token
-                ),
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("RepoCitation")),
             }
         }
-        citation
+
+        let repository = match value {
+            Some(xref) if xref.starts_with('@') && xref.ends_with('@') => {
+                if inline.name.is_some() || inline.address.is_some() {
+                    self.warn(format!(
+                        "Ignoring inline description of cited repository {xref}"
+                    ));
+                }
+                RepoReference::Pointer(xref)
+            }
+            // some exports write the repository name as the value
+            Some(name) => {
+                inline.name.get_or_insert(name);
+                RepoReference::Inline(Box::new(inline))
+            }
+            None => RepoReference::Inline(Box::new(inline)),
+        };
+        Ok(RepoCitation {
+            repository,
+            call_number,
+        })
     }
 
-    fn parse_gender(&mut self) -> Gender {
-        self.tokenizer.next_token();
+    fn parse_gender(&mut self) -> Result<Gender, GedcomError> {
+        self.next_token()?;
         let gender: Gender;
         if let Token::LineValue(gender_string) = &self.tokenizer.current_token {
             gender = match gender_string.as_str() {
                 "M" => Gender::Male,
                 "F" => Gender::Female,
-                "N" => Gender::Nonbinary,
+                // X is the GEDCOM 7.0 value, N is used by some older exports
+                "N" | "X" => Gender::Nonbinary,
                 "U" => Gender::Unknown,
-                _ => panic!("{} Unknown gender value {}", self.dbg(), gender_string),
+                _ => {
+                    let value = gender_string.clone();
+                    let line = self.tokenizer.line;
+                    return Err(Parser::invalid_value(line, value, "a SEX of M, F, X or U"));
+                }
             };
         } else {
-            panic!(
+            let message = format!(
                 "Expected gender LineValue, found {:?}",
                 self.tokenizer.current_token
             );
+            return Err(self.malformed(&message));
         }
-        self.tokenizer.next_token();
-        gender
+        self.next_token()?;
+        Ok(gender)
     }
 
-    fn parse_name(&mut self, level: u8) -> Name {
-        let mut name = Name::default();
-        name.value = Some(self.take_line_value());
+    fn parse_name(&mut self, level: u8) -> Result<Name, GedcomError> {
+        let mut name = Name {
+            value: Some(self.take_line_value()?),
+            ..Name::default()
+        };
 
-        loop {
-            if let Token::Level(cur_level) = self.tokenizer.current_token {
-                if cur_level <= level {
-                    break;
+        while !self.at_end_of(level) {
+            match &self.tokenizer.current_token {
+                Token::Tag(tag) => match tag.as_str() {
+                    "GIVN" => name.given = Some(self.take_line_value()?),
+                    "NICK" => name.nickname = Some(self.take_line_value()?),
+                    "NPFX" => name.prefix = Some(self.take_line_value()?),
+                    "NSFX" => name.suffix = Some(self.take_line_value()?),
+                    "SPFX" => name.surname_prefix = Some(self.take_line_value()?),
+                    "SURN" => name.surname = Some(self.take_line_value()?),
+                    "NOTE" => name.notes.push(self.parse_note(level + 1)?),
+                    "SOUR" => name.citations.push(self.parse_citation(level + 1)?),
+                    "TYPE" => name.name_type = Some(self.take_line_value()?),
+                    "LANG" => name.language = Some(self.take_line_value()?),
+                    "FONE" => name.phonetic.push(self.parse_name_variation(level + 1)?),
+                    "ROMN" => name.romanized.push(self.parse_name_variation(level + 1)?),
+                    _ => return Err(self.unexpected_tag("Name")),
+                },
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("Name")),
+            }
+        }
+
+        Ok(name)
+    }
+
+    /// Parses a FONE or ROMN variation of a name, with its own name pieces
+    fn parse_name_variation(&mut self, level: u8) -> Result<NameVariation, GedcomError> {
+        let mut variation = NameVariation {
+            value: self.take_line_value()?,
+            ..NameVariation::default()
+        };
+        while !self.at_end_of(level) {
+            match &self.tokenizer.current_token {
+                Token::Tag(tag) => match tag.as_str() {
+                    "TYPE" => variation.variation_type = Some(self.take_line_value()?),
+                    "GIVN" => variation.given = Some(self.take_line_value()?),
+                    "NICK" => variation.nickname = Some(self.take_line_value()?),
+                    "NPFX" => variation.prefix = Some(self.take_line_value()?),
+                    "NSFX" => variation.suffix = Some(self.take_line_value()?),
+                    "SPFX" => variation.surname_prefix = Some(self.take_line_value()?),
+                    "SURN" => variation.surname = Some(self.take_line_value()?),
+                    "NOTE" => variation.notes.push(self.parse_note(level + 1)?),
+                    "SOUR" => variation.citations.push(self.parse_citation(level + 1)?),
+                    _ => return Err(self.unexpected_tag("Name Variation")),
+                },
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("Name Variation")),
+            }
+        }
+        Ok(variation)
+    }
+
+    fn parse_event(&mut self, tag: &str, level: u8) -> Result<Event, GedcomError> {
+        let Some(etype) = EventType::from_tag(tag) else {
+            return Err(self.unexpected_tag("Event"));
+        };
+        self.next_token()?;
+        let mut event = Event::new(etype);
+        if let Token::LineValue(value) = &self.tokenizer.current_token {
+            if value.trim() == "Y" {
+                event.occurred = true;
+            } else {
+                let message = format!("Skipping the value {value} of the {tag} event");
+                self.warn(message);
+            }
+            self.next_token()?;
+        }
+        while !self.at_end_of(level) {
+            match &self.tokenizer.current_token {
+                Token::Tag(tag) => match tag.as_str() {
+                    "TYPE" => event.classification = Some(self.take_line_value()?),
+                    "DATE" => event.date = Some(self.take_line_value()?),
+                    "PLAC" => event.place = Some(self.parse_place(level + 1)?),
+                    "ADDR" => event.address = Some(self.parse_address(level + 1)?),
+                    "PHON" => event.phones.push(self.take_line_value()?),
+                    "EMAIL" => event.emails.push(self.take_line_value()?),
+                    "FAX" => event.faxes.push(self.take_line_value()?),
+                    "WWW" => event.websites.push(self.take_line_value()?),
+                    "AGNC" => event.agency = Some(self.take_line_value()?),
+                    "RELI" => event.religion = Some(self.take_line_value()?),
+                    "CAUS" => event.cause = Some(self.take_line_value()?),
+                    "RESN" => event.restriction_notice = Some(self.take_line_value()?),
+                    "AGE" => event.age = Some(self.take_line_value()?),
+                    "FAMC" => {
+                        event.family = Some(self.take_line_value()?);
+                        while !self.at_end_of(level + 1) {
+                            match &self.tokenizer.current_token {
+                                Token::Tag(tag) if tag == "ADOP" => {
+                                    event.adopted_by = Some(self.take_line_value()?);
+                                }
+                                Token::Level(_) => self.next_token()?,
+                                _ => return Err(self.unexpected_tag("Event Family")),
+                            }
+                        }
+                    }
+                    "SOUR" => event.add_citation(self.parse_citation(level + 1)?),
+                    "ASSO" => event.associations.push(self.parse_association(level + 1)?),
+                    "NOTE" => event.notes.push(self.parse_note(level + 1)?),
+                    "OBJE" => event
+                        .multimedia
+                        .push(self.parse_multimedia_link(level + 1)?),
+                    _ => return Err(self.unexpected_tag("Event")),
+                },
+                Token::CustomTag(tag) => {
+                    let tag_clone = tag.clone();
+                    event.add_custom_data(self.parse_custom_tag(tag_clone, level + 1)?);
                 }
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("Event")),
             }
+        }
+        Ok(event)
+    }
+
+    /// Parses an LDS ordinance, ie. `BAPL`
+    fn parse_lds_ordinance(&mut self, tag: &str, level: u8) -> Result<LdsOrdinance, GedcomError> {
+        let Some(ordinance) = LdsOrdinanceType::from_tag(tag) else {
+            return Err(self.unexpected_tag("LDS Ordinance"));
+        };
+        self.next_token()?;
+        let mut ordinance = LdsOrdinance::new(ordinance);
+        while !self.at_end_of(level) {
             match &self.tokenizer.current_token {
                 Token::Tag(tag) => match tag.as_str() {
-                    "GIVN" => name.given = Some(self.take_line_value()),
-                    "NPFX" => name.prefix = Some(self.take_line_value()),
-                    "NSFX" => name.suffix = Some(self.take_line_value()),
-                    "SPFX" => name.surname_prefix = Some(self.take_line_value()),
-                    "SURN" => name.surname = Some(self.take_line_value()),
-                    _ => panic!("{} Unhandled Name Tag: {}", self.dbg(), tag),
+                    "DATE" => ordinance.date = Some(self.take_line_value()?),
+                    "TEMP" => ordinance.temple = Some(self.take_line_value()?),
+                    "PLAC" => ordinance.place = Some(self.parse_place(level + 1)?),
+                    "FAMC" => ordinance.family = Some(self.take_line_value()?),
+                    "STAT" => {
+                        ordinance.status = Some(self.take_line_value()?);
+                        while !self.at_end_of(level + 1) {
+                            match &self.tokenizer.current_token {
+                                Token::Tag(tag) if tag == "DATE" => {
+                                    ordinance.status_date = Some(self.take_line_value()?);
+                                }
+                                Token::Level(_) => self.next_token()?,
+                                _ => return Err(self.unexpected_tag("LDS Ordinance Status")),
+                            }
+                        }
+                    }
+                    "SOUR" => ordinance.citations.push(self.parse_citation(level + 1)?),
+                    "NOTE" => ordinance.notes.push(self.parse_note(level + 1)?),
+                    _ => return Err(self.unexpected_tag("LDS Ordinance")),
                 },
-                Token::Level(_) => self.tokenizer.next_token(),
-                _ => panic!("Unhandled Name Token: {:?}", self.tokenizer.current_token),
+                Token::CustomTag(tag) => {
+                    let tag_clone = tag.clone();
+                    ordinance.add_custom_data(self.parse_custom_tag(tag_clone, level + 1)?);
+                }
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("LDS Ordinance")),
             }
         }
+        Ok(ordinance)
+    }
 
-        name
+    /// Parses a PLAC tag into the place, its `FORM`, variations & `MAP`
+    fn parse_place(&mut self, level: u8) -> Result<Place, GedcomError> {
+        let mut place = Place::new(self.take_line_value()?);
+        while !self.at_end_of(level) {
+            match &self.tokenizer.current_token {
+                Token::Tag(tag) => match tag.as_str() {
+                    "FORM" => place.form = Some(self.take_line_value()?),
+                    "FONE" => place.phonetic.push(self.parse_place_variation(level + 1)?),
+                    "ROMN" => place.romanized.push(self.parse_place_variation(level + 1)?),
+                    "MAP" => place.map = self.parse_map(level + 1)?,
+                    "NOTE" => place.notes.push(self.parse_note(level + 1)?),
+                    _ => return Err(self.unexpected_tag("Place")),
+                },
+                Token::CustomTag(tag) if tag == "_LOC" => {
+                    place.location = Some(self.take_line_value()?);
+                }
+                Token::CustomTag(tag) => {
+                    let tag_clone = tag.clone();
+                    place.add_custom_data(self.parse_custom_tag(tag_clone, level + 1)?);
+                }
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("Place")),
+            }
+        }
+        Ok(place)
     }
 
-    fn parse_event(&mut self, tag: &str, level: u8) -> Event {
-        self.tokenizer.next_token();
-        let mut event = Event::from_tag(tag);
-        loop {
-            if let Token::Level(cur_level) = self.tokenizer.current_token {
-                if cur_level <= level {
-                    break;
+    /// Parses the FONE or ROMN tag of a place
+    fn parse_place_variation(&mut self, level: u8) -> Result<PlaceVariation, GedcomError> {
+        let mut variation = PlaceVariation {
+            value: self.take_line_value()?,
+            variation_type: None,
+        };
+        while !self.at_end_of(level) {
+            match &self.tokenizer.current_token {
+                Token::Tag(tag) => match tag.as_str() {
+                    "TYPE" => variation.variation_type = Some(self.take_line_value()?),
+                    _ => return Err(self.unexpected_tag("Place Variation")),
+                },
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("Place Variation")),
+            }
+        }
+        Ok(variation)
+    }
+
+    /// Parses the subordinates of a PLAC tag, returning the value of its `FORM`
+    fn parse_place_form(&mut self, level: u8) -> Result<Option<String>, GedcomError> {
+        let mut form = None;
+        while !self.at_end_of(level) {
+            match &self.tokenizer.current_token {
+                Token::Tag(tag) => match tag.as_str() {
+                    "FORM" => form = Some(self.take_line_value()?),
+                    _ => return Err(self.unexpected_tag("Place")),
+                },
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("Place")),
+            }
+        }
+        Ok(form)
+    }
+
+    /// Parses a `NOTE` record, the text & the lines about it
+    fn parse_note_record(
+        &mut self,
+        level: u8,
+        xref: Option<String>,
+    ) -> Result<NoteRecord, GedcomError> {
+        let mut text = self.take_optional_line_value()?.unwrap_or_default();
+        let mut record = NoteRecord::new(xref, Note::new(String::new()));
+        let mut language = None;
+
+        while !self.at_end_of(level) {
+            match &self.tokenizer.current_token {
+                Token::Tag(tag) => match tag.as_str() {
+                    "CONT" | "CONC" => self.take_continuation(&mut text)?,
+                    "REFN" => record
+                        .user_reference_numbers
+                        .push(self.parse_user_reference_number(level + 1)?),
+                    "RIN" => record.automated_record_id = Some(self.take_line_value()?),
+                    "SOUR" => record.citations.push(self.parse_citation(level + 1)?),
+                    "CHAN" => record.last_updated = self.parse_change_date(level + 1)?,
+                    "LANG" => language = Some(self.take_line_value()?),
+                    _ => return Err(self.unexpected_tag("Note")),
+                },
+                Token::CustomTag(tag) => {
+                    let tag_clone = tag.clone();
+                    record.add_custom_data(self.parse_custom_tag(tag_clone, level + 1)?);
                 }
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("Note")),
             }
+        }
+        record.text = self.note(text);
+        if let Some(language) = language {
+            record.text = record.text.with_language(language);
+        }
+        Ok(record)
+    }
+
+    /// Parses a `REFN` tag & its `TYPE`
+    fn parse_user_reference_number(
+        &mut self,
+        level: u8,
+    ) -> Result<UserReferenceNumber, GedcomError> {
+        let mut reference = UserReferenceNumber {
+            number: self.take_line_value()?,
+            reference_type: None,
+        };
+        while !self.at_end_of(level) {
             match &self.tokenizer.current_token {
                 Token::Tag(tag) => match tag.as_str() {
-                    "DATE" => event.date = Some(self.take_line_value()),
-                    "PLAC" => event.place = Some(self.take_line_value()),
-                    "SOUR" => event.add_citation(self.parse_citation(level + 1)),
-                    _ => panic!("{} Unhandled Event Tag: {}", self.dbg(), tag),
+                    "TYPE" => reference.reference_type = Some(self.take_line_value()?),
+                    _ => return Err(self.unexpected_tag("User Reference")),
                 },
-                Token::Level(_) => self.tokenizer.next_token(),
-                _ => panic!("Unhandled Event Token: {:?}", self.tokenizer.current_token),
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("User Reference")),
             }
         }
-        event
+        Ok(reference)
     }
 
     /// Parses ADDR tag
-    fn parse_address(&mut self, level: u8) -> Address {
+    fn parse_address(&mut self, level: u8) -> Result<Address, GedcomError> {
         // skip ADDR tag
-        self.tokenizer.next_token();
+        self.next_token()?;
         let mut address = Address::default();
         let mut value = String::new();
 
         // handle value on ADDR line
         if let Token::LineValue(addr) = &self.tokenizer.current_token {
             value.push_str(addr);
-            self.tokenizer.next_token();
+            self.next_token()?;
         }
 
-        loop {
-            if let Token::Level(cur_level) = self.tokenizer.current_token {
-                if cur_level <= level {
-                    break;
-                }
-            }
+        while !self.at_end_of(level) {
             match &self.tokenizer.current_token {
                 Token::Tag(tag) => match tag.as_str() {
                     "CONT" => {
                         value.push('\n');
-                        value.push_str(&self.take_line_value());
+                        value.push_str(&self.take_optional_line_value()?.unwrap_or_default());
                     }
-                    "ADR1" => address.adr1 = Some(self.take_line_value()),
-                    "ADR2" => address.adr2 = Some(self.take_line_value()),
-                    "ADR3" => address.adr3 = Some(self.take_line_value()),
-                    "CITY" => address.city = Some(self.take_line_value()),
-                    "STAE" => address.state = Some(self.take_line_value()),
-                    "POST" => address.post = Some(self.take_line_value()),
-                    "CTRY" => address.country = Some(self.take_line_value()),
-                    _ => panic!("{} Unhandled Address Tag: {}", self.dbg(), tag),
+                    "CONC" => value.push_str(&self.take_line_value()?),
+                    "ADR1" => address.adr1 = Some(self.take_line_value()?),
+                    "ADR2" => address.adr2 = Some(self.take_line_value()?),
+                    "ADR3" => address.adr3 = Some(self.take_line_value()?),
+                    "CITY" => address.city = Some(self.take_line_value()?),
+                    "STAE" => address.state = Some(self.take_line_value()?),
+                    "POST" => address.post = Some(self.take_line_value()?),
+                    "CTRY" => address.country = Some(self.take_line_value()?),
+                    _ => return Err(self.unexpected_tag("Address")),
                 },
-                Token::Level(_) => self.tokenizer.next_token(),
-                _ => panic!(
-                    "Unhandled Address Token: {:?}",
-                    self.tokenizer.current_token
-                ),
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("Address")),
             }
         }
 
-        if &value != "" {
+        if !value.is_empty() {
             address.value = Some(value);
         }
 
-        address
+        Ok(address)
     }
 
-    fn parse_citation(&mut self, level: u8) -> SourceCitation {
+    fn parse_citation(&mut self, level: u8) -> Result<SourceCitation, GedcomError> {
         let mut citation = SourceCitation {
-            xref: self.take_line_value(),
+            xref: self.take_line_value()?,
             page: None,
+            date: None,
+            texts: Vec::new(),
+            event: None,
+            role: None,
+            quality: None,
+            notes: Vec::new(),
         };
-        loop {
-            if let Token::Level(cur_level) = self.tokenizer.current_token {
-                if cur_level <= level {
-                    break;
-                }
+        while !self.at_end_of(level) {
+            match &self.tokenizer.current_token {
+                Token::Tag(tag) => match tag.as_str() {
+                    "PAGE" => citation.page = Some(self.take_line_value()?),
+                    "EVEN" => {
+                        citation.event = self.take_optional_line_value()?;
+                        citation.role = self.parse_role(level + 1)?;
+                    }
+                    "DATA" => self.parse_citation_data(&mut citation, level + 1)?,
+                    "NOTE" => citation.notes.push(self.parse_note(level + 1)?),
+                    "QUAY" => {
+                        let line = self.tokenizer.line;
+                        let value = self.take_line_value()?;
+                        match value.trim().parse::<u8>() {
+                            Ok(quality) if quality <= 3 => citation.quality = Some(quality),
+                            _ => self.warn_at(line, format!("Invalid citation quality {value}")),
+                        }
+                    }
+                    // TEXT of citations without a pointer to a source record
+                    "TEXT" => citation.texts.push(self.take_continued_text(level + 1)?),
+                    _ => return Err(self.unexpected_tag("Citation")),
+                },
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("Citation")),
             }
+        }
+        Ok(citation)
+    }
+
+    /// Parses the subordinates of the `EVEN` of a citation, returning the value of its
+    /// `ROLE`
+    fn parse_role(&mut self, level: u8) -> Result<Option<String>, GedcomError> {
+        let mut role = None;
+        while !self.at_end_of(level) {
             match &self.tokenizer.current_token {
                 Token::Tag(tag) => match tag.as_str() {
-                    "PAGE" => citation.page = Some(self.take_line_value()),
-                    _ => panic!("{} Unhandled Citation Tag: {}", self.dbg(), tag),
+                    "ROLE" => role = Some(self.take_line_value()?),
+                    _ => return Err(self.unexpected_tag("Citation Event")),
                 },
-                Token::Level(_) => self.tokenizer.next_token(),
-                _ => panic!(
-                    "Unhandled Citation Token: {:?}",
-                    self.tokenizer.current_token
-                ),
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("Citation Event")),
+            }
+        }
+        Ok(role)
+    }
+
+    /// Parses an ASSO tag into the associated individual & their relationship
+    fn parse_association(&mut self, level: u8) -> Result<Association, GedcomError> {
+        let mut association = Association::new(self.take_line_value()?);
+        while !self.at_end_of(level) {
+            match &self.tokenizer.current_token {
+                Token::Tag(tag) => match tag.as_str() {
+                    "RELA" => association.relation = Some(self.take_line_value()?),
+                    "ROLE" => association.role = Some(self.take_line_value()?),
+                    "NOTE" => association.notes.push(self.parse_note(level + 1)?),
+                    "SOUR" => association.citations.push(self.parse_citation(level + 1)?),
+                    _ => return Err(self.unexpected_tag("Association")),
+                },
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("Association")),
+            }
+        }
+        Ok(association)
+    }
+
+    /// Parses the DATA tag of a citation into its `DATE` & `TEXT`
+    fn parse_citation_data(
+        &mut self,
+        citation: &mut SourceCitation,
+        level: u8,
+    ) -> Result<(), GedcomError> {
+        self.next_token()?;
+        while !self.at_end_of(level) {
+            match &self.tokenizer.current_token {
+                Token::Tag(tag) => match tag.as_str() {
+                    "DATE" => citation.date = Some(self.take_line_value()?),
+                    "TEXT" => citation.texts.push(self.take_continued_text(level + 1)?),
+                    _ => return Err(self.unexpected_tag("Citation Data")),
+                },
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("Citation Data")),
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks whether the tokenizer has moved past the structure at `level`, either
+    /// onto a line of the same or a higher level or to the end of the content.
+    fn at_end_of(&self, level: u8) -> bool {
+        match self.tokenizer.current_token {
+            Token::Level(cur_level) => cur_level <= level,
+            Token::EOF => true,
+            _ => false,
+        }
+    }
+
+    /// Skips all remaining lines of the current top-level record.
+    fn skip_record(&mut self) -> Result<(), GedcomError> {
+        loop {
+            self.next_token()?;
+            if self.tokenizer.current_token == Token::Level(0) || self.tokenizer.done() {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Consumes the level and optional xref pointer at the start of a record line,
+    /// leaving the tokenizer on the record's tag.
+    fn take_record_start(&mut self) -> Result<(u8, Option<String>), GedcomError> {
+        self.take_tokenizer_findings();
+        let Token::Level(level) = self.tokenizer.current_token else {
+            if self.tokenizer.done() {
+                return Err(GedcomError::Truncated);
+            }
+            let message = format!("Expected Level, found {:?}", self.tokenizer.current_token);
+            return Err(self.malformed(&message));
+        };
+
+        self.next_token()?;
+
+        let mut pointer: Option<String> = None;
+        if let Token::Pointer(xref) = &self.tokenizer.current_token {
+            pointer = Some(xref.clone());
+            self.next_token()?;
+        }
+
+        Ok((level, pointer))
+    }
+
+    /// Like `take_record_start`, but checks that the record has the expected tag.
+    fn take_record_start_for(
+        &mut self,
+        expected_tag: &str,
+    ) -> Result<(u8, Option<String>), GedcomError> {
+        let record_start = self.take_record_start()?;
+        match &self.tokenizer.current_token {
+            Token::Tag(tag) if tag == expected_tag => Ok(record_start),
+            token => {
+                let message = format!("Expected {expected_tag} record, found {token:?}");
+                Err(self.malformed(&message))
             }
         }
-        citation
     }
 
     /// Takes the value of the current line including handling
     /// multi-line values from CONT & CONC tags.
-    fn take_continued_text(&mut self, level: u8) -> String {
-        let mut value = self.take_line_value();
+    fn take_continued_text(&mut self, level: u8) -> Result<String, GedcomError> {
+        let mut value = self.take_optional_line_value()?.unwrap_or_default();
 
-        loop {
-            if let Token::Level(cur_level) = self.tokenizer.current_token {
-                if cur_level <= level {
-                    break;
-                }
+        while !self.at_end_of(level) {
+            match &self.tokenizer.current_token {
+                Token::Tag(tag) => match tag.as_str() {
+                    "CONT" | "CONC" => self.take_continuation(&mut value)?,
+                    _ => return Err(self.unexpected_tag("Continuation")),
+                },
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("Continuation")),
             }
+        }
+
+        Ok(value)
+    }
+
+    /// Parses a `NOTE` structure: its text, continued over `CONT` & `CONC` lines, and the
+    /// `LANG` of GEDCOM 7.0
+    fn parse_note(&mut self, level: u8) -> Result<Note, GedcomError> {
+        let mut text = self.take_optional_line_value()?.unwrap_or_default();
+        let mut language = None;
+
+        while !self.at_end_of(level) {
             match &self.tokenizer.current_token {
                 Token::Tag(tag) => match tag.as_str() {
-                    "CONT" => {
-                        value.push('\n');
-                        value.push_str(&self.take_line_value())
-                    }
-                    "CONC" => {
-                        value.push(' ');
-                        value.push_str(&self.take_line_value())
-                    }
-                    _ => panic!("{} Unhandled Continuation Tag: {}", self.dbg(), tag),
+                    "CONT" | "CONC" => self.take_continuation(&mut text)?,
+                    "LANG" => language = Some(self.take_line_value()?),
+                    _ => return Err(self.unexpected_tag("Note")),
                 },
-                Token::Level(_) => self.tokenizer.next_token(),
-                _ => panic!(
-                    "Unhandled Continuation Token: {:?}",
-                    self.tokenizer.current_token
-                ),
+                Token::Level(_) => self.next_token()?,
+                _ => return Err(self.unexpected_token("Note")),
             }
         }
 
-        value
+        let note = self.note(text);
+        Ok(match language {
+            Some(language) => note.with_language(language),
+            None => note,
+        })
+    }
+
+    /// Adds the current `CONT` or `CONC` line to `value`
+    fn take_continuation(&mut self, value: &mut String) -> Result<(), GedcomError> {
+        if let Token::Tag(tag) = &self.tokenizer.current_token {
+            if tag == "CONT" {
+                value.push('\n');
+                // blank lines of the text are empty CONT lines
+                value.push_str(&self.take_optional_line_value()?.unwrap_or_default());
+            } else {
+                let line = self.tokenizer.line;
+                let continued = self.take_optional_line_value()?.unwrap_or_default();
+                self.audit_conc(line, value, &continued);
+                let ends_with_space = value.ends_with(char::is_whitespace);
+                if self.conc_space && !value.is_empty() && !continued.is_empty() && !ends_with_space
+                {
+                    value.push(' ');
+                }
+                value.push_str(&continued);
+            }
+        }
+        Ok(())
+    }
+
+    /// Reports a `CONC` line joining `continued` to `value` if it looks suspicious, and
+    /// puts back a lost space when configured to. See `ConcAudit`.
+    fn audit_conc(&mut self, line: u32, value: &mut String, continued: &str) {
+        if self.options.conc_audit == ConcAudit::Off {
+            return;
+        }
+        let current_line = value.rsplit('\n').next().unwrap_or_default();
+        let Some(before) = current_line.chars().next_back() else {
+            self.warn_at(line, "CONC continues an empty line".to_string());
+            return;
+        };
+        let Some(after) = continued.chars().next() else {
+            return;
+        };
+        let glued = (matches!(before, '.' | ',' | ';' | ':' | '!' | '?') && after.is_alphabetic())
+            || (before.is_lowercase() && after.is_uppercase());
+        if glued {
+            self.warn_at(
+                line,
+                format!("CONC joins words without a space: {before}{after}"),
+            );
+            if self.options.conc_audit == ConcAudit::Repair {
+                value.push(' ');
+            }
+        }
+    }
+
+    /// Takes the value of an `NCHI` line, warning about values that aren't a number
+    fn take_child_count(&mut self) -> Result<Option<u8>, GedcomError> {
+        let line = self.tokenizer.line;
+        let value = self.take_line_value()?;
+        let count = value.trim().parse().ok();
+        if count.is_none() {
+            self.warn_at(line, format!("Invalid number of children: {value}"));
+        }
+        Ok(count)
+    }
+
+    /// Creates a `Note`, sharing its text or compressing it if configured to.
+    fn note(&mut self, text: String) -> Note {
+        if self.options.deduplicate_notes {
+            return self.notes.note(text);
+        }
+        match self.options.note_compression_threshold {
+            #[cfg(feature = "compression")]
+            Some(threshold) => Note::with_threshold(text, threshold),
+            _ => Note::new(text),
+        }
+    }
+
+    /// Like `take_line_value`, but for tags whose value may be left out.
+    fn take_optional_line_value(&mut self) -> Result<Option<String>, GedcomError> {
+        self.next_token()?;
+
+        if let Token::LineValue(val) = &self.tokenizer.current_token {
+            let value = val.clone();
+            self.next_token()?;
+            Ok(Some(value))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Skips the lines subordinate to the structure at `level`.
+    fn skip_subordinates(&mut self, level: u8) -> Result<(), GedcomError> {
+        while !self.at_end_of(level) {
+            self.next_token()?;
+        }
+        Ok(())
     }
 
     /// Grabs and returns to the end of the current line as a String
-    fn take_line_value(&mut self) -> String {
+    fn take_line_value(&mut self) -> Result<String, GedcomError> {
         let value: String;
-        self.tokenizer.next_token();
+        self.next_token()?;
 
         if let Token::LineValue(val) = &self.tokenizer.current_token {
-            value = val.to_string();
+            value = val.clone();
         } else {
-            panic!(
-                "{} Expected LineValue, found {:?}",
-                self.dbg(),
+            let message = format!(
+                "Expected LineValue, found {:?}",
                 self.tokenizer.current_token
             );
+            return Err(self.malformed(&message));
+        }
+        self.next_token()?;
+        Ok(value)
+    }
+
+    /// Records a warning for the current line.
+    fn warn(&mut self, message: String) {
+        self.warn_at(self.tokenizer.line, message);
+    }
+
+    /// Like `warn`, for a line the tokenizer has already moved past.
+    fn warn_at(&mut self, line: u32, message: String) {
+        self.diagnostics.push(Diagnostic::warning(line, message));
+    }
+
+    /// Moves the tokenizer's findings about the layout of lines into the diagnostics,
+    /// in line order.
+    fn take_tokenizer_findings(&mut self) {
+        let findings = self.tokenizer.take_findings(self.options.strict);
+        if !findings.is_empty() {
+            self.diagnostics.extend(findings);
+            self.diagnostics.sort_by_key(|diagnostic| diagnostic.line);
+        }
+    }
+
+    /// Reports a deviation from the specification that is accepted, as an error in
+    /// strict mode.
+    fn flag_nonstandard(&mut self, message: &str) {
+        if self.options.strict {
+            let line = self.tokenizer.line;
+            self.diagnostics
+                .push(Diagnostic::error(line, message.to_string()));
         }
-        self.tokenizer.next_token();
-        value
     }
 
     /// Debug function displaying GEDCOM line number of error message.
     fn dbg(&self) -> String {
         format!("line {}:", self.tokenizer.line)
     }
+
+    /// Moves to the next token of the content
+    fn next_token(&mut self) -> Result<(), GedcomError> {
+        self.tokenizer.try_next_token()?;
+        match (&self.tokenizer.current_token, self.options.max_level) {
+            (Token::Level(level), Some(max_level)) if *level > max_level => {
+                Err(GedcomError::LimitExceeded(format!(
+                    "maximum level of {max_level}, on line {}",
+                    self.tokenizer.line
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The error for content the parser can't make sense of, on the current line
+    fn malformed(&self, message: &str) -> GedcomError {
+        GedcomError::Malformed {
+            line: self.tokenizer.line,
+            message: format!("{} {message}", self.dbg()),
+        }
+    }
+
+    /// The error for a `value`, on `line`, that isn't one of the values its tag allows
+    fn invalid_value(line: u32, value: String, expected: &str) -> GedcomError {
+        GedcomError::InvalidValue {
+            line,
+            value,
+            expected: expected.to_string(),
+        }
+    }
+
+    /// The error for the current tag, which isn't handled in the `context` structure
+    fn unexpected_tag(&self, context: &str) -> GedcomError {
+        let tag = match &self.tokenizer.current_token {
+            Token::Tag(tag) | Token::CustomTag(tag) => tag.clone(),
+            token => format!("{token:?}"),
+        };
+        GedcomError::UnexpectedTag {
+            line: self.tokenizer.line,
+            tag,
+            context: context.to_string(),
+        }
+    }
+
+    /// The error for the current token, which isn't expected in the `context` structure
+    fn unexpected_token(&self, context: &str) -> GedcomError {
+        let message = format!(
+            "Unhandled {context} Token: {:?}",
+            self.tokenizer.current_token
+        );
+        self.malformed(&message)
+    }
 }
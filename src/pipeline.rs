@@ -0,0 +1,186 @@
+//! Passes run over the tree once it is parsed, in an explicit order.
+//!
+//! A `Pipeline` holds named passes, each a function of the tree and the diagnostics
+//! found so far. `Pipeline::standard` holds the passes of the crate itself, which
+//! applications can remove, or extend with passes of their own for their vendor's
+//! dialect or their validation rules:
+//!
+//! ```rust
+//! use gedcom::diagnostics::Diagnostic;
+//! use gedcom::parser::Parser;
+//! use gedcom::pipeline::Pipeline;
+//!
+//! let mut pipeline = Pipeline::standard();
+//! pipeline.add("unnamed", |data, diagnostics| {
+//!     for individual in &data.individuals {
+//!         if individual.names.is_empty() {
+//!             diagnostics.push(Diagnostic::warning(0, "Individual without a name".to_string()));
+//!         }
+//!     }
+//! });
+//!
+//! let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n0 @I1@ INDI\n0 TRLR";
+//! let mut parser = Parser::new(content.chars());
+//! let data = parser.parse_document_with(&pipeline).unwrap();
+//! assert_eq!(data.individuals.len(), 1);
+//! assert!(parser
+//!     .diagnostics()
+//!     .iter()
+//!     .any(|diagnostic| diagnostic.message == "Individual without a name"));
+//! ```
+use std::fmt;
+
+use crate::diagnostics::Diagnostic;
+use crate::repair::RepairMode;
+use crate::tree::{GedcomData, MultimediaPlacement};
+use crate::validate::{validate, Check};
+
+/// The function of a pass. Findings about the tree rather than a line of the content
+/// are reported on line 0.
+pub type PassFn = dyn Fn(&mut GedcomData, &mut Vec<Diagnostic>) + Send + Sync;
+
+/// Name of the standard pass moving the multimedia objects embedded in links into
+/// records, or the reverse, as the GEDCOM version of the header requires, see
+/// `GedcomData::place_multimedia`
+pub const PLACE_MULTIMEDIA: &str = "place-multimedia";
+/// Name of the standard pass moving the `FORM` & `TITL` of multimedia records under
+/// their `FILE`, see `GedcomData::normalize_multimedia_files`
+pub const NORMALIZE_MULTIMEDIA: &str = "normalize-multimedia";
+/// Name of the standard pass adding the missing side of one-sided family links, see
+/// `GedcomData::repair_links`
+pub const REPAIR_LINKS: &str = "repair-links";
+/// Name of the standard pass reporting the problems found by every check of
+/// `validate::validate`, once the links are repaired
+pub const VALIDATE: &str = "validate";
+
+/// Passes over a parsed tree, run in the order they were added
+#[derive(Default)]
+pub struct Pipeline {
+    passes: Vec<(String, Box<PassFn>)>,
+}
+
+impl Pipeline {
+    /// Creates a pipeline without any pass
+    #[must_use]
+    pub fn new() -> Pipeline {
+        Pipeline::default()
+    }
+
+    /// Creates a pipeline of the passes of the crate: `PLACE_MULTIMEDIA`,
+    /// `NORMALIZE_MULTIMEDIA`, `REPAIR_LINKS` then `VALIDATE`
+    #[must_use]
+    pub fn standard() -> Pipeline {
+        let mut pipeline = Pipeline::new();
+        pipeline.add(PLACE_MULTIMEDIA, |data, diagnostics| {
+            let version = data.header.version();
+            let Some(placement) = version.as_ref().and_then(MultimediaPlacement::required_by)
+            else {
+                return;
+            };
+            let changed = data.place_multimedia(placement);
+            if changed > 0 {
+                let message = match placement {
+                    MultimediaPlacement::Records => {
+                        format!("Moved the objects of {changed} multimedia links into records")
+                    }
+                    MultimediaPlacement::Inline => {
+                        format!("Moved {changed} multimedia records into their links")
+                    }
+                };
+                diagnostics.push(Diagnostic::warning(0, message));
+            }
+        });
+        pipeline.add(NORMALIZE_MULTIMEDIA, |data, diagnostics| {
+            let changed = data.normalize_multimedia_files();
+            if changed > 0 {
+                let message =
+                    format!("Moved FORM & TITL under FILE in {changed} multimedia records");
+                diagnostics.push(Diagnostic::warning(0, message));
+            }
+        });
+        pipeline.add(REPAIR_LINKS, |data, diagnostics| {
+            for repair in data.repair_links(RepairMode::Fix) {
+                let message = if repair.repaired {
+                    format!("{}, repaired", repair.issue)
+                } else {
+                    repair.issue.to_string()
+                };
+                diagnostics.push(Diagnostic::warning(0, message));
+            }
+        });
+        pipeline.add(VALIDATE, |data, diagnostics| {
+            for issue in validate(data, &Check::ALL) {
+                let record = issue.record.as_deref().unwrap_or("HEAD");
+                diagnostics.push(Diagnostic {
+                    severity: issue.severity,
+                    line: 0,
+                    message: format!("{record}: {}", issue.message),
+                });
+            }
+        });
+        pipeline
+    }
+
+    /// Adds a pass after the others, replacing the pass of the same name if there is one
+    pub fn add(
+        &mut self,
+        name: &str,
+        pass: impl Fn(&mut GedcomData, &mut Vec<Diagnostic>) + Send + Sync + 'static,
+    ) {
+        let pass: Box<PassFn> = Box::new(pass);
+        match self
+            .passes
+            .iter_mut()
+            .find(|(existing, _)| existing == name)
+        {
+            Some(existing) => existing.1 = pass,
+            None => self.passes.push((name.to_string(), pass)),
+        }
+    }
+
+    /// Adds a pass right before the pass named `before`, or after the others when there
+    /// is no such pass
+    pub fn add_before(
+        &mut self,
+        before: &str,
+        name: &str,
+        pass: impl Fn(&mut GedcomData, &mut Vec<Diagnostic>) + Send + Sync + 'static,
+    ) {
+        self.remove(name);
+        let index = self
+            .passes
+            .iter()
+            .position(|(existing, _)| existing == before)
+            .unwrap_or(self.passes.len());
+        self.passes
+            .insert(index, (name.to_string(), Box::new(pass)));
+    }
+
+    /// Removes the pass named `name`, returning whether there was one
+    pub fn remove(&mut self, name: &str) -> bool {
+        let count = self.passes.len();
+        self.passes.retain(|(existing, _)| existing != name);
+        self.passes.len() < count
+    }
+
+    /// The names of the passes, in the order they run
+    #[must_use]
+    pub fn names(&self) -> Vec<&str> {
+        self.passes.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    /// Runs the passes over `data` in order, adding their findings to `diagnostics`
+    pub fn run(&self, data: &mut GedcomData, diagnostics: &mut Vec<Diagnostic>) {
+        for (_, pass) in &self.passes {
+            pass(data, diagnostics);
+        }
+    }
+}
+
+impl fmt::Debug for Pipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pipeline")
+            .field("passes", &self.names())
+            .finish()
+    }
+}
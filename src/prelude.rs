@@ -0,0 +1,19 @@
+//! Re-exports the commonly used types, so they can be imported at once:
+//!
+//! ```rust
+//! use gedcom::prelude::*;
+//!
+//! let individual = Individual::try_parse_str("0 @I1@ INDI\n1 NAME John /Doe/\n").unwrap();
+//! assert_eq!(individual.events().len(), 0);
+//! ```
+pub use crate::diagnostics::{Diagnostic, Severity};
+pub use crate::parser::{ConcAudit, ParseOptions, Parser, Record};
+pub use crate::store::RecordStore;
+pub use crate::types::{
+    Address, CustomData, Event, EventType, Family, FamilyLink, FamilyLinkType, GedcomVersion,
+    Gender, HasCustomData, HasEvents, Header, HeaderSource, Individual, MultimediaFileRefn,
+    MultimediaRecord, Name, NameOrder, Note, ParentTag, Pedigree, Producer, RepoCitation,
+    RepoReference, Repository, Source, SourceCitation, SourceData, Submitter,
+};
+pub use crate::writer::{GedcomWriter, TagFilter, WriteOptions};
+pub use crate::{ChangeDatePolicy, ChangedRecord, GedcomData, GedcomError};
@@ -0,0 +1,254 @@
+//! Repairs family links that only one side of the link records
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::tree::{stamp, GedcomData};
+use crate::types::{FamilyLink, FamilyLinkType, Gender, ParentTag};
+
+/// Whether `GedcomData::repair_links` changes the tree or only reports
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RepairMode {
+    /// Adds the missing side of each one-sided link
+    #[default]
+    Fix,
+    /// Only reports the one-sided links, leaving the tree untouched
+    Report,
+}
+
+/// A family link recorded on only one of its sides
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LinkIssue {
+    /// The individual is a spouse in the family (`FAMS`), which doesn't list them as
+    /// `HUSB` or `WIFE`
+    MissingParent {
+        /// Reference to the individual
+        individual: String,
+        /// Reference to the family
+        family: String,
+    },
+    /// The individual is a child in the family (`FAMC`), which doesn't list them as `CHIL`
+    MissingChild {
+        /// Reference to the individual
+        individual: String,
+        /// Reference to the family
+        family: String,
+    },
+    /// The family lists the individual as `HUSB` or `WIFE`, who has no `FAMS` link to it
+    MissingSpouseLink {
+        /// Reference to the individual
+        individual: String,
+        /// Reference to the family
+        family: String,
+    },
+    /// The family lists the individual as `CHIL`, who has no `FAMC` link to it
+    MissingChildLink {
+        /// Reference to the individual
+        individual: String,
+        /// Reference to the family
+        family: String,
+    },
+    /// A record links to a record that doesn't exist. These are never repaired.
+    Dangling {
+        /// Reference to the record holding the link
+        from: String,
+        /// Reference to the missing record
+        to: String,
+    },
+}
+
+impl fmt::Display for LinkIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinkIssue::MissingParent { individual, family } => {
+                write!(
+                    f,
+                    "{individual} is a spouse in {family}, which lacks them as a parent"
+                )
+            }
+            LinkIssue::MissingChild { individual, family } => {
+                write!(
+                    f,
+                    "{individual} is a child in {family}, which lacks them as a child"
+                )
+            }
+            LinkIssue::MissingSpouseLink { individual, family } => {
+                write!(
+                    f,
+                    "{family} lists {individual} as a parent, who lacks a FAMS link"
+                )
+            }
+            LinkIssue::MissingChildLink { individual, family } => {
+                write!(
+                    f,
+                    "{family} lists {individual} as a child, who lacks a FAMC link"
+                )
+            }
+            LinkIssue::Dangling { from, to } => write!(f, "{from} links to missing record {to}"),
+        }
+    }
+}
+
+/// A one-sided link found by `GedcomData::repair_links`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinkRepair {
+    /// The link found
+    pub issue: LinkIssue,
+    /// Whether the missing side was added. A parent can't be added to a family that
+    /// already has two, and links to missing records are never repaired.
+    pub repaired: bool,
+}
+
+impl GedcomData {
+    /// Makes the family links of the tree consistent in both directions, the most
+    /// common corruption in files merged by hand: an individual's `FAMS` & `FAMC` links
+    /// must be matched by the family's `HUSB`/`WIFE` & `CHIL` lines, and vice versa.
+    ///
+    /// In `RepairMode::Fix` the missing side of each link is added, records changed are
+    /// stamped according to the change date policy. Every one-sided link is returned,
+    /// along with whether it was repaired.
+    pub fn repair_links(&mut self, mode: RepairMode) -> Vec<LinkRepair> {
+        let issues = self.one_sided_links();
+        let families: HashMap<String, usize> = self
+            .families
+            .iter()
+            .enumerate()
+            .filter_map(|(i, family)| Some((family.xref.clone()?, i)))
+            .collect();
+        let individuals: HashMap<String, usize> = self
+            .individuals
+            .iter()
+            .enumerate()
+            .filter_map(|(i, individual)| Some((individual.xref.clone()?, i)))
+            .collect();
+
+        let policy = self.change_dates;
+        issues
+            .into_iter()
+            .map(|issue| {
+                let repaired = mode == RepairMode::Fix
+                    && match &issue {
+                        LinkIssue::MissingParent { individual, family } => {
+                            let tag = match self.individuals[individuals[individual]].sex {
+                                Gender::Female => ParentTag::Wife,
+                                _ => ParentTag::Husband,
+                            };
+                            let family = &mut self.families[families[family]];
+                            let added = family.add_parent(individual.clone(), tag).is_ok();
+                            if added {
+                                stamp(policy, None, &mut family.last_updated);
+                            }
+                            added
+                        }
+                        LinkIssue::MissingChild { individual, family } => {
+                            let family = &mut self.families[families[family]];
+                            family.add_child(individual.clone());
+                            stamp(policy, None, &mut family.last_updated);
+                            true
+                        }
+                        LinkIssue::MissingSpouseLink { individual, family }
+                        | LinkIssue::MissingChildLink { individual, family } => {
+                            let tag = if matches!(issue, LinkIssue::MissingSpouseLink { .. }) {
+                                "FAMS"
+                            } else {
+                                "FAMC"
+                            };
+                            let individual = &mut self.individuals[individuals[individual]];
+                            individual
+                                .families
+                                .push(FamilyLink::new(family.clone(), tag));
+                            stamp(policy, None, &mut individual.last_updated);
+                            true
+                        }
+                        LinkIssue::Dangling { .. } => false,
+                    };
+                LinkRepair { issue, repaired }
+            })
+            .collect()
+    }
+
+    /// Finds the links recorded on one side only, in the order of the records
+    pub(crate) fn one_sided_links(&self) -> Vec<LinkIssue> {
+        let families: HashMap<&str, _> = self
+            .families
+            .iter()
+            .filter_map(|family| Some((family.xref.as_deref()?, family)))
+            .collect();
+        let individuals: HashMap<&str, _> = self
+            .individuals
+            .iter()
+            .filter_map(|individual| Some((individual.xref.as_deref()?, individual)))
+            .collect();
+
+        let mut issues = Vec::new();
+        for individual in &self.individuals {
+            let Some(xref) = &individual.xref else {
+                continue;
+            };
+            for FamilyLink {
+                xref: family_xref,
+                link_type,
+                ..
+            } in &individual.families
+            {
+                let Some(record) = families.get(family_xref.as_str()) else {
+                    issues.push(LinkIssue::Dangling {
+                        from: xref.clone(),
+                        to: family_xref.clone(),
+                    });
+                    continue;
+                };
+                let (individual, family) = (xref.clone(), family_xref.clone());
+                match link_type {
+                    FamilyLinkType::Spouse
+                        if record.individual1.as_ref() != Some(&individual)
+                            && record.individual2.as_ref() != Some(&individual) =>
+                    {
+                        issues.push(LinkIssue::MissingParent { individual, family });
+                    }
+                    FamilyLinkType::Child if !record.children.contains(&individual) => {
+                        issues.push(LinkIssue::MissingChild { individual, family });
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        for family in &self.families {
+            let Some(xref) = &family.xref else {
+                continue;
+            };
+            let parents = family.individual1.iter().chain(&family.individual2);
+            let members = parents
+                .map(|parent| (parent, FamilyLinkType::Spouse))
+                .chain(
+                    family
+                        .children
+                        .iter()
+                        .map(|child| (child, FamilyLinkType::Child)),
+                );
+            for (member, link_type) in members {
+                let Some(individual) = individuals.get(member.as_str()) else {
+                    issues.push(LinkIssue::Dangling {
+                        from: xref.clone(),
+                        to: member.clone(),
+                    });
+                    continue;
+                };
+                let linked = individual
+                    .families
+                    .iter()
+                    .any(|link| &link.xref == xref && link.link_type == link_type);
+                if !linked {
+                    let (individual, family) = (member.clone(), xref.clone());
+                    issues.push(match link_type {
+                        FamilyLinkType::Spouse => {
+                            LinkIssue::MissingSpouseLink { individual, family }
+                        }
+                        FamilyLinkType::Child => LinkIssue::MissingChildLink { individual, family },
+                    });
+                }
+            }
+        }
+        issues
+    }
+}
@@ -0,0 +1,221 @@
+//! Book-style reports of the people of a tree
+use crate::analysis::{number_descendants, NumberingSystem};
+use crate::store::RecordStore;
+use crate::types::{
+    DateStyle, DateValue, Event, EventType, Family, Gender, HasEvents, Individual, NameOrder,
+};
+
+/// Output format of a report
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// Plain text, with each person introduced by their number & name on a line
+    #[default]
+    PlainText,
+    /// Markdown, with a heading for each person
+    Markdown,
+}
+
+/// Sentence templates of the narrative report.
+///
+/// Templates are filled in by replacing placeholders:
+/// * `{name}`: the name of the person
+/// * `{pronoun}` & `{Pronoun}`: `he`, `she` or `they` depending on their sex
+/// * `{date}`: the date of the event as ` on 1 JAN 1899` or ` in 1899`, or nothing
+/// * `{place}`: the place of the event as ` in Boston`, or nothing
+/// * `{spouse}`: the name of the spouse, for marriages
+/// * `{count}` & `{children}`: the number & names of the children of a couple
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SentenceTemplates {
+    /// Sentence for the birth of the person
+    pub birth: String,
+    /// Sentence for the death of the person
+    pub death: String,
+    /// Sentence for each marriage of the person
+    pub marriage: String,
+    /// Sentence for the children of each marriage
+    pub children: String,
+}
+
+impl Default for SentenceTemplates {
+    fn default() -> SentenceTemplates {
+        SentenceTemplates {
+            birth: "{name} was born{date}{place}.".to_string(),
+            death: "{Pronoun} died{date}{place}.".to_string(),
+            marriage: "{Pronoun} married {spouse}{date}{place}.".to_string(),
+            children: "They had {count} children: {children}.".to_string(),
+        }
+    }
+}
+
+/// Options of the narrative report
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NarrativeOptions {
+    /// Format of the report
+    pub format: ReportFormat,
+    /// Number of generations of descendants to describe after the root person
+    pub generations: usize,
+    /// Order in which names are written
+    pub name_order: NameOrder,
+    /// Templates of the sentences
+    pub templates: SentenceTemplates,
+}
+
+impl Default for NarrativeOptions {
+    fn default() -> NarrativeOptions {
+        NarrativeOptions {
+            format: ReportFormat::default(),
+            generations: 3,
+            name_order: NameOrder::default(),
+            templates: SentenceTemplates::default(),
+        }
+    }
+}
+
+/// Writes a narrative of `root` and their descendants, with a paragraph per person like
+/// "John Doe was born on 1 JAN 1899 in Boston. He married Jane Roe in 1920."
+///
+/// People are introduced by their d'Aboville number and described in that order. The
+/// first birth & death recorded for a person are used.
+#[must_use]
+pub fn narrative<S: RecordStore>(store: &S, root: &str, options: &NarrativeOptions) -> String {
+    let numbers = number_descendants(store, root, NumberingSystem::DAboville);
+    let mut people: Vec<(&String, &String)> = numbers
+        .iter()
+        .filter(|(_, number)| generation(number) <= options.generations)
+        .collect();
+    people.sort_by_key(|(_, number)| sort_key(number));
+
+    let mut paragraphs = Vec::new();
+    for (xref, number) in people {
+        let Some(individual) = store.individual(xref) else {
+            continue;
+        };
+        let name = name_of(&individual, options.name_order);
+        let heading = match options.format {
+            ReportFormat::PlainText => format!("{number}. {name}"),
+            ReportFormat::Markdown => format!("### {number}. {name}"),
+        };
+        let text = describe(store, &individual, options);
+        paragraphs.push(if text.is_empty() {
+            heading
+        } else {
+            format!("{heading}\n\n{text}")
+        });
+    }
+    paragraphs.join("\n\n")
+}
+
+/// Generation of a d'Aboville number, the root being generation 0
+fn generation(number: &str) -> usize {
+    number.matches('.').count()
+}
+
+fn sort_key(number: &str) -> Vec<u32> {
+    number
+        .split('.')
+        .filter_map(|part| part.parse().ok())
+        .collect()
+}
+
+fn name_of(individual: &Individual, order: NameOrder) -> String {
+    individual
+        .display_name(order)
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+fn describe<S: RecordStore>(
+    store: &S,
+    individual: &Individual,
+    options: &NarrativeOptions,
+) -> String {
+    let templates = &options.templates;
+    let name = name_of(individual, options.name_order);
+    let pronoun = match individual.sex {
+        Gender::Male => "he",
+        Gender::Female => "she",
+        _ => "they",
+    };
+    let fill = |template: &str, event: Option<&Event>| {
+        let pronoun_capitalized = pronoun[..1].to_uppercase() + &pronoun[1..];
+        template
+            .replace("{name}", &name)
+            .replace("{Pronoun}", &pronoun_capitalized)
+            .replace("{pronoun}", pronoun)
+            .replace("{date}", &event.map(date_phrase).unwrap_or_default())
+            .replace("{place}", &event.map(place_phrase).unwrap_or_default())
+    };
+
+    let events = individual.events();
+    let mut sentences = Vec::new();
+    if let Some(birth) = events.iter().find(|e| e.event == EventType::Birth) {
+        sentences.push(fill(&templates.birth, Some(birth)));
+    }
+
+    let xref = individual.xref.as_deref().unwrap_or_default();
+    for family in store.spouse_families(individual) {
+        let marriage = family
+            .events()
+            .into_iter()
+            .find(|e| e.event == EventType::Marriage);
+        let spouse = family
+            .partner_of(xref)
+            .and_then(|spouse| store.individual(spouse))
+            .map(|spouse| name_of(&spouse, options.name_order));
+        if let Some(spouse) = &spouse {
+            sentences
+                .push(fill(&templates.marriage, marriage.as_ref()).replace("{spouse}", spouse));
+        }
+        let children = children_names(store, &family, options.name_order);
+        if !children.is_empty() {
+            sentences.push(
+                fill(&templates.children, None)
+                    .replace("{count}", &children.len().to_string())
+                    .replace("{children}", &join_names(&children)),
+            );
+        }
+    }
+
+    if let Some(death) = events.iter().find(|e| e.event == EventType::Death) {
+        sentences.push(fill(&templates.death, Some(death)));
+    }
+    sentences.join(" ")
+}
+
+fn children_names<S: RecordStore>(store: &S, family: &Family, order: NameOrder) -> Vec<String> {
+    family
+        .children
+        .iter()
+        .filter_map(|child| store.individual(child))
+        .map(|child| name_of(&child, order))
+        .collect()
+}
+
+/// Joins names as "A, B and C"
+fn join_names(names: &[String]) -> String {
+    match names {
+        [] => String::new(),
+        [name] => name.clone(),
+        [rest @ .., last] => format!("{} and {last}", rest.join(", ")),
+    }
+}
+
+/// A date as " on 1 JAN 1899" for full dates, " in 1899" for partial ones, and with
+/// the qualifiers of approximate dates spelled out, ie. " about 1899".
+fn date_phrase(event: &Event) -> String {
+    match event.date_value() {
+        None => String::new(),
+        Some(DateValue::Exact(date)) if date.day.is_some() => format!(" on {date}"),
+        Some(date @ (DateValue::Exact(_) | DateValue::Phrase(_))) => {
+            format!(" in {}", date.format(DateStyle::Words))
+        }
+        Some(date) => format!(" {}", date.format(DateStyle::Words)),
+    }
+}
+
+fn place_phrase(event: &Event) -> String {
+    match event.place_name().map(str::trim) {
+        Some(place) if !place.is_empty() => format!(" in {place}"),
+        _ => String::new(),
+    }
+}
@@ -0,0 +1,175 @@
+//! Resolves xref pointers into the records they refer to.
+//!
+//! Navigating between records only needs the records along the way, so lookups go
+//! through the `RecordStore` trait. `GedcomData` implements it for fully parsed files,
+//! while applications keeping their records in a database can implement it to load
+//! records on demand and navigate partial graphs.
+use std::borrow::Cow;
+
+use crate::tree::GedcomData;
+use crate::types::{Family, FamilyLinkType, Individual, Repository, Source, Submitter};
+
+/// Looks up records by their xref
+pub trait RecordStore {
+    /// Fetches the `Individual` with the given xref
+    fn individual(&self, xref: &str) -> Option<Cow<'_, Individual>>;
+
+    /// Fetches the `Family` with the given xref
+    fn family(&self, xref: &str) -> Option<Cow<'_, Family>>;
+
+    /// Fetches the `Source` with the given xref
+    fn source(&self, xref: &str) -> Option<Cow<'_, Source>>;
+
+    /// Fetches the `Repository` with the given xref
+    fn repository(&self, xref: &str) -> Option<Cow<'_, Repository>>;
+
+    /// Fetches the `Submitter` with the given xref
+    fn submitter(&self, xref: &str) -> Option<Cow<'_, Submitter>>;
+
+    /// Fetches the families an individual is a child of (`FAMC`). Links to families
+    /// missing from the store are skipped.
+    fn child_families(&self, individual: &Individual) -> Vec<Cow<'_, Family>> {
+        individual
+            .families
+            .iter()
+            .filter(|link| matches!(link.link_type, FamilyLinkType::Child))
+            .filter_map(|link| self.family(&link.xref))
+            .collect()
+    }
+
+    /// Fetches the families an individual is a spouse in (`FAMS`). Links to families
+    /// missing from the store are skipped.
+    fn spouse_families(&self, individual: &Individual) -> Vec<Cow<'_, Family>> {
+        individual
+            .families
+            .iter()
+            .filter(|link| matches!(link.link_type, FamilyLinkType::Spouse))
+            .filter_map(|link| self.family(&link.xref))
+            .collect()
+    }
+
+    /// Fetches the individuals of a family: the two partners followed by the children.
+    /// Pointers to individuals missing from the store are skipped.
+    fn members(&self, family: &Family) -> Vec<Cow<'_, Individual>> {
+        family
+            .individual1
+            .iter()
+            .chain(family.individual2.iter())
+            .chain(family.children.iter())
+            .filter_map(|xref| self.individual(xref))
+            .collect()
+    }
+
+    /// Fetches the parents of an individual, the partners of the families they are a
+    /// child of. Parents of several of these families, ie. of both a birth & an
+    /// adoptive family, are listed once.
+    fn parents_of(&self, xref: &str) -> Vec<Cow<'_, Individual>> {
+        let Some(individual) = self.individual(xref) else {
+            return Vec::new();
+        };
+        let parents = self
+            .child_families(&individual)
+            .into_iter()
+            .flat_map(|family| {
+                family
+                    .parents()
+                    .into_iter()
+                    .map(|(parent, _)| parent.to_string())
+                    .collect::<Vec<_>>()
+            });
+        resolve(self, parents, None)
+    }
+
+    /// Fetches the children of an individual over all the families they are a partner
+    /// in, in the order of the families
+    fn children_of(&self, xref: &str) -> Vec<Cow<'_, Individual>> {
+        let Some(individual) = self.individual(xref) else {
+            return Vec::new();
+        };
+        let children = self
+            .spouse_families(&individual)
+            .into_iter()
+            .flat_map(|family| family.children.clone());
+        resolve(self, children, None)
+    }
+
+    /// Fetches the partners of an individual in the families they are a partner in
+    fn spouses_of(&self, xref: &str) -> Vec<Cow<'_, Individual>> {
+        let Some(individual) = self.individual(xref) else {
+            return Vec::new();
+        };
+        let spouses = self
+            .spouse_families(&individual)
+            .into_iter()
+            .filter_map(|family| family.partner_of(xref).map(str::to_string));
+        resolve(self, spouses, None)
+    }
+
+    /// Fetches the other children of the families an individual is a child of, so
+    /// half-siblings & adoptive siblings included
+    fn siblings_of(&self, xref: &str) -> Vec<Cow<'_, Individual>> {
+        let Some(individual) = self.individual(xref) else {
+            return Vec::new();
+        };
+        let siblings = self
+            .child_families(&individual)
+            .into_iter()
+            .flat_map(|family| family.children.clone());
+        resolve(self, siblings, Some(xref))
+    }
+}
+
+/// Fetches the individuals of `xrefs` once each, leaving out `exclude` and those
+/// missing from the store
+fn resolve<'s, S: RecordStore + ?Sized>(
+    store: &'s S,
+    xrefs: impl Iterator<Item = String>,
+    exclude: Option<&str>,
+) -> Vec<Cow<'s, Individual>> {
+    let mut seen: Vec<String> = Vec::new();
+    for xref in xrefs {
+        if Some(xref.as_str()) != exclude && !seen.contains(&xref) {
+            seen.push(xref);
+        }
+    }
+    seen.iter()
+        .filter_map(|xref| store.individual(xref))
+        .collect()
+}
+
+impl RecordStore for GedcomData {
+    fn individual(&self, xref: &str) -> Option<Cow<'_, Individual>> {
+        self.individuals
+            .iter()
+            .find(|individual| individual.xref.as_deref() == Some(xref))
+            .map(Cow::Borrowed)
+    }
+
+    fn family(&self, xref: &str) -> Option<Cow<'_, Family>> {
+        self.families
+            .iter()
+            .find(|family| family.xref.as_deref() == Some(xref))
+            .map(Cow::Borrowed)
+    }
+
+    fn source(&self, xref: &str) -> Option<Cow<'_, Source>> {
+        self.sources
+            .iter()
+            .find(|source| source.xref.as_deref() == Some(xref))
+            .map(Cow::Borrowed)
+    }
+
+    fn repository(&self, xref: &str) -> Option<Cow<'_, Repository>> {
+        self.repositories
+            .iter()
+            .find(|repo| repo.xref.as_deref() == Some(xref))
+            .map(Cow::Borrowed)
+    }
+
+    fn submitter(&self, xref: &str) -> Option<Cow<'_, Submitter>> {
+        self.submitters
+            .iter()
+            .find(|submitter| submitter.xref.as_deref() == Some(xref))
+            .map(Cow::Borrowed)
+    }
+}
@@ -0,0 +1,127 @@
+//! A quick look at the records of a file, for previewing large files, see `survey`.
+//!
+//! Only the lines starting records are read, straight from the bytes of the file: the
+//! content isn't decoded, the other lines aren't tokenized and no record is built. The
+//! tags & xrefs are borrowed from the bytes, so surveying allocates little more than the
+//! lists of xrefs.
+use std::collections::BTreeMap;
+
+use crate::encoding::{detect, DetectedEncoding};
+use crate::GedcomError;
+
+/// The records of a file, by type, see `survey`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Survey<'a> {
+    /// The records of each tag, ie. `INDI`, in the order of the tags
+    pub records: BTreeMap<&'a str, RecordSurvey<'a>>,
+    /// Number of lines of the file, blank lines left out
+    pub lines: usize,
+}
+
+/// The records of a type found by `survey`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RecordSurvey<'a> {
+    /// Number of records
+    pub count: usize,
+    /// Size of the records in the file, in bytes, subordinate lines included
+    pub bytes: usize,
+    /// Xrefs of the records, in the order of the file. Records without one are counted
+    /// but not listed.
+    pub xrefs: Vec<&'a str>,
+}
+
+impl<'a> Survey<'a> {
+    /// Number of records tagged `tag`
+    #[must_use]
+    pub fn count(&self, tag: &str) -> usize {
+        self.records.get(tag).map_or(0, |records| records.count)
+    }
+
+    /// Xrefs of the records tagged `tag`, in the order of the file
+    #[must_use]
+    pub fn xrefs(&self, tag: &str) -> &[&'a str] {
+        self.records
+            .get(tag)
+            .map_or(&[], |records| records.xrefs.as_slice())
+    }
+}
+
+/// Counts the records of the raw bytes of a GEDCOM file by tag, with their xrefs &
+/// sizes, many times faster than parsing the file. Records are told apart by their
+/// level 0 lines alone, so the survey of a malformed file may differ from its parse.
+///
+/// ```rust
+/// let survey = gedcom::survey(b"0 HEAD\n0 @I1@ INDI\n1 NAME Ann /Doe/\n0 TRLR\n").unwrap();
+/// assert_eq!(survey.count("INDI"), 1);
+/// assert_eq!(survey.xrefs("INDI"), ["@I1@"]);
+/// ```
+///
+/// # Errors
+///
+/// Returns an error for UTF-16 content, whose lines can't be read without decoding it.
+pub fn survey(bytes: &[u8]) -> Result<Survey<'_>, GedcomError> {
+    let content = match detect(bytes) {
+        (DetectedEncoding::Utf16Le | DetectedEncoding::Utf16Be, _) => {
+            return Err(GedcomError::Encoding(
+                "UTF-16 content can't be surveyed".to_string(),
+            ))
+        }
+        (_, content) => content,
+    };
+
+    let mut survey = Survey::default();
+    // the tag & start of the record being read
+    let mut current: Option<(&str, usize)> = None;
+    let mut start = 0;
+    while start < content.len() {
+        let end = content[start..]
+            .iter()
+            .position(|&b| b == b'\n' || b == b'\r')
+            .map_or(content.len(), |length| start + length);
+        let line = &content[start..end];
+        if !line.iter().all(u8::is_ascii_whitespace) {
+            survey.lines += 1;
+            if let Some((xref, tag)) = record_line(line) {
+                if let Some((tag, record_start)) = current {
+                    add_bytes(&mut survey, tag, start - record_start);
+                }
+                current = Some((tag, start));
+                let records = survey.records.entry(tag).or_default();
+                records.count += 1;
+                records.xrefs.extend(xref);
+            }
+        }
+        start = end + 1;
+    }
+    if let Some((tag, record_start)) = current {
+        add_bytes(&mut survey, tag, content.len() - record_start);
+    }
+    Ok(survey)
+}
+
+fn add_bytes(survey: &mut Survey<'_>, tag: &str, bytes: usize) {
+    if let Some(records) = survey.records.get_mut(tag) {
+        records.bytes += bytes;
+    }
+}
+
+/// The xref & tag of a line starting a record, ie. `0 @I1@ INDI`. Only they are read
+/// as text, as the value of a note record may be in any encoding.
+fn record_line(line: &[u8]) -> Option<(Option<&str>, &str)> {
+    let mut parts = trim_start(line)
+        .strip_prefix(b"0 ")?
+        .split(u8::is_ascii_whitespace)
+        .filter(|part| !part.is_empty())
+        .map(std::str::from_utf8);
+    let first = parts.next()?.ok()?;
+    if first.starts_with('@') {
+        Some((Some(first), parts.next()?.ok()?))
+    } else {
+        Some((None, first))
+    }
+}
+
+fn trim_start(line: &[u8]) -> &[u8] {
+    let blank = line.iter().take_while(|b| b.is_ascii_whitespace()).count();
+    &line[blank..]
+}
@@ -0,0 +1,227 @@
+//! A lossless token stream of GEDCOM content, for editors that highlight & fold it.
+//!
+//! Unlike the `tokenizer` used for parsing, nothing is normalized or dropped: every
+//! byte of the content belongs to exactly one token, so concatenating the text of the
+//! tokens gives back the content, and malformed lines are marked rather than rejected.
+use std::ops::Range;
+
+/// What a piece of the content is
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyntaxKind {
+    /// Spaces & tabs between the parts of a line, or a byte order mark
+    Whitespace,
+    /// The level number starting a line
+    Level,
+    /// The cross-reference identifier of a record, ie. `@I1@` in `0 @I1@ INDI`
+    Xref,
+    /// A standard tag
+    Tag,
+    /// A user-defined tag, beginning with an underscore
+    CustomTag,
+    /// The value of a line
+    Value,
+    /// A value pointing to a record, ie. `@F1@` in `1 FAMS @F1@`
+    Pointer,
+    /// A line terminator, `\n`, `\r\n` or `\r`
+    Newline,
+    /// The rest of a line that doesn't follow the line syntax, ie. a missing level
+    Error,
+}
+
+/// A piece of the content
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyntaxToken<'a> {
+    /// What the piece is
+    pub kind: SyntaxKind,
+    /// The text of the piece, as written
+    pub text: &'a str,
+    /// Byte offsets of the piece in the content
+    pub span: Range<usize>,
+    /// Line number of the piece, from 1
+    pub line: u32,
+}
+
+/// A range of lines an editor can fold: a line with its subordinate lines
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FoldRange {
+    /// The line holding the subordinate lines, from 1
+    pub start_line: u32,
+    /// The last subordinate line
+    pub end_line: u32,
+}
+
+/// Splits `content` into tokens covering all of it
+#[must_use]
+pub fn syntax_tokens(content: &str) -> Vec<SyntaxToken<'_>> {
+    let mut tokens = Vec::new();
+    let mut offset = 0;
+    let mut line = 1;
+    while offset < content.len() {
+        let rest = &content[offset..];
+        let end = rest.find(['\n', '\r']).unwrap_or(rest.len());
+        let mut lexer = LineLexer {
+            content,
+            position: offset,
+            end: offset + end,
+            line,
+            tokens: &mut tokens,
+        };
+        lexer.lex();
+
+        let terminator = match &rest[end..] {
+            t if t.starts_with("\r\n") => 2,
+            "" => 0,
+            _ => 1,
+        };
+        offset += end;
+        if terminator > 0 {
+            tokens.push(token(
+                content,
+                SyntaxKind::Newline,
+                offset..offset + terminator,
+                line,
+            ));
+            offset += terminator;
+        }
+        line += 1;
+    }
+    tokens
+}
+
+/// The lines an editor can fold, computed from the levels of `tokens`. Lines without a
+/// level are folded along with the line before them.
+#[must_use]
+pub fn folding_ranges(tokens: &[SyntaxToken<'_>]) -> Vec<FoldRange> {
+    let mut ranges = Vec::new();
+    // lines still open for folding, with their levels
+    let mut open: Vec<(u32, u8)> = Vec::new();
+    let mut last_line = 0;
+    for token in tokens.iter().filter(|t| t.kind == SyntaxKind::Level) {
+        let level = token.text.parse::<u8>().unwrap_or(u8::MAX);
+        while let Some(&(start_line, open_level)) = open.last() {
+            if open_level < level {
+                break;
+            }
+            open.pop();
+            if token.line - 1 > start_line {
+                ranges.push(FoldRange {
+                    start_line,
+                    end_line: token.line - 1,
+                });
+            }
+        }
+        open.push((token.line, level));
+        last_line = token.line;
+    }
+    let end_line = tokens.last().map_or(last_line, |t| t.line).max(last_line);
+    while let Some((start_line, _)) = open.pop() {
+        if end_line > start_line {
+            ranges.push(FoldRange {
+                start_line,
+                end_line,
+            });
+        }
+    }
+    ranges.sort_by_key(|range| range.start_line);
+    ranges
+}
+
+fn token(content: &str, kind: SyntaxKind, span: Range<usize>, line: u32) -> SyntaxToken<'_> {
+    SyntaxToken {
+        kind,
+        text: &content[span.clone()],
+        span,
+        line,
+    }
+}
+
+/// Splits a single line, without its terminator, into tokens
+struct LineLexer<'a, 't> {
+    content: &'a str,
+    position: usize,
+    end: usize,
+    line: u32,
+    tokens: &'t mut Vec<SyntaxToken<'a>>,
+}
+
+impl LineLexer<'_, '_> {
+    fn lex(&mut self) {
+        self.take(SyntaxKind::Whitespace, |c| {
+            c == ' ' || c == '\t' || c == '\u{feff}'
+        });
+        if self.position == self.end {
+            return;
+        }
+        if !self.take(SyntaxKind::Level, |c| c.is_ascii_digit()) {
+            self.rest(SyntaxKind::Error);
+            return;
+        }
+        if !self.whitespace() {
+            self.rest(SyntaxKind::Error);
+            return;
+        }
+        if self.peek() == Some('@') {
+            self.take(SyntaxKind::Xref, |c| !c.is_whitespace());
+            self.whitespace();
+        }
+        let kind = if self.peek() == Some('_') {
+            SyntaxKind::CustomTag
+        } else {
+            SyntaxKind::Tag
+        };
+        if !self.take(kind, |c| !c.is_whitespace()) {
+            self.rest(SyntaxKind::Error);
+            return;
+        }
+        // a single delimiter, the spaces after it belong to the value
+        if self.take_one(SyntaxKind::Whitespace, |c| c == ' ' || c == '\t') {
+            let value = &self.content[self.position..self.end];
+            let is_pointer = value.len() > 2
+                && value.starts_with('@')
+                && value.ends_with('@')
+                && !value.contains(char::is_whitespace)
+                && !value.starts_with("@@");
+            self.rest(if is_pointer {
+                SyntaxKind::Pointer
+            } else {
+                SyntaxKind::Value
+            });
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.content[self.position..self.end].chars().next()
+    }
+
+    fn whitespace(&mut self) -> bool {
+        self.take(SyntaxKind::Whitespace, |c| c == ' ' || c == '\t')
+    }
+
+    /// Takes the characters matching `accept` as a token, returning whether there were any
+    fn take(&mut self, kind: SyntaxKind, accept: impl Fn(char) -> bool) -> bool {
+        let text = &self.content[self.position..self.end];
+        let length = text.find(|c| !accept(c)).unwrap_or(text.len());
+        self.push(kind, length)
+    }
+
+    fn take_one(&mut self, kind: SyntaxKind, accept: impl Fn(char) -> bool) -> bool {
+        match self.peek() {
+            Some(c) if accept(c) => self.push(kind, c.len_utf8()),
+            _ => false,
+        }
+    }
+
+    fn rest(&mut self, kind: SyntaxKind) {
+        self.push(kind, self.end - self.position);
+    }
+
+    fn push(&mut self, kind: SyntaxKind, length: usize) -> bool {
+        if length == 0 {
+            return false;
+        }
+        let span = self.position..self.position + length;
+        self.tokens.push(token(self.content, kind, span, self.line));
+        self.position += length;
+        true
+    }
+}
@@ -1,6 +1,9 @@
-//! Handles the tokenization of a GEDCOM file
+//! Handles the tokenization of a GEDCOM file, and the iteration over its lines
 use std::str::Chars;
 
+use crate::diagnostics::Diagnostic;
+use crate::GedcomError;
+
 /// The base enum of Token types
 ///
 /// making use of [GEDCOM Standard Release 5.5.1](https://edge.fscdn.org/assets/img/documents/ged551-5bac5e57fe88dd37df0e153d9c515335.pdf), p.11
@@ -33,17 +36,58 @@ pub struct Tokenizer<'a> {
     chars: Chars<'a>,
     /// The current line number of the file we are parsing
     pub line: u32,
+    /// Whether tags are uppercased, see `uppercase_tags`
+    uppercase_tags: bool,
+    /// The spelling of the current tag as written, when it was uppercased
+    original_tag: Option<String>,
+    /// Deviations in the layout of lines that were normalized away
+    findings: Vec<Diagnostic>,
 }
 
 impl<'a> Tokenizer<'a> {
     /// Creates a new tokenizer for a char interator of gedcom file contents
     #[must_use]
-    pub fn new(chars: Chars<'a>) -> Tokenizer {
+    pub fn new(chars: Chars<'a>) -> Tokenizer<'a> {
         Tokenizer {
             current_char: '\n',
             current_token: Token::None,
             chars,
             line: 0,
+            uppercase_tags: false,
+            original_tag: None,
+            findings: Vec::new(),
+        }
+    }
+
+    /// Takes the findings about lines whose layout was normalized so far, like levels
+    /// written as `01` or indented with tabs. These are reported as warnings, or as
+    /// errors when `strict`.
+    pub fn take_findings(&mut self, strict: bool) -> Vec<Diagnostic> {
+        self.findings
+            .drain(..)
+            .map(|finding| {
+                if strict {
+                    Diagnostic::error(finding.line, finding.message)
+                } else {
+                    finding
+                }
+            })
+            .collect()
+    }
+
+    /// Uppercases standard tags written in lower or mixed case, like `1 name` or
+    /// `2 Date`, which some old exports emit. The tag as written stays available
+    /// through `original_tag`.
+    pub fn uppercase_tags(&mut self, enabled: bool) {
+        self.uppercase_tags = enabled;
+    }
+
+    /// The current tag as it was written, when it was uppercased into the current token
+    #[must_use]
+    pub fn original_tag(&self) -> Option<&str> {
+        match self.current_token {
+            Token::Tag(_) => self.original_tag.as_deref(),
+            _ => None,
         }
     }
 
@@ -54,31 +98,50 @@ impl<'a> Tokenizer<'a> {
     }
 
     /// Loads the next token into state
+    ///
+    /// # Panics
+    ///
+    /// Panics when a token is encountered in a position where it is not expected, see
+    /// `try_next_token`.
     pub fn next_token(&mut self) {
+        if let Err(error) = self.try_next_token() {
+            panic!("{}", error);
+        }
+    }
+
+    /// Loads the next token into state.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a token is encountered in a position where it is not
+    /// expected, ie. a line not starting with a level number.
+    pub fn try_next_token(&mut self) -> Result<(), GedcomError> {
         if self.current_char == '\0' {
             self.current_token = Token::EOF;
-            return;
+            return Ok(());
         }
 
         // level number is at the start of each line.
-        if self.current_char == '\r' {
-            self.next_char();
-        }
-        if self.current_char == '\n' {
-            self.next_char();
+        if self.is_line_terminator() {
+            self.skip_line_terminator();
+
+            // trailing newline at the end of the file
+            if self.current_char == '\0' {
+                self.current_token = Token::EOF;
+                return Ok(());
+            }
 
-            self.current_token = Token::Level(self.extract_number());
+            self.current_token = Token::Level(self.extract_number()?);
             self.line += 1;
-            return;
+            return Ok(());
         }
 
         self.skip_whitespace();
 
         // handle tag with trailing whitespace
-        if self.current_char == '\n' {
+        if self.is_line_terminator() {
             // println!("line {}: trailing whitespace {:?}", self.line, self.current_token);
-            self.next_token();
-            return;
+            return self.try_next_token();
         }
 
         self.current_token = match self.current_token {
@@ -88,31 +151,67 @@ impl<'a> Tokenizer<'a> {
                 } else if self.current_char == '_' {
                     Token::CustomTag(self.extract_word())
                 } else {
-                    Token::Tag(self.extract_word())
+                    Token::Tag(self.extract_tag())
                 }
             }
-            Token::Pointer(_) => Token::Tag(self.extract_word()),
+            Token::Pointer(_) if self.current_char == '_' => Token::CustomTag(self.extract_word()),
+            Token::Pointer(_) => Token::Tag(self.extract_tag()),
             Token::Tag(_) | Token::CustomTag(_) => Token::LineValue(self.extract_value()),
-            _ => panic!(
-                "line {}: Tokenization error! {:?}",
-                self.line, self.current_token
-            ),
+            _ => return Err(GedcomError::Tokenize { line: self.line }),
         };
+        Ok(())
     }
 
     fn next_char(&mut self) {
         self.current_char = self.chars.next().unwrap_or('\0');
     }
 
-    fn extract_number(&mut self) -> u8 {
-        self.skip_whitespace();
+    /// Whether the current char ends a line: lines end with `\n`, `\r\n` or a lone `\r`,
+    /// as written by old Mac exports
+    fn is_line_terminator(&self) -> bool {
+        self.current_char == '\n' || self.current_char == '\r'
+    }
+
+    /// Moves past the terminator of the current line, counting `\r\n` as a single one
+    fn skip_line_terminator(&mut self) {
+        if self.current_char == '\r' {
+            self.next_char();
+            if self.current_char == '\n' {
+                self.next_char();
+            }
+        } else {
+            self.next_char();
+        }
+    }
+
+    fn extract_number(&mut self) -> Result<u8, GedcomError> {
+        if self.is_nonnewline_whitespace() {
+            self.skip_whitespace();
+            self.add_finding("Whitespace before level number".to_string());
+        }
         let mut digits: Vec<char> = Vec::new();
-        while self.current_char.is_digit(10) {
+        while self.current_char.is_ascii_digit() {
             digits.push(self.current_char);
             self.next_char();
         }
+        if digits.len() > 1 && digits[0] == '0' {
+            let level = digits.iter().collect::<String>();
+            self.add_finding(format!("Level number {level} has a leading zero"));
+        }
+
+        digits
+            .iter()
+            .collect::<String>()
+            .parse::<u8>()
+            .map_err(|_| GedcomError::Tokenize {
+                line: self.line + 1,
+            })
+    }
 
-        digits.iter().collect::<String>().parse::<u8>().unwrap()
+    /// Records a finding on the line being started.
+    fn add_finding(&mut self, message: String) {
+        self.findings
+            .push(Diagnostic::warning(self.line + 1, message));
     }
 
     fn extract_word(&mut self) -> String {
@@ -125,14 +224,27 @@ impl<'a> Tokenizer<'a> {
         letters.iter().collect::<String>()
     }
 
+    fn extract_tag(&mut self) -> String {
+        let tag = self.extract_word();
+        self.original_tag = None;
+        if self.uppercase_tags && tag.chars().any(char::is_lowercase) {
+            let uppercased = tag.to_uppercase();
+            self.original_tag = Some(tag);
+            uppercased
+        } else {
+            tag
+        }
+    }
+
     fn extract_value(&mut self) -> String {
         let mut letters: Vec<char> = Vec::new();
-        while self.current_char != '\n' && self.current_char != '\r' {
+        while !self.is_line_terminator() && self.current_char != '\0' {
             letters.push(self.current_char);
             self.next_char();
         }
 
-        letters.iter().collect::<String>()
+        // an @ within a value is written doubled, to tell it apart from a pointer
+        letters.iter().collect::<String>().replace("@@", "@")
     }
 
     fn skip_whitespace(&mut self) {
@@ -143,7 +255,112 @@ impl<'a> Tokenizer<'a> {
 
     fn is_nonnewline_whitespace(&self) -> bool {
         let is_zero_width_space = self.current_char as u32 == 65279_u32;
-        let not_a_newline = self.current_char != '\n';
+        let not_a_newline = !self.is_line_terminator();
         (self.current_char.is_whitespace() || is_zero_width_space) && not_a_newline
     }
 }
+
+/// A line of GEDCOM content, as yielded by `LineIter`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GedcomLine {
+    /// Line number of the content, from 1
+    pub line: u32,
+    /// The level, denoting the depth within the tree
+    pub level: u8,
+    /// The cross-reference identifier of a record, ie. `@I1@` in `0 @I1@ INDI`
+    pub xref: Option<String>,
+    /// The tag as written, standard or user-defined
+    pub tag: String,
+    /// The value of the line, with doubled `@@` unescaped
+    pub value: Option<String>,
+}
+
+/// Iterator over the lines of GEDCOM content, for tools filtering lines or gathering
+/// statistics without the typed parse. Lines are yielded as written: `CONT` & `CONC`
+/// lines aren't joined, and tags aren't checked against the specification. The
+/// iteration ends after the last line, or after the first line that can't be read.
+///
+/// ```rust
+/// use gedcom::tokenizer::LineIter;
+///
+/// let content = "0 HEAD\n0 @I1@ INDI\n1 NAME Ann /Doe/\n0 TRLR";
+/// let names: Vec<String> = LineIter::new(content.chars())
+///     .filter_map(Result::ok)
+///     .filter(|line| line.tag == "NAME")
+///     .filter_map(|line| line.value)
+///     .collect();
+/// assert_eq!(names, ["Ann /Doe/"]);
+/// ```
+pub struct LineIter<'a> {
+    tokenizer: Tokenizer<'a>,
+    done: bool,
+}
+
+impl<'a> LineIter<'a> {
+    /// Creates an iterator over the lines of a char iterator of gedcom file contents
+    #[must_use]
+    pub fn new(chars: Chars<'a>) -> LineIter<'a> {
+        LineIter {
+            tokenizer: Tokenizer::new(chars),
+            done: false,
+        }
+    }
+
+    /// Reads the line starting at the current token, `None` at the end of the content
+    fn read_line(&mut self) -> Result<Option<GedcomLine>, GedcomError> {
+        if self.tokenizer.current_token == Token::None {
+            self.tokenizer.try_next_token()?;
+        }
+        let level = match self.tokenizer.current_token {
+            Token::Level(level) => level,
+            Token::EOF => return Ok(None),
+            _ => return Err(self.malformed()),
+        };
+        let line = self.tokenizer.line;
+        self.tokenizer.try_next_token()?;
+
+        let mut xref = None;
+        if let Token::Pointer(pointer) = &self.tokenizer.current_token {
+            xref = Some(pointer.clone());
+            self.tokenizer.try_next_token()?;
+        }
+        let tag = match &self.tokenizer.current_token {
+            Token::Tag(tag) | Token::CustomTag(tag) => tag.clone(),
+            _ => return Err(self.malformed()),
+        };
+        self.tokenizer.try_next_token()?;
+
+        let mut value = None;
+        if let Token::LineValue(line_value) = &self.tokenizer.current_token {
+            value = Some(line_value.clone());
+            self.tokenizer.try_next_token()?;
+        }
+        Ok(Some(GedcomLine {
+            line,
+            level,
+            xref,
+            tag,
+            value,
+        }))
+    }
+
+    /// The error for a line missing its level or tag
+    fn malformed(&self) -> GedcomError {
+        GedcomError::Tokenize {
+            line: self.tokenizer.line,
+        }
+    }
+}
+
+impl Iterator for LineIter<'_> {
+    type Item = Result<GedcomLine, GedcomError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let line = self.read_line().transpose();
+        self.done = !matches!(line, Some(Ok(_)));
+        line
+    }
+}
@@ -1,4 +1,16 @@
-use crate::types::{Family, Header, Individual, Media, Repository, Source, Submitter};
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::time::SystemTime;
+
+use crate::analysis::{place_tree, PlaceNode, Statistics};
+use crate::parser::Record;
+use crate::types::{
+    Association, ChangeDate, CustomData, Event, Family, FamilyLinkType, GedcomVersion,
+    HasCustomData, HasEvents, Header, Individual, LdsOrdinance, Location, MultimediaLink,
+    MultimediaRecord, Name, Note, NoteRecord, Place, Repository, Source, SourceCitation, Submitter,
+    UserDefinedDataset,
+};
+use crate::writer::{event_tag, GedcomWriter};
 #[cfg(feature = "json")]
 use serde::{Deserialize, Serialize};
 
@@ -9,31 +21,238 @@ pub struct GedcomData {
     /// Header containing file metadata
     pub header: Header,
     /// List of submitters of the facts
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
     pub submitters: Vec<Submitter>,
     /// Individuals within the family tree
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
     pub individuals: Vec<Individual>,
     /// The family units of the tree, representing relationships between individuals
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
     pub families: Vec<Family>,
     /// A data repository where `sources` are held
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
     pub repositories: Vec<Repository>,
     /// Sources of facts. _ie._ book, document, census, etc.
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
     pub sources: Vec<Source>,
-    /// A multimedia asset linked to a fact
-    pub multimedia: Vec<Media>,
+    /// Multimedia records, linking files to the facts
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub multimedia: Vec<MultimediaRecord>,
+    /// Shared location records, the `_LOC` records of some programs
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub locations: Vec<Location>,
+    /// Note records, which structures point to with `NOTE @N1@`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub notes: Vec<NoteRecord>,
+    /// Records of tags the parser doesn't handle, see `ParseOptions::extension_records`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub extension_records: Vec<UserDefinedDataset>,
+    /// Notes whose text is shared by several structures, written as note records, see
+    /// `ParseOptions::deduplicate_notes`. JSON holds the text of each note in place.
+    #[cfg_attr(feature = "json", serde(skip))]
+    pub shared_notes: Vec<Note>,
+    /// Whether records added or edited through the tree get their change dates updated
+    #[cfg_attr(feature = "json", serde(skip))]
+    pub change_dates: ChangeDatePolicy,
+}
+
+/// Whether `GedcomData` keeps the change dates of its records up to date
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChangeDatePolicy {
+    /// Change dates are only set by hand, ie. when parsing a file
+    #[default]
+    Manual,
+    /// Records added to the tree get their creation (`CREA`) & change (`CHAN`) dates
+    /// set to the current time, and records edited through `GedcomData::edit_individual`,
+    /// `edit_family` & `edit_multimedia` get their change date updated
+    Automatic,
+}
+
+/// Where the multimedia objects of a tree are described, see
+/// `GedcomData::place_multimedia`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MultimediaPlacement {
+    /// In multimedia records the links point to: the objects embedded in links are
+    /// moved into records of their own
+    Records,
+    /// Within the links, as older programs expect: the records a single link points to
+    /// are moved into the link, without their change dates. Records pointed to by
+    /// several links, or by none, stay records.
+    Inline,
+}
+
+impl MultimediaPlacement {
+    /// The placement a GEDCOM version requires: `Records` for 7.0, which has no
+    /// embedded objects, and `None` for the versions allowing both
+    #[must_use]
+    pub fn required_by(version: &GedcomVersion) -> Option<MultimediaPlacement> {
+        match version {
+            GedcomVersion::V7_0 => Some(MultimediaPlacement::Records),
+            _ => None,
+        }
+    }
+}
+
+/// A record of the tree that keeps the date of its last change
+#[derive(Clone, Copy, Debug)]
+pub enum ChangedRecord<'a> {
+    /// A `SUBM` record
+    Submitter(&'a Submitter),
+    /// An `INDI` record
+    Individual(&'a Individual),
+    /// A `FAM` record
+    Family(&'a Family),
+    /// A `REPO` record
+    Repository(&'a Repository),
+    /// An `OBJE` record
+    Multimedia(&'a MultimediaRecord),
+    /// A `_LOC` record
+    Location(&'a Location),
+    /// A `NOTE` record
+    Note(&'a NoteRecord),
+}
+
+impl<'a> ChangedRecord<'a> {
+    /// The cross-reference identifier of the record
+    #[must_use]
+    pub fn xref(&self) -> Option<&'a str> {
+        match self {
+            ChangedRecord::Submitter(submitter) => submitter.xref.as_deref(),
+            ChangedRecord::Individual(individual) => individual.xref.as_deref(),
+            ChangedRecord::Family(family) => family.xref.as_deref(),
+            ChangedRecord::Repository(repository) => repository.xref.as_deref(),
+            ChangedRecord::Multimedia(multimedia) => multimedia.xref.as_deref(),
+            ChangedRecord::Location(location) => location.xref.as_deref(),
+            ChangedRecord::Note(note) => note.xref.as_deref(),
+        }
+    }
+
+    /// The date of the last change to the record, `CHAN.DATE`
+    #[must_use]
+    pub fn last_updated(&self) -> Option<&'a str> {
+        match self {
+            ChangedRecord::Submitter(submitter) => submitter.last_updated.as_deref(),
+            ChangedRecord::Individual(individual) => individual.last_updated.as_deref(),
+            ChangedRecord::Family(family) => family.last_updated.as_deref(),
+            ChangedRecord::Repository(repository) => repository.last_updated.as_deref(),
+            ChangedRecord::Multimedia(multimedia) => multimedia.last_updated.as_deref(),
+            ChangedRecord::Location(location) => location.last_updated.as_deref(),
+            ChangedRecord::Note(note) => note.last_updated.as_deref(),
+        }
+    }
+}
+
+/// Where a note is found in a tree, see `GedcomData::iter_notes`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NoteOwner<'a> {
+    /// Tag of the record holding the note, ie. `INDI`, or `HEAD` for the header
+    pub record: &'static str,
+    /// Cross-reference identifier of the record, `None` for the header & records
+    /// without one
+    pub xref: Option<&'a str>,
+    /// Tags of the structures from the record down to the note, ie. `["BIRT", "ASSO"]`
+    /// for a note on a witness of a birth. Empty for a note of the record itself.
+    pub path: Vec<&'static str>,
+}
+
+impl<'a> NoteOwner<'a> {
+    fn new(record: &'static str, xref: Option<&'a str>, path: &[&'static str]) -> Self {
+        NoteOwner {
+            record,
+            xref,
+            path: path.to_vec(),
+        }
+    }
 }
 
 // should maybe store these by xref if available?
 impl GedcomData {
     /// Adds a `Family` (a relationship between individuals) to the tree
-    pub fn add_family(&mut self, family: Family) {
+    pub fn add_family(&mut self, mut family: Family) {
+        stamp(
+            self.change_dates,
+            Some(&mut family.created),
+            &mut family.last_updated,
+        );
         self.families.push(family);
     }
 
     /// Adds an `Individual` to the tree
-    pub fn add_individual(&mut self, individual: Individual) {
+    pub fn add_individual(&mut self, mut individual: Individual) {
+        stamp(
+            self.change_dates,
+            Some(&mut individual.created),
+            &mut individual.last_updated,
+        );
         self.individuals.push(individual);
     }
 
+    /// Adds an `Individual` to the tree, giving it an unused xref of the form `@I1@`,
+    /// numbered after the individuals of the tree, when it has none. Returns the xref
+    /// of the individual.
+    pub fn add_individual_with_xref(&mut self, mut individual: Individual) -> String {
+        let xref = match &individual.xref {
+            Some(xref) => xref.clone(),
+            None => self.unused_xref('I', self.individuals.len()),
+        };
+        individual.xref = Some(xref.clone());
+        self.add_individual(individual);
+        xref
+    }
+
+    /// Adds a `Family` to the tree, giving it an unused xref of the form `@F1@`,
+    /// numbered after the families of the tree, when it has none. Returns the xref of
+    /// the family.
+    pub fn add_family_with_xref(&mut self, mut family: Family) -> String {
+        let xref = match &family.xref {
+            Some(xref) => xref.clone(),
+            None => self.unused_xref('F', self.families.len()),
+        };
+        family.xref = Some(xref.clone());
+        self.add_family(family);
+        xref
+    }
+
+    /// The first xref made of `prefix` & a number above `count` that no record has
+    fn unused_xref(&self, prefix: char, count: usize) -> String {
+        let xrefs: HashSet<&str> = self.xrefs().collect();
+        let mut number = count + 1;
+        loop {
+            let xref = format!("@{prefix}{number}@");
+            if !xrefs.contains(xref.as_str()) {
+                return xref;
+            }
+            number += 1;
+        }
+    }
+
     /// Adds a data `Repository` to the tree
     pub fn add_repository(&mut self, repo: Repository) {
         self.repositories.push(repo);
@@ -44,22 +263,683 @@ impl GedcomData {
         self.sources.push(source);
     }
 
+    /// Adds a `MultimediaRecord` to the tree
+    pub fn add_multimedia(&mut self, mut multimedia: MultimediaRecord) {
+        stamp(
+            self.change_dates,
+            Some(&mut multimedia.created),
+            &mut multimedia.last_updated,
+        );
+        self.multimedia.push(multimedia);
+    }
+
+    /// Moves the `FORM` & `TITL` of multimedia records written as siblings of `FILE`
+    /// under the files, see `MultimediaRecord::normalize_files`. Returns the number of
+    /// records changed.
+    pub fn normalize_multimedia_files(&mut self) -> usize {
+        self.multimedia
+            .iter_mut()
+            .map(MultimediaRecord::normalize_files)
+            .filter(|changed| *changed)
+            .count()
+    }
+
+    /// Moves the objects embedded in multimedia links into records the links point to,
+    /// or the reverse, see `MultimediaPlacement`. Returns the number of links changed.
+    pub fn place_multimedia(&mut self, placement: MultimediaPlacement) -> usize {
+        match placement {
+            MultimediaPlacement::Records => self.multimedia_to_records(),
+            MultimediaPlacement::Inline => self.inline_multimedia(),
+        }
+    }
+
+    /// Gives each embedded object a record of its own, with a new xref
+    fn multimedia_to_records(&mut self) -> usize {
+        let xrefs: HashSet<String> = self.xrefs().map(String::from).collect();
+        let mut number = self.multimedia.len();
+        let mut records = Vec::new();
+        self.multimedia_links_mut(|link| {
+            let Some(mut multimedia) = link.embedded.take() else {
+                return;
+            };
+            let xref = loop {
+                number += 1;
+                let xref = format!("@M{number}@");
+                if !xrefs.contains(&xref) {
+                    break xref;
+                }
+            };
+            multimedia.xref = Some(xref.clone());
+            link.xref = xref;
+            records.push(*multimedia);
+        });
+        let count = records.len();
+        for multimedia in records {
+            self.add_multimedia(multimedia);
+        }
+        count
+    }
+
+    /// Moves the records a single link points to into the link
+    fn inline_multimedia(&mut self) -> usize {
+        let mut uses: HashMap<String, usize> = HashMap::new();
+        self.multimedia_links_mut(|link| {
+            if link.embedded.is_none() {
+                *uses.entry(link.xref.clone()).or_default() += 1;
+            }
+        });
+        let mut inlined: HashMap<String, MultimediaRecord> = HashMap::new();
+        let mut kept = Vec::new();
+        for multimedia in std::mem::take(&mut self.multimedia) {
+            match &multimedia.xref {
+                Some(xref) if uses.get(xref) == Some(&1) && !inlined.contains_key(xref) => {
+                    inlined.insert(xref.clone(), multimedia);
+                }
+                _ => kept.push(multimedia),
+            }
+        }
+        self.multimedia = kept;
+        let mut count = 0;
+        self.multimedia_links_mut(|link| {
+            if let Some(mut multimedia) = inlined.remove(&link.xref) {
+                multimedia.xref = None;
+                multimedia.last_updated = None;
+                multimedia.created = None;
+                link.xref.clear();
+                link.embedded = Some(Box::new(multimedia));
+                count += 1;
+            }
+        });
+        count
+    }
+
+    /// Calls `f` with each multimedia link of the tree, in the order of the records
+    fn multimedia_links_mut(&mut self, mut f: impl FnMut(&mut MultimediaLink)) {
+        for individual in &mut self.individuals {
+            individual.multimedia.iter_mut().for_each(&mut f);
+            for event in individual.events_mut() {
+                event.multimedia.iter_mut().for_each(&mut f);
+            }
+        }
+        let events = self
+            .families
+            .iter_mut()
+            .flat_map(HasEvents::events_mut)
+            .chain(
+                self.sources
+                    .iter_mut()
+                    .flat_map(|source| source.data.events_mut()),
+            );
+        for event in events {
+            event.multimedia.iter_mut().for_each(&mut f);
+        }
+    }
+
+    /// Replaces the places of all events & LDS ordinances that exactly match a key of
+    /// `mapping` by its value, ie. to standardize `"NY, USA"` as `"New York, United
+    /// States"`. Returns the number of places replaced for each key that matched.
+    pub fn replace_places(&mut self, mapping: &HashMap<String, String>) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        let mut replace = |place: &mut Option<Place>| {
+            let Some(place) = place else {
+                return;
+            };
+            if let Some(replacement) = mapping.get(place.value.as_str()) {
+                *counts.entry(place.value.clone()).or_insert(0) += 1;
+                place.value.clone_from(replacement);
+            }
+        };
+        let events = self
+            .individuals
+            .iter_mut()
+            .flat_map(HasEvents::events_mut)
+            .chain(self.families.iter_mut().flat_map(HasEvents::events_mut))
+            .chain(
+                self.sources
+                    .iter_mut()
+                    .flat_map(|source| source.data.events_mut()),
+            );
+        for event in events {
+            replace(&mut event.place);
+        }
+        let ordinances = self
+            .individuals
+            .iter_mut()
+            .flat_map(|individual| &mut individual.lds_ordinances)
+            .chain(
+                self.families
+                    .iter_mut()
+                    .flat_map(|family| &mut family.lds_ordinances),
+            );
+        for ordinance in ordinances {
+            replace(&mut ordinance.place);
+        }
+        counts
+    }
+
+    /// Edits the `Individual` with the given xref, updating its change date according
+    /// to the `change_dates` policy. Returns the result of `edit`, or `None` when there
+    /// is no such individual.
+    pub fn edit_individual<R>(
+        &mut self,
+        xref: &str,
+        edit: impl FnOnce(&mut Individual) -> R,
+    ) -> Option<R> {
+        let policy = self.change_dates;
+        let individual = self
+            .individuals
+            .iter_mut()
+            .find(|individual| individual.xref.as_deref() == Some(xref))?;
+        let result = edit(individual);
+        stamp(policy, None, &mut individual.last_updated);
+        Some(result)
+    }
+
+    /// Like `edit_individual`, for the `Family` with the given xref
+    pub fn edit_family<R>(&mut self, xref: &str, edit: impl FnOnce(&mut Family) -> R) -> Option<R> {
+        let policy = self.change_dates;
+        let family = self
+            .families
+            .iter_mut()
+            .find(|family| family.xref.as_deref() == Some(xref))?;
+        let result = edit(family);
+        stamp(policy, None, &mut family.last_updated);
+        Some(result)
+    }
+
+    /// Like `edit_individual`, for the `MultimediaRecord` with the given xref
+    pub fn edit_multimedia<R>(
+        &mut self,
+        xref: &str,
+        edit: impl FnOnce(&mut MultimediaRecord) -> R,
+    ) -> Option<R> {
+        let policy = self.change_dates;
+        let multimedia = self
+            .multimedia
+            .iter_mut()
+            .find(|multimedia| multimedia.xref.as_deref() == Some(xref))?;
+        let result = edit(multimedia);
+        stamp(policy, None, &mut multimedia.last_updated);
+        Some(result)
+    }
+
     /// Adds a `Submitter` to the tree
     pub fn add_submitter(&mut self, submitter: Submitter) {
         self.submitters.push(submitter);
     }
 
+    /// Adds a record read by `Parser::records` to the tree, the header replacing the
+    /// current one
+    pub fn add_record(&mut self, record: Record) {
+        match record {
+            Record::Header(header) => self.header = header,
+            Record::Submitter(submitter) => self.add_submitter(submitter),
+            Record::Individual(individual) => self.add_individual(individual),
+            Record::Family(family) => self.add_family(family),
+            Record::Source(source) => self.add_source(source),
+            Record::Repository(repository) => self.add_repository(repository),
+            Record::Multimedia(multimedia) => self.add_multimedia(multimedia),
+            Record::Location(location) => self.locations.push(location),
+            Record::Note(note) => self.notes.push(note),
+            Record::Extension(record) => self.extension_records.push(record),
+        }
+    }
+
+    /// Finds the lines tagged `tag` among the user-defined tags of the whole tree: those
+    /// of every record, of the events of records, and their subordinate lines. Each is
+    /// returned with the xref of the record it belongs to.
+    #[must_use]
+    pub fn find_custom(&self, tag: &str) -> Vec<(Option<&str>, &CustomData)> {
+        self.custom_data()
+            .into_iter()
+            .flat_map(|(xref, data)| data.find_by_tag(tag).into_iter().map(move |d| (xref, d)))
+            .collect()
+    }
+
+    /// The distinct user-defined tags of the whole tree, at any level, in the order they
+    /// first appear, ie. to declare them in the `SCHMA` of a GEDCOM 7.0 header
+    #[must_use]
+    pub fn custom_tags(&self) -> Vec<&str> {
+        fn collect<'a>(data: &'a CustomData, tags: &mut Vec<&'a str>) {
+            if data.tag.starts_with('_') && !tags.contains(&data.tag.as_str()) {
+                tags.push(&data.tag);
+            }
+            for child in &data.children {
+                collect(child, tags);
+            }
+        }
+
+        let mut tags = Vec::new();
+        for (_, data) in self.custom_data() {
+            collect(data, &mut tags);
+        }
+        tags
+    }
+
+    /// The user-defined tags of every record, of the events, places & multimedia links
+    /// of records, and the extension records, each with the xref of its record
+    fn custom_data(&self) -> Vec<(Option<&str>, &CustomData)> {
+        fn of<'a>(
+            xref: Option<&'a str>,
+            holder: &'a impl HasCustomData,
+        ) -> impl Iterator<Item = (Option<&'a str>, &'a CustomData)> {
+            holder.custom_data().iter().map(move |data| (xref, data))
+        }
+
+        let mut results: Vec<_> = of(None, &self.header).collect();
+        for submitter in &self.submitters {
+            results.extend(of(submitter.xref.as_deref(), submitter));
+        }
+        for individual in &self.individuals {
+            let xref = individual.xref.as_deref();
+            results.extend(of(xref, individual));
+            for event in &individual.events {
+                results.extend(of(xref, event));
+                results.extend(event.place.iter().flat_map(|place| of(xref, place)));
+            }
+            for link in &individual.multimedia {
+                results.extend(of(xref, link));
+            }
+            for ordinance in &individual.lds_ordinances {
+                results.extend(of(xref, ordinance));
+                results.extend(ordinance.place.iter().flat_map(|place| of(xref, place)));
+            }
+        }
+        for family in &self.families {
+            let xref = family.xref.as_deref();
+            results.extend(of(xref, family));
+            for event in &family.events {
+                results.extend(of(xref, event));
+                results.extend(event.place.iter().flat_map(|place| of(xref, place)));
+            }
+            for ordinance in &family.lds_ordinances {
+                results.extend(of(xref, ordinance));
+                results.extend(ordinance.place.iter().flat_map(|place| of(xref, place)));
+            }
+        }
+        for repository in &self.repositories {
+            results.extend(of(repository.xref.as_deref(), repository));
+        }
+        for source in &self.sources {
+            let xref = source.xref.as_deref();
+            results.extend(of(xref, source));
+            for event in source.data.events() {
+                results.extend(of(xref, event));
+                results.extend(event.place.iter().flat_map(|place| of(xref, place)));
+            }
+        }
+        for multimedia in &self.multimedia {
+            results.extend(of(multimedia.xref.as_deref(), multimedia));
+        }
+        for location in &self.locations {
+            results.extend(of(location.xref.as_deref(), location));
+        }
+        for note in &self.notes {
+            results.extend(of(note.xref.as_deref(), note));
+        }
+        for record in &self.extension_records {
+            results.push((record.xref.as_deref(), &record.data));
+        }
+        results
+    }
+
+    /// Every note of the tree, with the record & structure it is found in, in the order
+    /// of the file. Notes shared by several structures are yielded for each of them.
+    pub fn iter_notes(&self) -> impl Iterator<Item = (&Note, NoteOwner<'_>)> {
+        let mut notes = Vec::new();
+        push_notes(&mut notes, "HEAD", None, &[], &self.header.notes);
+        for submitter in &self.submitters {
+            let xref = submitter.xref.as_deref();
+            push_notes(&mut notes, "SUBM", xref, &[], &submitter.notes);
+        }
+        for individual in &self.individuals {
+            let xref = individual.xref.as_deref();
+            for name in &individual.names {
+                name_notes(&mut notes, xref, name);
+            }
+            for event in &individual.events {
+                event_notes(&mut notes, "INDI", xref, &[], event);
+            }
+            for link in &individual.families {
+                let path = match link.link_type {
+                    FamilyLinkType::Child => ["FAMC"],
+                    FamilyLinkType::Spouse => ["FAMS"],
+                };
+                push_notes(&mut notes, "INDI", xref, &path, &link.notes);
+                citation_notes(&mut notes, "INDI", xref, &path, &link.citations);
+            }
+            association_notes(&mut notes, "INDI", xref, &[], &individual.associations);
+            ordinance_notes(&mut notes, "INDI", xref, &individual.lds_ordinances);
+            push_notes(&mut notes, "INDI", xref, &[], &individual.notes);
+        }
+        for family in &self.families {
+            let xref = family.xref.as_deref();
+            for event in &family.events {
+                event_notes(&mut notes, "FAM", xref, &[], event);
+            }
+            ordinance_notes(&mut notes, "FAM", xref, &family.lds_ordinances);
+            push_notes(&mut notes, "FAM", xref, &[], &family.notes);
+        }
+        for repository in &self.repositories {
+            let xref = repository.xref.as_deref();
+            push_notes(&mut notes, "REPO", xref, &[], &repository.notes);
+        }
+        for source in &self.sources {
+            let xref = source.xref.as_deref();
+            for event in source.data.events() {
+                event_notes(&mut notes, "SOUR", xref, &["DATA"], event);
+            }
+            push_notes(&mut notes, "SOUR", xref, &["DATA"], &source.data.notes);
+            push_notes(&mut notes, "SOUR", xref, &[], &source.notes);
+        }
+        for multimedia in &self.multimedia {
+            let xref = multimedia.xref.as_deref();
+            push_notes(&mut notes, "OBJE", xref, &[], &multimedia.notes);
+        }
+        for location in &self.locations {
+            push_notes(
+                &mut notes,
+                "_LOC",
+                location.xref.as_deref(),
+                &[],
+                &location.notes,
+            );
+        }
+        for record in &self.notes {
+            let xref = record.xref.as_deref();
+            notes.push((&record.text, NoteOwner::new("NOTE", xref, &[])));
+            citation_notes(&mut notes, "NOTE", xref, &[], &record.citations);
+        }
+        notes.into_iter()
+    }
+
+    /// The location record `xref` points to, ie. the `location` of an event
+    #[must_use]
+    pub fn location(&self, xref: &str) -> Option<&Location> {
+        self.locations
+            .iter()
+            .find(|location| location.xref.as_deref() == Some(xref))
+    }
+
+    /// The location record `xref` points to, followed by the larger locations it lies
+    /// within, ie. a town, its county & its state. The first link of each location is
+    /// followed, until a location without one or one already listed.
+    #[must_use]
+    pub fn location_hierarchy(&self, xref: &str) -> Vec<&Location> {
+        let mut hierarchy: Vec<&Location> = Vec::new();
+        let mut next = self.location(xref);
+        while let Some(location) = next {
+            if hierarchy
+                .iter()
+                .any(|listed| std::ptr::eq(*listed, location))
+            {
+                break;
+            }
+            hierarchy.push(location);
+            next = location
+                .within
+                .first()
+                .and_then(|link| self.location(&link.xref));
+        }
+        hierarchy
+    }
+
+    /// The note record `xref` points to
+    #[must_use]
+    pub fn note_record(&self, xref: &str) -> Option<&NoteRecord> {
+        self.notes
+            .iter()
+            .find(|record| record.xref.as_deref() == Some(xref))
+    }
+
+    /// The note record a note points to, when the note is a pointer like `NOTE @N1@`
+    /// rather than text
+    #[must_use]
+    pub fn resolve_note(&self, note: &Note) -> Option<&NoteRecord> {
+        let text = note.text();
+        let xref = text.trim();
+        if xref.len() > 2 && xref.starts_with('@') && xref.ends_with('@') {
+            self.note_record(xref)
+        } else {
+            None
+        }
+    }
+
+    /// The extension record `xref` points to, see `ParseOptions::extension_records`
+    #[must_use]
+    pub fn extension_record(&self, xref: &str) -> Option<&UserDefinedDataset> {
+        self.extension_records
+            .iter()
+            .find(|record| record.xref.as_deref() == Some(xref))
+    }
+
+    /// The records changed after `since`, according to their change date (`CHAN`), in the
+    /// order of the tree. Records without a change date, or with one that can't be read,
+    /// are left out. Incremental exports can keep the time of their last run as the
+    /// `since` of the next, ie. `ChangeDate::from(SystemTime::now())`.
+    #[must_use]
+    pub fn changed_since(&self, since: &ChangeDate) -> Vec<ChangedRecord<'_>> {
+        let submitters = self.submitters.iter().map(ChangedRecord::Submitter);
+        let individuals = self.individuals.iter().map(ChangedRecord::Individual);
+        let families = self.families.iter().map(ChangedRecord::Family);
+        let repositories = self.repositories.iter().map(ChangedRecord::Repository);
+        let multimedia = self.multimedia.iter().map(ChangedRecord::Multimedia);
+        let locations = self.locations.iter().map(ChangedRecord::Location);
+        let notes = self.notes.iter().map(ChangedRecord::Note);
+        submitters
+            .chain(individuals)
+            .chain(families)
+            .chain(repositories)
+            .chain(multimedia)
+            .chain(locations)
+            .chain(notes)
+            .filter(|record| {
+                record
+                    .last_updated()
+                    .and_then(ChangeDate::parse)
+                    .is_some_and(|changed| changed > *since)
+            })
+            .collect()
+    }
+
+    /// The cross-reference identifiers of the records of the tree
+    pub(crate) fn xrefs(&self) -> impl Iterator<Item = &str> {
+        let submitters = self.submitters.iter().map(|r| r.xref.as_deref());
+        let individuals = self.individuals.iter().map(|r| r.xref.as_deref());
+        let families = self.families.iter().map(|r| r.xref.as_deref());
+        let repositories = self.repositories.iter().map(|r| r.xref.as_deref());
+        let sources = self.sources.iter().map(|r| r.xref.as_deref());
+        let multimedia = self.multimedia.iter().map(|r| r.xref.as_deref());
+        let locations = self.locations.iter().map(|r| r.xref.as_deref());
+        let note_records = self.notes.iter().map(|r| r.xref.as_deref());
+        let extensions = self.extension_records.iter().map(|r| r.xref.as_deref());
+        let notes = self.shared_notes.iter().map(Note::shared_xref);
+        submitters
+            .chain(individuals)
+            .chain(families)
+            .chain(repositories)
+            .chain(sources)
+            .chain(multimedia)
+            .chain(locations)
+            .chain(note_records)
+            .chain(extensions)
+            .chain(notes)
+            .flatten()
+    }
+
+    /// Writes the tree to `out` as a GEDCOM 5.5.1 file, see `writer::GedcomWriter` for
+    /// the options of the output.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when writing to `out` fails, or when an xref of the tree is not
+    /// valid, like `I 1`, so that the record would not be read back.
+    pub fn write<W: io::Write>(&self, out: &mut W) -> io::Result<()> {
+        GedcomWriter::default().write(self, out)
+    }
+
+    /// Writes the tree as the content of a GEDCOM 5.5.1 file
+    ///
+    /// # Panics
+    ///
+    /// Panics when the tree holds an invalid xref, see `GedcomData::write` which
+    /// returns the error instead.
+    #[must_use]
+    pub fn to_gedcom_string(&self) -> String {
+        GedcomWriter::default().write_string(self)
+    }
+
+    /// Builds the hierarchy of jurisdictions of the places of the tree, see
+    /// `analysis::place_tree`
+    #[must_use]
+    pub fn place_tree(&self) -> Vec<PlaceNode> {
+        place_tree(self)
+    }
+
+    /// Summarizes the data contained in the tree
+    #[must_use]
+    pub fn statistics(&self) -> Statistics {
+        Statistics::of(self)
+    }
+
     /// Outputs a summary of data contained in the tree to stdout
     pub fn stats(&self) {
+        let stats = self.statistics();
         println!("----------------------");
         println!("| Gedcom Data Stats: |");
         println!("----------------------");
-        println!("  submitters: {}", self.submitters.len());
-        println!("  individuals: {}", self.individuals.len());
-        println!("  families: {}", self.families.len());
-        println!("  repositories: {}", self.repositories.len());
-        println!("  sources: {}", self.sources.len());
-        println!("  multimedia: {}", self.multimedia.len());
+        println!("  submitters: {}", stats.submitters);
+        println!("  individuals: {}", stats.individuals);
+        println!("  families: {}", stats.families);
+        println!("  repositories: {}", stats.repositories);
+        println!("  sources: {}", stats.sources);
+        println!("  multimedia: {}", stats.multimedia);
+        if let Some(age) = stats.median_age_at_marriage {
+            println!("  median age at marriage: {age:.1}");
+        }
+        if let Some(rate) = stats.remarriage_rate {
+            println!("  remarriage rate: {:.1}%", rate * 100.0);
+        }
         println!("----------------------");
     }
 }
+
+/// Adds the notes `owned` by a structure to `notes`
+fn push_notes<'a>(
+    notes: &mut Vec<(&'a Note, NoteOwner<'a>)>,
+    record: &'static str,
+    xref: Option<&'a str>,
+    path: &[&'static str],
+    owned: &'a [Note],
+) {
+    for note in owned {
+        notes.push((note, NoteOwner::new(record, xref, path)));
+    }
+}
+
+fn event_notes<'a>(
+    notes: &mut Vec<(&'a Note, NoteOwner<'a>)>,
+    record: &'static str,
+    xref: Option<&'a str>,
+    path: &[&'static str],
+    event: &'a Event,
+) {
+    let path = [path, &[event_tag(&event.event)]].concat();
+    place_notes(notes, record, xref, &path, event.place.as_ref());
+    push_notes(notes, record, xref, &path, &event.notes);
+    citation_notes(notes, record, xref, &path, &event.citations);
+    association_notes(notes, record, xref, &path, &event.associations);
+}
+
+fn ordinance_notes<'a>(
+    notes: &mut Vec<(&'a Note, NoteOwner<'a>)>,
+    record: &'static str,
+    xref: Option<&'a str>,
+    ordinances: &'a [LdsOrdinance],
+) {
+    for ordinance in ordinances {
+        let path = [ordinance.ordinance.tag()];
+        place_notes(notes, record, xref, &path, ordinance.place.as_ref());
+        push_notes(notes, record, xref, &path, &ordinance.notes);
+        citation_notes(notes, record, xref, &path, &ordinance.citations);
+    }
+}
+
+fn name_notes<'a>(
+    notes: &mut Vec<(&'a Note, NoteOwner<'a>)>,
+    xref: Option<&'a str>,
+    name: &'a Name,
+) {
+    push_notes(notes, "INDI", xref, &["NAME"], &name.notes);
+    citation_notes(notes, "INDI", xref, &["NAME"], &name.citations);
+    for (tag, variations) in [("FONE", &name.phonetic), ("ROMN", &name.romanized)] {
+        for variation in variations {
+            let path = ["NAME", tag];
+            push_notes(notes, "INDI", xref, &path, &variation.notes);
+            citation_notes(notes, "INDI", xref, &path, &variation.citations);
+        }
+    }
+}
+
+fn place_notes<'a>(
+    notes: &mut Vec<(&'a Note, NoteOwner<'a>)>,
+    record: &'static str,
+    xref: Option<&'a str>,
+    path: &[&'static str],
+    place: Option<&'a Place>,
+) {
+    if let Some(place) = place {
+        push_notes(
+            notes,
+            record,
+            xref,
+            &[path, &["PLAC"]].concat(),
+            &place.notes,
+        );
+    }
+}
+
+fn association_notes<'a>(
+    notes: &mut Vec<(&'a Note, NoteOwner<'a>)>,
+    record: &'static str,
+    xref: Option<&'a str>,
+    path: &[&'static str],
+    associations: &'a [Association],
+) {
+    let path = [path, &["ASSO"]].concat();
+    for association in associations {
+        push_notes(notes, record, xref, &path, &association.notes);
+        citation_notes(notes, record, xref, &path, &association.citations);
+    }
+}
+
+fn citation_notes<'a>(
+    notes: &mut Vec<(&'a Note, NoteOwner<'a>)>,
+    record: &'static str,
+    xref: Option<&'a str>,
+    path: &[&'static str],
+    citations: &'a [SourceCitation],
+) {
+    let path = [path, &["SOUR"]].concat();
+    for citation in citations {
+        push_notes(notes, record, xref, &path, &citation.notes);
+    }
+}
+
+/// Sets the change date, and the creation date when given one that isn't set yet, to
+/// the current time under the `Automatic` policy.
+pub(crate) fn stamp(
+    policy: ChangeDatePolicy,
+    created: Option<&mut Option<String>>,
+    last_updated: &mut Option<String>,
+) {
+    if policy == ChangeDatePolicy::Manual {
+        return;
+    }
+    let (date, time) = crate::writer::timestamp(SystemTime::now());
+    let now = format!("{date} {time}");
+    if let Some(created) = created {
+        created.get_or_insert_with(|| now.clone());
+    }
+    *last_updated = Some(now);
+}
@@ -3,16 +3,56 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// Physical address at which a fact occurs
-#[derive(Default)]
+#[derive(Clone, Default)]
 #[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
 pub struct Address {
+    /// Full address as written on the `ADDR` line and its `CONT` lines
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub value: Option<String>,
+    /// First line of the address
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub adr1: Option<String>,
+    /// Second line of the address
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub adr2: Option<String>,
+    /// Third line of the address
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub adr3: Option<String>,
+    /// City of the address
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub city: Option<String>,
+    /// State, province or other division of the address
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub state: Option<String>,
+    /// Postal code of the address
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub post: Option<String>,
+    /// Country of the address
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub country: Option<String>,
 }
 
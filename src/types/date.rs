@@ -0,0 +1,597 @@
+//! Structured values of `DATE` lines, ie. `ABT 1832`, `BET 1830 AND 1835` or
+//! `@#DJULIAN@ 11 FEB 1731`
+use std::convert::TryFrom;
+use std::fmt;
+use std::time::SystemTime;
+
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const HEBREW_MONTHS: [&str; 13] = [
+    "TSH", "CSH", "KSL", "TVT", "SHV", "ADR", "ADS", "NSN", "IYR", "SVN", "TMZ", "AAV", "ELL",
+];
+const FRENCH_MONTHS: [&str; 13] = [
+    "VEND", "BRUM", "FRIM", "NIVO", "PLUV", "VENT", "GERM", "FLOR", "PRAI", "MESS", "THER", "FRUC",
+    "COMP",
+];
+
+/// The calendar of a date, given by an escape like `@#DJULIAN@` before it, or by a
+/// keyword like `JULIAN` in GEDCOM 7.0
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Calendar {
+    /// The gregorian calendar, `@#DGREGORIAN@`, used when no calendar is given
+    #[default]
+    Gregorian,
+    /// The julian calendar, `@#DJULIAN@`
+    Julian,
+    /// The hebrew calendar, `@#DHEBREW@`, with months from `TSH` (Tishri) to `ELL` (Elul)
+    Hebrew,
+    /// The french republican calendar, `@#DFRENCH R@`, with months from `VEND`
+    /// (Vendémiaire) to `COMP` (the complementary days)
+    FrenchRepublican,
+}
+
+impl Calendar {
+    /// The escape introducing dates of the calendar in GEDCOM 5.5.1, ie. `@#DJULIAN@`
+    #[must_use]
+    pub fn escape(self) -> &'static str {
+        match self {
+            Calendar::Gregorian => "@#DGREGORIAN@",
+            Calendar::Julian => "@#DJULIAN@",
+            Calendar::Hebrew => "@#DHEBREW@",
+            Calendar::FrenchRepublican => "@#DFRENCH R@",
+        }
+    }
+
+    /// Reads the calendar of an escape like `@#DJULIAN@`, or of a GEDCOM 7.0 keyword
+    /// like `JULIAN`
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Calendar> {
+        let name = value.trim();
+        let name = name
+            .strip_prefix("@#D")
+            .and_then(|name| name.strip_suffix('@'))
+            .unwrap_or(name);
+        match name.to_uppercase().as_str() {
+            "GREGORIAN" => Some(Calendar::Gregorian),
+            "JULIAN" => Some(Calendar::Julian),
+            "HEBREW" => Some(Calendar::Hebrew),
+            "FRENCH R" | "FRENCH_R" => Some(Calendar::FrenchRepublican),
+            _ => None,
+        }
+    }
+
+    /// Whether the calendar numbers its years & months like the gregorian calendar, so
+    /// that its dates compare with gregorian ones: the julian calendar only differs by
+    /// days
+    #[must_use]
+    pub fn is_gregorian_like(self) -> bool {
+        matches!(self, Calendar::Gregorian | Calendar::Julian)
+    }
+
+    fn months(self) -> &'static [&'static str] {
+        match self {
+            Calendar::Gregorian | Calendar::Julian => &MONTHS,
+            Calendar::Hebrew => &HEBREW_MONTHS,
+            Calendar::FrenchRepublican => &FRENCH_MONTHS,
+        }
+    }
+
+    fn month_number(self, month: &str) -> Option<u8> {
+        match self {
+            Calendar::Gregorian | Calendar::Julian => month_number(month),
+            _ => {
+                let index = self.months().iter().position(|m| *m == month)?;
+                u8::try_from(index + 1).ok()
+            }
+        }
+    }
+}
+
+/// A date of a calendar, with the parts left out of the value missing
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CalendarDate {
+    /// The year, negative before the common era: `44 B.C.` is -44
+    pub year: i32,
+    /// The month, from 1 for the first month of the calendar, ie. January
+    pub month: Option<u8>,
+    /// The day of the month
+    pub day: Option<u8>,
+    /// The calendar of the date
+    pub calendar: Calendar,
+}
+
+impl CalendarDate {
+    /// Reads dates like `1 JAN 1899`, `JAN 1899` & `1899`, optionally preceded by a
+    /// calendar escape like `@#DJULIAN@`. Dual years like `1699/00` are read as their
+    /// first year, and years followed by `B.C.` as negative.
+    #[must_use]
+    pub fn parse(value: &str) -> Option<CalendarDate> {
+        let value = value.trim();
+        let (calendar, value) = match value.strip_prefix("@#D") {
+            Some(rest) => {
+                let (escape, rest) = rest.split_once('@')?;
+                (Calendar::parse(escape)?, rest)
+            }
+            None => match value.split_once(' ') {
+                Some((keyword, rest)) if Calendar::parse(keyword).is_some() => {
+                    (Calendar::parse(keyword)?, rest)
+                }
+                _ => (Calendar::Gregorian, value),
+            },
+        };
+
+        let mut words: Vec<String> = value.split_whitespace().map(str::to_uppercase).collect();
+        let before_common_era = matches!(
+            words.last().map(String::as_str),
+            Some("B.C." | "BC" | "BCE")
+        );
+        if before_common_era {
+            words.pop();
+        }
+        let (day, month, year) = match words.as_slice() {
+            [day, month_word, year] => (
+                Some(day.parse().ok()?),
+                Some(calendar.month_number(month_word)?),
+                year,
+            ),
+            [month_word, year] => (None, Some(calendar.month_number(month_word)?), year),
+            [year] => (None, None, year),
+            _ => return None,
+        };
+        // the second year of a dual year, ie. `1699/00`, is only meaningful in context
+        let year: i32 = year.split('/').next()?.parse().ok()?;
+        Some(CalendarDate {
+            year: if before_common_era { -year } else { year },
+            month,
+            day,
+            calendar,
+        })
+    }
+
+    /// Number of full years from this date until `later`, ie. an age. When either date
+    /// lacks a month or day, the difference of the years is used.
+    #[must_use]
+    pub fn years_until(self, later: CalendarDate) -> i32 {
+        let years = later.year - self.year;
+        match (self.month, self.day, later.month, later.day) {
+            (Some(month), Some(day), Some(later_month), Some(later_day))
+                if (later_month, later_day) < (month, day) =>
+            {
+                years - 1
+            }
+            _ => years,
+        }
+    }
+
+    /// The date as a `chrono` date of the proleptic gregorian calendar. Julian dates are
+    /// converted, dates of the other calendars and dates missing their day or month
+    /// give `None`.
+    #[cfg(feature = "chrono")]
+    #[must_use]
+    pub fn to_chrono(&self) -> Option<chrono::NaiveDate> {
+        let (month, day) = (u32::from(self.month?), u32::from(self.day?));
+        // chrono counts years astronomically, with a year 0 for 1 B.C.
+        let year = if self.year < 0 {
+            self.year + 1
+        } else {
+            self.year
+        };
+        match self.calendar {
+            Calendar::Gregorian => chrono::NaiveDate::from_ymd_opt(year, month, day),
+            Calendar::Julian => {
+                if month > 12 || day > 31 {
+                    return None;
+                }
+                let a = (14 - i64::from(month)) / 12;
+                let y = i64::from(year) + 4800 - a;
+                let m = i64::from(month) + 12 * a - 3;
+                let julian_day = i64::from(day) + (153 * m + 2) / 5 + 365 * y + y / 4 - 32083;
+                // 1 JAN 1 of the proleptic gregorian calendar is julian day 1721426
+                let days = i32::try_from(julian_day - 1_721_425).ok()?;
+                chrono::NaiveDate::from_num_days_from_ce_opt(days)
+            }
+            Calendar::Hebrew | Calendar::FrenchRepublican => None,
+        }
+    }
+}
+
+impl fmt::Display for CalendarDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.calendar != Calendar::Gregorian {
+            write!(f, "{} ", self.calendar.escape())?;
+        }
+        if let Some(day) = self.day {
+            write!(f, "{day} ")?;
+        }
+        let months = self.calendar.months();
+        if let Some(month) = self.month.and_then(|m| months.get(usize::from(m) - 1)) {
+            write!(f, "{month} ")?;
+        }
+        if self.year < 0 {
+            write!(f, "{} B.C.", -self.year)
+        } else {
+            write!(f, "{}", self.year)
+        }
+    }
+}
+
+/// The number of a month of the gregorian calendar, from 1 for `JAN`
+pub(crate) fn month_number(month: &str) -> Option<u8> {
+    let index = MONTHS.iter().position(|m| *m == month)?;
+    u8::try_from(index + 1).ok()
+}
+
+/// A time of day, the value of a `TIME` tag, ie. `08:30:05.25Z`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Time {
+    /// The hours, from 0 to 23
+    pub hours: u8,
+    /// The minutes, from 0 to 59
+    pub minutes: u8,
+    /// The seconds, from 0 to 59, when given
+    pub seconds: Option<u8>,
+    /// The fraction of the second in nanoseconds, when given, ie. `250_000_000` for `.25`
+    pub fraction: Option<u32>,
+    /// Whether the time is in UTC, marked by a `Z` suffix since GEDCOM 7.0
+    pub utc: bool,
+}
+
+impl Time {
+    /// Reads times like `08:30`, `08:30:05`, `08:30:05.25` & `08:30:05Z`. Returns `None`
+    /// for hours, minutes or seconds out of range, or fractions below a nanosecond.
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Time> {
+        let value = value.trim();
+        let (value, utc) = match value.strip_suffix('Z') {
+            Some(value) => (value, true),
+            None => (value, false),
+        };
+        let number = |part: &str, limit: u8| {
+            let all_digits = !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+            let number: u8 = part.parse().ok().filter(|_| all_digits)?;
+            (number < limit).then_some(number)
+        };
+
+        let mut parts = value.split(':');
+        let hours = number(parts.next()?, 24)?;
+        let minutes = number(parts.next()?, 60)?;
+        let (seconds, fraction) = match parts.next() {
+            Some(part) => match part.split_once('.') {
+                Some((seconds, fraction)) => {
+                    let valid = (1..=9).contains(&fraction.len())
+                        && fraction.bytes().all(|b| b.is_ascii_digit());
+                    if !valid {
+                        return None;
+                    }
+                    let padded = format!("{fraction:0<9}");
+                    (Some(number(seconds, 60)?), Some(padded.parse().ok()?))
+                }
+                None => (Some(number(part, 60)?), None),
+            },
+            None => (None, None),
+        };
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(Time {
+            hours,
+            minutes,
+            seconds,
+            fraction,
+            utc,
+        })
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.hours, self.minutes)?;
+        if let Some(seconds) = self.seconds {
+            write!(f, ":{seconds:02}")?;
+            if let Some(fraction) = self.fraction {
+                let digits = format!("{fraction:09}");
+                write!(f, ".{}", digits.trim_end_matches('0'))?;
+            }
+        }
+        if self.utc {
+            write!(f, "Z")?;
+        }
+        Ok(())
+    }
+}
+
+/// The date & time of a change to a record, the value of `CHAN.DATE` & its `TIME`, as
+/// kept by `last_updated` ie. `16 OCT 2026 08:30:05`. Change dates are ordered in time,
+/// a date without a time coming before the same date with one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ChangeDate {
+    /// The day of the change
+    pub date: CalendarDate,
+    /// The time of the change, when known
+    pub time: Option<Time>,
+}
+
+impl ChangeDate {
+    /// Reads change dates like `16 OCT 2026`, `16 OCT 2026 08:30:05` &
+    /// `16 OCT 2026 08:30:05.5Z`, see `Time::parse`
+    #[must_use]
+    pub fn parse(value: &str) -> Option<ChangeDate> {
+        let value = value.trim();
+        let (date, time) = match value.rsplit_once(' ') {
+            Some((date, time)) if time.contains(':') => (date, Some(Time::parse(time)?)),
+            _ => (value, None),
+        };
+        Some(ChangeDate {
+            date: CalendarDate::parse(date)?,
+            time,
+        })
+    }
+}
+
+impl From<SystemTime> for ChangeDate {
+    fn from(time: SystemTime) -> ChangeDate {
+        let (date, time) = crate::writer::timestamp(time);
+        ChangeDate::parse(&format!("{date} {time}")).expect("timestamps are valid change dates")
+    }
+}
+
+impl fmt::Display for ChangeDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.date)?;
+        if let Some(time) = self.time {
+            write!(f, " {time}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The keyword qualifying a date value, ie. `ABT` in `ABT 1832`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Qualifier {
+    /// `ABT`, the date is about right
+    About,
+    /// `CAL`, the date was calculated from other facts, ie. an age
+    Calculated,
+    /// `EST`, the date was estimated
+    Estimated,
+    /// `BEF`, sometime before the date
+    Before,
+    /// `AFT`, sometime after the date
+    After,
+    /// `BET ... AND ...`, sometime between the dates
+    Between,
+    /// `FROM`, lasting from the date, maybe `TO` another
+    From,
+    /// `TO`, lasting until the date
+    To,
+    /// `INT`, the date was interpreted from a phrase
+    Interpreted,
+}
+
+/// The value of a `DATE` line
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DateValue {
+    /// A date, ie. `1 JAN 1899`
+    Exact(CalendarDate),
+    /// `ABT 1832`
+    About(CalendarDate),
+    /// `CAL 1832`
+    Calculated(CalendarDate),
+    /// `EST 1832`
+    Estimated(CalendarDate),
+    /// `BEF 1832`
+    Before(CalendarDate),
+    /// `AFT 1832`
+    After(CalendarDate),
+    /// `BET 1830 AND 1835`
+    Between(CalendarDate, CalendarDate),
+    /// `FROM 1830 TO 1835`, `FROM 1830` or `TO 1835`
+    Period {
+        /// Start of the period
+        from: Option<CalendarDate>,
+        /// End of the period
+        to: Option<CalendarDate>,
+    },
+    /// `INT 1832 (phrase)`, a date interpreted from a phrase
+    Interpreted(CalendarDate, String),
+    /// Text that is not a date, ie. `(stillborn)` or a date the parser can't read
+    Phrase(String),
+}
+
+/// How a `DateValue` is displayed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DateStyle {
+    /// As written in GEDCOM: `ABT 1832`, `BET 1830 AND 1835`
+    #[default]
+    Gedcom,
+    /// Lowercase abbreviations and dashed ranges: `abt 1832`, `bef 1832`, `1830–1835`
+    Abbreviated,
+    /// Genealogical shorthand: `c. 1832`, `bef. 1832`, `1830–1835`
+    Circa,
+    /// Spelled out for sentences: `about 1832`, `between 1830 and 1835`
+    Words,
+}
+
+impl DateValue {
+    /// Reads a date value. Values that are not a date are kept as a `Phrase`, so parsing
+    /// never fails.
+    #[must_use]
+    pub fn parse(value: &str) -> DateValue {
+        let value = value.trim();
+        let phrase = || DateValue::Phrase(value.to_string());
+        let date = |text: &str| CalendarDate::parse(text);
+        let (keyword, rest) = value.split_once(' ').unwrap_or((value, ""));
+        let parsed = match keyword.to_uppercase().as_str() {
+            "ABT" => date(rest).map(DateValue::About),
+            "CAL" => date(rest).map(DateValue::Calculated),
+            "EST" => date(rest).map(DateValue::Estimated),
+            "BEF" => date(rest).map(DateValue::Before),
+            "AFT" => date(rest).map(DateValue::After),
+            "BET" => split_keyword(rest, "AND")
+                .and_then(|(start, end)| Some(DateValue::Between(date(start)?, date(end)?))),
+            "FROM" => match split_keyword(rest, "TO") {
+                Some((from, to)) => date(from)
+                    .zip(date(to))
+                    .map(|(from, to)| DateValue::Period {
+                        from: Some(from),
+                        to: Some(to),
+                    }),
+                None => date(rest).map(|from| DateValue::Period {
+                    from: Some(from),
+                    to: None,
+                }),
+            },
+            "TO" => date(rest).map(|to| DateValue::Period {
+                from: None,
+                to: Some(to),
+            }),
+            "INT" => {
+                let (text, interpretation) = rest.split_once('(').unwrap_or((rest, ""));
+                date(text).map(|date| {
+                    let interpretation = interpretation.trim_end().trim_end_matches(')');
+                    DateValue::Interpreted(date, interpretation.to_string())
+                })
+            }
+            _ => date(value).map(DateValue::Exact),
+        };
+        parsed.unwrap_or_else(phrase)
+    }
+
+    /// The keyword qualifying the date, `None` for exact dates & phrases
+    #[must_use]
+    pub fn qualifier(&self) -> Option<Qualifier> {
+        let qualifier = match self {
+            DateValue::Exact(_) | DateValue::Phrase(_) => return None,
+            DateValue::About(_) => Qualifier::About,
+            DateValue::Calculated(_) => Qualifier::Calculated,
+            DateValue::Estimated(_) => Qualifier::Estimated,
+            DateValue::Before(_) => Qualifier::Before,
+            DateValue::After(_) => Qualifier::After,
+            DateValue::Between(..) => Qualifier::Between,
+            DateValue::Period { from: Some(_), .. } => Qualifier::From,
+            DateValue::Period { from: None, .. } => Qualifier::To,
+            DateValue::Interpreted(..) => Qualifier::Interpreted,
+        };
+        Some(qualifier)
+    }
+
+    /// Whether the date is approximate: `ABT`, `CAL` or `EST`
+    #[must_use]
+    pub fn is_estimated(&self) -> bool {
+        matches!(
+            self,
+            DateValue::About(_) | DateValue::Calculated(_) | DateValue::Estimated(_)
+        )
+    }
+
+    /// Whether the date is a range the event happened in: `BEF`, `AFT` or `BET`
+    #[must_use]
+    pub fn is_range(&self) -> bool {
+        matches!(
+            self,
+            DateValue::Before(_) | DateValue::After(_) | DateValue::Between(..)
+        )
+    }
+
+    /// Whether the date is a period the fact lasted: `FROM` and/or `TO`
+    #[must_use]
+    pub fn is_period(&self) -> bool {
+        matches!(self, DateValue::Period { .. })
+    }
+
+    /// The first date of the value, ie. the start of a range
+    #[must_use]
+    pub fn date(&self) -> Option<CalendarDate> {
+        match self {
+            DateValue::Exact(date)
+            | DateValue::About(date)
+            | DateValue::Calculated(date)
+            | DateValue::Estimated(date)
+            | DateValue::Before(date)
+            | DateValue::After(date)
+            | DateValue::Between(date, _)
+            | DateValue::Interpreted(date, _) => Some(*date),
+            DateValue::Period { from, to } => from.or(*to),
+            DateValue::Phrase(_) => None,
+        }
+    }
+
+    /// The year of the first date of the value, in the calendar of the date
+    #[must_use]
+    pub fn year(&self) -> Option<i32> {
+        self.date().map(|date| date.year)
+    }
+
+    /// The first date of the value as a `chrono` date, see `CalendarDate::to_chrono`
+    #[cfg(feature = "chrono")]
+    #[must_use]
+    pub fn to_chrono(&self) -> Option<chrono::NaiveDate> {
+        self.date()?.to_chrono()
+    }
+
+    /// Displays the date in `style`
+    #[must_use]
+    pub fn format(&self, style: DateStyle) -> String {
+        let (about, calculated, estimated, before, after) = match style {
+            DateStyle::Gedcom => ("ABT", "CAL", "EST", "BEF", "AFT"),
+            DateStyle::Abbreviated => ("abt", "cal", "est", "bef", "aft"),
+            DateStyle::Circa => ("c.", "c.", "c.", "bef.", "aft."),
+            DateStyle::Words => ("about", "about", "about", "before", "after"),
+        };
+        match self {
+            DateValue::Exact(date) => date.to_string(),
+            DateValue::About(date) => format!("{about} {date}"),
+            DateValue::Calculated(date) => format!("{calculated} {date}"),
+            DateValue::Estimated(date) => format!("{estimated} {date}"),
+            DateValue::Before(date) => format!("{before} {date}"),
+            DateValue::After(date) => format!("{after} {date}"),
+            DateValue::Between(start, end) => match style {
+                DateStyle::Gedcom => format!("BET {start} AND {end}"),
+                DateStyle::Words => format!("between {start} and {end}"),
+                _ => format!("{start}–{end}"),
+            },
+            DateValue::Period { from, to } => {
+                let (from_word, to_word) = match style {
+                    DateStyle::Gedcom => ("FROM", "TO"),
+                    _ => ("from", "to"),
+                };
+                match (from, to) {
+                    (Some(from), Some(to))
+                        if matches!(style, DateStyle::Abbreviated | DateStyle::Circa) =>
+                    {
+                        format!("{from}–{to}")
+                    }
+                    (Some(from), Some(to)) => format!("{from_word} {from} {to_word} {to}"),
+                    (Some(from), None) => format!("{from_word} {from}"),
+                    (None, Some(to)) => format!("{to_word} {to}"),
+                    (None, None) => String::new(),
+                }
+            }
+            DateValue::Interpreted(date, phrase) => match style {
+                DateStyle::Gedcom => format!("INT {date} ({phrase})"),
+                _ => date.to_string(),
+            },
+            DateValue::Phrase(text) => match style {
+                DateStyle::Gedcom => text.clone(),
+                _ => text
+                    .trim_start_matches('(')
+                    .trim_end_matches(')')
+                    .to_string(),
+            },
+        }
+    }
+}
+
+impl fmt::Display for DateValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.format(DateStyle::Gedcom))
+    }
+}
+
+/// Splits `text` around the first `keyword` standing as a word
+fn split_keyword<'a>(text: &'a str, keyword: &str) -> Option<(&'a str, &'a str)> {
+    let upper = text.to_ascii_uppercase();
+    let start = upper.find(&format!(" {keyword} "))?;
+    Some((&text[..start], &text[start + keyword.len() + 2..]))
+}
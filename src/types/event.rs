@@ -1,28 +1,113 @@
-use crate::types::SourceCitation;
+//! Events of individuals & families, and the `HasEvents` trait of the records having them
+use crate::types::{
+    Address, Association, CustomData, DateValue, MultimediaLink, Note, Place, SourceCitation,
+};
 #[cfg(feature = "json")]
 use serde::{Deserialize, Serialize};
-use std::{fmt, string::ToString};
+use std::fmt;
 
 #[allow(clippy::module_name_repetitions)]
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+/// Kinds of events
 pub enum EventType {
+    /// Adoption of a child, the `ADOP` tag
     Adoption,
+    /// Birth, the `BIRT` tag
     Birth,
+    /// Burial, the `BURI` tag
     Burial,
+    /// Death, the `DEAT` tag
     Death,
+    /// Divorce of a family, the `DIV` tag
+    Divorce,
+    /// Christening, the `CHR` tag
     Christening,
+    /// Marriage of a family, the `MARR` tag
     Marriage,
+    /// Residence, the `RESI` tag
     Residence,
+    /// Census, the `CENS` tag
+    Census,
+    /// Baptism, the `BAPM` tag
+    Baptism,
+    /// Bar mitzvah, the `BARM` tag
+    BarMitzvah,
+    /// Bas mitzvah, the `BASM` tag
+    BasMitzvah,
+    /// Blessing, the `BLES` tag
+    Blessing,
+    /// Christening of an adult, the `CHRA` tag
+    AdultChristening,
+    /// Confirmation, the `CONF` tag
+    Confirmation,
+    /// Cremation, the `CREM` tag
+    Cremation,
+    /// Emigration, the `EMIG` tag
+    Emigration,
+    /// First communion, the `FCOM` tag
+    FirstCommunion,
+    /// Graduation, the `GRAD` tag
+    Graduation,
+    /// Immigration, the `IMMI` tag
+    Immigration,
+    /// Naturalization, the `NATU` tag
+    Naturalization,
+    /// Ordination, the `ORDN` tag
+    Ordination,
+    /// Retirement, the `RETI` tag
+    Retirement,
+    /// Probate of a will, the `PROB` tag
+    Probate,
+    /// Will, the `WILL` tag
+    Will,
+    /// Events recorded by a `Source`, the value of its `DATA.EVEN` tag
     SourceData(String),
 
     // "Other" is used to construct an event without requiring an explicit event type
+    /// A generic event, the `EVEN` tag
     Other,
 }
 
-impl ToString for EventType {
-    fn to_string(&self) -> String {
-        format!("{:?}", self)
+impl EventType {
+    /// The type of event of a tag, ie. `BIRT`
+    #[must_use]
+    pub fn from_tag(tag: &str) -> Option<EventType> {
+        match tag {
+            "ADOP" => Some(EventType::Adoption),
+            "BIRT" => Some(EventType::Birth),
+            "BURI" => Some(EventType::Burial),
+            "CHR" => Some(EventType::Christening),
+            "DEAT" => Some(EventType::Death),
+            "DIV" => Some(EventType::Divorce),
+            "MARR" => Some(EventType::Marriage),
+            "RESI" => Some(EventType::Residence),
+            "CENS" => Some(EventType::Census),
+            "BAPM" => Some(EventType::Baptism),
+            "BARM" => Some(EventType::BarMitzvah),
+            "BASM" => Some(EventType::BasMitzvah),
+            "BLES" => Some(EventType::Blessing),
+            "CHRA" => Some(EventType::AdultChristening),
+            "CONF" => Some(EventType::Confirmation),
+            "CREM" => Some(EventType::Cremation),
+            "EMIG" => Some(EventType::Emigration),
+            "FCOM" => Some(EventType::FirstCommunion),
+            "GRAD" => Some(EventType::Graduation),
+            "IMMI" => Some(EventType::Immigration),
+            "NATU" => Some(EventType::Naturalization),
+            "ORDN" => Some(EventType::Ordination),
+            "RETI" => Some(EventType::Retirement),
+            "PROB" => Some(EventType::Probate),
+            "WILL" => Some(EventType::Will),
+            "EVEN" | "OTHER" => Some(EventType::Other),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for EventType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
     }
 }
 
@@ -30,20 +115,166 @@ impl ToString for EventType {
 #[derive(Clone)]
 #[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
 pub struct Event {
+    /// Kind of event
     pub event: EventType,
+    /// Classification of the event, the `TYPE` tag, ie. `Census` for a generic `EVEN`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub classification: Option<String>,
+    /// Whether the event is only known to have happened, the `Y` value of ie. `1 DEAT Y`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "std::ops::Not::not")
+    )]
+    pub occurred: bool,
+    /// Date of the event, as written in the file
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub date: Option<String>,
-    pub place: Option<String>,
+    /// Place at which the event took place, `PLAC`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub place: Option<Place>,
+    /// Address at which the event took place, ie. the street address of a residence
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub address: Option<Address>,
+    /// Phone numbers of the place of the event, `PHON`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub phones: Vec<String>,
+    /// Email addresses of the place of the event, `EMAIL`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub emails: Vec<String>,
+    /// Fax numbers of the place of the event, `FAX`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub faxes: Vec<String>,
+    /// Web pages of the place of the event, `WWW`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub websites: Vec<String>,
+    /// Institution or authority responsible for the event or its record, `AGNC`, ie.
+    /// the enumeration district of a census
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub agency: Option<String>,
+    /// Religion the event is associated with, `RELI`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub religion: Option<String>,
+    /// Cause of the event, `CAUS`, ie. the cause of a death
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub cause: Option<String>,
+    /// Restriction on the access to the event, the `RESN` tag, ie. `privacy`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub restriction_notice: Option<String>,
+    /// Age of the individual at the time of the event, `AGE`, ie. `42y 3m`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub age: Option<String>,
+    /// Family the individual was born or adopted into, the `FAMC` of a birth,
+    /// christening or adoption
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub family: Option<String>,
+    /// Which parent of `family` adopted the individual, `FAMC.ADOP`: `HUSB`, `WIFE`
+    /// or `BOTH`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub adopted_by: Option<String>,
+    /// Sources documenting the event
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
     pub citations: Vec<SourceCitation>,
+    /// Other individuals taking part in the event, ie. witnesses or household members
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub associations: Vec<Association>,
+    /// Notes about the event
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub notes: Vec<Note>,
+    /// Links to multimedia records of the event, `OBJE`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub multimedia: Vec<MultimediaLink>,
+    /// User-defined tags of the event
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub custom_data: Vec<CustomData>,
 }
 
 impl Event {
+    /// Creates an event of the given type
     #[must_use]
     pub fn new(etype: EventType) -> Event {
         Event {
             event: etype,
+            classification: None,
+            occurred: false,
             date: None,
             place: None,
+            address: None,
+            phones: Vec::new(),
+            emails: Vec::new(),
+            faxes: Vec::new(),
+            websites: Vec::new(),
+            agency: None,
+            religion: None,
+            cause: None,
+            restriction_notice: None,
+            age: None,
+            family: None,
+            adopted_by: None,
             citations: Vec::new(),
+            associations: Vec::new(),
+            notes: Vec::new(),
+            multimedia: Vec::new(),
+            custom_data: Vec::new(),
         }
     }
 
@@ -52,30 +283,76 @@ impl Event {
         self.event = EventType::SourceData(value);
     }
 
+    /// The tags of the events recorded by a `Source`, from the comma-separated value of
+    /// its `DATA.EVEN`, ie. `["BIRT", "DEAT"]`, and empty for other events. The period
+    /// the source covers is the `date_value()` of the event, ie. `FROM 1820 TO 1870`,
+    /// and its jurisdiction the `place`.
+    #[must_use]
+    pub fn events_recorded(&self) -> Vec<&str> {
+        match &self.event {
+            EventType::SourceData(events) => events
+                .split(',')
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics when `tag` is not a recognized event tag.
     #[must_use]
     pub fn from_tag(tag: &str) -> Event {
-        let etype = match tag {
-            "ADOP" => EventType::Adoption,
-            "BIRT" => EventType::Birth,
-            "BURI" => EventType::Burial,
-            "CHR" => EventType::Christening,
-            "DEAT" => EventType::Death,
-            "MARR" => EventType::Marriage,
-            "RESI" => EventType::Residence,
-            "OTHER" => EventType::Other,
-            _ => panic!("Unrecognized event tag: {}", tag),
-        };
-        Event::new(etype)
+        match EventType::from_tag(tag) {
+            Some(etype) => Event::new(etype),
+            None => panic!("Unrecognized event tag: {}", tag),
+        }
+    }
+
+    /// The date of the event as a structured value, see `DateValue::parse`
+    #[must_use]
+    pub fn date_value(&self) -> Option<DateValue> {
+        let date = self.date.as_deref().map(str::trim)?;
+        (!date.is_empty()).then(|| DateValue::parse(date))
+    }
+
+    /// Whether the event is a census: a `CENS` event, or an `EVEN` of `TYPE` census
+    #[must_use]
+    pub fn is_census(&self) -> bool {
+        match &self.event {
+            EventType::Census => true,
+            EventType::Other => self
+                .classification
+                .as_deref()
+                .is_some_and(|class| class.trim().eq_ignore_ascii_case("census")),
+            _ => false,
+        }
     }
 
+    /// Adds a citation of a source documenting the event
     pub fn add_citation(&mut self, citation: SourceCitation) {
-        self.citations.push(citation)
+        self.citations.push(citation);
     }
 
+    /// Sources documenting the event
     #[must_use]
     pub fn get_citations(&self) -> Vec<SourceCitation> {
         self.citations.clone()
     }
+
+    /// The name of the place of the event, ie. `Boston, Suffolk, Massachusetts`
+    #[must_use]
+    pub fn place_name(&self) -> Option<&str> {
+        self.place.as_ref().map(|place| place.value.as_str())
+    }
+
+    /// The latitude & longitude of the place in degrees, from `PLAC.MAP`, south & west
+    /// being negative
+    #[must_use]
+    pub fn coordinates(&self) -> Option<(f64, f64)> {
+        self.place.as_ref()?.coordinates()
+    }
 }
 
 impl std::fmt::Debug for Event {
@@ -84,16 +361,22 @@ impl std::fmt::Debug for Event {
         let mut debug = f.debug_struct(&event_type);
 
         fmt_optional_value!(debug, "date", &self.date);
-        fmt_optional_value!(debug, "place", &self.place);
+        fmt_optional_value!(debug, "place", &self.place_name());
+        fmt_optional_value!(debug, "address", &self.address);
 
-        debug.finish()
+        debug.finish_non_exhaustive()
     }
 }
 
 /// Trait given to structs representing entities that have events.
 pub trait HasEvents {
-    fn add_event(&mut self, event: Event) -> ();
+    /// Adds an event to the entity
+    fn add_event(&mut self, event: Event);
+    /// Events of the entity
     fn events(&self) -> Vec<Event>;
+    /// Mutable access to the events of the entity, to edit them in place
+    fn events_mut(&mut self) -> &mut [Event];
+    /// Dates of the events of the entity
     fn dates(&self) -> Vec<String> {
         let mut dates: Vec<String> = Vec::new();
         for event in self.events() {
@@ -103,11 +386,12 @@ pub trait HasEvents {
         }
         dates
     }
+    /// Places of the events of the entity
     fn places(&self) -> Vec<String> {
         let mut places: Vec<String> = Vec::new();
         for event in self.events() {
-            if let Some(p) = &event.place {
-                places.push(p.clone());
+            if let Some(p) = event.place_name() {
+                places.push(p.to_string());
             }
         }
         places
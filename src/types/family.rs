@@ -1,4 +1,7 @@
-use crate::types::{event::HasEvents, Event};
+use crate::entry::{check_xref, FieldError};
+use crate::parser::Parser;
+use crate::types::{event::HasEvents, CustomData, Event, LdsOrdinance, Note, UserReferenceNumber};
+use crate::GedcomError;
 #[cfg(feature = "json")]
 use serde::{Deserialize, Serialize};
 
@@ -8,18 +11,104 @@ type Xref = String;
 ///
 /// This data representation understands that HUSB & WIFE are just poorly-named
 /// pointers to individuals. no gender "validating" is done on parse.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
 pub struct Family {
+    /// Optional reference to link to this family
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub xref: Option<Xref>,
+    /// First partner of the family
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub individual1: Option<Xref>, // mapped from HUSB
+    /// Second partner of the family
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub individual2: Option<Xref>, // mapped from WIFE
+    /// Children of the family, in order
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
     pub children: Vec<Xref>,
+    /// Number of children, the `NCHI` tag
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub num_children: Option<u8>,
-    events: Vec<Event>,
+    /// Submitters of the record, the `SUBM` pointers
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub submitters: Vec<Xref>,
+    /// Restriction on the access to the record, the `RESN` tag, ie. `locked`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub restriction_notice: Option<String>,
+    /// Numbers given to the record by the user, the `REFN` tags
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub user_reference_numbers: Vec<UserReferenceNumber>,
+    /// Number given to the record by the program that produced the file, `RIN`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub automated_record_id: Option<String>,
+    /// Date of the last change to the record, `CHAN.DATE`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub last_updated: Option<String>,
+    /// Date the record was created, `CREA.DATE` in GEDCOM 7.0
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub created: Option<String>,
+    /// LDS sealing of the couple, the `SLGS` tags
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub lds_ordinances: Vec<LdsOrdinance>,
+    /// Notes about the family
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub notes: Vec<Note>,
+    /// User-defined tags of the record
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub custom_data: Vec<CustomData>,
+    /// The tags `individual1` & `individual2` were recorded with
+    parent_tags: [ParentTag; 2],
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub(crate) events: Vec<Event>,
 }
 
 impl Family {
+    /// Shorthand for creating a `Family` from its `xref`
     #[must_use]
     pub fn new(xref: Option<Xref>) -> Family {
         Family {
@@ -28,40 +117,237 @@ impl Family {
             individual2: None,
             children: Vec::new(),
             num_children: None,
+            submitters: Vec::new(),
+            restriction_notice: None,
+            user_reference_numbers: Vec::new(),
+            automated_record_id: None,
+            last_updated: None,
+            created: None,
+            lds_ordinances: Vec::new(),
+            notes: Vec::new(),
+            custom_data: Vec::new(),
+            parent_tags: [ParentTag::Husband, ParentTag::Wife],
             events: Vec::new(),
         }
     }
 
-    pub fn set_individual1(&mut self, xref: Xref) {
-        match self.individual1 {
-            Some(_) => panic!("First individual of family already exists."),
-            None => self.individual1 = Some(xref),
-        };
+    /// Starts building a `Family`, ie.
+    /// `Family::builder().husband("@I1@").wife("@I2@").child("@I3@").build()?`
+    #[must_use]
+    pub fn builder() -> FamilyBuilder {
+        FamilyBuilder {
+            family: Family::new(None),
+            error: None,
+        }
+    }
+
+    /// Parses a `FAM` record from a GEDCOM fragment, without requiring the
+    /// `HEAD`/`TRLR` scaffolding of a full document. Lines following the record are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the fragment does not begin with a `FAM` record or the
+    /// record is malformed.
+    pub fn try_parse_str(content: &str) -> Result<Family, GedcomError> {
+        Parser::new(content.chars()).try_parse_family_record()
+    }
+
+    /// Parses a `FAM` record from a GEDCOM fragment like `try_parse_str`, panicking on
+    /// errors.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the fragment does not begin with a `FAM` record or the record is malformed.
+    #[must_use]
+    pub fn parse_str(content: &str) -> Family {
+        Parser::new(content.chars()).parse_family_record()
     }
 
-    pub fn set_individual2(&mut self, xref: Xref) {
-        match self.individual2 {
-            Some(_) => panic!("Second individual of family already exists."),
-            None => self.individual2 = Some(xref),
+    /// Sets the first partner, mapped from `HUSB`
+    ///
+    /// # Errors
+    ///
+    /// Gives back the xref when the first individual has already been set.
+    pub fn set_individual1(&mut self, xref: Xref) -> Result<(), Xref> {
+        if self.individual1.is_some() {
+            return Err(xref);
+        }
+        self.individual1 = Some(xref);
+        self.parent_tags[0] = ParentTag::Husband;
+        Ok(())
+    }
+
+    /// Sets the second partner, mapped from `WIFE`
+    ///
+    /// # Errors
+    ///
+    /// Gives back the xref when the second individual has already been set.
+    pub fn set_individual2(&mut self, xref: Xref) -> Result<(), Xref> {
+        if self.individual2.is_some() {
+            return Err(xref);
+        }
+        self.individual2 = Some(xref);
+        self.parent_tags[1] = ParentTag::Wife;
+        Ok(())
+    }
+
+    /// Adds a parent from a `HUSB` or `WIFE` line. The parent takes the slot matching
+    /// the tag, or the other slot when it is already taken, as couples of the same sex
+    /// are written with two `HUSB` or two `WIFE` lines.
+    ///
+    /// # Errors
+    ///
+    /// Gives back the xref when the family already has two parents.
+    pub fn add_parent(&mut self, xref: Xref, tag: ParentTag) -> Result<(), Xref> {
+        let preferred = match tag {
+            ParentTag::Husband => 0,
+            ParentTag::Wife => 1,
         };
+        for slot in [preferred, 1 - preferred] {
+            let individual = if slot == 0 {
+                &mut self.individual1
+            } else {
+                &mut self.individual2
+            };
+            if individual.is_none() {
+                *individual = Some(xref);
+                self.parent_tags[slot] = tag;
+                return Ok(());
+            }
+        }
+        Err(xref)
+    }
+
+    /// The parents of the family along with the tag each was recorded with
+    #[must_use]
+    pub fn parents(&self) -> Vec<(&str, ParentTag)> {
+        [&self.individual1, &self.individual2]
+            .iter()
+            .zip(self.parent_tags.iter())
+            .filter_map(|(xref, tag)| Some((xref.as_deref()?, *tag)))
+            .collect()
+    }
+
+    /// The other parent of the family, when `xref` is one of its parents
+    #[must_use]
+    pub fn partner_of(&self, xref: &str) -> Option<&str> {
+        if self.individual1.as_deref() == Some(xref) {
+            self.individual2.as_deref()
+        } else if self.individual2.as_deref() == Some(xref) {
+            self.individual1.as_deref()
+        } else {
+            None
+        }
     }
 
+    /// Adds a child to the family
     pub fn add_child(&mut self, xref: Xref) {
         self.children.push(xref);
     }
 }
 
 impl HasEvents for Family {
-    fn add_event(&mut self, event: Event) -> () {
-        let event_type = &event.event;
-        for e in &self.events {
-            if &e.event == event_type {
-                panic!("Family already has a {:?} event", e.event);
-            }
-        }
+    /// Adds an event, after the events of the same kind, ie. a second marriage of the
+    /// couple
+    fn add_event(&mut self, event: Event) {
         self.events.push(event);
     }
     fn events(&self) -> Vec<Event> {
         self.events.clone()
     }
+    fn events_mut(&mut self) -> &mut [Event] {
+        &mut self.events
+    }
+}
+
+/// Builds a `Family` for authoring a tree, see `Family::builder`. Pointers that can't be
+/// written as GEDCOM, ie. `I 1`, are checked as they are set and the first of them is
+/// returned by `build`.
+#[derive(Clone, Debug)]
+pub struct FamilyBuilder {
+    family: Family,
+    error: Option<FieldError>,
+}
+
+impl FamilyBuilder {
+    /// Sets the xref of the family, ie. `@F1@`. `GedcomData::add_family_with_xref` gives
+    /// one to families without.
+    #[must_use]
+    pub fn xref(mut self, xref: &str) -> FamilyBuilder {
+        self.check(check_xref(xref));
+        self.family.xref = Some(xref.to_string());
+        self
+    }
+
+    /// Adds a parent recorded as `HUSB`, see `Family::add_parent`. A third parent is
+    /// ignored.
+    #[must_use]
+    pub fn husband(mut self, xref: &str) -> FamilyBuilder {
+        self.check(check_xref(xref));
+        let _ = self.family.add_parent(xref.to_string(), ParentTag::Husband);
+        self
+    }
+
+    /// Adds a parent recorded as `WIFE`, see `Family::add_parent`. A third parent is
+    /// ignored.
+    #[must_use]
+    pub fn wife(mut self, xref: &str) -> FamilyBuilder {
+        self.check(check_xref(xref));
+        let _ = self.family.add_parent(xref.to_string(), ParentTag::Wife);
+        self
+    }
+
+    /// Adds a child, after the children already added
+    #[must_use]
+    pub fn child(mut self, xref: &str) -> FamilyBuilder {
+        self.check(check_xref(xref));
+        self.family.add_child(xref.to_string());
+        self
+    }
+
+    /// Adds an event, ie. a marriage
+    #[must_use]
+    pub fn event(mut self, event: Event) -> FamilyBuilder {
+        self.family.events.push(event);
+        self
+    }
+
+    /// The family built
+    ///
+    /// # Errors
+    ///
+    /// Returns the first invalid pointer set, see `entry::FieldError`.
+    pub fn build(self) -> Result<Family, FieldError> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(self.family),
+        }
+    }
+
+    fn check(&mut self, result: Result<(), FieldError>) {
+        if let Err(error) = result {
+            self.error.get_or_insert(error);
+        }
+    }
+}
+
+/// The tag a parent of a `Family` was recorded with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub enum ParentTag {
+    /// The `HUSB` tag
+    Husband,
+    /// The `WIFE` tag
+    Wife,
+}
+
+impl ParentTag {
+    /// The GEDCOM tag of the parent
+    #[must_use]
+    pub fn tag(self) -> &'static str {
+        match self {
+            ParentTag::Husband => "HUSB",
+            ParentTag::Wife => "WIFE",
+        }
+    }
 }
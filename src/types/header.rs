@@ -1,37 +1,339 @@
-use crate::types::Source;
+use crate::{
+    parser::Parser,
+    types::{CustomData, Note},
+    GedcomError,
+};
 #[cfg(feature = "json")]
 use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, fmt};
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 #[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
 /// Header containing GEDCOM metadata
 pub struct Header {
+    /// Character set of the file, the `CHAR` tag
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub encoding: Option<String>,
+    /// Version of the character set of the file, `CHAR.VERS`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub encoding_version: Option<String>,
+    /// Copyright statement of the file
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub copyright: Option<String>,
+    /// Business that produced the file
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub corporation: Option<String>,
+    /// Date the file was created, with the `TIME` appended if given
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub date: Option<String>,
+    /// Systems the file is meant for, the `DEST` tags
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
     pub destinations: Vec<String>,
+    /// Version of the GEDCOM specification, `GEDC.VERS`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub gedcom_version: Option<String>,
-    pub language: Option<String>,
+    /// Languages of the file's content, the `LANG` tags. The first is the default
+    /// language of the texts of the file, see `Note::language_in`.
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub languages: Vec<String>,
+    /// Name of the file
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub filename: Option<String>,
-    pub note: Option<String>,
-    pub sources: Vec<Source>,
+    /// Notes about the contents of the file
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub notes: Vec<Note>,
+    /// Default jurisdictions of the places in the file, ie. `City, County, State, Country`,
+    /// from `PLAC.FORM`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub place_form: Option<String>,
+    /// The program that produced the file
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub source: Option<HeaderSource>,
+    /// Reference to the `Submitter` of the file
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub submitter_tag: Option<String>,
+    /// Reference to the submission record of the file
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub submission_tag: Option<String>,
+    /// Extension tags declared by the file, `SCHMA.TAG`, GEDCOM 7.0 only
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub schema: Vec<ExtensionTag>,
+    /// User-defined tags of the header
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub custom_data: Vec<CustomData>,
 }
 
 impl Header {
+    /// Parses a `HEAD` record from a GEDCOM fragment, without requiring the rest of
+    /// a full document. Lines following the record are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the fragment does not begin with a `HEAD` record or the
+    /// record is malformed.
+    pub fn try_parse_str(content: &str) -> Result<Header, GedcomError> {
+        Parser::new(content.chars()).try_parse_header_record()
+    }
+
+    /// Parses a `HEAD` record from a GEDCOM fragment like `try_parse_str`, panicking on
+    /// errors.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the fragment does not begin with a `HEAD` record or the record is malformed.
+    #[must_use]
+    pub fn parse_str(content: &str) -> Header {
+        Parser::new(content.chars()).parse_header_record()
+    }
+
+    /// Adds a system the file is meant for
     pub fn add_destination(&mut self, destination: String) {
         self.destinations.push(destination);
     }
 
-    pub fn add_source(&mut self, source: Source) {
-        self.sources.push(source);
+    /// Detects the program that produced the file from `HEAD.SOUR`, so that
+    /// dialect-specific handling can key off of a single detection point.
+    #[must_use]
+    pub fn producer(&self) -> Option<Producer> {
+        self.source.as_ref().map(HeaderSource::producer)
+    }
+
+    /// The GEDCOM version of the file, `GEDC.VERS`
+    #[must_use]
+    pub fn version(&self) -> Option<GedcomVersion> {
+        self.gedcom_version.as_deref().map(GedcomVersion::from)
+    }
+
+    /// The default language of the texts of the file, the first `LANG`
+    #[must_use]
+    pub fn language(&self) -> Option<&str> {
+        self.languages.first().map(String::as_str)
+    }
+
+    /// URI declared for an extension tag in the `SCHMA` of the header
+    #[must_use]
+    pub fn schema_uri(&self, tag: &str) -> Option<&str> {
+        self.schema
+            .iter()
+            .find(|declared| declared.tag == tag)
+            .map(|declared| declared.uri.as_str())
+    }
+
+    /// Version of the program that produced the file, from `HEAD.SOUR.VERS`
+    #[must_use]
+    pub fn producer_version(&self) -> Option<&str> {
+        self.source.as_ref()?.version.as_deref()
+    }
+}
+
+/// An extension tag declared in the header of a GEDCOM 7.0 file, ie.
+/// `2 TAG _MILT https://example.com/military`, giving the URI that defines the tag
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct ExtensionTag {
+    /// The tag, ie. `_MILT`
+    pub tag: String,
+    /// URI defining the meaning of the tag
+    pub uri: String,
+}
+
+/// The program that produced the file, the `SOUR` tag of the header
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct HeaderSource {
+    /// The approved system identifier of the program
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub value: Option<String>,
+    /// Version of the program
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub version: Option<String>,
+    /// Name of the program
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub name: Option<String>,
+    /// Name of the business that owns the program
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub corporation: Option<String>,
+}
+
+impl HeaderSource {
+    /// Detects the program from the system identifier and name
+    #[must_use]
+    pub fn producer(&self) -> Producer {
+        let value = self.value.as_deref().unwrap_or_default().to_lowercase();
+        let name = self.name.as_deref().unwrap_or_default().to_lowercase();
+        let either = |pattern: &str| value.contains(pattern) || name.contains(pattern);
+
+        if value == "ftm" || value == "ftw" || either("family tree maker") {
+            Producer::FamilyTreeMaker
+        } else if either("ancestry") {
+            Producer::AncestryFamilyTrees
+        } else if either("gramps") {
+            Producer::Gramps
+        } else if either("myheritage") {
+            Producer::MyHeritage
+        } else if value == "paf" || either("personal ancestral file") {
+            Producer::Paf
+        } else if either("rootsmagic") {
+            Producer::RootsMagic
+        } else {
+            Producer::Other(
+                self.value
+                    .clone()
+                    .or_else(|| self.name.clone())
+                    .unwrap_or_default(),
+            )
+        }
+    }
+}
+
+/// Genealogy programs whose GEDCOM dialects are recognized
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub enum Producer {
+    /// Ancestry.com Family Trees
+    AncestryFamilyTrees,
+    /// Family Tree Maker
+    FamilyTreeMaker,
+    /// Gramps
+    Gramps,
+    /// `MyHeritage` Family Tree Builder
+    MyHeritage,
+    /// Personal Ancestral File
+    Paf,
+    /// `RootsMagic`
+    RootsMagic,
+    /// Any other program, with its system identifier
+    Other(String),
+}
+
+/// Versions of the GEDCOM specification, ordered by release
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub enum GedcomVersion {
+    /// GEDCOM 5.5
+    V5_5,
+    /// GEDCOM 5.5.1
+    V5_5_1,
+    /// GEDCOM 7.0, including its patch releases
+    V7_0,
+    /// Any other version, as written in the file
+    Other(String),
+}
+
+impl GedcomVersion {
+    /// The numbers of the version, ie. `[5, 5, 1]`. `None` for versions that aren't numeric.
+    fn numbers(&self) -> Option<Vec<u32>> {
+        match self {
+            GedcomVersion::V5_5 => Some(vec![5, 5]),
+            GedcomVersion::V5_5_1 => Some(vec![5, 5, 1]),
+            GedcomVersion::V7_0 => Some(vec![7, 0]),
+            GedcomVersion::Other(version) => version
+                .split('.')
+                .map(|number| number.trim().parse().ok())
+                .collect(),
+        }
+    }
+}
+
+impl From<&str> for GedcomVersion {
+    fn from(version: &str) -> GedcomVersion {
+        match version.trim() {
+            "5.5" => GedcomVersion::V5_5,
+            "5.5.1" => GedcomVersion::V5_5_1,
+            "7.0" => GedcomVersion::V7_0,
+            // 7.0 patch releases don't change the format
+            patch if patch.starts_with("7.0.") => GedcomVersion::V7_0,
+            other => GedcomVersion::Other(other.to_string()),
+        }
+    }
+}
+
+impl PartialOrd for GedcomVersion {
+    fn partial_cmp(&self, other: &GedcomVersion) -> Option<Ordering> {
+        if self == other {
+            return Some(Ordering::Equal);
+        }
+        let mut numbers = self.numbers()?;
+        let mut other_numbers = other.numbers()?;
+        // 5.5 is 5.5.0
+        let length = numbers.len().max(other_numbers.len());
+        numbers.resize(length, 0);
+        other_numbers.resize(length, 0);
+        match numbers.cmp(&other_numbers) {
+            // different versions with the same numbers, ie. `Other("5.5.0")` & `V5_5`
+            Ordering::Equal => None,
+            ordering => Some(ordering),
+        }
     }
 }
 
-// pub struct HeaderSource {
-//     version: Option<String>,
-//     name: Option<String>,
-//     coroporation:
-// }
+impl fmt::Display for GedcomVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GedcomVersion::V5_5 => write!(f, "5.5"),
+            GedcomVersion::V5_5_1 => write!(f, "5.5.1"),
+            GedcomVersion::V7_0 => write!(f, "7.0"),
+            GedcomVersion::Other(version) => write!(f, "{version}"),
+        }
+    }
+}
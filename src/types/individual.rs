@@ -1,40 +1,190 @@
-use crate::types::{event::HasEvents, CustomData, Event};
+use crate::entry::{check_text, check_xref, FieldError};
+use crate::parser::Parser;
+use crate::tree::GedcomData;
+use crate::types::{
+    event::HasEvents, Association, CustomData, Event, EventType, GedcomVersion, LdsOrdinance,
+    MultimediaLink, Note, ParentTag, SourceCitation, UserReferenceNumber,
+};
+use crate::GedcomError;
 #[cfg(feature = "json")]
 use serde::{Deserialize, Serialize};
 
 type Xref = String;
 
 /// A Person within the family tree
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
 pub struct Individual {
+    /// Optional reference to link to this individual
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub xref: Option<Xref>,
-    pub name: Option<Name>,
+    /// Names of the individual, the primary one first, then ie. aliases & married
+    /// names, the `NAME` tags
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub names: Vec<Name>,
+    /// Gender of the individual, the `SEX` tag
     pub sex: Gender,
+    /// Number of children of the individual in all their families, the `NCHI` tag
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub num_children: Option<u8>,
+    /// Families the individual is a child or spouse in
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
     pub families: Vec<FamilyLink>,
+    /// Multimedia records of the individual, ie. photos, the `OBJE` links
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub multimedia: Vec<MultimediaLink>,
+    /// Other individuals associated with the individual, ie. godparents, the `ASSO` tags
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub associations: Vec<Association>,
+    /// LDS baptism, confirmation, endowment & child sealing of the individual
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub lds_ordinances: Vec<LdsOrdinance>,
+    /// Notes about the individual
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub notes: Vec<Note>,
+    /// Restriction on the access to the record, the `RESN` tag, ie. `confidential`,
+    /// `locked` or `privacy`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub restriction_notice: Option<String>,
+    /// Number of the record in a permanent file, the `RFN` tag
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub permanent_record_file_number: Option<String>,
+    /// Number of the record in the Ancestral File, the `AFN` tag
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub ancestral_file_number: Option<String>,
+    /// Numbers given to the record by the user, the `REFN` tags
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub user_reference_numbers: Vec<UserReferenceNumber>,
+    /// Number given to the record by the program that produced the file, `RIN`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub automated_record_id: Option<String>,
+    /// User-defined tags of the record
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
     pub custom_data: Vec<CustomData>,
+    /// Date of the last change to the record, `CHAN.DATE`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub last_updated: Option<String>,
-    events: Vec<Event>,
+    /// Date the record was created, `CREA.DATE` in GEDCOM 7.0
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub created: Option<String>,
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub(crate) events: Vec<Event>,
 }
 
 impl Individual {
+    /// Shorthand for creating an `Individual` from its `xref`
     #[must_use]
     pub fn new(xref: Option<Xref>) -> Individual {
         Individual {
             xref,
-            name: None,
+            names: Vec::new(),
             sex: Gender::Unknown,
+            num_children: None,
             events: Vec::new(),
             families: Vec::new(),
+            multimedia: Vec::new(),
+            associations: Vec::new(),
+            lds_ordinances: Vec::new(),
+            notes: Vec::new(),
+            restriction_notice: None,
+            permanent_record_file_number: None,
+            ancestral_file_number: None,
+            user_reference_numbers: Vec::new(),
+            automated_record_id: None,
             custom_data: Vec::new(),
             last_updated: None,
+            created: None,
+        }
+    }
+
+    /// Starts building an `Individual`, ie.
+    /// `Individual::builder().name("Jane /Doe/").sex(Gender::Female).build()?`
+    #[must_use]
+    pub fn builder() -> IndividualBuilder {
+        IndividualBuilder {
+            individual: Individual::new(None),
+            error: None,
         }
     }
 
+    /// Parses an `INDI` record from a GEDCOM fragment, without requiring the
+    /// `HEAD`/`TRLR` scaffolding of a full document. Lines following the record are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the fragment does not begin with an `INDI` record or the
+    /// record is malformed.
+    pub fn try_parse_str(content: &str) -> Result<Individual, GedcomError> {
+        Parser::new(content.chars()).try_parse_individual_record()
+    }
+
+    /// Parses an `INDI` record from a GEDCOM fragment like `try_parse_str`, panicking on
+    /// errors.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the fragment does not begin with an `INDI` record or the record is malformed.
+    #[must_use]
+    pub fn parse_str(content: &str) -> Individual {
+        Parser::new(content.chars()).parse_individual_record()
+    }
+
+    /// Links the individual to a family, unless it is already linked to it
     pub fn add_family(&mut self, link: FamilyLink) {
         let mut do_add = true;
-        let xref = &link.0;
-        for FamilyLink(family, _, _) in &self.families {
+        let xref = &link.xref;
+        for FamilyLink { xref: family, .. } in &self.families {
             if family.as_str() == xref.as_str() {
                 do_add = false;
             }
@@ -44,52 +194,349 @@ impl Individual {
         }
     }
 
-    pub fn add_custom_data(&mut self, data: CustomData) {
-        self.custom_data.push(data)
+    /// The photo to show for the individual: the multimedia link marked as preferred,
+    /// or the first link when none is
+    #[must_use]
+    pub fn primary_photo(&self) -> Option<&MultimediaLink> {
+        self.multimedia
+            .iter()
+            .find(|link| link.primary)
+            .or_else(|| self.multimedia.first())
+    }
+
+    /// The primary name of the individual, the first `NAME`
+    #[must_use]
+    pub fn primary_name(&self) -> Option<&Name> {
+        self.names.first()
+    }
+
+    /// The name of the individual for display in the given order, see `Name::display`
+    #[must_use]
+    pub fn display_name(&self, order: NameOrder) -> Option<String> {
+        self.primary_name().map(|name| name.display(order))
+    }
+
+    /// The parents the individual was born to: the parents of the families it is a
+    /// child of with a `birth` pedigree, or without a pedigree unless an adoption event
+    /// links to the family. When a single parent adopted the individual, ie. `ADOP HUSB`,
+    /// the other parent of the family is a birth parent. Parents missing from `data`
+    /// are skipped.
+    #[must_use]
+    pub fn birth_parents<'a>(&self, data: &'a GedcomData) -> Vec<&'a Individual> {
+        self.parents_by_adoption(data, false)
+    }
+
+    /// The parents who adopted the individual: the parents of the families it is a
+    /// child of with an `adopted` pedigree, and of the family of its adoption events,
+    /// limited to the adopting parent of an `ADOP HUSB` or `ADOP WIFE`. Parents missing
+    /// from `data` are skipped.
+    #[must_use]
+    pub fn adoptive_parents<'a>(&self, data: &'a GedcomData) -> Vec<&'a Individual> {
+        self.parents_by_adoption(data, true)
+    }
+
+    /// The parents of the child families whose adoption of the individual is `adopted`.
+    /// Foster & sealing families are neither birth nor adoptive families.
+    fn parents_by_adoption<'a>(&self, data: &'a GedcomData, adopted: bool) -> Vec<&'a Individual> {
+        let mut families: Vec<(&str, Option<&Pedigree>)> = self
+            .families
+            .iter()
+            .filter(|link| link.link_type == FamilyLinkType::Child)
+            .map(|link| (link.xref.as_str(), link.pedigree.as_ref()))
+            .collect();
+        for family in self
+            .events
+            .iter()
+            .filter_map(|event| event.family.as_deref())
+        {
+            if !families.iter().any(|(xref, _)| *xref == family) {
+                families.push((family, None));
+            }
+        }
+
+        let mut parents: Vec<&Individual> = Vec::new();
+        for (xref, pedigree) in families {
+            let adoption = self.events.iter().find(|event| {
+                event.event == EventType::Adoption && event.family.as_deref() == Some(xref)
+            });
+            // `None` when the individual wasn't adopted into the family, `Some(None)`
+            // when adopted by both parents
+            let adopted_by = match (pedigree, adoption) {
+                (Some(Pedigree::Foster | Pedigree::Sealing), _) => continue,
+                (_, Some(event)) => {
+                    Some(match event.adopted_by.as_deref().map(str::to_uppercase) {
+                        Some(tag) if tag == "HUSB" => Some(ParentTag::Husband),
+                        Some(tag) if tag == "WIFE" => Some(ParentTag::Wife),
+                        _ => None,
+                    })
+                }
+                (Some(Pedigree::Adopted), None) => Some(None),
+                _ => None,
+            };
+            let Some(family) = data
+                .families
+                .iter()
+                .find(|f| f.xref.as_deref() == Some(xref))
+            else {
+                continue;
+            };
+            for (parent, tag) in family.parents() {
+                let adoptive = match adopted_by {
+                    None => false,
+                    Some(None) => true,
+                    Some(Some(adopting)) => adopting == tag,
+                };
+                if adoptive != adopted {
+                    continue;
+                }
+                let individual = data
+                    .individuals
+                    .iter()
+                    .find(|individual| individual.xref.as_deref() == Some(parent));
+                if let Some(individual) = individual {
+                    if !parents.iter().any(|known| std::ptr::eq(*known, individual)) {
+                        parents.push(individual);
+                    }
+                }
+            }
+        }
+        parents
+    }
+}
+
+/// Builds an `Individual` for authoring a tree, see `Individual::builder`. Values that
+/// can't be written as GEDCOM, ie. an xref with spaces, are checked as they are set and
+/// the first of them is returned by `build`.
+#[derive(Clone, Debug)]
+pub struct IndividualBuilder {
+    individual: Individual,
+    error: Option<FieldError>,
+}
+
+impl IndividualBuilder {
+    /// Sets the xref of the individual, ie. `@I1@`. `GedcomData::add_individual_with_xref`
+    /// gives one to individuals without.
+    #[must_use]
+    pub fn xref(mut self, xref: &str) -> IndividualBuilder {
+        self.check(check_xref(xref));
+        self.individual.xref = Some(xref.to_string());
+        self
+    }
+
+    /// Adds a name, with the surname between slashes, ie. `Jane /Doe/`. The first name
+    /// added is the primary name.
+    #[must_use]
+    pub fn name(mut self, value: &str) -> IndividualBuilder {
+        self.check(check_text(value));
+        self.individual.names.push(Name {
+            value: Some(value.to_string()),
+            ..Name::default()
+        });
+        self
+    }
+
+    /// Sets the gender of the individual
+    #[must_use]
+    pub fn sex(mut self, sex: Gender) -> IndividualBuilder {
+        self.individual.sex = sex;
+        self
+    }
+
+    /// Adds an event, ie. a birth
+    #[must_use]
+    pub fn event(mut self, event: Event) -> IndividualBuilder {
+        self.individual.add_event(event);
+        self
+    }
+
+    /// Links the individual as a child of the family `xref`, `FAMC`
+    #[must_use]
+    pub fn child_of(mut self, xref: &str) -> IndividualBuilder {
+        self.check(check_xref(xref));
+        self.individual
+            .add_family(FamilyLink::new(xref.to_string(), "FAMC"));
+        self
+    }
+
+    /// Links the individual as a spouse in the family `xref`, `FAMS`
+    #[must_use]
+    pub fn spouse_in(mut self, xref: &str) -> IndividualBuilder {
+        self.check(check_xref(xref));
+        self.individual
+            .add_family(FamilyLink::new(xref.to_string(), "FAMS"));
+        self
+    }
+
+    /// Adds a note about the individual
+    #[must_use]
+    pub fn note(mut self, text: &str) -> IndividualBuilder {
+        self.individual.notes.push(Note::new(text.to_string()));
+        self
+    }
+
+    /// The individual built
+    ///
+    /// # Errors
+    ///
+    /// Returns the first invalid value set, see `entry::FieldError`.
+    pub fn build(self) -> Result<Individual, FieldError> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(self.individual),
+        }
+    }
+
+    fn check(&mut self, result: Result<(), FieldError>) {
+        if let Err(error) = result {
+            self.error.get_or_insert(error);
+        }
     }
 }
 
 impl HasEvents for Individual {
-    fn add_event(&mut self, event: Event) -> () {
+    fn add_event(&mut self, event: Event) {
         self.events.push(event);
     }
     fn events(&self) -> Vec<Event> {
         self.events.clone()
     }
+    fn events_mut(&mut self) -> &mut [Event] {
+        &mut self.events
+    }
 }
 
 /// Gender of an `Individual`
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
 pub enum Gender {
+    /// Male, `M`
     Male,
+    /// Female, `F`
     Female,
     // come at me LDS, i support "N" as a gender value
+    /// Nonbinary, `X` in GEDCOM 7.0
     Nonbinary,
+    /// Unknown or not given, `U`
     Unknown,
 }
 
+impl Gender {
+    /// Converts the gender to a `SEX` payload for the given GEDCOM version.
+    ///
+    /// GEDCOM 7.0 writes `Nonbinary` as `X`. Earlier versions only define `M`, `F` & `U`,
+    /// so it degrades to `U` with the original value preserved in a `_SEX` custom tag.
+    #[must_use]
+    pub fn to_sex_value(&self, gedcom_version: &GedcomVersion) -> SexConversion {
+        let value = match self {
+            Gender::Male => "M",
+            Gender::Female => "F",
+            Gender::Unknown => "U",
+            Gender::Nonbinary if *gedcom_version >= GedcomVersion::V7_0 => "X",
+            Gender::Nonbinary => {
+                return SexConversion {
+                    value: "U",
+                    preserved: Some(CustomData {
+                        tag: "_SEX".to_string(),
+                        value: "X".to_string(),
+                        children: Vec::new(),
+                    }),
+                    warning: Some(format!(
+                        "GEDCOM {gedcom_version} does not define a nonbinary SEX value, writing U"
+                    )),
+                };
+            }
+        };
+        SexConversion {
+            value,
+            preserved: None,
+            warning: None,
+        }
+    }
+}
+
+/// The `SEX` payload of a `Gender` converted for a particular GEDCOM version
 #[derive(Debug)]
+pub struct SexConversion {
+    /// Value of the `SEX` line
+    pub value: &'static str,
+    /// Custom data preserving the original value when it had to be degraded
+    pub preserved: Option<CustomData>,
+    /// Describes the degradation, if the value could not be represented
+    pub warning: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
-enum FamilyLinkType {
+/// How an `Individual` is linked to a `Family`
+pub enum FamilyLinkType {
+    /// Spouse in the family, the `FAMS` tag
     Spouse,
+    /// Child in the family, the `FAMC` tag
     Child,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
-enum Pedigree {
+/// How a child belongs to its family, the `PEDI` tag
+pub enum Pedigree {
+    /// Adopted child
     Adopted,
+    /// Birth child
     Birth,
+    /// Foster child
     Foster,
+    /// Child sealed to the parents in an LDS ordinance
     Sealing,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
-pub struct FamilyLink(Xref, FamilyLinkType, Option<Pedigree>);
+/// Link of an `Individual` to a `Family`, the `FAMC` & `FAMS` tags
+pub struct FamilyLink {
+    /// Reference to the `Family`
+    pub xref: Xref,
+    /// Whether the individual is a child or a spouse in the family
+    pub link_type: FamilyLinkType,
+    /// How a child belongs to the family, the `PEDI` tag
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub pedigree: Option<Pedigree>,
+    /// Notes about the link
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub notes: Vec<Note>,
+    /// Sources documenting the link, allowed since GEDCOM 7.0
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub citations: Vec<SourceCitation>,
+}
+
+impl Pedigree {
+    /// Reads the value of a `PEDI` tag, in any case
+    #[must_use]
+    pub fn parse(pedigree_text: &str) -> Option<Pedigree> {
+        match pedigree_text.to_lowercase().as_str() {
+            "adopted" => Some(Pedigree::Adopted),
+            "birth" => Some(Pedigree::Birth),
+            "foster" => Some(Pedigree::Foster),
+            "sealing" => Some(Pedigree::Sealing),
+            _ => None,
+        }
+    }
+}
 
 impl FamilyLink {
+    /// Creates a link from a `FAMC` or `FAMS` tag
+    ///
+    /// # Panics
+    ///
+    /// Panics when `tag` is neither `FAMC` nor `FAMS`.
     #[must_use]
     pub fn new(xref: Xref, tag: &str) -> FamilyLink {
         let link_type = match tag {
@@ -97,27 +544,298 @@ impl FamilyLink {
             "FAMS" => FamilyLinkType::Spouse,
             _ => panic!("Unrecognized family type tag: {}", tag),
         };
-        FamilyLink(xref, link_type, None)
+        FamilyLink {
+            xref,
+            link_type,
+            pedigree: None,
+            notes: Vec::new(),
+            citations: Vec::new(),
+        }
     }
 
+    /// Sets the pedigree from the value of a `PEDI` tag
+    ///
+    /// # Panics
+    ///
+    /// Panics when `pedigree_text` is not a recognized pedigree.
     pub fn set_pedigree(&mut self, pedigree_text: &str) {
-        self.2 = match pedigree_text.to_lowercase().as_str() {
-            "adopted" => Some(Pedigree::Adopted),
-            "birth" => Some(Pedigree::Birth),
-            "foster" => Some(Pedigree::Foster),
-            "sealing" => Some(Pedigree::Sealing),
-            _ => panic!("Unrecognized family link pedigree: {}", pedigree_text),
-        };
+        match Pedigree::parse(pedigree_text) {
+            Some(pedigree) => self.pedigree = Some(pedigree),
+            None => panic!("Unrecognized family link pedigree: {}", pedigree_text),
+        }
     }
 }
 
-#[derive(Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
 #[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+/// Name of an `Individual`, the `NAME` tag
 pub struct Name {
+    /// Full name, with the surname between slashes
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub value: Option<String>,
+    /// Given names, `GIVN`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub given: Option<String>,
+    /// Nickname, `NICK`, ie. `Bill` for William
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub nickname: Option<String>,
+    /// Surname, `SURN`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub surname: Option<String>,
+    /// Prefix of the name, ie. `Dr.`, `NPFX`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub prefix: Option<String>,
+    /// Prefix of the surname, ie. `van`, `SPFX`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub surname_prefix: Option<String>,
+    /// Suffix of the name, ie. `Jr.`, `NSFX`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub suffix: Option<String>,
+    /// Notes about the name
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub notes: Vec<Note>,
+    /// Sources of the name
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub citations: Vec<SourceCitation>,
+    /// Kind of name, ie. `birth` or `married`, the `TYPE` tag
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub name_type: Option<String>,
+    /// Language the name is written in, the `LANG` tag
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub language: Option<String>,
+    /// Phonetic variations of the name, ie. kana for a name written in kanji, the
+    /// `FONE` tags
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub phonetic: Vec<NameVariation>,
+    /// Romanized variations of the name, the `ROMN` tags
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub romanized: Vec<NameVariation>,
+}
+
+/// A phonetic (`FONE`) or romanized (`ROMN`) variation of a `Name`, with the pieces of
+/// the name as pronounced or romanized
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct NameVariation {
+    /// The name as pronounced or romanized, with the surname between slashes
+    pub value: String,
+    /// Method used for the variation, `TYPE`, ie. `kana` or `pinyin`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub variation_type: Option<String>,
+    /// Prefix of the name, `NPFX`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub prefix: Option<String>,
+    /// Given names, `GIVN`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub given: Option<String>,
+    /// Nickname, `NICK`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub nickname: Option<String>,
+    /// Prefix of the surname, `SPFX`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub surname_prefix: Option<String>,
+    /// Surname, `SURN`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub surname: Option<String>,
+    /// Suffix of the name, `NSFX`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub suffix: Option<String>,
+    /// Notes about the variation
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub notes: Vec<Note>,
+    /// Sources of the variation
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub citations: Vec<SourceCitation>,
+}
+
+impl Name {
+    /// Writes the name for display in the given order. The name's own language takes
+    /// precedence over `Western` & `Eastern`, so that ie. a Hungarian name is displayed
+    /// surname first within an English tree, see `language_order`.
+    #[must_use]
+    pub fn display(&self, order: NameOrder) -> String {
+        let order = match order {
+            NameOrder::SurnameFirstComma => order,
+            _ => self.language_order().unwrap_or(order),
+        };
+        let (given, surname, suffix) = self.pieces();
+        let prefix = self.prefix.as_deref();
+
+        let parts = match (order, surname.as_deref()) {
+            (NameOrder::SurnameFirstComma, Some(surname)) => {
+                let rest = join(&[prefix, given.as_deref(), suffix.as_deref()]);
+                return if rest.is_empty() {
+                    surname.to_string()
+                } else {
+                    format!("{surname}, {rest}")
+                };
+            }
+            (NameOrder::Eastern, _) => [
+                prefix,
+                surname.as_deref(),
+                given.as_deref(),
+                suffix.as_deref(),
+            ],
+            _ => [
+                prefix,
+                given.as_deref(),
+                surname.as_deref(),
+                suffix.as_deref(),
+            ],
+        };
+        join(&parts)
+    }
+
+    /// The order names of the name's language are written in, for the languages
+    /// writing the surname first. A name of `TYPE` `IMMIGRANT`, assumed on immigration,
+    /// follows the order of its new country rather than that of its language.
+    #[must_use]
+    pub fn language_order(&self) -> Option<NameOrder> {
+        let name_type = self.name_type.as_deref().unwrap_or_default().trim();
+        if name_type.eq_ignore_ascii_case("IMMIGRANT") {
+            return None;
+        }
+        let language = self.language.as_deref()?.trim().to_lowercase();
+        // BCP 47 tags of GEDCOM 7.0 or language names of 5.5.1
+        let primary = language.split(['-', '_']).next().unwrap_or_default();
+        match primary {
+            "zh" | "ja" | "ko" | "hu" | "vi" | "chinese" | "japanese" | "korean" | "hungarian"
+            | "vietnamese" => Some(NameOrder::Eastern),
+            _ => None,
+        }
+    }
+
+    /// The given names of the `GIVN` piece, which lists them separated by commas, ie.
+    /// `["Mary", "Ann"]` for `Mary, Ann`. Empty without a `GIVN`.
+    #[must_use]
+    pub fn given_names(&self) -> Vec<String> {
+        split_list(self.given.as_deref())
+    }
+
+    /// The surnames of the `SURN` piece, which lists them separated by commas, ie.
+    /// `["Garcia", "Lopez"]` for `Garcia, Lopez`. Empty without a `SURN`.
+    #[must_use]
+    pub fn surnames(&self) -> Vec<String> {
+        split_list(self.surname.as_deref())
+    }
+
+    /// The given names, surname & suffix, from the name pieces or else the value
+    pub(crate) fn pieces(&self) -> (Option<String>, Option<String>, Option<String>) {
+        let value = self.value.as_deref().unwrap_or_default();
+        let mut slashes = value.splitn(3, '/');
+        let before = slashes.next().map(str::trim);
+        let between = slashes.next().map(str::trim);
+        let after = slashes.next().map(str::trim);
+        let non_empty = |piece: Option<&str>| piece.filter(|p| !p.is_empty()).map(String::from);
+
+        let given = self.given.clone().or_else(|| non_empty(before));
+        let surname = match &self.surname {
+            Some(surname) => Some(join(&[self.surname_prefix.as_deref(), Some(surname)])),
+            None => non_empty(between),
+        };
+        let suffix = self.suffix.clone().or_else(|| non_empty(after));
+        (given, surname, suffix)
+    }
+}
+
+/// Splits a comma-separated name piece into its trimmed, non-empty values
+fn split_list(piece: Option<&str>) -> Vec<String> {
+    piece
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Joins the present parts of a name with spaces
+fn join(parts: &[Option<&str>]) -> String {
+    parts
+        .iter()
+        .flatten()
+        .filter(|part| !part.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Order in which the parts of a name are displayed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub enum NameOrder {
+    /// Given names before the surname, ie. `John Doe`
+    #[default]
+    Western,
+    /// Surname before the given names, ie. `Doe John`, as in Chinese, Japanese, Korean,
+    /// Hungarian & Vietnamese
+    Eastern,
+    /// Surname followed by a comma & the given names, ie. `Doe, John`, for indexes
+    SurnameFirstComma,
 }
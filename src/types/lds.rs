@@ -0,0 +1,130 @@
+use crate::types::{CustomData, Note, Place, SourceCitation};
+#[cfg(feature = "json")]
+use serde::{Deserialize, Serialize};
+
+type Xref = String;
+
+/// Kind of LDS ordinance, as exported by the programs of the Church of Jesus Christ of
+/// Latter-day Saints
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub enum LdsOrdinanceType {
+    /// LDS baptism, the `BAPL` tag
+    Baptism,
+    /// LDS confirmation, the `CONL` tag
+    Confirmation,
+    /// LDS endowment, the `ENDL` tag
+    Endowment,
+    /// Sealing of a child to their parents, the `SLGC` tag
+    ChildSealing,
+    /// Sealing of a couple, the `SLGS` tag of a family
+    SpouseSealing,
+}
+
+impl LdsOrdinanceType {
+    /// The kind of ordinance of a tag, ie. `BAPL`
+    #[must_use]
+    pub fn from_tag(tag: &str) -> Option<LdsOrdinanceType> {
+        match tag {
+            "BAPL" => Some(LdsOrdinanceType::Baptism),
+            "CONL" => Some(LdsOrdinanceType::Confirmation),
+            "ENDL" => Some(LdsOrdinanceType::Endowment),
+            "SLGC" => Some(LdsOrdinanceType::ChildSealing),
+            "SLGS" => Some(LdsOrdinanceType::SpouseSealing),
+            _ => None,
+        }
+    }
+
+    /// The tag of the ordinance
+    #[must_use]
+    pub fn tag(self) -> &'static str {
+        match self {
+            LdsOrdinanceType::Baptism => "BAPL",
+            LdsOrdinanceType::Confirmation => "CONL",
+            LdsOrdinanceType::Endowment => "ENDL",
+            LdsOrdinanceType::ChildSealing => "SLGC",
+            LdsOrdinanceType::SpouseSealing => "SLGS",
+        }
+    }
+}
+
+/// An LDS ordinance of an individual or a family
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct LdsOrdinance {
+    /// Kind of ordinance
+    pub ordinance: LdsOrdinanceType,
+    /// Date of the ordinance, as written in the file
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub date: Option<String>,
+    /// Code of the temple the ordinance was performed in, `TEMP`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub temple: Option<String>,
+    /// Place of the ordinance, `PLAC`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub place: Option<Place>,
+    /// Status of the ordinance, `STAT`, ie. `COMPLETED` or `BIC`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub status: Option<String>,
+    /// Date the status was set, `STAT.DATE`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub status_date: Option<String>,
+    /// Family the child is sealed to, the `FAMC` of a child sealing
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub family: Option<Xref>,
+    /// Sources of the ordinance
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub citations: Vec<SourceCitation>,
+    /// Notes about the ordinance
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub notes: Vec<Note>,
+    /// User-defined tags of the ordinance
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub custom_data: Vec<CustomData>,
+}
+
+impl LdsOrdinance {
+    /// Creates an ordinance of the kind `ordinance` without any detail
+    #[must_use]
+    pub fn new(ordinance: LdsOrdinanceType) -> LdsOrdinance {
+        LdsOrdinance {
+            ordinance,
+            date: None,
+            temple: None,
+            place: None,
+            status: None,
+            status_date: None,
+            family: None,
+            citations: Vec::new(),
+            notes: Vec::new(),
+            custom_data: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use crate::entry::FieldError;
+use crate::types::{CustomData, Note};
+#[cfg(feature = "json")]
+use serde::{Deserialize, Serialize};
+
+type Xref = String;
+
+/// Shared location record, the top-level `_LOC` tag some programs (ie. Gramps & Legacy,
+/// following GEDCOM-L) export so that places are described once and pointed to from
+/// `PLAC._LOC`
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct Location {
+    /// Optional reference to link to this location
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub xref: Option<Xref>,
+    /// Names of the location, the `NAME` tags, which can change over time
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub names: Vec<LocationName>,
+    /// Kind of location, ie. `City`, the `TYPE` tag
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub location_type: Option<String>,
+    /// Coordinates of the location, the `MAP` tag
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub map: Option<Map>,
+    /// The larger locations this one lies within, the `_LOC` tags
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub within: Vec<LocationLink>,
+    /// Notes about the location
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub notes: Vec<Note>,
+    /// Lines of the record that are not read into the fields above
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub custom_data: Vec<CustomData>,
+    /// Date of the last change to the record, `CHAN.DATE`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub last_updated: Option<String>,
+}
+
+impl Location {
+    /// Creates an empty location record
+    #[must_use]
+    pub fn new(xref: Option<Xref>) -> Location {
+        Location {
+            xref,
+            ..Location::default()
+        }
+    }
+
+    /// The first name of the location
+    #[must_use]
+    pub fn name(&self) -> Option<&str> {
+        self.names.first().map(|name| name.value.as_str())
+    }
+}
+
+/// A name of a `Location`, with the period it was in use
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct LocationName {
+    /// The name, ie. `Boston`
+    pub value: String,
+    /// When the name was in use, ie. `FROM 1630`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub date: Option<String>,
+}
+
+/// Link from a `Location` to a larger location it lies within
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct LocationLink {
+    /// Reference to the larger location
+    pub xref: Xref,
+    /// When the location lay within the larger one
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub date: Option<String>,
+}
+
+/// Coordinates of a place, the `MAP` tag, in degrees: north & east positive, south &
+/// west negative. `LATI N42.3601` & `LONG W71.0589` are read as `42.3601` & `-71.0589`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "json",
+    derive(Serialize, Deserialize),
+    serde(try_from = "MapJson")
+)]
+pub struct Map {
+    latitude: f64,
+    longitude: f64,
+}
+
+/// The coordinates as JSON, checked when read back into a `Map`
+#[cfg(feature = "json")]
+#[derive(Deserialize)]
+struct MapJson {
+    latitude: f64,
+    longitude: f64,
+}
+
+#[cfg(feature = "json")]
+impl std::convert::TryFrom<MapJson> for Map {
+    type Error = FieldError;
+
+    fn try_from(json: MapJson) -> Result<Map, FieldError> {
+        Map::new(json.latitude, json.longitude)
+    }
+}
+
+impl Map {
+    /// Creates the coordinates of a place from its latitude & longitude in degrees.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FieldError::Coordinate` when the latitude is not between -90 & 90, or
+    /// the longitude between -180 & 180.
+    pub fn new(latitude: f64, longitude: f64) -> Result<Map, FieldError> {
+        let check = |degrees: f64, limit: f64, name: &str| {
+            if (-limit..=limit).contains(&degrees) {
+                Ok(degrees)
+            } else {
+                Err(FieldError::Coordinate(format!(
+                    "{name} {degrees} is not between -{limit} and {limit}"
+                )))
+            }
+        };
+        Ok(Map {
+            latitude: check(latitude, 90.0, "latitude")?,
+            longitude: check(longitude, 180.0, "longitude")?,
+        })
+    }
+
+    /// Reads the `LATI` & `LONG` values of a `MAP`, ie. `N42.3601` & `W71.0589`. Values
+    /// without a hemisphere letter are read as signed numbers.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FieldError::Coordinate` when a value is not a number of degrees, or is
+    /// out of range.
+    pub fn parse(latitude: &str, longitude: &str) -> Result<Map, FieldError> {
+        Map::new(degrees(latitude, 'N', 'S')?, degrees(longitude, 'E', 'W')?)
+    }
+
+    /// Latitude in degrees, south being negative
+    #[must_use]
+    pub fn latitude(&self) -> f64 {
+        self.latitude
+    }
+
+    /// Longitude in degrees, west being negative
+    #[must_use]
+    pub fn longitude(&self) -> f64 {
+        self.longitude
+    }
+
+    /// The latitude & longitude in degrees
+    #[must_use]
+    pub fn coordinates(&self) -> (f64, f64) {
+        (self.latitude, self.longitude)
+    }
+
+    /// The latitude as written in `LATI`, ie. `N42.3601`
+    #[must_use]
+    pub fn latitude_value(&self) -> String {
+        hemisphere_value(self.latitude, 'N', 'S')
+    }
+
+    /// The longitude as written in `LONG`, ie. `W71.0589`
+    #[must_use]
+    pub fn longitude_value(&self) -> String {
+        hemisphere_value(self.longitude, 'E', 'W')
+    }
+}
+
+fn degrees(value: &str, positive: char, negative: char) -> Result<f64, FieldError> {
+    let value = value.trim();
+    let mut chars = value.chars();
+    let (sign, number) = match chars.next().map(|c| c.to_ascii_uppercase()) {
+        Some(c) if c == positive => (1.0, chars.as_str()),
+        Some(c) if c == negative => (-1.0, chars.as_str()),
+        _ => (1.0, value),
+    };
+    match number.trim().parse::<f64>() {
+        Ok(degrees) if degrees.is_finite() => Ok(sign * degrees),
+        _ => Err(FieldError::Coordinate(format!(
+            "{value:?} is not a number of degrees"
+        ))),
+    }
+}
+
+fn hemisphere_value(degrees: f64, positive: char, negative: char) -> String {
+    let hemisphere = if degrees < 0.0 { negative } else { positive };
+    format!("{hemisphere}{}", degrees.abs())
+}
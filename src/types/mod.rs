@@ -2,76 +2,425 @@
 
 // holy wow, this data format is heteronormative af...
 
-#![allow(missing_docs)]
-
+use crate::parser::Parser;
+use crate::GedcomError;
 #[cfg(feature = "json")]
 use serde::{Deserialize, Serialize};
 
 pub mod event;
-pub use event::{Event, EventType};
+pub use event::{Event, EventType, HasEvents};
 
 mod address;
-pub use address::*;
+pub use address::Address;
+
+mod date;
+pub(crate) use date::month_number;
+pub use date::{Calendar, CalendarDate, ChangeDate, DateStyle, DateValue, Qualifier, Time};
+
+mod note;
+pub(crate) use note::NoteInterner;
+pub use note::{Note, NoteRecord};
 
 type Xref = String;
 
 // top-level record types
 mod header;
-pub use header::*;
+pub use header::{ExtensionTag, GedcomVersion, Header, HeaderSource, Producer};
 
 mod individual;
-pub use individual::*;
+pub use individual::{
+    FamilyLink, FamilyLinkType, Gender, Individual, IndividualBuilder, Name, NameOrder,
+    NameVariation, Pedigree, SexConversion,
+};
 
 mod family;
-pub use family::*;
+pub use family::{Family, FamilyBuilder, ParentTag};
 
 mod submitter;
-pub use submitter::*;
+pub use submitter::Submitter;
 
 mod source;
-pub use source::*;
+pub use source::{Source, SourceData};
 
-// TODO
-/// Multimedia item
-#[derive(Debug)]
-#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
-pub struct Media {}
+mod location;
+pub use location::{Location, LocationLink, LocationName, Map};
+
+mod place;
+pub use place::{Place, PlaceVariation};
+
+mod lds;
+pub use lds::{LdsOrdinance, LdsOrdinanceType};
+
+mod multimedia;
+pub use multimedia::{MultimediaFileRefn, MultimediaLink, MultimediaRecord};
 
 /// Data repository, the `REPO` tag
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
 pub struct Repository {
     /// Optional reference to link to this repo
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub xref: Option<Xref>,
     /// Name of the repository
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub name: Option<String>,
     /// Physical address of the data repository
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub address: Option<Address>,
+    /// Phone numbers of the repository, `PHON`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub phones: Vec<String>,
+    /// Email addresses of the repository, `EMAIL`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub emails: Vec<String>,
+    /// Fax numbers of the repository, `FAX`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub faxes: Vec<String>,
+    /// Web pages of the repository, `WWW`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub websites: Vec<String>,
+    /// Notes about the repository
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub notes: Vec<Note>,
+    /// Numbers given to the record by the user, the `REFN` tags
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub user_reference_numbers: Vec<UserReferenceNumber>,
+    /// Number given to the record by the program that produced the file, `RIN`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub automated_record_id: Option<String>,
+    /// User-defined tags of the record
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub custom_data: Vec<CustomData>,
+    /// Date of the last change to the record, `CHAN.DATE`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub last_updated: Option<String>,
 }
 
-/// Citation linking a genealogy fact to a data `Source`
+impl Repository {
+    /// Shorthand for creating a `Repository` from its `xref`
+    #[must_use]
+    pub fn new(xref: Option<Xref>) -> Repository {
+        Repository {
+            xref,
+            name: None,
+            address: None,
+            phones: Vec::new(),
+            emails: Vec::new(),
+            faxes: Vec::new(),
+            websites: Vec::new(),
+            notes: Vec::new(),
+            user_reference_numbers: Vec::new(),
+            automated_record_id: None,
+            custom_data: Vec::new(),
+            last_updated: None,
+        }
+    }
+
+    /// Parses a `REPO` record from a GEDCOM fragment, without requiring the
+    /// `HEAD`/`TRLR` scaffolding of a full document. Lines following the record are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the fragment does not begin with a `REPO` record or the
+    /// record is malformed.
+    pub fn try_parse_str(content: &str) -> Result<Repository, GedcomError> {
+        Parser::new(content.chars()).try_parse_repository_record()
+    }
+
+    /// Parses a `REPO` record from a GEDCOM fragment like `try_parse_str`, panicking on
+    /// errors.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the fragment does not begin with a `REPO` record or the record is malformed.
+    #[must_use]
+    pub fn parse_str(content: &str) -> Repository {
+        Parser::new(content.chars()).parse_repository_record()
+    }
+}
+
+/// Link to an individual associated with a person or a fact, ie. a godparent or a
+/// witness, the `ASSO` tag
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct Association {
+    /// Reference to the associated `Individual`
+    pub xref: Xref,
+    /// Relationship of the associated individual, `RELA`, ie. `Godfather` (GEDCOM 5.5.1)
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub relation: Option<String>,
+    /// Role of the associated individual, `ROLE`, ie. `WITN` (GEDCOM 7)
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub role: Option<String>,
+    /// Notes about the association
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub notes: Vec<Note>,
+    /// Sources of the association
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub citations: Vec<SourceCitation>,
+}
+
+impl Association {
+    /// Shorthand for creating an `Association` with the individual `xref`
+    #[must_use]
+    pub fn new(xref: Xref) -> Association {
+        Association {
+            xref,
+            relation: None,
+            role: None,
+            notes: Vec::new(),
+            citations: Vec::new(),
+        }
+    }
+}
+
+/// Citation linking a genealogy fact to a data `Source`
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
 pub struct SourceCitation {
     /// Reference to the `Source`
     pub xref: Xref,
     /// Page number of source
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub page: Option<String>,
+    /// Date the cited entry was recorded in the source, `DATA.DATE`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub date: Option<String>,
+    /// Excerpts of the source, as transcribed by the researcher, `DATA.TEXT`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub texts: Vec<String>,
+    /// Type of event the cited entry recorded, `EVEN`, ie. `CENS`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub event: Option<String>,
+    /// Role of the cited person in that event, `EVEN.ROLE`, ie. `HEAD` or `(boarder)`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub role: Option<String>,
+    /// Credibility of the evidence, `QUAY`: from 0 for unreliable evidence to 3 for
+    /// direct & primary evidence
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub quality: Option<u8>,
+    /// Notes about the citation
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub notes: Vec<Note>,
+}
+
+impl SourceCitation {
+    /// Splits a structured `PAGE` into its key/value pairs, as written by Ancestry in
+    /// the form `Database: X; Page: Y; Record #: Z`. Parts without a `key: value` shape
+    /// are left out; the raw string remains available as `page`.
+    #[must_use]
+    pub fn page_fields(&self) -> Vec<(&str, &str)> {
+        let Some(page) = &self.page else {
+            return Vec::new();
+        };
+        page.split(';')
+            .filter_map(|part| part.split_once(':'))
+            .map(|(key, value)| (key.trim(), value.trim()))
+            .filter(|(key, _)| !key.is_empty())
+            .collect()
+    }
+}
+
+/// A number given to a record by the user, the `REFN` tag
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct UserReferenceNumber {
+    /// The number, ie. `1234`
+    pub number: String,
+    /// What the number refers to, `REFN.TYPE`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub reference_type: Option<String>,
 }
 
 /// Citation linking a `Source` to a data `Repository`
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
 pub struct RepoCitation {
-    /// Reference to the `Repository`
-    pub xref: Xref,
+    /// The cited `Repository`
+    pub repository: RepoReference,
     /// Call number to find the source at this repository
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub call_number: Option<String>,
 }
 
-#[derive(Debug)]
+/// The repository a `RepoCitation` refers to
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub enum RepoReference {
+    /// Reference to a `Repository` record
+    Pointer(Xref),
+    /// A repository described within the citation, as written by exports that
+    /// have no repository records, ie. `1 REPO` followed by `2 NAME`
+    Inline(Box<Repository>),
+}
+
+impl RepoReference {
+    /// The xref of the cited `Repository` record, if the citation points to one
+    #[must_use]
+    pub fn xref(&self) -> Option<&str> {
+        match self {
+            RepoReference::Pointer(xref) => Some(xref),
+            RepoReference::Inline(_) => None,
+        }
+    }
+}
+
+/// Data of a user-defined tag, ie. one beginning with an underscore
+#[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
 pub struct CustomData {
+    /// The tag of the line, ie. `_MILT`
     pub tag: String,
+    /// Value of the line, empty when the tag only groups its children
     pub value: String,
+    /// Subordinate lines of the tag, standard tags included
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub children: Vec<CustomData>,
 }
+
+/// A top-level record of a tag the parser doesn't handle, ie. a GEDCOM 7.0 extension
+/// record like `0 @T1@ _TODO`, kept as it was written, see
+/// `ParseOptions::extension_records`
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct UserDefinedDataset {
+    /// Optional reference to link to this record
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub xref: Option<String>,
+    /// The first line of the record, with its subordinate lines
+    pub data: CustomData,
+}
+
+impl CustomData {
+    /// The lines tagged `tag` among this line & its subordinate lines, at any depth
+    #[must_use]
+    pub fn find_by_tag(&self, tag: &str) -> Vec<&CustomData> {
+        let mut found = Vec::new();
+        if self.tag == tag {
+            found.push(self);
+        }
+        for child in &self.children {
+            found.extend(child.find_by_tag(tag));
+        }
+        found
+    }
+}
+
+/// Records & structures that keep the user-defined tags they were written with, so
+/// applications whose data lives in vendor tags can reach it the same way everywhere
+pub trait HasCustomData {
+    /// The user-defined tags, in the order they were written
+    fn custom_data(&self) -> &[CustomData];
+    /// Mutable access to the user-defined tags
+    fn custom_data_mut(&mut self) -> &mut Vec<CustomData>;
+
+    /// Adds the data of a user-defined tag
+    fn add_custom_data(&mut self, data: CustomData) {
+        self.custom_data_mut().push(data);
+    }
+
+    /// The lines tagged `tag` among the user-defined tags & their subordinate lines
+    fn find_by_tag(&self, tag: &str) -> Vec<&CustomData> {
+        self.custom_data()
+            .iter()
+            .flat_map(|data| data.find_by_tag(tag))
+            .collect()
+    }
+}
+
+impl_has_custom_data!(
+    Header,
+    Individual,
+    Family,
+    Source,
+    Repository,
+    Submitter,
+    MultimediaRecord,
+    MultimediaLink,
+    Location,
+    Event,
+    Place,
+    LdsOrdinance,
+    NoteRecord
+);
@@ -0,0 +1,204 @@
+use crate::types::{CustomData, Note};
+#[cfg(feature = "json")]
+use serde::{Deserialize, Serialize};
+
+type Xref = String;
+
+/// Multimedia record, the top-level `OBJE` tag
+///
+/// A record may reference several files, ie. the photo of a document along with its
+/// transcript. GEDCOM 5.5.1 places the format & title of each file under its `FILE`
+/// line, but some exports, notably Ancestry's, write `FORM` & `TITL` as siblings of
+/// `FILE`; those are kept in `form` & `title` of the record.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct MultimediaRecord {
+    /// Optional reference to link to this record
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub xref: Option<Xref>,
+    /// Files of the record, in the order they were listed
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub files: Vec<MultimediaFileRefn>,
+    /// Format of the files, when written as a sibling of `FILE`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub form: Option<String>,
+    /// Title of the record, when written as a sibling of `FILE`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub title: Option<String>,
+    /// Notes about the record
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub notes: Vec<Note>,
+    /// Data of user-defined tags
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub custom_data: Vec<CustomData>,
+    /// Date of the last change to the record, `CHAN.DATE`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub last_updated: Option<String>,
+    /// Date the record was created, `CREA.DATE` in GEDCOM 7.0
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub created: Option<String>,
+}
+
+impl MultimediaRecord {
+    /// Creates an empty multimedia record
+    #[must_use]
+    pub fn new(xref: Option<Xref>) -> MultimediaRecord {
+        MultimediaRecord {
+            xref,
+            ..MultimediaRecord::default()
+        }
+    }
+
+    /// Adds a file reference to the record
+    pub fn add_file(&mut self, file: MultimediaFileRefn) {
+        self.files.push(file);
+    }
+
+    /// Moves a `FORM` & `TITL` written as siblings of `FILE` under the files missing
+    /// their own, as the specification places them. Records without files are left
+    /// as they are. Returns whether the record was changed.
+    pub fn normalize_files(&mut self) -> bool {
+        if self.files.is_empty() || (self.form.is_none() && self.title.is_none()) {
+            return false;
+        }
+        let form = self.form.take();
+        let title = self.title.take();
+        for file in &mut self.files {
+            if file.form.is_none() {
+                file.form.clone_from(&form);
+            }
+            if file.title.is_none() {
+                file.title.clone_from(&title);
+            }
+        }
+        true
+    }
+}
+
+/// A file of a multimedia record, the `FILE` tag
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct MultimediaFileRefn {
+    /// Path or URL of the file
+    pub value: String,
+    /// Format of the file, ie. `jpg`, from `FORM`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub form: Option<String>,
+    /// Kind of medium the file was made from, ie. `photo`, from `FORM.MEDI` (or
+    /// `FORM.TYPE` in GEDCOM 5.5)
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub source_media_type: Option<String>,
+    /// Title of the file, from `TITL`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub title: Option<String>,
+}
+
+impl MultimediaFileRefn {
+    /// Creates a reference to the file at `value`
+    #[must_use]
+    pub fn new(value: String) -> MultimediaFileRefn {
+        MultimediaFileRefn {
+            value,
+            ..MultimediaFileRefn::default()
+        }
+    }
+}
+
+/// Link from a record to a multimedia record, the `OBJE` tag with a pointer, or to an
+/// object described within the link, the `OBJE` tag without one
+///
+/// Vendors mark the preferred object of a record, ie. the portrait shown for an
+/// individual, with a user-defined flag like `_PRIM Y`. The flag is read into `primary`
+/// and kept as written in `custom_data`.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct MultimediaLink {
+    /// Reference to the multimedia record, empty for an embedded object
+    pub xref: Xref,
+    /// The object described within the link rather than in a record, as GEDCOM 5.5 &
+    /// 5.5.1 allow, see `GedcomData::place_multimedia`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub embedded: Option<Box<MultimediaRecord>>,
+    /// Whether the object is marked as the preferred one of the record
+    pub primary: bool,
+    /// Data of user-defined tags, including the flag marking the object as preferred
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub custom_data: Vec<CustomData>,
+}
+
+impl MultimediaLink {
+    /// User-defined tags vendors mark the preferred object with
+    pub const PRIMARY_TAGS: [&'static str; 2] = ["_PRIM", "_PRIMARY"];
+
+    /// Creates a link to the multimedia record `xref`
+    #[must_use]
+    pub fn new(xref: Xref) -> MultimediaLink {
+        MultimediaLink {
+            xref,
+            ..MultimediaLink::default()
+        }
+    }
+
+    /// Creates a link to an object described within the link
+    #[must_use]
+    pub fn embedded(multimedia: MultimediaRecord) -> MultimediaLink {
+        MultimediaLink {
+            embedded: Some(Box::new(multimedia)),
+            ..MultimediaLink::default()
+        }
+    }
+
+    /// Whether `data` is a flag marking the object as preferred, ie. `_PRIM Y`
+    #[must_use]
+    pub fn is_primary_flag(data: &CustomData) -> bool {
+        MultimediaLink::is_primary_tag(&data.tag)
+            && matches!(data.value.to_ascii_uppercase().as_str(), "Y" | "YES")
+    }
+
+    /// Whether `tag` is one of `PRIMARY_TAGS`, in any case
+    #[must_use]
+    pub fn is_primary_tag(tag: &str) -> bool {
+        MultimediaLink::PRIMARY_TAGS
+            .iter()
+            .any(|primary| tag.eq_ignore_ascii_case(primary))
+    }
+}
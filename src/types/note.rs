@@ -0,0 +1,311 @@
+use crate::tree::GedcomData;
+use crate::types::{CustomData, SourceCitation, UserReferenceNumber};
+#[cfg(feature = "json")]
+use serde::{Deserialize, Serialize};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt,
+    sync::{Arc, OnceLock},
+};
+
+/// Text of a `NOTE`, with `CONT` & `CONC` lines joined.
+///
+/// With the `compression` feature, long note bodies can be stored compressed to cut
+/// memory usage of note-heavy files. `Note::text()` transparently decompresses them.
+///
+/// Notes can also share their text, see `ParseOptions::deduplicate_notes`: a shared note
+/// is stored once, and written as a note record that the structures holding it point to.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "json",
+    derive(Serialize, Deserialize),
+    serde(from = "NoteJson", into = "NoteJson")
+)]
+pub struct Note {
+    storage: NoteStorage,
+    /// Language of the text, the `LANG` tag of GEDCOM 7.0
+    language: Option<String>,
+}
+
+/// A note as JSON: its text, or an object with its text & language when it has one
+#[cfg(feature = "json")]
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum NoteJson {
+    Text(String),
+    WithLanguage { text: String, language: String },
+}
+
+#[cfg(feature = "json")]
+impl From<NoteJson> for Note {
+    fn from(json: NoteJson) -> Note {
+        match json {
+            NoteJson::Text(text) => Note::new(text),
+            NoteJson::WithLanguage { text, language } => Note::new(text).with_language(language),
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<Note> for NoteJson {
+    fn from(mut note: Note) -> NoteJson {
+        match note.language.take() {
+            Some(language) => NoteJson::WithLanguage {
+                text: note.into(),
+                language,
+            },
+            None => NoteJson::Text(note.into()),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+enum NoteStorage {
+    Plain(String),
+    #[cfg(feature = "compression")]
+    Compressed(Vec<u8>),
+    Shared(Arc<SharedText>),
+}
+
+/// Text stored once for all the notes repeating it
+#[derive(PartialEq, Eq)]
+struct SharedText {
+    text: Arc<str>,
+    /// Set when the text is written as a note record of its own
+    xref: OnceLock<String>,
+}
+
+/// Gives the notes with identical text a single copy of it. The notes are only written
+/// as a shared note record once the text repeats, see `shared_notes`.
+#[derive(Default)]
+pub(crate) struct NoteInterner {
+    /// The distinct texts, in the order they were first seen
+    texts: Vec<Arc<SharedText>>,
+    index: HashMap<Arc<str>, usize>,
+}
+
+impl NoteInterner {
+    /// Creates a note sharing its text with the notes created before it
+    pub(crate) fn note(&mut self, text: String) -> Note {
+        if let Some(&i) = self.index.get(text.as_str()) {
+            return Note {
+                storage: NoteStorage::Shared(Arc::clone(&self.texts[i])),
+                language: None,
+            };
+        }
+        let text: Arc<str> = text.into();
+        let shared = Arc::new(SharedText {
+            text: Arc::clone(&text),
+            xref: OnceLock::new(),
+        });
+        self.index.insert(text, self.texts.len());
+        self.texts.push(Arc::clone(&shared));
+        Note {
+            storage: NoteStorage::Shared(shared),
+            language: None,
+        }
+    }
+
+    /// Gives the texts held by more than one note a cross-reference identifier, skipping
+    /// the identifiers `taken`, and returns a note of each as the shared note records
+    pub(crate) fn shared_notes(self, taken: impl Fn(&str) -> bool) -> Vec<Note> {
+        let mut number = 0;
+        self.texts
+            .into_iter()
+            // held by the interner & at least two notes
+            .filter(|shared| Arc::strong_count(shared) > 2)
+            .map(|shared| {
+                let xref = loop {
+                    number += 1;
+                    let xref = format!("@N{number}@");
+                    if !taken(&xref) {
+                        break xref;
+                    }
+                };
+                shared.xref.get_or_init(|| xref);
+                Note {
+                    storage: NoteStorage::Shared(shared),
+                    language: None,
+                }
+            })
+            .collect()
+    }
+}
+
+impl Note {
+    /// Creates a note stored as plain text
+    #[must_use]
+    pub fn new(text: String) -> Note {
+        Note {
+            storage: NoteStorage::Plain(text),
+            language: None,
+        }
+    }
+
+    /// Creates a note, storing it compressed when the text is at least `threshold` bytes long
+    #[cfg(feature = "compression")]
+    #[must_use]
+    pub fn with_threshold(text: String, threshold: usize) -> Note {
+        if text.len() < threshold {
+            return Note::new(text);
+        }
+        let compressed = miniz_oxide::deflate::compress_to_vec(text.as_bytes(), 6);
+        Note {
+            storage: NoteStorage::Compressed(compressed),
+            language: None,
+        }
+    }
+
+    /// The text of the note, decompressed if needed
+    ///
+    /// # Panics
+    ///
+    /// Panics if compressed storage was corrupted, which `Note` does not allow to happen.
+    #[must_use]
+    pub fn text(&self) -> Cow<'_, str> {
+        match &self.storage {
+            NoteStorage::Plain(text) => Cow::Borrowed(text),
+            NoteStorage::Shared(shared) => Cow::Borrowed(&shared.text),
+            #[cfg(feature = "compression")]
+            NoteStorage::Compressed(bytes) => {
+                let text = miniz_oxide::inflate::decompress_to_vec(bytes)
+                    .expect("note storage is compressed by Note itself");
+                Cow::Owned(String::from_utf8(text).expect("note storage is valid UTF-8"))
+            }
+        }
+    }
+
+    /// Whether the note is stored compressed
+    #[must_use]
+    pub fn is_compressed(&self) -> bool {
+        match self.storage {
+            #[cfg(feature = "compression")]
+            NoteStorage::Compressed(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Gives the note the language its text is written in, ie. `en-US`
+    #[must_use]
+    pub fn with_language(mut self, language: impl Into<String>) -> Note {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// The language of the text, when the note gives it with `LANG`
+    #[must_use]
+    pub fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
+
+    /// The language of the text within the tree: the note's own, else the one of the note
+    /// record it points to, else the default language of the file, `HEAD.LANG`
+    #[must_use]
+    pub fn language_in<'a>(&'a self, data: &'a GedcomData) -> Option<&'a str> {
+        self.language()
+            .or_else(|| data.resolve_note(self)?.text.language())
+            .or_else(|| data.header.language())
+    }
+
+    /// The cross-reference identifier of the shared note record holding the text, when
+    /// the note shares its text with other notes
+    #[must_use]
+    pub fn shared_xref(&self) -> Option<&str> {
+        if let NoteStorage::Shared(shared) = &self.storage {
+            shared.xref.get().map(String::as_str)
+        } else {
+            None
+        }
+    }
+}
+
+impl From<String> for Note {
+    fn from(text: String) -> Note {
+        Note::new(text)
+    }
+}
+
+impl From<Note> for String {
+    fn from(note: Note) -> String {
+        match note.storage {
+            NoteStorage::Plain(text) => text,
+            NoteStorage::Shared(shared) => shared.text.to_string(),
+            #[cfg(feature = "compression")]
+            NoteStorage::Compressed(_) => note.text().into_owned(),
+        }
+    }
+}
+
+impl fmt::Debug for Note {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.text(), f)
+    }
+}
+
+impl fmt::Display for Note {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text())
+    }
+}
+
+/// A note record, the top-level `NOTE` tag (`SNOTE` in GEDCOM 7.0) that structures point
+/// to with `NOTE @N1@`, see `GedcomData::resolve_note`
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct NoteRecord {
+    /// Optional reference to link to this note
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub xref: Option<String>,
+    /// Text of the note
+    pub text: Note,
+    /// Numbers given to the record by the user, the `REFN` tags
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub user_reference_numbers: Vec<UserReferenceNumber>,
+    /// Number given to the record by the program that produced the file, `RIN`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub automated_record_id: Option<String>,
+    /// Sources of the note
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub citations: Vec<SourceCitation>,
+    /// User-defined tags of the record
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub custom_data: Vec<CustomData>,
+    /// Date of the last change to the record, `CHAN.DATE`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub last_updated: Option<String>,
+}
+
+impl NoteRecord {
+    /// Creates a note record holding `text`
+    #[must_use]
+    pub fn new(xref: Option<String>, text: Note) -> NoteRecord {
+        NoteRecord {
+            xref,
+            text,
+            user_reference_numbers: Vec::new(),
+            automated_record_id: None,
+            citations: Vec::new(),
+            custom_data: Vec::new(),
+            last_updated: None,
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use crate::types::{CustomData, Map, Note};
+#[cfg(feature = "json")]
+use serde::{Deserialize, Serialize};
+
+type Xref = String;
+
+/// Place of an event or an ordinance, the `PLAC` tag, with the jurisdictions of its
+/// parts, its variations & its coordinates
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct Place {
+    /// Name of the place, its jurisdictions separated by commas, ie.
+    /// `Boston, Suffolk, Massachusetts`
+    pub value: String,
+    /// Jurisdictions of the parts of the name, `FORM`, when they differ from the default
+    /// of the header
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub form: Option<String>,
+    /// The name as pronounced, `FONE`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub phonetic: Vec<PlaceVariation>,
+    /// The name in Latin letters, `ROMN`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub romanized: Vec<PlaceVariation>,
+    /// Coordinates of the place, `MAP`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub map: Option<Map>,
+    /// Reference to the shared location record of the place, `_LOC`, see
+    /// `GedcomData::location`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub location: Option<Xref>,
+    /// Notes about the place
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub notes: Vec<Note>,
+    /// User-defined tags of the place
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub custom_data: Vec<CustomData>,
+}
+
+impl Place {
+    /// Creates a place of the name `value` without any detail
+    #[must_use]
+    pub fn new(value: String) -> Place {
+        Place {
+            value,
+            ..Place::default()
+        }
+    }
+
+    /// The latitude & longitude of the place in degrees, from its `MAP`, south & west
+    /// being negative
+    #[must_use]
+    pub fn coordinates(&self) -> Option<(f64, f64)> {
+        self.map.as_ref().map(Map::coordinates)
+    }
+}
+
+/// Variation of the name of a place, `PLAC.FONE` or `PLAC.ROMN`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct PlaceVariation {
+    /// The place as pronounced or romanized
+    pub value: String,
+    /// Method used for the variation, `TYPE`, ie. `kana` or `pinyin`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub variation_type: Option<String>,
+}
@@ -1,19 +1,60 @@
-use crate::types::{Event, RepoCitation};
+use crate::parser::Parser;
+use crate::types::{CustomData, Event, Note, RepoCitation};
+use crate::GedcomError;
 #[cfg(feature = "json")]
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
 /// Source for genealogy facts
 pub struct Source {
+    /// Optional reference to link to this source
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub xref: Option<String>,
+    /// Data recorded by the source
     pub data: SourceData,
+    /// Short title of the source, for sorting & display
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub abbreviation: Option<String>,
+    /// Title of the source
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub title: Option<String>,
+    /// Text of the source, as transcribed by the researcher, `TEXT`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub text: Option<String>,
+    /// Notes about the source
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub notes: Vec<Note>,
+    /// User-defined tags of the record
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub custom_data: Vec<CustomData>,
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
     repo_citations: Vec<RepoCitation>,
 }
 
 impl Source {
+    /// Shorthand for creating a `Source` from its `xref`
     #[must_use]
     pub fn new(xref: Option<String>) -> Source {
         Source {
@@ -21,28 +62,95 @@ impl Source {
             data: SourceData {
                 events: Vec::new(),
                 agency: None,
+                notes: Vec::new(),
             },
             abbreviation: None,
             title: None,
+            text: None,
+            notes: Vec::new(),
+            custom_data: Vec::new(),
             repo_citations: Vec::new(),
         }
     }
 
+    /// Parses a `SOUR` record from a GEDCOM fragment, without requiring the
+    /// `HEAD`/`TRLR` scaffolding of a full document. Lines following the record are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the fragment does not begin with a `SOUR` record or the
+    /// record is malformed.
+    pub fn try_parse_str(content: &str) -> Result<Source, GedcomError> {
+        Parser::new(content.chars()).try_parse_source_record()
+    }
+
+    /// Parses a `SOUR` record from a GEDCOM fragment like `try_parse_str`, panicking on
+    /// errors.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the fragment does not begin with a `SOUR` record or the record is malformed.
+    #[must_use]
+    pub fn parse_str(content: &str) -> Source {
+        Parser::new(content.chars()).parse_source_record()
+    }
+
+    /// Adds a citation of a repository holding this source
     pub fn add_repo_citation(&mut self, citation: RepoCitation) {
         self.repo_citations.push(citation);
     }
+
+    /// Citations of the repositories holding this source
+    #[must_use]
+    pub fn repo_citations(&self) -> &[RepoCitation] {
+        &self.repo_citations
+    }
+
+    /// Mutable access to the citations of the repositories holding this source
+    pub fn repo_citations_mut(&mut self) -> &mut [RepoCitation] {
+        &mut self.repo_citations
+    }
 }
 
 #[allow(clippy::module_name_repetitions)]
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+/// Data recorded by a `Source`, the `DATA` tag: the kinds of events recorded, with
+/// the period & place they cover, the agency responsible for them and notes
 pub struct SourceData {
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
     events: Vec<Event>,
+    /// Agency responsible for the recorded data
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub agency: Option<String>,
+    /// Notes about the recorded data
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub notes: Vec<Note>,
 }
 
 impl SourceData {
+    /// Adds an event recorded by the source
     pub fn add_event(&mut self, event: Event) {
         self.events.push(event);
     }
+
+    /// The events recorded by the source, the `DATA.EVEN` tags
+    #[must_use]
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// Mutable access to the events recorded by the source
+    pub fn events_mut(&mut self) -> &mut [Event] {
+        &mut self.events
+    }
 }
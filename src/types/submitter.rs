@@ -1,21 +1,64 @@
-use crate::types::Address;
+use crate::parser::Parser;
+use crate::types::{Address, CustomData, Note};
+use crate::GedcomError;
 #[cfg(feature = "json")]
 use serde::{Deserialize, Serialize};
 
 type Xref = String;
 
 /// Submitter of the data, ie. who reported the genealogy fact
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
 pub struct Submitter {
     /// Optional reference to link to this submitter
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub xref: Option<Xref>,
     /// Name of the submitter
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub name: Option<String>,
     /// Physical address of the submitter
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub address: Option<Address>,
     /// Phone number of the submitter
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub phone: Option<String>,
+    /// Languages the submitter prefers to receive data in, the `LANG` tags, of which
+    /// GEDCOM 5.5.1 allows up to three
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub languages: Vec<String>,
+    /// Notes about the submitter
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub notes: Vec<Note>,
+    /// User-defined tags of the record
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub custom_data: Vec<CustomData>,
+    /// Date of the last change to the record, `CHAN.DATE`
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub last_updated: Option<String>,
 }
 
 impl Submitter {
@@ -27,6 +70,32 @@ impl Submitter {
             name: None,
             address: None,
             phone: None,
+            languages: Vec::new(),
+            notes: Vec::new(),
+            custom_data: Vec::new(),
+            last_updated: None,
         }
     }
+
+    /// Parses a `SUBM` record from a GEDCOM fragment, without requiring the
+    /// `HEAD`/`TRLR` scaffolding of a full document. Lines following the record are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the fragment does not begin with a `SUBM` record or the
+    /// record is malformed.
+    pub fn try_parse_str(content: &str) -> Result<Submitter, GedcomError> {
+        Parser::new(content.chars()).try_parse_submitter_record()
+    }
+
+    /// Parses a `SUBM` record from a GEDCOM fragment like `try_parse_str`, panicking on
+    /// errors.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the fragment does not begin with a `SUBM` record or the record is malformed.
+    #[must_use]
+    pub fn parse_str(content: &str) -> Submitter {
+        Parser::new(content.chars()).parse_submitter_record()
+    }
 }
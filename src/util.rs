@@ -1,3 +1,20 @@
+/// Implements `HasCustomData` for records keeping their user-defined tags in a
+/// `custom_data` field.
+macro_rules! impl_has_custom_data {
+    ($($record: ty),+) => {
+        $(
+            impl $crate::types::HasCustomData for $record {
+                fn custom_data(&self) -> &[$crate::types::CustomData] {
+                    &self.custom_data
+                }
+                fn custom_data_mut(&mut self) -> &mut Vec<$crate::types::CustomData> {
+                    &mut self.custom_data
+                }
+            }
+        )+
+    };
+}
+
 /// Macro for displaying `Option`s in debug mode without the text wrapping.
 #[macro_export]
 macro_rules! fmt_optional_value {
@@ -0,0 +1,383 @@
+//! Checks the structure of a parsed tree: pointers to missing records, duplicate xrefs,
+//! links recorded on one side only, children born after a parent died, header fields
+//! required by the specification & numbers of children disagreeing with the children
+//! listed.
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::diagnostics::Severity;
+use crate::repair::LinkIssue;
+use crate::tree::GedcomData;
+use crate::types::{
+    CalendarDate, DateValue, Event, EventType, GedcomVersion, Individual, LdsOrdinance,
+    MultimediaLink, ParentTag, RepoReference, SourceCitation,
+};
+
+/// The checks `validate` runs
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Check {
+    /// A pointer refers to a record missing from the tree
+    DanglingPointer,
+    /// Several records share an xref
+    DuplicateXref,
+    /// A family link is recorded on the individual or on the family only
+    OneSidedLink,
+    /// A child was born after the death of their mother, or more than nine months after
+    /// the death of their father
+    BirthAfterParentDeath,
+    /// The header lacks a field the GEDCOM version requires
+    MissingHeaderField,
+    /// The number of children given by the `NCHI` of a family or an individual differs
+    /// from the number of children listed, ie. after an import that lost some of them
+    ChildCount,
+}
+
+impl Check {
+    /// Every check, in the order `validate` runs them
+    pub const ALL: [Check; 6] = [
+        Check::DanglingPointer,
+        Check::DuplicateXref,
+        Check::OneSidedLink,
+        Check::BirthAfterParentDeath,
+        Check::MissingHeaderField,
+        Check::ChildCount,
+    ];
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Check::DanglingPointer => write!(f, "dangling-pointer"),
+            Check::DuplicateXref => write!(f, "duplicate-xref"),
+            Check::OneSidedLink => write!(f, "one-sided-link"),
+            Check::BirthAfterParentDeath => write!(f, "birth-after-parent-death"),
+            Check::MissingHeaderField => write!(f, "missing-header-field"),
+            Check::ChildCount => write!(f, "child-count"),
+        }
+    }
+}
+
+/// A problem found by `validate`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationIssue {
+    /// The check that found the problem
+    pub check: Check,
+    /// How severe the problem is
+    pub severity: Severity,
+    /// Xref of the record the problem was found in, `None` for the header
+    pub record: Option<String>,
+    /// Human-readable description of the problem
+    pub message: String,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let record = self.record.as_deref().unwrap_or("HEAD");
+        write!(f, "{}: {}: {}", record, self.severity, self.message)
+    }
+}
+
+/// Runs the given checks over the tree, ie. `Check::ALL`, returning the problems found
+/// in the order of the checks, then of the records.
+#[must_use]
+pub fn validate(data: &GedcomData, checks: &[Check]) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    for check in Check::ALL {
+        if !checks.contains(&check) {
+            continue;
+        }
+        match check {
+            Check::DanglingPointer => dangling_pointers(data, &mut issues),
+            Check::DuplicateXref => duplicate_xrefs(data, &mut issues),
+            Check::OneSidedLink => one_sided_links(data, &mut issues),
+            Check::BirthAfterParentDeath => births_after_death(data, &mut issues),
+            Check::MissingHeaderField => header_fields(data, &mut issues),
+            Check::ChildCount => child_counts(data, &mut issues),
+        }
+    }
+    issues
+}
+
+fn dangling_pointers(data: &GedcomData, issues: &mut Vec<ValidationIssue>) {
+    let xrefs: HashSet<&str> = data.xrefs().collect();
+    let mut check = |record: Option<&str>, pointer: &str| {
+        if !xrefs.contains(pointer) {
+            issues.push(ValidationIssue {
+                check: Check::DanglingPointer,
+                severity: Severity::Error,
+                record: record.map(String::from),
+                message: format!(
+                    "{} links to missing record {}",
+                    record.unwrap_or("HEAD"),
+                    pointer
+                ),
+            });
+        }
+    };
+
+    let header = &data.header;
+    for pointer in header.submitter_tag.iter().chain(&header.submission_tag) {
+        check(None, pointer);
+    }
+    for individual in &data.individuals {
+        let xref = individual.xref.as_deref();
+        let mut pointers: Vec<&str> = Vec::new();
+        for link in &individual.families {
+            pointers.push(&link.xref);
+            pointers.extend(citation_pointers(&link.citations));
+        }
+        pointers.extend(multimedia_pointers(&individual.multimedia));
+        for association in &individual.associations {
+            pointers.push(&association.xref);
+            pointers.extend(citation_pointers(&association.citations));
+        }
+        pointers.extend(individual.events.iter().flat_map(event_pointers));
+        pointers.extend(ordinance_pointers(&individual.lds_ordinances));
+        for pointer in pointers {
+            check(xref, pointer);
+        }
+    }
+    for family in &data.families {
+        let xref = family.xref.as_deref();
+        let mut pointers: Vec<&str> = family.parents().into_iter().map(|(p, _)| p).collect();
+        pointers.extend(family.children.iter().map(String::as_str));
+        pointers.extend(family.submitters.iter().map(String::as_str));
+        pointers.extend(family.events.iter().flat_map(event_pointers));
+        pointers.extend(ordinance_pointers(&family.lds_ordinances));
+        for pointer in pointers {
+            check(xref, pointer);
+        }
+    }
+    for source in &data.sources {
+        for citation in source.repo_citations() {
+            if let RepoReference::Pointer(repository) = &citation.repository {
+                check(source.xref.as_deref(), repository);
+            }
+        }
+    }
+}
+
+/// The sources pointed to by citations, leaving out the sources described within the
+/// citation of GEDCOM 5.5.1, ie. `SOUR Parish register`
+fn citation_pointers(citations: &[SourceCitation]) -> impl Iterator<Item = &str> {
+    citations
+        .iter()
+        .map(|citation| citation.xref.as_str())
+        .filter(|xref| xref.starts_with('@'))
+}
+
+/// The records pointed to by multimedia links, leaving out the embedded objects
+fn multimedia_pointers(links: &[MultimediaLink]) -> impl Iterator<Item = &str> {
+    links
+        .iter()
+        .filter(|link| link.embedded.is_none())
+        .map(|link| link.xref.as_str())
+}
+
+fn event_pointers(event: &Event) -> Vec<&str> {
+    let mut pointers: Vec<&str> = citation_pointers(&event.citations).collect();
+    for association in &event.associations {
+        pointers.push(&association.xref);
+        pointers.extend(citation_pointers(&association.citations));
+    }
+    pointers.extend(event.family.as_deref());
+    if let Some(place) = &event.place {
+        pointers.extend(place.location.as_deref());
+    }
+    pointers.extend(multimedia_pointers(&event.multimedia));
+    pointers
+}
+
+fn ordinance_pointers(ordinances: &[LdsOrdinance]) -> Vec<&str> {
+    ordinances
+        .iter()
+        .flat_map(|ordinance| {
+            ordinance
+                .family
+                .as_deref()
+                .into_iter()
+                .chain(citation_pointers(&ordinance.citations))
+        })
+        .collect()
+}
+
+fn duplicate_xrefs(data: &GedcomData, issues: &mut Vec<ValidationIssue>) {
+    let mut seen = HashSet::new();
+    let mut reported = HashSet::new();
+    for xref in data.xrefs() {
+        if !seen.insert(xref) && reported.insert(xref) {
+            issues.push(ValidationIssue {
+                check: Check::DuplicateXref,
+                severity: Severity::Error,
+                record: Some(xref.to_string()),
+                message: format!("Several records have the xref {xref}"),
+            });
+        }
+    }
+}
+
+fn one_sided_links(data: &GedcomData, issues: &mut Vec<ValidationIssue>) {
+    for issue in data.one_sided_links() {
+        let record = match &issue {
+            LinkIssue::MissingParent { individual, .. }
+            | LinkIssue::MissingChild { individual, .. } => individual,
+            LinkIssue::MissingSpouseLink { family, .. }
+            | LinkIssue::MissingChildLink { family, .. } => family,
+            // Reported by the dangling pointers check
+            LinkIssue::Dangling { .. } => continue,
+        };
+        issues.push(ValidationIssue {
+            check: Check::OneSidedLink,
+            severity: Severity::Warning,
+            record: Some(record.clone()),
+            message: issue.to_string(),
+        });
+    }
+}
+
+fn births_after_death(data: &GedcomData, issues: &mut Vec<ValidationIssue>) {
+    let individuals: HashMap<&str, &Individual> = data
+        .individuals
+        .iter()
+        .filter_map(|individual| Some((individual.xref.as_deref()?, individual)))
+        .collect();
+    for family in &data.families {
+        for (parent, tag) in family.parents() {
+            let Some(death) = individuals
+                .get(parent)
+                .and_then(|parent| event_date(parent, &EventType::Death))
+            else {
+                continue;
+            };
+            // A father can die before his child is born
+            let grace_months = match tag {
+                ParentTag::Husband => 9,
+                ParentTag::Wife => 0,
+            };
+            for child in &family.children {
+                let Some(birth) = individuals
+                    .get(child.as_str())
+                    .and_then(|child| event_date(child, &EventType::Birth))
+                else {
+                    continue;
+                };
+                if born_after(birth, death, grace_months) {
+                    issues.push(ValidationIssue {
+                        check: Check::BirthAfterParentDeath,
+                        severity: Severity::Warning,
+                        record: Some(child.clone()),
+                        message: format!("{child} was born after the death of {parent}"),
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// The date of the first event of a type with a readable date, of the gregorian or
+/// julian calendar for its months to compare
+fn event_date(individual: &Individual, event_type: &EventType) -> Option<CalendarDate> {
+    individual
+        .events
+        .iter()
+        .filter(|event| &event.event == event_type)
+        .filter_map(|event| DateValue::parse(event.date.as_deref()?).date())
+        .find(|date| date.calendar.is_gregorian_like())
+}
+
+/// Whether `birth` is surely more than `grace_months` after `death`. Dates missing their
+/// month are compared by their years.
+fn born_after(birth: CalendarDate, death: CalendarDate, grace_months: i32) -> bool {
+    match (birth.month, death.month) {
+        (Some(birth_month), Some(death_month)) => {
+            let months =
+                (birth.year - death.year) * 12 + i32::from(birth_month) - i32::from(death_month);
+            match (birth.day, death.day) {
+                (Some(birth_day), Some(death_day)) if months == grace_months => {
+                    birth_day > death_day
+                }
+                _ => months > grace_months,
+            }
+        }
+        _ => birth.year > death.year + i32::from(grace_months > 0),
+    }
+}
+
+fn header_fields(data: &GedcomData, issues: &mut Vec<ValidationIssue>) {
+    let header = &data.header;
+    let mut missing = Vec::new();
+    match header.version() {
+        // Without a version, the other fields can't be told required or not
+        None => missing.push(("GEDC.VERS", Severity::Error)),
+        Some(GedcomVersion::V7_0) => {}
+        Some(_) => {
+            if header
+                .source
+                .as_ref()
+                .and_then(|s| s.value.as_ref())
+                .is_none()
+            {
+                missing.push(("SOUR", Severity::Warning));
+            }
+            if header.submitter_tag.is_none() {
+                missing.push(("SUBM", Severity::Warning));
+            }
+            if header.encoding.is_none() {
+                missing.push(("CHAR", Severity::Warning));
+            }
+        }
+    }
+    for (field, severity) in missing {
+        issues.push(ValidationIssue {
+            check: Check::MissingHeaderField,
+            severity,
+            record: None,
+            message: format!("The header lacks the required {field} field"),
+        });
+    }
+}
+
+fn child_counts(data: &GedcomData, issues: &mut Vec<ValidationIssue>) {
+    let mut issue = |record: &str, message: String| {
+        issues.push(ValidationIssue {
+            check: Check::ChildCount,
+            severity: Severity::Warning,
+            record: Some(record.to_string()),
+            message,
+        });
+    };
+    for family in &data.families {
+        let (Some(xref), Some(count)) = (family.xref.as_deref(), family.num_children) else {
+            continue;
+        };
+        let listed = family.children.len();
+        if usize::from(count) != listed {
+            issue(
+                xref,
+                format!("{xref} has {count} children by NCHI, but lists {listed}"),
+            );
+        }
+    }
+    for individual in &data.individuals {
+        let (Some(xref), Some(count)) = (individual.xref.as_deref(), individual.num_children)
+        else {
+            continue;
+        };
+        // the children of every family the individual is a partner in
+        let children: HashSet<&str> = data
+            .families
+            .iter()
+            .filter(|family| family.parents().iter().any(|(parent, _)| *parent == xref))
+            .flat_map(|family| family.children.iter().map(String::as_str))
+            .collect();
+        if usize::from(count) != children.len() {
+            issue(
+                xref,
+                format!(
+                    "{xref} has {count} children by NCHI, but {} are listed in their families",
+                    children.len()
+                ),
+            );
+        }
+    }
+}
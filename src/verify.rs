@@ -0,0 +1,299 @@
+//! Checks GEDCOM files for conformance, for the export test suites of genealogy
+//! software: each file is parsed in strict mode, then its links & facts are checked.
+//! The findings are printed as JSON, or as a SARIF log for code scanning tools.
+use gedcom::analysis::event_conflicts;
+use gedcom::diagnostics::Severity;
+use gedcom::parser::{ParseOptions, Parser};
+use gedcom::repair::{LinkIssue, RepairMode};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+
+/// Exit code when an error was found in a file
+const EXIT_INVALID: i32 = 1;
+/// Exit code when the command line arguments are not understood
+const EXIT_USAGE: i32 = 2;
+
+/// The checks run on each file, with their description
+const RULES: [(&str, &str); 4] = [
+    (
+        "syntax",
+        "The content follows the GEDCOM grammar & the tags of the specification",
+    ),
+    (
+        "dangling-pointer",
+        "Every pointer refers to a record of the file",
+    ),
+    (
+        "one-sided-link",
+        "Family links are recorded on the individual & on the family",
+    ),
+    (
+        "event-conflict",
+        "Once-in-a-life events recorded twice agree on their date & place",
+    ),
+];
+
+/// Output formats of the findings
+enum Format {
+    Json,
+    Sarif,
+}
+
+/// A problem found in a file
+struct Finding {
+    rule: &'static str,
+    severity: Severity,
+    line: Option<u32>,
+    /// Xref of the record the problem was found in
+    record: Option<String>,
+    message: String,
+}
+
+fn main() {
+    let mut format = Format::Json;
+    let mut deny_warnings = false;
+    let mut filenames = Vec::new();
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--help" | "-h" => usage(""),
+            "--format" => {
+                format = match args.next().map(String::as_str) {
+                    Some("json") => Format::Json,
+                    Some("sarif") => Format::Sarif,
+                    other => usage(&format!("Unknown format {:?}.", other)),
+                }
+            }
+            "--deny-warnings" => deny_warnings = true,
+            _ if arg.starts_with("--") => usage(&format!("Unknown option '{}'.", arg)),
+            filename => filenames.push(filename),
+        }
+    }
+    if filenames.is_empty() {
+        usage("Missing filename.");
+    }
+
+    let mut files = Vec::new();
+    for filename in filenames {
+        let bytes = fs::read(filename).unwrap_or_else(|error| {
+            eprintln!("Error! Can't read '{}': {}", filename, error);
+            std::process::exit(EXIT_USAGE);
+        });
+        let (contents, _, _) = gedcom::encoding::decode(&bytes);
+        files.push((filename, verify(&contents)));
+    }
+
+    match format {
+        Format::Json => print_json(&files),
+        Format::Sarif => print_sarif(&files),
+    }
+    let failed = files
+        .iter()
+        .flat_map(|(_, findings)| findings)
+        .any(|finding| finding.severity == Severity::Error || deny_warnings);
+    if failed {
+        std::process::exit(EXIT_INVALID);
+    }
+}
+
+/// Parses the content in strict mode, then checks the tree when it could be parsed
+fn verify(contents: &str) -> Vec<Finding> {
+    let options = ParseOptions {
+        strict: true,
+        ..ParseOptions::default()
+    };
+    let mut parser = Parser::with_options(contents.chars(), options);
+    let result = parser.parse_document();
+    let mut findings: Vec<Finding> = parser
+        .diagnostics()
+        .iter()
+        .map(|diagnostic| Finding {
+            rule: "syntax",
+            severity: diagnostic.severity,
+            line: Some(diagnostic.line),
+            record: None,
+            message: diagnostic.message.clone(),
+        })
+        .collect();
+    let mut data = match result {
+        Ok(data) => data,
+        Err(error) => {
+            findings.push(Finding {
+                rule: "syntax",
+                severity: Severity::Error,
+                line: Some(error.line().unwrap_or_else(|| parser.line())),
+                record: None,
+                message: error.to_string(),
+            });
+            return findings;
+        }
+    };
+
+    let lines = record_lines(contents);
+    let mut record_finding = |rule, severity, record: &str, message: String| {
+        findings.push(Finding {
+            rule,
+            severity,
+            line: lines.get(record).copied(),
+            record: Some(record.to_string()),
+            message,
+        });
+    };
+    for repair in data.repair_links(RepairMode::Report) {
+        let message = repair.issue.to_string();
+        match repair.issue {
+            LinkIssue::Dangling { from, .. } => {
+                record_finding("dangling-pointer", Severity::Error, &from, message);
+            }
+            LinkIssue::MissingParent { individual, .. }
+            | LinkIssue::MissingChild { individual, .. } => {
+                record_finding("one-sided-link", Severity::Warning, &individual, message);
+            }
+            LinkIssue::MissingSpouseLink { family, .. }
+            | LinkIssue::MissingChildLink { family, .. } => {
+                record_finding("one-sided-link", Severity::Warning, &family, message);
+            }
+        }
+    }
+    for conflict in event_conflicts(&data) {
+        let message = format!(
+            "{} has {} {} records that disagree",
+            conflict.individual,
+            conflict.facts.len(),
+            conflict.event_type
+        );
+        record_finding(
+            "event-conflict",
+            Severity::Warning,
+            &conflict.individual,
+            message,
+        );
+    }
+    findings
+}
+
+/// The line each record begins on, by xref
+fn record_lines(contents: &str) -> HashMap<&str, u32> {
+    let mut lines = HashMap::new();
+    for (number, line) in (1..).zip(contents.lines()) {
+        let mut parts = line.split_whitespace();
+        if let (Some("0"), Some(xref)) = (parts.next(), parts.next()) {
+            if xref.starts_with('@') {
+                lines.insert(xref, number);
+            }
+        }
+    }
+    lines
+}
+
+/// Prints the findings of each file as a JSON document
+fn print_json(files: &[(&str, Vec<Finding>)]) {
+    let files: Vec<String> = files
+        .iter()
+        .map(|(filename, findings)| {
+            let findings: Vec<String> = findings.iter().map(json_finding).collect();
+            format!(
+                "{{\"file\":\"{}\",\"findings\":[{}]}}",
+                json_escape(filename),
+                findings.join(",")
+            )
+        })
+        .collect();
+    println!("{{\"files\":[{}]}}", files.join(","));
+}
+
+fn json_finding(finding: &Finding) -> String {
+    let line = finding
+        .line
+        .map_or_else(|| "null".to_string(), |line| line.to_string());
+    let record = finding.record.as_deref().map_or_else(
+        || "null".to_string(),
+        |xref| format!("\"{}\"", json_escape(xref)),
+    );
+    format!(
+        "{{\"rule\":\"{}\",\"severity\":\"{}\",\"line\":{},\"record\":{},\"message\":\"{}\"}}",
+        finding.rule,
+        finding.severity,
+        line,
+        record,
+        json_escape(&finding.message)
+    )
+}
+
+/// Prints the findings as a SARIF 2.1.0 log with a single run
+fn print_sarif(files: &[(&str, Vec<Finding>)]) {
+    let rules: Vec<String> = RULES
+        .iter()
+        .map(|(id, description)| {
+            format!("{{\"id\":\"{id}\",\"shortDescription\":{{\"text\":\"{description}\"}}}}")
+        })
+        .collect();
+    let mut results = Vec::new();
+    for (filename, findings) in files {
+        for finding in findings {
+            let region = finding
+                .line
+                .map(|line| format!(",\"region\":{{\"startLine\":{line}}}"))
+                .unwrap_or_default();
+            let logical = finding
+                .record
+                .as_deref()
+                .map(|xref| {
+                    format!(
+                        ",\"logicalLocations\":[{{\"name\":\"{}\",\"kind\":\"object\"}}]",
+                        json_escape(xref)
+                    )
+                })
+                .unwrap_or_default();
+            results.push(format!(
+                "{{\"ruleId\":\"{}\",\"level\":\"{}\",\"message\":{{\"text\":\"{}\"}},\
+                 \"locations\":[{{\"physicalLocation\":{{\"artifactLocation\":{{\"uri\":\"{}\"}}{}}}{}}}]}}",
+                finding.rule,
+                finding.severity,
+                json_escape(&finding.message),
+                json_escape(filename),
+                region,
+                logical
+            ));
+        }
+    }
+    println!(
+        "{{\"version\":\"2.1.0\",\
+         \"$schema\":\"https://json.schemastore.org/sarif-2.1.0.json\",\
+         \"runs\":[{{\"tool\":{{\"driver\":{{\"name\":\"gedcom-verify\",\"version\":\"{}\",\
+         \"informationUri\":\"{}\",\"rules\":[{}]}}}},\"results\":[{}]}}]}}",
+        env!("CARGO_PKG_VERSION"),
+        env!("CARGO_PKG_REPOSITORY"),
+        rules.join(","),
+        results.join(",")
+    );
+}
+
+fn json_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn usage(msg: &str) -> ! {
+    if !msg.is_empty() {
+        println!("{}", msg);
+    }
+    println!(
+        "Usage: gedcom-verify [--format json|sarif] [--deny-warnings] ./path/to/gedcom.ged..."
+    );
+    std::process::exit(if msg.is_empty() { 0 } else { EXIT_USAGE });
+}
@@ -0,0 +1,194 @@
+//! Reloads a GEDCOM file when it changes on disk, for applications displaying a tree
+//! that is edited elsewhere.
+//!
+//! The file is polled for changes to its modification time & size. On change its
+//! records are parsed again one at a time, see `Parser::records`, and compared with the
+//! previous contents: record by record, so views only need to refresh the records that
+//! were added, removed or modified, and field by field for individuals & families, see
+//! `diff::diff`.
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::diff::{diff, GedcomDiff};
+use crate::encoding;
+use crate::parser::{ParseOptions, Parser};
+use crate::tree::GedcomData;
+
+/// A change to a record of the watched file. Records are identified by their xref,
+/// or by their tag for records without one, ie. `HEAD`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RecordChange {
+    /// The record was added to the file
+    Added(String),
+    /// The record was removed from the file
+    Removed(String),
+    /// The lines of the record changed
+    Modified(String),
+}
+
+/// The changes found on reloading the watched file
+#[derive(Clone, Debug)]
+pub struct Reload {
+    /// The records added, removed or modified, in the order of their xref or tag
+    pub records: Vec<RecordChange>,
+    /// The changes to the individuals & families, with the lines & events that changed
+    pub diff: GedcomDiff,
+}
+
+/// Watches a GEDCOM file, keeping its parsed data up to date
+pub struct Watcher {
+    path: PathBuf,
+    options: ParseOptions,
+    /// Modification time & size of the file when it was last read
+    version: (Option<SystemTime>, u64),
+    /// Lines of each record when the file was last read
+    records: HashMap<String, String>,
+    data: GedcomData,
+}
+
+impl Watcher {
+    /// Reads & parses the file at `path` and starts watching it. Records are parsed one
+    /// at a time, so `ParseOptions::deduplicate_notes` has no effect.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the file can't be read or is malformed.
+    pub fn new<P: AsRef<Path>>(path: P, options: ParseOptions) -> io::Result<Watcher> {
+        let mut watcher = Watcher {
+            path: path.as_ref().to_path_buf(),
+            options,
+            version: (None, 0),
+            records: HashMap::new(),
+            data: GedcomData::default(),
+        };
+        watcher.reload()?;
+        Ok(watcher)
+    }
+
+    /// The data of the file as it was last read
+    #[must_use]
+    pub fn data(&self) -> &GedcomData {
+        &self.data
+    }
+
+    /// Checks whether the file changed since it was last read, reloading it if so.
+    /// Returns the changes, or `None` when the file is unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the file can't be read or is malformed, ie. while it is
+    /// still being written. The previously read data is kept.
+    pub fn poll(&mut self) -> io::Result<Option<Reload>> {
+        if file_version(&self.path)? == self.version {
+            return Ok(None);
+        }
+        self.reload().map(Some)
+    }
+
+    /// Blocks until the file changes, checking it every `interval`, and returns the
+    /// changes once it is reloaded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the file can't be read or is malformed.
+    pub fn wait_for_change(&mut self, interval: Duration) -> io::Result<Reload> {
+        loop {
+            if let Some(changes) = self.poll()? {
+                return Ok(changes);
+            }
+            thread::sleep(interval);
+        }
+    }
+
+    fn reload(&mut self) -> io::Result<Reload> {
+        let invalid =
+            |error: &dyn ToString| io::Error::new(io::ErrorKind::InvalidData, error.to_string());
+        let version = file_version(&self.path)?;
+        let content = encoding::decode_strict(&fs::read(&self.path)?).map_err(|e| invalid(&e))?;
+
+        // a file caught in the middle of being written may be malformed
+        let mut parser = Parser::with_options(content.chars(), self.options.clone());
+        let mut data = GedcomData::default();
+        for record in parser.records() {
+            data.add_record(record.map_err(|e| invalid(&e))?);
+        }
+
+        let records = split_records(&content);
+        let reload = Reload {
+            records: diff_records(&self.records, &records),
+            diff: diff(&self.data, &data),
+        };
+        self.version = version;
+        self.records = records;
+        self.data = data;
+        Ok(reload)
+    }
+}
+
+fn file_version(path: &Path) -> io::Result<(Option<SystemTime>, u64)> {
+    let metadata = fs::metadata(path)?;
+    Ok((metadata.modified().ok(), metadata.len()))
+}
+
+/// Splits the content into the lines of each record, keyed by the record's xref or tag.
+fn split_records(content: &str) -> HashMap<String, String> {
+    let mut records = HashMap::new();
+    let mut key = String::new();
+    let mut lines = String::new();
+
+    for line in content.lines() {
+        let line = line.trim_start_matches('\u{feff}');
+        if line.trim_start().starts_with("0 ") {
+            if !key.is_empty() {
+                records.insert(key, lines);
+            }
+            // the xref if there is one, the tag otherwise
+            key = line
+                .split_whitespace()
+                .nth(1)
+                .unwrap_or_default()
+                .to_string();
+            lines = String::new();
+        }
+        lines.push_str(line);
+        lines.push('\n');
+    }
+    if !key.is_empty() {
+        records.insert(key, lines);
+    }
+    records
+}
+
+fn diff_records(
+    previous: &HashMap<String, String>,
+    current: &HashMap<String, String>,
+) -> Vec<RecordChange> {
+    let mut changes: Vec<RecordChange> = current
+        .iter()
+        .filter_map(|(key, lines)| match previous.get(key) {
+            None => Some(RecordChange::Added(key.clone())),
+            Some(previous_lines) if previous_lines != lines => {
+                Some(RecordChange::Modified(key.clone()))
+            }
+            Some(_) => None,
+        })
+        .chain(
+            previous
+                .keys()
+                .filter(|key| !current.contains_key(*key))
+                .map(|key| RecordChange::Removed(key.clone())),
+        )
+        .collect();
+    changes.sort_by(|a, b| change_key(a).cmp(change_key(b)));
+    changes
+}
+
+fn change_key(change: &RecordChange) -> &str {
+    match change {
+        RecordChange::Added(key) | RecordChange::Removed(key) | RecordChange::Modified(key) => key,
+    }
+}
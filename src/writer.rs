@@ -0,0 +1,1333 @@
+//! Writes the gedcom data structure out as a GEDCOM file.
+//!
+//! The header is updated to describe the file actually written: `HEAD.DATE` & `TIME`
+//! are set to the time of writing, `HEAD.FILE` to the name of the written file and
+//! `GEDC.VERS` to the version the writer produces. `WriteOptions::preserve_header`
+//! keeps the original values instead. `WriteOptions::version` picks the version written;
+//! values the version can't hold, like a nonbinary `SEX` in 5.5.1, are degraded and
+//! reported by `GedcomWriter::write_with_diagnostics`. Output always ends with a `TRLR` record, so that
+//! partial or extracted trees are complete files.
+//!
+//! Values are encoded so that any data can be written: `@` is doubled outside of
+//! pointers, line breaks of text are continued with `CONT` and text too long for a
+//! single line is split across `CONC` lines.
+//!
+//! `WriteOptions::tags` slims exports down by leaving out structures by their tag, ie.
+//! all notes, LDS ordinances or custom `_` tags, along with their subordinate lines.
+use std::borrow::Cow;
+use std::io::{self, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::diagnostics::Diagnostic;
+use crate::entry::check_xref;
+use crate::tree::GedcomData;
+use crate::types::{
+    event::HasEvents, Address, Association, CustomData, Event, EventType, Family, FamilyLink,
+    FamilyLinkType, GedcomVersion, Gender, Individual, LdsOrdinance, Location, Map,
+    MultimediaFileRefn, MultimediaLink, MultimediaRecord, Name, Note, NoteRecord, Pedigree, Place,
+    RepoCitation, RepoReference, Repository, Source, SourceCitation, Submitter, UserDefinedDataset,
+    UserReferenceNumber,
+};
+
+/// The GEDCOM version of the files written, unless `WriteOptions::version` says otherwise
+pub const GEDCOM_VERSION: GedcomVersion = GedcomVersion::V5_5_1;
+
+/// Longest line allowed by GEDCOM 5.5.1, not counting the line terminator
+pub const MAX_LINE_LENGTH: usize = 255;
+
+/// Longest value that fits on a line regardless of its level & tag
+pub const MAX_VALUE_LENGTH: usize = 248;
+
+/// Why a value can't be written as the value of a single line
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InvalidValue {
+    /// The value contains a line break, which only text can continue with `CONT`
+    LineBreak,
+    /// The encoded value is longer than `MAX_VALUE_LENGTH`
+    TooLong(usize),
+}
+
+impl std::fmt::Display for InvalidValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvalidValue::LineBreak => write!(f, "Value contains a line break"),
+            InvalidValue::TooLong(length) => write!(
+                f,
+                "Value is {length} characters long, at most {MAX_VALUE_LENGTH} fit on a line"
+            ),
+        }
+    }
+}
+
+/// Checks that a value fits on a single line, for values that can't be continued with
+/// `CONT` & `CONC` like names & dates. The writer replaces line breaks of such values
+/// with spaces; checking them as they are set lets data entry reject them instead.
+///
+/// # Errors
+///
+/// Returns the reason the value can't be written on a single line.
+pub fn check_line_value(value: &str) -> Result<(), InvalidValue> {
+    if value.contains(['\n', '\r']) {
+        return Err(InvalidValue::LineBreak);
+    }
+    let length = encode(value).chars().count();
+    if length > MAX_VALUE_LENGTH {
+        return Err(InvalidValue::TooLong(length));
+    }
+    Ok(())
+}
+
+/// Options changing how a file is written
+#[derive(Clone, Debug, Default)]
+pub struct WriteOptions {
+    /// Keeps `HEAD.DATE`, `HEAD.FILE` & `GEDC.VERS` as they were parsed, instead of
+    /// updating them to describe the written file
+    pub preserve_header: bool,
+    /// Name of the written file, used for `HEAD.FILE`
+    pub filename: Option<String>,
+    /// Time at which the file is written, used for `HEAD.DATE`. Defaults to the current time.
+    pub timestamp: Option<SystemTime>,
+    /// Which structures of the records are written, the header is always written whole
+    pub tags: TagFilter,
+    /// Base URI of the custom tags of a GEDCOM 7.0 file, ie. `https://example.com/tags/`.
+    /// Custom tags not declared in the parsed header are declared in `HEAD.SCHMA` with
+    /// the tag appended to this URI; without it, only the declared tags are written.
+    pub schema_namespace: Option<String>,
+    /// Where the custom tags of the records are written, or whether they are left out
+    pub custom_tags: CustomTagPolicy,
+    /// Version of the GEDCOM written. Defaults to `GEDCOM_VERSION`, or to the parsed
+    /// version with `preserve_header`.
+    pub version: Option<GedcomVersion>,
+}
+
+/// Where custom tags, those starting with `_`, are written
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CustomTagPlacement {
+    /// In the order they were parsed, after the standard structures they are found among
+    #[default]
+    AsParsed,
+    /// Grouped at the end of the record, after `CHAN`, sorted by tag and with the tag in
+    /// uppercase. Custom tags within the structures of a record stay at the end of them.
+    Last,
+    /// Left out, along with their subordinate lines
+    Drop,
+}
+
+/// Places the custom tags of the records, for programs picky about where they find
+/// unknown tags. Tags ending in `*` match by prefix, like in `TagFilter`.
+#[derive(Clone, Debug, Default)]
+pub struct CustomTagPolicy {
+    /// Placement of the custom tags without an override
+    pub placement: CustomTagPlacement,
+    /// Placements of tags overriding `placement`, the first matching one wins, ie.
+    /// `("_UID", CustomTagPlacement::Drop)`
+    pub overrides: Vec<(String, CustomTagPlacement)>,
+}
+
+impl CustomTagPolicy {
+    /// Where lines tagged `tag` are written. Standard tags are always written as parsed,
+    /// and the overrides match tags ignoring case, as `Last` writes them in uppercase.
+    #[must_use]
+    pub fn placement_of(&self, tag: &str) -> CustomTagPlacement {
+        if !tag.starts_with('_') {
+            return CustomTagPlacement::AsParsed;
+        }
+        let tag = tag.to_ascii_uppercase();
+        self.overrides
+            .iter()
+            .find(|(pattern, _)| matches_tag(&pattern.to_ascii_uppercase(), &tag))
+            .map_or(self.placement, |(_, placement)| *placement)
+    }
+}
+
+/// Tags of the LDS ordinances, for leaving them out of exports
+pub const LDS_ORDINANCE_TAGS: [&str; 5] = ["BAPL", "CONL", "ENDL", "SLGC", "SLGS"];
+
+/// Selects the structures written by their tag. A structure left out is left out along
+/// with all of its subordinate lines, and pointers to records left out are kept.
+///
+/// Tags ending in `*` match by prefix, so `_*` matches all custom tags. `CONT` & `CONC`
+/// lines always go with the line they continue.
+#[derive(Clone, Debug, Default)]
+pub enum TagFilter {
+    /// Writes all structures
+    #[default]
+    All,
+    /// Writes only the structures with these tags, at every level
+    Only(Vec<String>),
+    /// Writes all structures except those with these tags
+    Except(Vec<String>),
+}
+
+impl TagFilter {
+    /// Whether structures tagged `tag` are written
+    #[must_use]
+    pub fn writes(&self, tag: &str) -> bool {
+        let listed = |tags: &[String]| tags.iter().any(|pattern| matches_tag(pattern, tag));
+        match self {
+            TagFilter::All => true,
+            TagFilter::Only(tags) => listed(tags),
+            TagFilter::Except(tags) => !listed(tags),
+        }
+    }
+}
+
+/// Whether the tag matches the pattern, a tag or a prefix followed by `*`
+fn matches_tag(pattern: &str, tag: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => tag.starts_with(prefix),
+        None => pattern == tag,
+    }
+}
+
+/// Writes `GedcomData` as GEDCOM content
+#[derive(Debug, Default)]
+pub struct GedcomWriter {
+    options: WriteOptions,
+}
+
+impl GedcomWriter {
+    /// Creates a writer using `options`
+    #[must_use]
+    pub fn new(options: WriteOptions) -> GedcomWriter {
+        GedcomWriter { options }
+    }
+
+    /// Writes `data` to `out` as a complete GEDCOM file, from `HEAD` to `TRLR`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when writing to `out` fails, or an `InvalidInput` error holding
+    /// an `entry::FieldError` when an xref of the tree, or a pointer to a record, is not
+    /// a valid xref like `@I1@`. Lines written before the invalid xref are left in `out`.
+    pub fn write<W: Write>(&self, data: &GedcomData, out: &mut W) -> io::Result<()> {
+        self.write_with_diagnostics(data, out).map(|_| ())
+    }
+
+    /// Writes `data` to `out` like `write`, returning a warning for each value degraded
+    /// because the version written can't hold it, ie. a nonbinary `SEX` written as `U`
+    /// to GEDCOM 5.5.1. Warnings give the line of the written file.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `write`.
+    pub fn write_with_diagnostics<W: Write>(
+        &self,
+        data: &GedcomData,
+        out: &mut W,
+    ) -> io::Result<Vec<Diagnostic>> {
+        let version = match &self.options.version {
+            Some(version) => version.clone(),
+            None if self.options.preserve_header => data.header.version().unwrap_or(GEDCOM_VERSION),
+            None => GEDCOM_VERSION,
+        };
+        let mut lines = Lines {
+            out,
+            version: &version,
+            tags: &TagFilter::All,
+            skip_below: None,
+            custom_tags: &self.options.custom_tags,
+            deferred: Vec::new(),
+            written: 0,
+            diagnostics: Vec::new(),
+        };
+
+        self.write_header(&mut lines, data)?;
+        lines.tags = &self.options.tags;
+        for submitter in &data.submitters {
+            lines.submitter(submitter)?;
+        }
+        for individual in &data.individuals {
+            lines.individual(individual)?;
+        }
+        for family in &data.families {
+            lines.family(family)?;
+        }
+        for source in &data.sources {
+            lines.source(source)?;
+        }
+        for repository in &data.repositories {
+            lines.repository(repository, 0)?;
+        }
+        for multimedia in &data.multimedia {
+            lines.multimedia(multimedia)?;
+        }
+        for location in &data.locations {
+            lines.location(location)?;
+        }
+        for note in &data.notes {
+            lines.note_record(note)?;
+        }
+        for record in &data.extension_records {
+            lines.extension_record(record)?;
+        }
+        for note in &data.shared_notes {
+            lines.shared_note(note)?;
+        }
+        lines.tags = &TagFilter::All;
+        lines.line(0, "TRLR", None)?;
+        Ok(lines.diagnostics)
+    }
+
+    /// Writes `data` as a complete GEDCOM file into a `String`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the tree holds an invalid xref, see `write`, which returns the error
+    /// instead. Writing into memory does not fail otherwise.
+    #[must_use]
+    pub fn write_string(&self, data: &GedcomData) -> String {
+        let mut out = Vec::new();
+        if let Err(error) = self.write(data, &mut out) {
+            panic!("{}", error);
+        }
+        String::from_utf8(out).expect("written content is valid UTF-8")
+    }
+
+    fn write_header<W: Write>(
+        &self,
+        lines: &mut Lines<'_, W>,
+        tree: &GedcomData,
+    ) -> io::Result<()> {
+        let header = &tree.header;
+        lines.line(0, "HEAD", None)?;
+        if let Some(source) = &header.source {
+            lines.line(1, "SOUR", source.value.as_deref())?;
+            lines.optional(2, "VERS", source.version.as_deref())?;
+            lines.optional(2, "NAME", source.name.as_deref())?;
+            lines.optional(2, "CORP", source.corporation.as_deref())?;
+        } else {
+            lines.line(1, "SOUR", Some("rust-gedcom"))?;
+            lines.line(2, "VERS", Some(env!("CARGO_PKG_VERSION")))?;
+        }
+        for destination in &header.destinations {
+            lines.line(1, "DEST", Some(destination))?;
+        }
+
+        if self.options.preserve_header {
+            if let Some(datetime) = &header.date {
+                // the parser joins TIME onto the DATE value
+                let (date, time) = match datetime.rsplit_once(' ') {
+                    Some((date, time)) if time.contains(':') => (date, Some(time)),
+                    _ => (datetime.as_str(), None),
+                };
+                lines.line(1, "DATE", Some(date))?;
+                lines.optional(2, "TIME", time)?;
+            }
+        } else {
+            let (date, time) = timestamp(self.options.timestamp.unwrap_or_else(SystemTime::now));
+            lines.line(1, "DATE", Some(&date))?;
+            lines.line(2, "TIME", Some(&time))?;
+        }
+
+        for (tag, xref) in [
+            ("SUBM", &header.submitter_tag),
+            ("SUBN", &header.submission_tag),
+        ] {
+            if let Some(xref) = xref {
+                lines.pointer(1, tag, xref)?;
+            }
+        }
+        let filename = if self.options.preserve_header {
+            header.filename.as_deref()
+        } else {
+            self.options.filename.as_deref()
+        };
+        lines.optional(1, "FILE", filename)?;
+        lines.optional(1, "COPR", header.copyright.as_deref())?;
+        lines.line(1, "GEDC", None)?;
+        lines.line(2, "VERS", Some(&lines.version.to_string()))?;
+        lines.line(2, "FORM", Some("LINEAGE-LINKED"))?;
+        if *lines.version >= GedcomVersion::V7_0 {
+            self.write_schema(lines, tree)?;
+        }
+        // content is always written as UTF-8
+        lines.line(1, "CHAR", Some("UTF-8"))?;
+        for language in &header.languages {
+            lines.line(1, "LANG", Some(language))?;
+        }
+        if let Some(form) = &header.place_form {
+            lines.line(1, "PLAC", None)?;
+            lines.line(2, "FORM", Some(form))?;
+        }
+        for note in &header.notes {
+            lines.text(1, "NOTE", &note.text())?;
+            lines.optional(2, "LANG", note.language())?;
+        }
+        lines.optional(1, "CORP", header.corporation.as_deref())?;
+        for data in &header.custom_data {
+            lines.custom_data(1, data)?;
+        }
+        Ok(())
+    }
+
+    /// Declares the extension tags of a 7.0 file: those declared in the parsed header,
+    /// then the other custom tags of the tree under `WriteOptions::schema_namespace`
+    fn write_schema<W: Write>(
+        &self,
+        lines: &mut Lines<'_, W>,
+        data: &GedcomData,
+    ) -> io::Result<()> {
+        let mut schema: Vec<(&str, Cow<'_, str>)> = data
+            .header
+            .schema
+            .iter()
+            .map(|declared| (declared.tag.as_str(), Cow::Borrowed(declared.uri.as_str())))
+            .collect();
+        if let Some(namespace) = &self.options.schema_namespace {
+            for tag in data.custom_tags() {
+                if data.header.schema_uri(tag).is_none() {
+                    schema.push((tag, Cow::Owned(format!("{namespace}{tag}"))));
+                }
+            }
+        }
+        if schema.is_empty() {
+            return Ok(());
+        }
+        lines.line(1, "SCHMA", None)?;
+        for (tag, uri) in schema {
+            lines.line(2, "TAG", Some(&format!("{tag} {uri}")))?;
+        }
+        Ok(())
+    }
+}
+
+/// Emits the lines of the records
+struct Lines<'a, W: Write> {
+    out: &'a mut W,
+    /// The GEDCOM version being written
+    version: &'a GedcomVersion,
+    /// Which structures are written
+    tags: &'a TagFilter,
+    /// Level of the structure left out, whose subordinate lines are left out too
+    skip_below: Option<u8>,
+    /// Where custom tags are written
+    custom_tags: &'a CustomTagPolicy,
+    /// Custom tags of the current record put off until its end
+    deferred: Vec<CustomData>,
+    /// Number of lines written
+    written: u32,
+    /// Values degraded for the version written
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<W: Write> Lines<'_, W> {
+    /// Whether the line is left out by the tag filter
+    fn filtered(&mut self, level: u8, tag: &str) -> bool {
+        match self.skip_below {
+            Some(skipped) if level > skipped => return true,
+            _ => self.skip_below = None,
+        }
+        if tag == "CONT" || tag == "CONC" || self.tags.writes(tag) {
+            false
+        } else {
+            self.skip_below = Some(level);
+            true
+        }
+    }
+
+    fn line(&mut self, level: u8, tag: &str, value: Option<&str>) -> io::Result<()> {
+        self.xref_line(level, None, tag, value)
+    }
+
+    fn record(&mut self, xref: Option<&str>, tag: &str) -> io::Result<()> {
+        self.xref_line(0, xref, tag, None)
+    }
+
+    /// Writes a line, with the cross-reference identifier of the record it starts if any
+    fn xref_line(
+        &mut self,
+        level: u8,
+        xref: Option<&str>,
+        tag: &str,
+        value: Option<&str>,
+    ) -> io::Result<()> {
+        // the record before this one ends
+        if level == 0 && !self.deferred.is_empty() {
+            self.write_deferred()?;
+        }
+        if self.filtered(level, tag) {
+            return Ok(());
+        }
+        self.written += 1;
+        write!(self.out, "{level} ")?;
+        if let Some(xref) = xref {
+            check_pointer(xref)?;
+            write!(self.out, "{xref} ")?;
+        }
+        match value {
+            Some(value) if !value.is_empty() => {
+                // only text can be continued, other values are kept on their line
+                let value = if value.contains(['\n', '\r']) {
+                    Cow::Owned(value.replace("\r\n", " ").replace(['\n', '\r'], " "))
+                } else {
+                    Cow::Borrowed(value)
+                };
+                writeln!(self.out, "{tag} {}", encode(&value))
+            }
+            _ => writeln!(self.out, "{tag}"),
+        }
+    }
+
+    /// Writes a line pointing to the record `xref`
+    fn pointer(&mut self, level: u8, tag: &str, xref: &str) -> io::Result<()> {
+        check_pointer(xref)?;
+        self.line(level, tag, Some(xref))
+    }
+
+    /// Writes a line only when there is a value
+    fn optional(&mut self, level: u8, tag: &str, value: Option<&str>) -> io::Result<()> {
+        match value {
+            Some(value) => self.line(level, tag, Some(value)),
+            None => Ok(()),
+        }
+    }
+
+    /// Writes multi-line text, continuing each line break with a `CONT` line and
+    /// splitting lines that are too long across `CONC` lines.
+    fn text(&mut self, level: u8, tag: &str, text: &str) -> io::Result<()> {
+        self.xref_text(level, None, tag, text)
+    }
+
+    /// Writes multi-line text on a line starting a record with `xref`, see `text`
+    fn xref_text(
+        &mut self,
+        level: u8,
+        xref: Option<&str>,
+        tag: &str,
+        text: &str,
+    ) -> io::Result<()> {
+        // room for the level, xref, tag & delimiters of the longest of the lines
+        let xref_length = xref.map_or(0, |xref| xref.len() + 1);
+        let prefix_length = (level + 1).to_string().len() + xref_length + tag.len().max(4) + 2;
+        let limit = MAX_LINE_LENGTH - prefix_length;
+
+        for (i, text_line) in text.split('\n').enumerate() {
+            let text_line = text_line.strip_suffix('\r').unwrap_or(text_line);
+            let mut chunks = split_text(text_line, limit).into_iter();
+            if i == 0 {
+                self.xref_line(level, xref, tag, chunks.next())?;
+            } else {
+                self.line(level + 1, "CONT", chunks.next())?;
+            }
+            for chunk in chunks {
+                self.line(level + 1, "CONC", Some(chunk))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn submitter(&mut self, submitter: &Submitter) -> io::Result<()> {
+        self.record(submitter.xref.as_deref(), "SUBM")?;
+        self.optional(1, "NAME", submitter.name.as_deref())?;
+        if let Some(address) = &submitter.address {
+            self.address(1, address)?;
+        }
+        self.optional(1, "PHON", submitter.phone.as_deref())?;
+        for language in &submitter.languages {
+            self.line(1, "LANG", Some(language))?;
+        }
+        for note in &submitter.notes {
+            self.note(1, note)?;
+        }
+        for data in &submitter.custom_data {
+            self.custom_data(1, data)?;
+        }
+        self.change_dates(None, submitter.last_updated.as_deref())
+    }
+
+    fn individual(&mut self, individual: &Individual) -> io::Result<()> {
+        self.record(individual.xref.as_deref(), "INDI")?;
+        self.optional(1, "RESN", individual.restriction_notice.as_deref())?;
+        for name in &individual.names {
+            self.name(name)?;
+        }
+        // an unknown gender is also how a missing SEX is parsed
+        if !matches!(individual.sex, Gender::Unknown) {
+            let sex = individual.sex.to_sex_value(self.version);
+            self.line(1, "SEX", Some(sex.value))?;
+            if let Some(warning) = sex.warning {
+                self.diagnostics
+                    .push(Diagnostic::warning(self.written, warning));
+            }
+            if let Some(preserved) = &sex.preserved {
+                self.custom_data(1, preserved)?;
+            }
+        }
+        if let Some(num_children) = individual.num_children {
+            self.line(1, "NCHI", Some(&num_children.to_string()))?;
+        }
+        for event in individual.events() {
+            self.event(1, &event)?;
+        }
+        for link in &individual.families {
+            self.family_link(link)?;
+        }
+        for link in &individual.multimedia {
+            self.multimedia_link(1, link)?;
+        }
+        for association in &individual.associations {
+            self.association(1, association)?;
+        }
+        for ordinance in &individual.lds_ordinances {
+            self.lds_ordinance(ordinance)?;
+        }
+        for note in &individual.notes {
+            self.note(1, note)?;
+        }
+        self.optional(1, "RFN", individual.permanent_record_file_number.as_deref())?;
+        self.optional(1, "AFN", individual.ancestral_file_number.as_deref())?;
+        self.user_reference_numbers(1, &individual.user_reference_numbers)?;
+        self.optional(1, "RIN", individual.automated_record_id.as_deref())?;
+        for data in &individual.custom_data {
+            self.custom_data(1, data)?;
+        }
+        self.change_dates(
+            individual.created.as_deref(),
+            individual.last_updated.as_deref(),
+        )?;
+        Ok(())
+    }
+
+    fn name(&mut self, name: &Name) -> io::Result<()> {
+        self.line(1, "NAME", name.value.as_deref())?;
+        self.name_pieces(
+            2,
+            [
+                name.prefix.as_deref(),
+                name.given.as_deref(),
+                name.nickname.as_deref(),
+                name.surname_prefix.as_deref(),
+                name.surname.as_deref(),
+                name.suffix.as_deref(),
+            ],
+            &name.notes,
+            &name.citations,
+        )?;
+        self.optional(2, "TYPE", name.name_type.as_deref())?;
+        self.optional(2, "LANG", name.language.as_deref())?;
+        for (tag, variations) in [("FONE", &name.phonetic), ("ROMN", &name.romanized)] {
+            for variation in variations {
+                self.line(2, tag, Some(&variation.value))?;
+                self.optional(3, "TYPE", variation.variation_type.as_deref())?;
+                self.name_pieces(
+                    3,
+                    [
+                        variation.prefix.as_deref(),
+                        variation.given.as_deref(),
+                        variation.nickname.as_deref(),
+                        variation.surname_prefix.as_deref(),
+                        variation.surname.as_deref(),
+                        variation.suffix.as_deref(),
+                    ],
+                    &variation.notes,
+                    &variation.citations,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the `NPFX`, `GIVN`, `NICK`, `SPFX`, `SURN` & `NSFX` pieces of a name, in
+    /// that order, then its notes & sources
+    fn name_pieces(
+        &mut self,
+        level: u8,
+        pieces: [Option<&str>; 6],
+        notes: &[Note],
+        citations: &[SourceCitation],
+    ) -> io::Result<()> {
+        let tags = ["NPFX", "GIVN", "NICK", "SPFX", "SURN", "NSFX"];
+        for (tag, piece) in tags.iter().zip(pieces) {
+            self.optional(level, tag, piece)?;
+        }
+        for note in notes {
+            self.note(level, note)?;
+        }
+        for citation in citations {
+            self.citation(level, citation)?;
+        }
+        Ok(())
+    }
+
+    fn multimedia_link(&mut self, level: u8, link: &MultimediaLink) -> io::Result<()> {
+        match &link.embedded {
+            Some(multimedia) => {
+                self.line(level, "OBJE", None)?;
+                self.embedded_multimedia(level + 1, multimedia)?;
+            }
+            None => self.pointer(level, "OBJE", &link.xref)?,
+        }
+        let mut flagged = false;
+        for data in &link.custom_data {
+            if MultimediaLink::is_primary_tag(&data.tag) {
+                // flags contradicting `primary` were changed by the application
+                if MultimediaLink::is_primary_flag(data) != link.primary {
+                    continue;
+                }
+                flagged = true;
+            }
+            self.custom_data(level + 1, data)?;
+        }
+        if link.primary && !flagged {
+            self.line(level + 1, "_PRIM", Some("Y"))?;
+        }
+        Ok(())
+    }
+
+    /// Writes the lines of an object embedded in a link, its title below the link as
+    /// GEDCOM 5.5.1 places it
+    fn embedded_multimedia(&mut self, level: u8, multimedia: &MultimediaRecord) -> io::Result<()> {
+        for file in &multimedia.files {
+            let form = file.form.as_deref().or(multimedia.form.as_deref());
+            self.multimedia_file(level, file, form)?;
+        }
+        let title = multimedia.title.as_deref().or_else(|| {
+            multimedia
+                .files
+                .iter()
+                .find_map(|file| file.title.as_deref())
+        });
+        self.optional(level, "TITL", title)?;
+        for note in &multimedia.notes {
+            self.note(level, note)?;
+        }
+        for data in &multimedia.custom_data {
+            self.custom_data(level, data)?;
+        }
+        Ok(())
+    }
+
+    /// Writes a `FILE` line with its format & medium
+    fn multimedia_file(
+        &mut self,
+        level: u8,
+        file: &MultimediaFileRefn,
+        form: Option<&str>,
+    ) -> io::Result<()> {
+        self.line(level, "FILE", Some(&file.value))?;
+        if let Some(form) = form {
+            self.line(level + 1, "FORM", Some(form))?;
+            let medium_tag = if *self.version < GedcomVersion::V5_5_1 {
+                "TYPE"
+            } else {
+                "MEDI"
+            };
+            self.optional(level + 2, medium_tag, file.source_media_type.as_deref())?;
+        }
+        Ok(())
+    }
+
+    fn family_link(&mut self, link: &FamilyLink) -> io::Result<()> {
+        let tag = match link.link_type {
+            FamilyLinkType::Child => "FAMC",
+            FamilyLinkType::Spouse => "FAMS",
+        };
+        self.pointer(1, tag, &link.xref)?;
+        let pedigree = link.pedigree.as_ref().map(|pedigree| match pedigree {
+            Pedigree::Adopted => "adopted",
+            Pedigree::Birth => "birth",
+            Pedigree::Foster => "foster",
+            Pedigree::Sealing => "sealing",
+        });
+        self.optional(2, "PEDI", pedigree)?;
+        for note in &link.notes {
+            self.note(2, note)?;
+        }
+        for citation in &link.citations {
+            self.citation(2, citation)?;
+        }
+        Ok(())
+    }
+
+    fn family(&mut self, family: &Family) -> io::Result<()> {
+        self.record(family.xref.as_deref(), "FAM")?;
+        self.optional(1, "RESN", family.restriction_notice.as_deref())?;
+        for (xref, tag) in family.parents() {
+            self.pointer(1, tag.tag(), xref)?;
+        }
+        for child in &family.children {
+            self.pointer(1, "CHIL", child)?;
+        }
+        if let Some(num_children) = family.num_children {
+            self.line(1, "NCHI", Some(&num_children.to_string()))?;
+        }
+        for submitter in &family.submitters {
+            self.pointer(1, "SUBM", submitter)?;
+        }
+        for event in family.events() {
+            self.event(1, &event)?;
+        }
+        for ordinance in &family.lds_ordinances {
+            self.lds_ordinance(ordinance)?;
+        }
+        for note in &family.notes {
+            self.note(1, note)?;
+        }
+        self.user_reference_numbers(1, &family.user_reference_numbers)?;
+        self.optional(1, "RIN", family.automated_record_id.as_deref())?;
+        for data in &family.custom_data {
+            self.custom_data(1, data)?;
+        }
+        self.change_dates(family.created.as_deref(), family.last_updated.as_deref())
+    }
+
+    fn source(&mut self, source: &Source) -> io::Result<()> {
+        self.record(source.xref.as_deref(), "SOUR")?;
+        let data = &source.data;
+        if !data.events().is_empty() || data.agency.is_some() || !data.notes.is_empty() {
+            self.line(1, "DATA", None)?;
+            for event in data.events() {
+                self.event(2, event)?;
+            }
+            self.optional(2, "AGNC", data.agency.as_deref())?;
+            for note in &data.notes {
+                self.note(2, note)?;
+            }
+        }
+        if let Some(abbreviation) = &source.abbreviation {
+            self.text(1, "ABBR", abbreviation)?;
+        }
+        if let Some(title) = &source.title {
+            self.text(1, "TITL", title)?;
+        }
+        if let Some(text) = &source.text {
+            self.text(1, "TEXT", text)?;
+        }
+        for citation in source.repo_citations() {
+            self.repo_citation(citation)?;
+        }
+        for note in &source.notes {
+            self.note(1, note)?;
+        }
+        for data in &source.custom_data {
+            self.custom_data(1, data)?;
+        }
+        Ok(())
+    }
+
+    fn repo_citation(&mut self, citation: &RepoCitation) -> io::Result<()> {
+        match &citation.repository {
+            RepoReference::Pointer(xref) => self.pointer(1, "REPO", xref)?,
+            RepoReference::Inline(repository) => self.repository(repository, 1)?,
+        }
+        self.optional(2, "CALN", citation.call_number.as_deref())
+    }
+
+    /// Writes a `REPO` record, or an inline repository description below `level` 0.
+    fn repository(&mut self, repository: &Repository, level: u8) -> io::Result<()> {
+        if level == 0 {
+            self.record(repository.xref.as_deref(), "REPO")?;
+        } else {
+            self.line(level, "REPO", None)?;
+        }
+        self.optional(level + 1, "NAME", repository.name.as_deref())?;
+        if let Some(address) = &repository.address {
+            self.address(level + 1, address)?;
+        }
+        for (tag, values) in [
+            ("PHON", &repository.phones),
+            ("EMAIL", &repository.emails),
+            ("FAX", &repository.faxes),
+            ("WWW", &repository.websites),
+        ] {
+            for value in values {
+                self.line(level + 1, tag, Some(value))?;
+            }
+        }
+        for note in &repository.notes {
+            self.note(level + 1, note)?;
+        }
+        self.user_reference_numbers(level + 1, &repository.user_reference_numbers)?;
+        self.optional(level + 1, "RIN", repository.automated_record_id.as_deref())?;
+        for data in &repository.custom_data {
+            self.custom_data(level + 1, data)?;
+        }
+        if level == 0 {
+            self.change_dates(None, repository.last_updated.as_deref())?;
+        }
+        Ok(())
+    }
+
+    fn multimedia(&mut self, multimedia: &MultimediaRecord) -> io::Result<()> {
+        // FORM & TITL belong under FILE, whatever the dialect of the parsed file
+        let mut normalized = multimedia.clone();
+        normalized.normalize_files();
+        let multimedia = &normalized;
+
+        self.record(multimedia.xref.as_deref(), "OBJE")?;
+        for file in &multimedia.files {
+            self.multimedia_file(1, file, file.form.as_deref())?;
+            self.optional(2, "TITL", file.title.as_deref())?;
+        }
+        self.optional(1, "FORM", multimedia.form.as_deref())?;
+        self.optional(1, "TITL", multimedia.title.as_deref())?;
+        for note in &multimedia.notes {
+            self.note(1, note)?;
+        }
+        for data in &multimedia.custom_data {
+            self.custom_data(1, data)?;
+        }
+        self.change_dates(
+            multimedia.created.as_deref(),
+            multimedia.last_updated.as_deref(),
+        )
+    }
+
+    /// Writes the `CREA` & `CHAN` dates of a record. `CREA` only exists since GEDCOM 7.0
+    /// and is left out of older versions.
+    fn change_dates(
+        &mut self,
+        created: Option<&str>,
+        last_updated: Option<&str>,
+    ) -> io::Result<()> {
+        if let Some(created) = created {
+            if *self.version >= GedcomVersion::V7_0 {
+                self.change_date("CREA", created)?;
+            }
+        }
+        match last_updated {
+            Some(last_updated) => self.change_date("CHAN", last_updated),
+            None => Ok(()),
+        }
+    }
+
+    fn change_date(&mut self, tag: &str, datetime: &str) -> io::Result<()> {
+        // the parser joins TIME onto the DATE value
+        let (date, time) = match datetime.rsplit_once(' ') {
+            Some((date, time)) if time.contains(':') => (date, Some(time)),
+            _ => (datetime, None),
+        };
+        self.line(1, tag, None)?;
+        self.line(2, "DATE", Some(date))?;
+        self.optional(3, "TIME", time)
+    }
+
+    fn event(&mut self, level: u8, event: &Event) -> io::Result<()> {
+        match &event.event {
+            EventType::SourceData(events_recorded) => {
+                self.line(level, "EVEN", Some(events_recorded))?;
+            }
+            event_type => {
+                let occurred = event.occurred.then_some("Y");
+                self.line(level, event_tag(event_type), occurred)?;
+            }
+        }
+        self.optional(level + 1, "TYPE", event.classification.as_deref())?;
+        self.optional(level + 1, "DATE", event.date.as_deref())?;
+        if let Some(place) = &event.place {
+            self.place(level + 1, place)?;
+        }
+        if let Some(address) = &event.address {
+            self.address(level + 1, address)?;
+        }
+        for (tag, values) in [
+            ("PHON", &event.phones),
+            ("EMAIL", &event.emails),
+            ("FAX", &event.faxes),
+            ("WWW", &event.websites),
+        ] {
+            for value in values {
+                self.line(level + 1, tag, Some(value))?;
+            }
+        }
+        self.optional(level + 1, "AGNC", event.agency.as_deref())?;
+        self.optional(level + 1, "RELI", event.religion.as_deref())?;
+        self.optional(level + 1, "CAUS", event.cause.as_deref())?;
+        self.optional(level + 1, "RESN", event.restriction_notice.as_deref())?;
+        self.optional(level + 1, "AGE", event.age.as_deref())?;
+        if let Some(family) = &event.family {
+            self.pointer(level + 1, "FAMC", family)?;
+            self.optional(level + 2, "ADOP", event.adopted_by.as_deref())?;
+        }
+        for citation in &event.citations {
+            self.citation(level + 1, citation)?;
+        }
+        for association in &event.associations {
+            self.association(level + 1, association)?;
+        }
+        for note in &event.notes {
+            self.note(level + 1, note)?;
+        }
+        for link in &event.multimedia {
+            self.multimedia_link(level + 1, link)?;
+        }
+        for data in &event.custom_data {
+            self.custom_data(level + 1, data)?;
+        }
+        Ok(())
+    }
+
+    fn lds_ordinance(&mut self, ordinance: &LdsOrdinance) -> io::Result<()> {
+        self.line(1, ordinance.ordinance.tag(), None)?;
+        self.optional(2, "DATE", ordinance.date.as_deref())?;
+        self.optional(2, "TEMP", ordinance.temple.as_deref())?;
+        if let Some(place) = &ordinance.place {
+            self.place(2, place)?;
+        }
+        if let Some(family) = &ordinance.family {
+            self.pointer(2, "FAMC", family)?;
+        }
+        if let Some(status) = &ordinance.status {
+            self.line(2, "STAT", Some(status))?;
+            self.optional(3, "DATE", ordinance.status_date.as_deref())?;
+        }
+        for citation in &ordinance.citations {
+            self.citation(2, citation)?;
+        }
+        for note in &ordinance.notes {
+            self.note(2, note)?;
+        }
+        for data in &ordinance.custom_data {
+            self.custom_data(2, data)?;
+        }
+        Ok(())
+    }
+
+    fn association(&mut self, level: u8, association: &Association) -> io::Result<()> {
+        self.pointer(level, "ASSO", &association.xref)?;
+        self.optional(level + 1, "RELA", association.relation.as_deref())?;
+        self.optional(level + 1, "ROLE", association.role.as_deref())?;
+        for note in &association.notes {
+            self.note(level + 1, note)?;
+        }
+        for citation in &association.citations {
+            self.citation(level + 1, citation)?;
+        }
+        Ok(())
+    }
+
+    /// Writes a note, as a pointer to its shared note record if it has one
+    fn note(&mut self, level: u8, note: &Note) -> io::Result<()> {
+        let text = note.text();
+        match note.shared_xref() {
+            Some(xref) => self.line(level, self.note_tag(), Some(xref)),
+            // a pointer to a note record
+            None if is_pointer(&text) => self.line(level, self.note_tag(), Some(&text)),
+            None => {
+                self.text(level, "NOTE", &text)?;
+                self.optional(level + 1, "LANG", note.language())
+            }
+        }
+    }
+
+    fn note_record(&mut self, record: &NoteRecord) -> io::Result<()> {
+        self.xref_text(
+            0,
+            record.xref.as_deref(),
+            self.note_tag(),
+            &record.text.text(),
+        )?;
+        self.optional(1, "LANG", record.text.language())?;
+        self.user_reference_numbers(1, &record.user_reference_numbers)?;
+        self.optional(1, "RIN", record.automated_record_id.as_deref())?;
+        for citation in &record.citations {
+            self.citation(1, citation)?;
+        }
+        for data in &record.custom_data {
+            self.custom_data(1, data)?;
+        }
+        self.change_dates(None, record.last_updated.as_deref())
+    }
+
+    fn user_reference_numbers(
+        &mut self,
+        level: u8,
+        references: &[UserReferenceNumber],
+    ) -> io::Result<()> {
+        for reference in references {
+            self.line(level, "REFN", Some(&reference.number))?;
+            self.optional(level + 1, "TYPE", reference.reference_type.as_deref())?;
+        }
+        Ok(())
+    }
+
+    /// Writes the record of a note shared by several structures
+    fn shared_note(&mut self, note: &Note) -> io::Result<()> {
+        match note.shared_xref() {
+            Some(xref) => self.xref_text(0, Some(xref), self.note_tag(), &note.text()),
+            None => Ok(()),
+        }
+    }
+
+    /// The tag of shared notes, `SNOTE` since GEDCOM 7.0
+    fn note_tag(&self) -> &'static str {
+        if *self.version >= GedcomVersion::V7_0 {
+            "SNOTE"
+        } else {
+            "NOTE"
+        }
+    }
+
+    fn citation(&mut self, level: u8, citation: &SourceCitation) -> io::Result<()> {
+        self.line(level, "SOUR", Some(&citation.xref))?;
+        self.optional(level + 1, "PAGE", citation.page.as_deref())?;
+        if citation.event.is_some() || citation.role.is_some() {
+            self.line(level + 1, "EVEN", citation.event.as_deref())?;
+            self.optional(level + 2, "ROLE", citation.role.as_deref())?;
+        }
+        if citation.date.is_some() || !citation.texts.is_empty() {
+            self.line(level + 1, "DATA", None)?;
+            self.optional(level + 2, "DATE", citation.date.as_deref())?;
+            for text in &citation.texts {
+                self.text(level + 2, "TEXT", text)?;
+            }
+        }
+        if let Some(quality) = citation.quality {
+            self.line(level + 1, "QUAY", Some(&quality.to_string()))?;
+        }
+        for note in &citation.notes {
+            self.note(level + 1, note)?;
+        }
+        Ok(())
+    }
+
+    fn address(&mut self, level: u8, address: &Address) -> io::Result<()> {
+        self.text(level, "ADDR", address.value.as_deref().unwrap_or_default())?;
+        self.optional(level + 1, "ADR1", address.adr1.as_deref())?;
+        self.optional(level + 1, "ADR2", address.adr2.as_deref())?;
+        self.optional(level + 1, "ADR3", address.adr3.as_deref())?;
+        self.optional(level + 1, "CITY", address.city.as_deref())?;
+        self.optional(level + 1, "STAE", address.state.as_deref())?;
+        self.optional(level + 1, "POST", address.post.as_deref())?;
+        self.optional(level + 1, "CTRY", address.country.as_deref())
+    }
+
+    fn location(&mut self, location: &Location) -> io::Result<()> {
+        self.record(location.xref.as_deref(), "_LOC")?;
+        for name in &location.names {
+            self.line(1, "NAME", Some(&name.value))?;
+            self.optional(2, "DATE", name.date.as_deref())?;
+        }
+        self.optional(1, "TYPE", location.location_type.as_deref())?;
+        if let Some(map) = &location.map {
+            self.map(1, map)?;
+        }
+        for link in &location.within {
+            self.pointer(1, "_LOC", &link.xref)?;
+            self.optional(2, "DATE", link.date.as_deref())?;
+        }
+        for note in &location.notes {
+            self.note(1, note)?;
+        }
+        for data in &location.custom_data {
+            self.custom_data(1, data)?;
+        }
+        self.change_dates(None, location.last_updated.as_deref())
+    }
+
+    fn place(&mut self, level: u8, place: &Place) -> io::Result<()> {
+        self.line(level, "PLAC", Some(&place.value))?;
+        self.optional(level + 1, "FORM", place.form.as_deref())?;
+        for (tag, variations) in [("FONE", &place.phonetic), ("ROMN", &place.romanized)] {
+            for variation in variations {
+                self.line(level + 1, tag, Some(&variation.value))?;
+                self.optional(level + 2, "TYPE", variation.variation_type.as_deref())?;
+            }
+        }
+        if let Some(map) = &place.map {
+            self.map(level + 1, map)?;
+        }
+        for note in &place.notes {
+            self.note(level + 1, note)?;
+        }
+        if let Some(location) = &place.location {
+            self.pointer(level + 1, "_LOC", location)?;
+        }
+        for data in &place.custom_data {
+            self.custom_data(level + 1, data)?;
+        }
+        Ok(())
+    }
+
+    fn map(&mut self, level: u8, map: &Map) -> io::Result<()> {
+        self.line(level, "MAP", None)?;
+        self.line(level + 1, "LATI", Some(&map.latitude_value()))?;
+        self.line(level + 1, "LONG", Some(&map.longitude_value()))
+    }
+
+    fn extension_record(&mut self, record: &UserDefinedDataset) -> io::Result<()> {
+        let data = &record.data;
+        self.xref_line(0, record.xref.as_deref(), &data.tag, Some(&data.value))?;
+        for child in &data.children {
+            self.custom_data(1, child)?;
+        }
+        Ok(())
+    }
+
+    fn custom_data(&mut self, level: u8, data: &CustomData) -> io::Result<()> {
+        match self.custom_tags.placement_of(&data.tag) {
+            CustomTagPlacement::Drop => return Ok(()),
+            CustomTagPlacement::Last if level == 1 => {
+                self.deferred.push(data.clone());
+                return Ok(());
+            }
+            _ => {}
+        }
+        self.line(level, &data.tag, Some(&data.value))?;
+        for child in &data.children {
+            self.custom_data(level + 1, child)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the custom tags put off until the end of the record, sorted by tag
+    fn write_deferred(&mut self) -> io::Result<()> {
+        let mut deferred = std::mem::take(&mut self.deferred);
+        for data in &mut deferred {
+            data.tag.make_ascii_uppercase();
+        }
+        deferred.sort_by(|a, b| a.tag.cmp(&b.tag));
+        for data in &deferred {
+            self.line(1, &data.tag, Some(&data.value))?;
+            for child in &data.children {
+                self.custom_data(2, child)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Doubles the `@` of a value so it isn't mistaken for a pointer. Pointers and
+/// escapes like `@#DJULIAN@` are kept as they are.
+fn encode(value: &str) -> Cow<'_, str> {
+    if is_pointer(value) || value.starts_with("@#") || !value.contains('@') {
+        Cow::Borrowed(value)
+    } else {
+        Cow::Owned(value.replace('@', "@@"))
+    }
+}
+
+/// Refuses an xref that would be read back as something else, ie. `I 1` for which
+/// `0 I 1 INDI` would not start a record
+fn check_pointer(xref: &str) -> io::Result<()> {
+    check_xref(xref).map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))
+}
+
+/// Whether a value is a pointer to a record, ie. `@I1@`
+fn is_pointer(value: &str) -> bool {
+    value.len() > 2
+        && value.starts_with('@')
+        && value.ends_with('@')
+        && !value[1..value.len() - 1].contains(|c: char| c == '@' || c.is_whitespace())
+}
+
+/// Splits a line of text into chunks of at most `limit` characters once encoded.
+/// Splits next to a space are avoided, as some programs trim line values.
+fn split_text(text: &str, limit: usize) -> Vec<&str> {
+    let encoded_length = |c: char| if c == '@' { 2 } else { 1 };
+    let mut chunks = Vec::new();
+    let mut rest = text;
+
+    while rest.chars().map(encoded_length).sum::<usize>() > limit {
+        // the longest prefix that fits
+        let mut length = 0;
+        let mut end = 0;
+        for (index, c) in rest.char_indices() {
+            length += encoded_length(c);
+            if length > limit {
+                break;
+            }
+            end = index + c.len_utf8();
+        }
+
+        let split = (1..=end)
+            .rev()
+            .filter(|index| rest.is_char_boundary(*index))
+            .find(|index| {
+                let before = rest[..*index].chars().next_back();
+                let after = rest[*index..].chars().next();
+                !before.is_some_and(char::is_whitespace) && !after.is_some_and(char::is_whitespace)
+            })
+            .unwrap_or(end);
+        chunks.push(&rest[..split]);
+        rest = &rest[split..];
+    }
+    chunks.push(rest);
+    chunks
+}
+
+pub(crate) fn event_tag(event_type: &EventType) -> &'static str {
+    match event_type {
+        EventType::Adoption => "ADOP",
+        EventType::Birth => "BIRT",
+        EventType::Burial => "BURI",
+        EventType::Death => "DEAT",
+        EventType::Divorce => "DIV",
+        EventType::Christening => "CHR",
+        EventType::Marriage => "MARR",
+        EventType::Residence => "RESI",
+        EventType::Census => "CENS",
+        EventType::Baptism => "BAPM",
+        EventType::BarMitzvah => "BARM",
+        EventType::BasMitzvah => "BASM",
+        EventType::Blessing => "BLES",
+        EventType::AdultChristening => "CHRA",
+        EventType::Confirmation => "CONF",
+        EventType::Cremation => "CREM",
+        EventType::Emigration => "EMIG",
+        EventType::FirstCommunion => "FCOM",
+        EventType::Graduation => "GRAD",
+        EventType::Immigration => "IMMI",
+        EventType::Naturalization => "NATU",
+        EventType::Ordination => "ORDN",
+        EventType::Retirement => "RETI",
+        EventType::Probate => "PROB",
+        EventType::Will => "WILL",
+        EventType::SourceData(_) | EventType::Other => "EVEN",
+    }
+}
+
+/// Formats a time as the `DATE` & `TIME` values of the header, in UTC.
+pub(crate) fn timestamp(time: SystemTime) -> (String, String) {
+    let seconds = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let (year, month, day) = civil_date(seconds / 86_400);
+    let seconds_of_day = seconds % 86_400;
+    (
+        format!("{day} {} {year}", month_abbreviation(month)),
+        format!(
+            "{:02}:{:02}:{:02}",
+            seconds_of_day / 3600,
+            seconds_of_day % 3600 / 60,
+            seconds_of_day % 60
+        ),
+    )
+}
+
+/// Converts days since the unix epoch to a (year, month, day) date of the
+/// proleptic gregorian calendar.
+pub(crate) fn civil_date(days: u64) -> (u64, u64, u64) {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    // months counted from march, so that the leap day ends the year
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+fn month_abbreviation(month: u64) -> &'static str {
+    match month {
+        1 => "JAN",
+        2 => "FEB",
+        3 => "MAR",
+        4 => "APR",
+        5 => "MAY",
+        6 => "JUN",
+        7 => "JUL",
+        8 => "AUG",
+        9 => "SEP",
+        10 => "OCT",
+        11 => "NOV",
+        _ => "DEC",
+    }
+}
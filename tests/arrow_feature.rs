@@ -0,0 +1,67 @@
+#[cfg(test)]
+#[cfg(feature = "arrow")]
+mod arrow_feature_tests {
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::Int32Type;
+    use gedcom::export::arrow::{events_batch, families_batch, individuals_batch, write_parquet};
+    use gedcom::parser::Parser;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use std::fs::{self, File};
+
+    const CONTENT: &str = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+        0 @I1@ INDI\n1 NAME John /Doe/\n2 GIVN John\n2 SURN Doe\n1 SEX M\n\
+        1 BIRT\n2 DATE 1 JAN 1899\n2 PLAC Boston\n1 DEAT\n2 DATE ABT 1970\n\
+        0 @I2@ INDI\n1 NAME Ann /Doe/\n1 FAMC @F1@\n\
+        0 @F1@ FAM\n1 HUSB @I1@\n1 CHIL @I2@\n1 MARR\n2 DATE 1920\n0 TRLR\n";
+
+    #[test]
+    fn exports_record_batches() {
+        let data = Parser::new(CONTENT.chars()).parse_record();
+
+        let individuals = individuals_batch(&data).unwrap();
+        assert_eq!(individuals.num_rows(), 2);
+        let column = |name| individuals.column_by_name(name).unwrap();
+        assert_eq!(column("name").as_string::<i32>().value(0), "John Doe");
+        assert_eq!(column("surname").as_string::<i32>().value(0), "Doe");
+        assert_eq!(column("birth_place").as_string::<i32>().value(0), "Boston");
+        assert_eq!(
+            column("death_year").as_primitive::<Int32Type>().value(0),
+            1970
+        );
+        assert!(column("sex").is_null(1));
+
+        let families = families_batch(&data).unwrap();
+        assert_eq!(families.num_rows(), 1);
+        let children = families
+            .column_by_name("children")
+            .unwrap()
+            .as_list::<i32>();
+        assert_eq!(children.value(0).as_string::<i32>().value(0), "@I2@");
+        assert!(families.column_by_name("wife").unwrap().is_null(0));
+
+        let events = events_batch(&data).unwrap();
+        assert_eq!(events.num_rows(), 3);
+        let records = events.column_by_name("record").unwrap().as_string::<i32>();
+        let kinds = events.column_by_name("event").unwrap().as_string::<i32>();
+        assert_eq!((records.value(2), kinds.value(2)), ("@F1@", "Marriage"));
+    }
+
+    #[test]
+    fn writes_parquet_files() {
+        let data = Parser::new(CONTENT.chars()).parse_record();
+        let dir = std::env::temp_dir().join(format!("gedcom-parquet-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        write_parquet(&data, &dir).unwrap();
+        for (table, rows) in [("individuals", 2), ("families", 1), ("events", 3)] {
+            let file = File::open(dir.join(format!("{}.parquet", table))).unwrap();
+            let reader = ParquetRecordBatchReaderBuilder::try_new(file)
+                .unwrap()
+                .build()
+                .unwrap();
+            let read: usize = reader.map(|batch| batch.unwrap().num_rows()).sum();
+            assert_eq!(read, rows);
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
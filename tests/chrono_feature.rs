@@ -0,0 +1,24 @@
+#[cfg(test)]
+#[cfg(feature = "chrono")]
+mod chrono_feature_tests {
+    use chrono::NaiveDate;
+    use gedcom::types::DateValue;
+
+    #[test]
+    fn converts_dates_to_chrono() {
+        let date = |value| DateValue::parse(value).to_chrono();
+        assert_eq!(date("ABT 1 JAN 1900"), NaiveDate::from_ymd_opt(1900, 1, 1));
+        // the julian calendar ran 11 days behind the gregorian in the 18th century
+        assert_eq!(
+            date("@#DJULIAN@ 11 FEB 1731"),
+            NaiveDate::from_ymd_opt(1731, 2, 22)
+        );
+        assert_eq!(
+            date("@#DJULIAN@ 4 OCT 1582"),
+            NaiveDate::from_ymd_opt(1582, 10, 14)
+        );
+        assert_eq!(date("JAN 1900"), None);
+        assert_eq!(date("30 FEB 1900"), None);
+        assert_eq!(date("@#DHEBREW@ 1 TSH 5600"), None);
+    }
+}
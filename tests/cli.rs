@@ -0,0 +1,148 @@
+use std::process::Command;
+
+fn parse_gedcom(args: &[&str]) -> (Option<i32>, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_parse_gedcom"))
+        .args(args)
+        .output()
+        .unwrap();
+    (
+        output.status.code(),
+        String::from_utf8(output.stdout).unwrap(),
+    )
+}
+
+#[test]
+fn validate_succeeds_on_valid_file() {
+    let (code, stdout) = parse_gedcom(&["validate", "./tests/fixtures/simple.ged"]);
+    assert_eq!(code, Some(0));
+    assert!(stdout.ends_with("0 error(s), 0 warning(s)\n"));
+}
+
+#[test]
+fn validate_reports_json_diagnostics() {
+    let (code, stdout) = parse_gedcom(&[
+        "validate",
+        "--format",
+        "json",
+        "./tests/fixtures/corrupted/individual_unknown_sex.ged",
+    ]);
+    assert_eq!(code, Some(1));
+    assert_eq!(
+        stdout,
+        "{\"file\":\"./tests/fixtures/corrupted/individual_unknown_sex.ged\",\"line\":3,\
+         \"severity\":\"error\",\"message\":\"line 3: Invalid value Z, expected a SEX of M, F, X or U\"}\n"
+    );
+}
+
+#[test]
+fn validate_rejects_unknown_format() {
+    let (code, _) = parse_gedcom(&["validate", "--format", "xml", "./tests/fixtures/simple.ged"]);
+    assert_eq!(code, Some(2));
+}
+
+#[test]
+fn extract_writes_privatized_branch() {
+    let (code, stdout) = parse_gedcom(&[
+        "extract",
+        "./tests/fixtures/lineage.ged",
+        "--root",
+        "@I2@",
+        "--ancestors",
+        "1",
+        "--descendants",
+        "0",
+        "--privatize",
+    ]);
+    assert_eq!(code, Some(0));
+    assert!(stdout.starts_with("0 HEAD\n"));
+    assert!(stdout.contains("0 @I4@ INDI\n1 NAME Living /Smith/\n"));
+    assert!(!stdout.contains("@I1@"));
+    assert!(!stdout.contains("@I6@"));
+    assert!(stdout.ends_with("0 TRLR\n"));
+}
+
+#[test]
+fn extract_requires_root() {
+    let (code, _) = parse_gedcom(&["extract", "./tests/fixtures/lineage.ged"]);
+    assert_eq!(code, Some(2));
+}
+
+#[test]
+fn batch_reports_each_file_and_totals() {
+    let (code, stdout) = parse_gedcom(&["batch", "./tests/fixtures/corrupted"]);
+    assert_eq!(code, Some(1));
+    assert!(stdout.contains(
+        "tests/fixtures/corrupted/individual_unknown_sex.ged: failed: line 3: Invalid value Z, expected a SEX of M, F, X or U\n"
+    ));
+    assert!(stdout.contains(
+        "tests/fixtures/corrupted/multimedia_form_title_siblings.ged: 0 individual(s), 0 family(ies), \
+         0 error(s), 0 warning(s)\n"
+    ));
+    let summary = stdout.lines().last().unwrap();
+    assert!(summary.starts_with("13 file(s), "), "{}", summary);
+}
+
+fn gedcom_verify(args: &[&str]) -> (Option<i32>, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_gedcom-verify"))
+        .args(args)
+        .output()
+        .unwrap();
+    (
+        output.status.code(),
+        String::from_utf8(output.stdout).unwrap(),
+    )
+}
+
+#[test]
+fn verify_reports_json_findings() {
+    let (code, stdout) = gedcom_verify(&["./tests/fixtures/simple.ged"]);
+    assert_eq!(code, Some(0));
+    assert_eq!(
+        stdout,
+        "{\"files\":[{\"file\":\"./tests/fixtures/simple.ged\",\"findings\":[]}]}\n"
+    );
+
+    let (code, stdout) =
+        gedcom_verify(&["./tests/fixtures/corrupted/family_duplicate_husband.ged"]);
+    assert_eq!(code, Some(1));
+    assert!(stdout.contains(
+        "{\"rule\":\"syntax\",\"severity\":\"warning\",\"line\":3,\"record\":null,\
+         \"message\":\"Family has more than one HUSB, recording @I2@ as the other parent\"}"
+    ));
+    assert!(stdout.contains(
+        "{\"rule\":\"dangling-pointer\",\"severity\":\"error\",\"line\":1,\"record\":\"@F1@\",\
+         \"message\":\"@F1@ links to missing record @I1@\"}"
+    ));
+}
+
+#[test]
+fn verify_reports_sarif_results() {
+    let (code, stdout) = gedcom_verify(&[
+        "--format",
+        "sarif",
+        "./tests/fixtures/simple.ged",
+        "./tests/fixtures/corrupted/individual_unknown_sex.ged",
+    ]);
+    assert_eq!(code, Some(1));
+    assert!(stdout.starts_with("{\"version\":\"2.1.0\","));
+    assert!(stdout.contains("\"driver\":{\"name\":\"gedcom-verify\""));
+    assert!(stdout.contains(
+        "\"results\":[{\"ruleId\":\"syntax\",\"level\":\"error\",\
+         \"message\":{\"text\":\"line 3: Invalid value Z, expected a SEX of M, F, X or U\"},\
+         \"locations\":[{\"physicalLocation\":{\"artifactLocation\":\
+         {\"uri\":\"./tests/fixtures/corrupted/individual_unknown_sex.ged\"},\
+         \"region\":{\"startLine\":3}}}]}]"
+    ));
+}
+
+#[test]
+fn verify_denies_warnings_on_request() {
+    // only skipped custom records, reported as warnings
+    let file = "./tests/fixtures/washington.ged";
+    let (code, _) = gedcom_verify(&[file]);
+    assert_eq!(code, Some(0));
+    let (code, _) = gedcom_verify(&["--deny-warnings", file]);
+    assert_eq!(code, Some(1));
+    let (code, _) = gedcom_verify(&["--format", "xml", file]);
+    assert_eq!(code, Some(2));
+}
@@ -0,0 +1,104 @@
+//! Golden snapshots of the parse of real-world & torture test files.
+//!
+//! Each file of `CORPUS` is parsed and compared, as pretty JSON, to its snapshot in
+//! `tests/fixtures/snapshots`, so that changes in how the parser reads them show up as
+//! snapshot diffs in review. After an intended change, regenerate the snapshots with:
+//!
+//! ```bash
+//! UPDATE_SNAPSHOTS=1 cargo test --features json --test compatibility
+//! ```
+//!
+//! Every file of the corpus must be in `tests/fixtures`, a missing one fails the test.
+//! The famous `royal92.ged` & `pres2020.ged` are left out of the corpus until copies of
+//! them are checked in, along with their snapshots.
+//!
+//! The parse of `allged.ged` stops at line 526, on the `CAST` of an individual: the
+//! attributes of individuals (`CAST`, `DSCR`, `EDUC`, `OCCU`...) are not modeled yet.
+//! Its snapshot holds the records read before it and that error.
+#![allow(special_module_name)]
+
+#[cfg(test)]
+mod lib;
+
+#[cfg(test)]
+#[cfg(feature = "json")]
+mod compatibility {
+    use super::lib::util::read_relative;
+    use gedcom::{parser::Parser, GedcomData};
+    use std::{
+        env, fs,
+        path::{Path, PathBuf},
+    };
+
+    /// Files of the corpus, below `tests/fixtures`
+    const CORPUS: [&str; 5] = [
+        // the GEDCOM 5.5 torture test, exercising every tag of the specification
+        "allged.ged",
+        // a RootsMagic export of a real family tree
+        "washington.ged",
+        "sample.ged",
+        "simple.ged",
+        "lineage.ged",
+    ];
+
+    /// The parse of `content` as pretty JSON: the records read up to the end of the file
+    /// or up to the error stopping the parse, that error, and the diagnostics
+    fn snapshot(content: &str) -> String {
+        let mut parser = Parser::new(content.chars());
+        let mut data = GedcomData::default();
+        let mut error = None;
+        for record in parser.records() {
+            match record {
+                Ok(record) => data.add_record(record),
+                Err(parse_error) => error = Some(parse_error.to_string()),
+            }
+        }
+        let result = match error {
+            Some(error) => serde_json::json!({ "data": data, "error": error }),
+            None => serde_json::json!({ "data": data }),
+        };
+        let diagnostics: Vec<String> = parser
+            .diagnostics()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let snapshot = serde_json::json!({ "result": result, "diagnostics": diagnostics });
+        serde_json::to_string_pretty(&snapshot).unwrap() + "\n"
+    }
+
+    #[test]
+    fn matches_golden_snapshots() {
+        let update = env::var_os("UPDATE_SNAPSHOTS").is_some();
+        let mut mismatches = Vec::new();
+        for file in CORPUS {
+            let fixture = format!("./tests/fixtures/{}", file);
+            assert!(
+                Path::new(&fixture).exists(),
+                "{} of the corpus is missing from tests/fixtures",
+                file
+            );
+            let content = read_relative(&fixture);
+            let actual = snapshot(&content);
+            let path = PathBuf::from(format!("./tests/fixtures/snapshots/{}.json", file));
+            if update {
+                fs::write(&path, &actual).unwrap();
+                continue;
+            }
+
+            let expected = fs::read_to_string(&path).unwrap_or_default();
+            if actual != expected {
+                let line = actual
+                    .lines()
+                    .zip(expected.lines())
+                    .position(|(actual, expected)| actual != expected)
+                    .unwrap_or_else(|| actual.lines().count().min(expected.lines().count()));
+                mismatches.push(format!("{} differs from line {}", path.display(), line + 1));
+            }
+        }
+        assert!(
+            mismatches.is_empty(),
+            "Snapshots out of date, rerun with UPDATE_SNAPSHOTS=1 and review the diff:\n{}",
+            mismatches.join("\n")
+        );
+    }
+}
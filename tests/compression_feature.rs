@@ -0,0 +1,31 @@
+#[cfg(test)]
+#[cfg(feature = "compression")]
+mod compression_feature_tests {
+    use gedcom::parser::{ParseOptions, Parser};
+
+    #[test]
+    fn compresses_long_notes() {
+        let long_text = "A long note about the family history. ".repeat(20);
+        let sample =
+            format!("0 HEAD\n1 GEDC\n2 VERS 5.5\n1 NOTE {long_text}\n2 CONT second line\n0 TRLR\n");
+        let options = ParseOptions {
+            note_compression_threshold: Some(256),
+            ..ParseOptions::default()
+        };
+
+        let data = Parser::with_options(sample.chars(), options).parse_record();
+        let note = &data.header.notes[0];
+        assert!(note.is_compressed());
+        assert_eq!(note.text(), format!("{long_text}\nsecond line"));
+
+        let short = "0 HEAD\n1 NOTE short\n0 TRLR\n";
+        let options = ParseOptions {
+            note_compression_threshold: Some(256),
+            ..ParseOptions::default()
+        };
+        let data = Parser::with_options(short.chars(), options).parse_record();
+        let note = &data.header.notes[0];
+        assert!(!note.is_compressed());
+        assert_eq!(note.text(), "short");
+    }
+}
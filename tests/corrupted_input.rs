@@ -0,0 +1,349 @@
+#![allow(special_module_name)]
+
+#[cfg(test)]
+mod lib;
+
+#[cfg(test)]
+mod individual {
+    use super::lib::util::read_relative;
+    use gedcom::parser::Parser;
+    use gedcom::GedcomError;
+
+    #[test]
+    fn rejects_unknown_sex() {
+        let content = read_relative("./tests/fixtures/corrupted/individual_unknown_sex.ged");
+        let error = Parser::new(content.chars())
+            .try_parse_individual_record()
+            .unwrap_err();
+        match error {
+            GedcomError::InvalidValue { line, value, .. } => {
+                assert_eq!(line, 3);
+                assert_eq!(value, "Z");
+            }
+            other => panic!("Expected an invalid value error, found {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_pedigree() {
+        let content = read_relative("./tests/fixtures/corrupted/individual_unknown_pedigree.ged");
+        let error = Parser::new(content.chars())
+            .try_parse_individual_record()
+            .unwrap_err();
+        match error {
+            GedcomError::InvalidValue {
+                line,
+                value,
+                expected,
+            } => {
+                assert_eq!(line, 4);
+                assert_eq!(value, "stepchild");
+                assert!(expected.starts_with("a PEDI"));
+            }
+            other => panic!("Expected an invalid value error, found {:?}", other),
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "line 3: Expected LineValue, found Level(2)")]
+    fn rejects_name_without_value() {
+        let content = read_relative("./tests/fixtures/corrupted/individual_name_without_value.ged");
+        Parser::new(content.chars()).parse_individual_record();
+    }
+
+    #[test]
+    fn parses_record_without_trailing_newline() {
+        let content =
+            read_relative("./tests/fixtures/corrupted/individual_no_trailing_newline.ged");
+        let individual = Parser::new(content.chars()).parse_individual_record();
+        assert_eq!(individual.xref.as_deref(), Some("@I1@"));
+    }
+
+    #[test]
+    #[should_panic(expected = "line 1: Expected INDI record, found Tag(\"FAM\")")]
+    fn rejects_record_of_another_type() {
+        let content = read_relative("./tests/fixtures/corrupted/family_duplicate_husband.ged");
+        Parser::new(content.chars()).parse_individual_record();
+    }
+
+    #[test]
+    fn returns_fragment_errors() {
+        use gedcom::types::{Family, Individual};
+
+        let content = read_relative("./tests/fixtures/corrupted/individual_unknown_sex.ged");
+        let error = Individual::try_parse_str(&content).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 3: Invalid value Z, expected a SEX of M, F, X or U"
+        );
+        let error = Parser::new(content.chars())
+            .try_parse_family_record()
+            .unwrap_err();
+        assert!(error.to_string().starts_with("line 1: Expected FAM record"));
+        assert!(Family::try_parse_str("0 @F1@ FAM\n1 CHIL @I1@\n").is_ok());
+    }
+}
+
+#[cfg(test)]
+mod family {
+    use super::lib::util::read_relative;
+    use gedcom::parser::Parser;
+    use gedcom::types::{HasEvents, ParentTag};
+
+    #[test]
+    fn flags_duplicate_husband() {
+        let content = read_relative("./tests/fixtures/corrupted/family_duplicate_husband.ged");
+        let mut parser = Parser::new(content.chars());
+        let family = parser.parse_family_record();
+        assert_eq!(
+            family.parents(),
+            vec![("@I1@", ParentTag::Husband), ("@I2@", ParentTag::Husband)]
+        );
+        assert_eq!(parser.diagnostics().len(), 1);
+        assert_eq!(parser.diagnostics()[0].line, 3);
+    }
+
+    #[test]
+    fn records_duplicate_marriage() {
+        let content = read_relative("./tests/fixtures/corrupted/family_duplicate_marriage.ged");
+        let mut parser = Parser::new(content.chars());
+        let family = parser.parse_family_record();
+        assert_eq!(family.events().len(), 2);
+        assert_eq!(family.events()[1].date.as_deref(), Some("2 FEB 1902"));
+        assert_eq!(parser.diagnostics().len(), 1);
+        assert_eq!(parser.diagnostics()[0].line, 4);
+    }
+
+    #[test]
+    fn gives_back_taken_partners() {
+        use gedcom::types::Family;
+
+        let mut family = Family::new(None);
+        assert_eq!(family.set_individual1("@I1@".to_string()), Ok(()));
+        assert_eq!(
+            family.set_individual1("@I2@".to_string()),
+            Err("@I2@".to_string())
+        );
+        assert_eq!(family.individual1.as_deref(), Some("@I1@"));
+    }
+}
+
+#[cfg(test)]
+mod source {
+    use super::lib::util::read_relative;
+    use gedcom::parser::Parser;
+
+    #[test]
+    #[should_panic(expected = "line 4: Unhandled RepoCitation Tag: MEDI")]
+    fn rejects_repo_citation_media_type() {
+        let content = read_relative("./tests/fixtures/corrupted/source_repo_citation_media.ged");
+        Parser::new(content.chars()).parse_source_record();
+    }
+}
+
+#[cfg(test)]
+mod header {
+    use super::lib::util::read_relative;
+    use gedcom::parser::Parser;
+
+    #[test]
+    #[should_panic(expected = "Expected TIME to be under DATE in header.")]
+    fn rejects_time_without_date() {
+        let content = read_relative("./tests/fixtures/corrupted/header_time_without_date.ged");
+        Parser::new(content.chars()).parse_header_record();
+    }
+}
+
+#[cfg(test)]
+mod multimedia {
+    use super::lib::util::read_relative;
+    use gedcom::diagnostics::Severity;
+    use gedcom::parser::{ParseOptions, Parser};
+
+    #[test]
+    fn keeps_form_title_siblings_on_record() {
+        let content =
+            read_relative("./tests/fixtures/corrupted/multimedia_form_title_siblings.ged");
+        let data = Parser::new(content.chars()).parse_record();
+        assert_eq!(data.header.gedcom_version.as_deref(), Some("5.5.1"));
+        let multimedia = &data.multimedia[0];
+        assert_eq!(multimedia.files[0].value, "photo.jpg");
+        assert_eq!(multimedia.files[0].form, None);
+        assert_eq!(multimedia.form.as_deref(), Some("jpg"));
+        assert_eq!(multimedia.title.as_deref(), Some("Portrait"));
+    }
+
+    #[test]
+    fn flags_form_title_siblings_in_strict_mode() {
+        let content =
+            read_relative("./tests/fixtures/corrupted/multimedia_form_title_siblings.ged");
+        let mut parser = Parser::new(content.chars());
+        parser.parse_record();
+        assert!(parser.diagnostics().is_empty());
+
+        let options = ParseOptions {
+            strict: true,
+            ..ParseOptions::default()
+        };
+        let mut parser = Parser::with_options(content.chars(), options);
+        let mut data = parser.parse_record();
+        let diagnostics = parser.diagnostics();
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].line, 7);
+        assert_eq!(
+            diagnostics[1].message,
+            "TITL of multimedia record should be under its FILE"
+        );
+
+        assert_eq!(data.normalize_multimedia_files(), 1);
+        let file = &data.multimedia[0].files[0];
+        assert_eq!(file.form.as_deref(), Some("jpg"));
+        assert_eq!(file.title.as_deref(), Some("Portrait"));
+        assert!(data.multimedia[0].form.is_none());
+    }
+}
+
+#[cfg(test)]
+mod document {
+    use super::lib::util::read_relative;
+    use gedcom::diagnostics::Severity;
+    use gedcom::parser::{ParseOptions, Parser};
+    use gedcom::types::event::HasEvents;
+    use gedcom::GedcomError;
+
+    #[test]
+    fn uppercases_tags_in_lenient_mode() {
+        let content = read_relative("./tests/fixtures/corrupted/document_lowercase_tags.ged");
+        let data = Parser::new(content.chars()).parse_record();
+        assert_eq!(data.header.gedcom_version.as_deref(), Some("5.5.1"));
+        let individual = &data.individuals[0];
+        let name = individual.primary_name().unwrap();
+        assert_eq!(name.value.as_deref(), Some("John /Doe/"));
+        assert_eq!(name.given.as_deref(), Some("John"));
+        let birth = &individual.events()[0];
+        assert_eq!(birth.date.as_deref(), Some("1 JAN 1900"));
+        assert_eq!(birth.place_name(), Some("Salem"));
+        // standard tags under custom ones keep their spelling, to be written back as is
+        assert_eq!(individual.custom_data[0].children[0].tag, "date");
+    }
+
+    #[test]
+    fn normalizes_indented_and_zero_padded_levels() {
+        let content = read_relative("./tests/fixtures/corrupted/document_indented_levels.ged");
+        let mut parser = Parser::new(content.chars());
+        let data = parser.parse_record();
+        assert_eq!(data.header.gedcom_version.as_deref(), Some("5.5.1"));
+        let individual = &data.individuals[0];
+        let name = individual.primary_name().unwrap();
+        assert_eq!(name.given.as_deref(), Some("John"));
+        assert_eq!(individual.events()[0].date.as_deref(), Some("1 JAN 1900"));
+
+        let diagnostics = parser.diagnostics();
+        let lines: Vec<u32> = diagnostics.iter().map(|d| d.line).collect();
+        assert_eq!(lines, vec![2, 3, 5, 6, 7, 8, 8]);
+        assert!(diagnostics.iter().all(|d| d.severity == Severity::Warning));
+        assert_eq!(diagnostics[0].message, "Level number 01 has a leading zero");
+        assert_eq!(diagnostics[2].message, "Whitespace before level number");
+
+        let options = ParseOptions {
+            strict: true,
+            ..ParseOptions::default()
+        };
+        let mut parser = Parser::with_options(content.chars(), options);
+        parser.parse_record();
+        assert_eq!(parser.diagnostics().len(), 7);
+        assert!(parser
+            .diagnostics()
+            .iter()
+            .all(|d| d.severity == Severity::Error));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unhandled Header Tag: gedc")]
+    fn matches_tags_as_written_in_strict_mode() {
+        let content = read_relative("./tests/fixtures/corrupted/document_lowercase_tags.ged");
+        let options = ParseOptions {
+            strict: true,
+            ..ParseOptions::default()
+        };
+        Parser::with_options(content.chars(), options).parse_record();
+    }
+
+    #[test]
+    fn rejects_invalid_level() {
+        let content = read_relative("./tests/fixtures/corrupted/document_invalid_level.ged");
+        let error = Parser::new(content.chars()).parse_document().unwrap_err();
+        assert!(matches!(error, GedcomError::Tokenize { line: 3 }));
+    }
+
+    #[test]
+    fn limits_the_level_of_lines() {
+        let content = "0 HEAD\n0 @I1@ INDI\n1 _DEEP\n2 _DEEPER\n3 _DEEPEST\n0 TRLR\n";
+        let options = ParseOptions {
+            max_level: Some(2),
+            ..ParseOptions::default()
+        };
+        let error = Parser::with_options(content.chars(), options)
+            .parse_document()
+            .unwrap_err();
+        match error {
+            GedcomError::LimitExceeded(limit) => {
+                assert_eq!(limit, "maximum level of 2, on line 5");
+            }
+            other => panic!("Expected a limit error, found {:?}", other),
+        }
+        assert!(Parser::new(content.chars()).parse_document().is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "The content ended before the trailer")]
+    fn rejects_missing_trailer() {
+        let content = read_relative("./tests/fixtures/corrupted/document_missing_trailer.ged");
+        Parser::new(content.chars()).parse_record();
+    }
+
+    #[test]
+    fn returns_parse_errors() {
+        let content = read_relative("./tests/fixtures/corrupted/source_repo_citation_media.ged");
+        match gedcom::parse_document(&content) {
+            Err(GedcomError::UnexpectedTag { line, tag, context }) => {
+                assert_eq!(line, 4);
+                assert_eq!(tag, "MEDI");
+                assert_eq!(context, "RepoCitation");
+            }
+            other => panic!("Expected an unexpected tag error, found {:?}", other),
+        }
+
+        let content = read_relative("./tests/fixtures/corrupted/document_missing_trailer.ged");
+        let error = Parser::new(content.chars()).parse_document().unwrap_err();
+        assert!(matches!(error, GedcomError::Truncated));
+
+        let content = read_relative("./tests/fixtures/corrupted/document_invalid_level.ged");
+        let error = Parser::new(content.chars()).parse_document().unwrap_err();
+        assert_eq!(error.line(), Some(3));
+    }
+
+    #[test]
+    fn reads_every_line_terminator() {
+        for terminator in ["lf", "crlf", "cr"] {
+            let path = format!("./tests/fixtures/line_endings/{terminator}.ged");
+            let content = read_relative(&path);
+            let mut parser = Parser::new(content.chars());
+            let data = parser.parse_document().unwrap();
+            let individual = &data.individuals[0];
+            let name = individual.primary_name().unwrap();
+            assert_eq!(name.value.as_deref(), Some("John /Doe/"), "{terminator}");
+            assert_eq!(
+                individual.notes[0].text(),
+                "First line\nsecond line continued",
+                "{terminator}"
+            );
+            assert_eq!(individual.events()[0].date.as_deref(), Some("1 JAN 1900"));
+            // The unhandled record is reported on its own line
+            let lines: Vec<u32> = parser.diagnostics().iter().map(|d| d.line).collect();
+            assert_eq!(lines, [12], "{terminator}");
+            assert_eq!(parser.line(), 13, "{terminator}");
+        }
+    }
+}
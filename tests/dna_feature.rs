@@ -0,0 +1,49 @@
+#[cfg(test)]
+#[cfg(feature = "dna")]
+mod dna_feature_tests {
+    use gedcom::dna::{DnaData, HaplogroupKind};
+    use gedcom::types::Individual;
+
+    #[test]
+    fn reads_haplogroups() {
+        let individual = Individual::parse_str(
+            "0 @I1@ INDI\n1 NAME John /Doe/\n1 _Y-DNA R1b-M269\n1 _DNA\n2 _MTDNA H1a\n",
+        );
+
+        let haplogroups = individual.haplogroups();
+        assert_eq!(haplogroups.len(), 2);
+        assert_eq!(haplogroups[0].kind, HaplogroupKind::YDna);
+        assert_eq!(haplogroups[0].name, "R1b-M269");
+        assert_eq!(haplogroups[1].kind, HaplogroupKind::MtDna);
+        assert_eq!(haplogroups[1].name, "H1a");
+    }
+
+    #[test]
+    fn reads_dna_matches() {
+        let individual = Individual::parse_str(
+            "0 @I1@ INDI
+1 _DNA @I2@
+2 _CM 45.5
+2 _SEGS 3
+2 _LONGEST 21.2 cM
+1 _DNA Shared 1,720 cM; 38 segments
+2 _MATCH @I3@
+2 DATE 1 JAN 2020
+",
+        );
+
+        let matches = individual.dna_matches();
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].matched.as_deref(), Some("@I2@"));
+        assert_eq!(matches[0].shared_cm, Some(45.5));
+        assert_eq!(matches[0].segments, Some(3));
+        assert_eq!(matches[0].longest_segment_cm, Some(21.2));
+
+        assert_eq!(matches[1].matched.as_deref(), Some("@I3@"));
+        assert_eq!(matches[1].shared_cm, Some(1720.0));
+        assert_eq!(matches[1].segments, Some(38));
+        // the raw data is kept, including standard tags under the custom tag
+        assert_eq!(matches[1].raw.children[1].tag, "DATE");
+        assert_eq!(individual.custom_data.len(), 2);
+    }
+}
@@ -1,3 +1,5 @@
+#![allow(special_module_name)]
+
 #[cfg(test)]
 mod lib;
 
@@ -5,8 +7,7 @@ mod lib;
 #[cfg(feature = "json")]
 mod json_feature_tests {
     use super::lib::util::read_relative;
-    use gedcom::{parse, types::Name};
-    use serde_json;
+    use gedcom::{parse, types::Name, GedcomData};
     use serde_test::{assert_tokens, Token};
 
     #[test]
@@ -14,10 +15,17 @@ mod json_feature_tests {
         let name = Name {
             value: Some("Gregor Johann /Mendel/".into()),
             given: Some("Gregor Johann".into()),
+            nickname: None,
             surname: Some("Mendel".into()),
             prefix: None,
             surname_prefix: None,
             suffix: None,
+            notes: Vec::new(),
+            citations: Vec::new(),
+            name_type: None,
+            language: None,
+            phonetic: Vec::new(),
+            romanized: Vec::new(),
         };
 
         assert_tokens(
@@ -25,7 +33,7 @@ mod json_feature_tests {
             &[
                 Token::Struct {
                     name: "Name",
-                    len: 6,
+                    len: 3,
                 },
                 Token::Str("value"),
                 Token::Some,
@@ -36,12 +44,6 @@ mod json_feature_tests {
                 Token::Str("surname"),
                 Token::Some,
                 Token::String("Mendel"),
-                Token::Str("prefix"),
-                Token::None,
-                Token::Str("surname_prefix"),
-                Token::None,
-                Token::Str("suffix"),
-                Token::None,
                 Token::StructEnd,
             ],
         );
@@ -62,13 +64,17 @@ mod json_feature_tests {
     \"children\": [
       \"@CHILD@\"
     ],
-    \"num_children\": null,
+    \"parent_tags\": [
+      \"Husband\",
+      \"Wife\"
+    ],
     \"events\": [
       {
         \"event\": \"Marriage\",
         \"date\": \"1 APR 1950\",
-        \"place\": \"marriage place\",
-        \"citations\": []
+        \"place\": {
+          \"value\": \"marriage place\"
+        }
       }
     ]
   }
@@ -80,106 +86,94 @@ mod json_feature_tests {
             "[
   {
     \"xref\": \"@FATHER@\",
-    \"name\": {
-      \"value\": \"/Father/\",
-      \"given\": null,
-      \"surname\": null,
-      \"prefix\": null,
-      \"surname_prefix\": null,
-      \"suffix\": null
-    },
+    \"names\": [
+      {
+        \"value\": \"/Father/\"
+      }
+    ],
     \"sex\": \"Male\",
     \"families\": [
-      [
-        \"@FAMILY@\",
-        \"Spouse\",
-        null
-      ]
+      {
+        \"xref\": \"@FAMILY@\",
+        \"link_type\": \"Spouse\"
+      }
     ],
-    \"custom_data\": [],
-    \"last_updated\": null,
     \"events\": [
       {
         \"event\": \"Birth\",
         \"date\": \"1 JAN 1899\",
-        \"place\": \"birth place\",
-        \"citations\": []
+        \"place\": {
+          \"value\": \"birth place\"
+        }
       },
       {
         \"event\": \"Death\",
         \"date\": \"31 DEC 1990\",
-        \"place\": \"death place\",
-        \"citations\": []
+        \"place\": {
+          \"value\": \"death place\"
+        }
       }
     ]
   },
   {
     \"xref\": \"@MOTHER@\",
-    \"name\": {
-      \"value\": \"/Mother/\",
-      \"given\": null,
-      \"surname\": null,
-      \"prefix\": null,
-      \"surname_prefix\": null,
-      \"suffix\": null
-    },
+    \"names\": [
+      {
+        \"value\": \"/Mother/\"
+      }
+    ],
     \"sex\": \"Female\",
     \"families\": [
-      [
-        \"@FAMILY@\",
-        \"Spouse\",
-        null
-      ]
+      {
+        \"xref\": \"@FAMILY@\",
+        \"link_type\": \"Spouse\"
+      }
     ],
-    \"custom_data\": [],
-    \"last_updated\": null,
     \"events\": [
       {
         \"event\": \"Birth\",
         \"date\": \"1 JAN 1899\",
-        \"place\": \"birth place\",
-        \"citations\": []
+        \"place\": {
+          \"value\": \"birth place\"
+        }
       },
       {
         \"event\": \"Death\",
         \"date\": \"31 DEC 1990\",
-        \"place\": \"death place\",
-        \"citations\": []
+        \"place\": {
+          \"value\": \"death place\"
+        }
       }
     ]
   },
   {
     \"xref\": \"@CHILD@\",
-    \"name\": {
-      \"value\": \"/Child/\",
-      \"given\": null,
-      \"surname\": null,
-      \"prefix\": null,
-      \"surname_prefix\": null,
-      \"suffix\": null
-    },
+    \"names\": [
+      {
+        \"value\": \"/Child/\"
+      }
+    ],
     \"sex\": \"Unknown\",
     \"families\": [
-      [
-        \"@FAMILY@\",
-        \"Child\",
-        null
-      ]
+      {
+        \"xref\": \"@FAMILY@\",
+        \"link_type\": \"Child\"
+      }
     ],
-    \"custom_data\": [],
-    \"last_updated\": null,
     \"events\": [
       {
         \"event\": \"Birth\",
         \"date\": \"31 JUL 1950\",
-        \"place\": \"birth place\",
-        \"citations\": []
+        \"place\": {
+          \"value\": \"birth place\"
+        }
       },
       {
         \"event\": \"Death\",
         \"date\": \"29 FEB 2000\",
-        \"place\": \"death place\",
-        \"citations\": []
+        \"place\": {
+          \"value\": \"death place\"
+        }
       }
     ]
   }
@@ -189,4 +183,56 @@ mod json_feature_tests {
         // let json_data = serde_json::to_string_pretty(&data.individuals).unwrap();
         // panic!("{:?}", json_data);
     }
+
+    #[test]
+    fn exports_tree_graph_json() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 NAME John /Doe/\n1 SEX M\n1 BIRT\n2 DATE ABT 1899\n1 DEAT\n2 DATE 1970\n\
+            0 @I2@ INDI\n1 NAME Jane /Roe/\n1 SEX F\n\
+            0 @I3@ INDI\n1 NAME Ann /Doe/\n\
+            0 @F1@ FAM\n1 HUSB @I1@\n1 WIFE @I2@\n1 CHIL @I3@\n0 TRLR\n";
+        let data = parse(content.chars());
+
+        assert_eq!(
+            gedcom::export::tree_json(&data),
+            "{\"nodes\":[\
+             {\"id\":\"I1\",\"name\":\"John Doe\",\"sex\":\"M\",\"birth\":1899,\"death\":1970},\
+             {\"id\":\"I2\",\"name\":\"Jane Roe\",\"sex\":\"F\",\"birth\":null,\"death\":null},\
+             {\"id\":\"I3\",\"name\":\"Ann Doe\",\"sex\":\"U\",\"birth\":null,\"death\":null}],\
+             \"edges\":[\
+             {\"from\":\"I1\",\"to\":\"I2\",\"kind\":\"spouse\",\"family\":\"F1\"},\
+             {\"from\":\"I1\",\"to\":\"I3\",\"kind\":\"parent\",\"family\":\"F1\"},\
+             {\"from\":\"I2\",\"to\":\"I3\",\"kind\":\"parent\",\"family\":\"F1\"}]}"
+        );
+    }
+
+    #[test]
+    fn leaves_empty_fields_out_of_json() {
+        let content: String = read_relative("./tests/fixtures/washington.ged");
+        let data = parse(content.chars());
+
+        let json = serde_json::to_string(&data).unwrap();
+        assert!(!json.contains(":null"));
+        assert!(!json.contains(":[]"));
+
+        // left out fields read back as empty
+        let read: GedcomData = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.individuals.len(), data.individuals.len());
+        assert!(read.individuals[0].lds_ordinances.is_empty());
+        assert_eq!(serde_json::to_string(&read).unwrap(), json);
+    }
+
+    #[test]
+    fn serializes_the_language_of_notes() {
+        use gedcom::types::Note;
+
+        let notes = vec![
+            Note::new("Hello".into()),
+            Note::new("Bonjour".into()).with_language("fr"),
+        ];
+        let json = serde_json::to_string(&notes).unwrap();
+        assert_eq!(json, r#"["Hello",{"text":"Bonjour","language":"fr"}]"#);
+        let read: Vec<Note> = serde_json::from_str(&json).unwrap();
+        assert_eq!(read, notes);
+    }
 }
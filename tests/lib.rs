@@ -11,13 +11,21 @@ pub mod util {
 #[cfg(test)]
 mod tests {
     use super::util::read_relative;
+    use gedcom::analysis::{
+        census_households, extract_branch, marriage_durations, number_descendants, sosa_numbers,
+        source_excerpts, source_text, ExcerptOrigin, HouseholdRole, LivingFilter, MarriageEnd,
+        NumberingSystem,
+    };
     use gedcom::parser::Parser;
-    use gedcom::types::event::HasEvents;
+    use gedcom::types::{
+        event::HasEvents, Family, GedcomVersion, Gender, Header, Individual, LdsOrdinanceType,
+        Name, NameOrder, ParentTag, Producer,
+    };
 
     #[test]
     fn parses_basic_gedcom() {
         let simple_ged: String = read_relative("./tests/fixtures/simple.ged");
-        assert!(simple_ged.len() > 0);
+        assert!(!simple_ged.is_empty());
 
         let mut parser = Parser::new(simple_ged.chars());
         let data = parser.parse_record();
@@ -33,8 +41,7 @@ mod tests {
         // names
         assert_eq!(
             data.individuals[0]
-                .name
-                .as_ref()
+                .primary_name()
                 .unwrap()
                 .value
                 .as_ref()
@@ -60,4 +67,2752 @@ mod tests {
         assert_eq!(events[0].event.to_string(), "Marriage");
         assert_eq!(events[0].date.as_ref().unwrap(), "1 APR 1950");
     }
+
+    #[test]
+    fn parses_record_fragments() {
+        let individual = Individual::parse_str(
+            "0 @I1@ INDI\n1 NAME Jane /Doe/\n1 SEX F\n1 BIRT\n2 DATE 2 MAR 1901\n",
+        );
+        assert_eq!(individual.xref.as_deref(), Some("@I1@"));
+        assert_eq!(
+            individual.primary_name().unwrap().value.as_deref(),
+            Some("Jane /Doe/")
+        );
+        assert_eq!(individual.events()[0].date.as_deref(), Some("2 MAR 1901"));
+
+        let family = Family::parse_str("0 @F1@ FAM\n1 WIFE @I1@\n1 CHIL @I2@\n0 TRLR");
+        assert_eq!(family.individual2.as_deref(), Some("@I1@"));
+        assert_eq!(family.children, vec!["@I2@".to_string()]);
+    }
+
+    #[test]
+    fn converts_nonbinary_sex_for_older_versions() {
+        let individual = Individual::parse_str("0 @I1@ INDI\n1 SEX X\n");
+        assert!(matches!(individual.sex, Gender::Nonbinary));
+
+        let sex = individual.sex.to_sex_value(&GedcomVersion::V7_0);
+        assert_eq!(sex.value, "X");
+        assert!(sex.warning.is_none());
+
+        let sex = individual.sex.to_sex_value(&GedcomVersion::V5_5_1);
+        assert_eq!(sex.value, "U");
+        assert_eq!(sex.preserved.unwrap().value, "X");
+        assert!(sex.warning.is_some());
+    }
+
+    #[test]
+    fn resolves_links_from_a_lazy_store() {
+        use gedcom::store::RecordStore;
+        use gedcom::types::{Repository, Source, Submitter};
+        use std::{borrow::Cow, cell::Cell, collections::HashMap};
+
+        /// Keeps raw records and only parses them when they are fetched
+        struct LazyStore {
+            records: HashMap<&'static str, &'static str>,
+            fetches: Cell<usize>,
+        }
+
+        impl LazyStore {
+            fn fetch(&self, xref: &str) -> Option<&'static str> {
+                self.fetches.set(self.fetches.get() + 1);
+                self.records.get(xref).copied()
+            }
+        }
+
+        impl RecordStore for LazyStore {
+            fn individual(&self, xref: &str) -> Option<Cow<'_, Individual>> {
+                self.fetch(xref)
+                    .map(|raw| Cow::Owned(Individual::parse_str(raw)))
+            }
+            fn family(&self, xref: &str) -> Option<Cow<'_, Family>> {
+                self.fetch(xref)
+                    .map(|raw| Cow::Owned(Family::parse_str(raw)))
+            }
+            fn source(&self, xref: &str) -> Option<Cow<'_, Source>> {
+                self.fetch(xref)
+                    .map(|raw| Cow::Owned(Source::parse_str(raw)))
+            }
+            fn repository(&self, xref: &str) -> Option<Cow<'_, Repository>> {
+                self.fetch(xref)
+                    .map(|raw| Cow::Owned(Repository::parse_str(raw)))
+            }
+            fn submitter(&self, xref: &str) -> Option<Cow<'_, Submitter>> {
+                self.fetch(xref)
+                    .map(|raw| Cow::Owned(Submitter::parse_str(raw)))
+            }
+        }
+
+        let store = LazyStore {
+            records: HashMap::from([
+                (
+                    "@CHILD@",
+                    "0 @CHILD@ INDI\n1 NAME /Child/\n1 FAMC @FAMILY@\n",
+                ),
+                (
+                    "@FAMILY@",
+                    "0 @FAMILY@ FAM\n1 HUSB @FATHER@\n1 CHIL @CHILD@\n",
+                ),
+                (
+                    "@FATHER@",
+                    "0 @FATHER@ INDI\n1 NAME /Father/\n1 FAMS @FAMILY@\n",
+                ),
+            ]),
+            fetches: Cell::new(0),
+        };
+
+        let child = store.individual("@CHILD@").unwrap();
+        let families = store.child_families(&child);
+        assert_eq!(families.len(), 1);
+        let members = store.members(&families[0]);
+        assert_eq!(members[0].xref.as_deref(), Some("@FATHER@"));
+        assert_eq!(store.fetches.get(), 4);
+
+        // the parsed document resolves the same links
+        let data = Parser::new(read_relative("./tests/fixtures/simple.ged").chars()).parse_record();
+        let child = data.individual("@CHILD@").unwrap();
+        let father = data.individual("@FATHER@").unwrap();
+        assert_eq!(
+            data.child_families(&child)[0].xref.as_deref(),
+            Some("@FAMILY@")
+        );
+        assert_eq!(
+            data.spouse_families(&father)[0].xref.as_deref(),
+            Some("@FAMILY@")
+        );
+    }
+
+    #[test]
+    fn traces_surname_lines() {
+        use gedcom::analysis::{surname_line, Lineage};
+
+        let data =
+            Parser::new(read_relative("./tests/fixtures/lineage.ged").chars()).parse_record();
+
+        // the adopted family of @I4@ is not followed
+        let paternal = surname_line(&data, "@I1@", Lineage::Paternal);
+        let surnames: Vec<_> = paternal.iter().map(|a| a.surname.as_deref()).collect();
+        assert_eq!(surnames, vec![Some("Smith"), Some("Smith"), Some("Smith")]);
+        assert_eq!(paternal[2].xref, "@I4@");
+
+        let maternal = surname_line(&data, "@I1@", Lineage::Maternal);
+        let surnames: Vec<_> = maternal.iter().map(|a| a.surname.as_deref()).collect();
+        assert_eq!(surnames, vec![Some("Smith"), Some("Jones"), Some("Brown")]);
+
+        assert!(surname_line(&data, "@MISSING@", Lineage::Paternal).is_empty());
+    }
+
+    #[test]
+    fn detects_header_producer() {
+        let header = Header::parse_str(
+            "0 HEAD\n1 SOUR RootsMagic\n2 NAME RootsMagic\n2 VERS 8.0\n2 CORP RootsMagic, Inc.\n3 ADDR PO Box 495\n1 CHAR UTF-8\n",
+        );
+        assert_eq!(header.producer(), Some(Producer::RootsMagic));
+        assert_eq!(header.producer_version(), Some("8.0"));
+        assert_eq!(header.encoding.as_deref(), Some("UTF-8"));
+
+        let header =
+            Header::parse_str("0 HEAD\n1 SOUR FTM\n2 NAME Family Tree Maker for Windows\n");
+        assert_eq!(header.producer(), Some(Producer::FamilyTreeMaker));
+
+        let data = Parser::new(read_relative("./tests/fixtures/sample.ged").chars()).parse_record();
+        assert_eq!(
+            data.header.producer(),
+            Some(Producer::Other("GS".to_string()))
+        );
+        assert_eq!(data.header.producer_version(), Some("5.5.5"));
+
+        assert_eq!(Header::parse_str("0 HEAD\n").producer(), None);
+    }
+
+    #[test]
+    fn parses_event_addresses() {
+        let individual = Individual::parse_str(
+            "0 @I1@ INDI\n1 RESI\n2 DATE 1900\n2 ADDR 12 Main Street\n3 CITY Springfield\n3 POST 12345\n2 PLAC Springfield, Illinois\n",
+        );
+        let events = individual.events();
+        let address = events[0].address.as_ref().unwrap();
+        assert_eq!(address.value.as_deref(), Some("12 Main Street"));
+        assert_eq!(address.city.as_deref(), Some("Springfield"));
+        assert_eq!(address.post.as_deref(), Some("12345"));
+        assert_eq!(events[0].place_name(), Some("Springfield, Illinois"));
+    }
+
+    #[test]
+    fn parses_pointer_and_inline_repo_citations() {
+        use gedcom::types::{RepoReference, Source};
+
+        let source = Source::parse_str(
+            "0 @S1@ SOUR\n1 TITL Parish register\n1 REPO @R1@\n2 CALN 12-A\n1 REPO\n2 NAME County Archive\n2 ADDR Market Square\n1 REPO Family Bible\n",
+        );
+        let citations = source.repo_citations();
+        assert_eq!(citations.len(), 3);
+        assert_eq!(citations[0].repository.xref(), Some("@R1@"));
+        assert_eq!(citations[0].call_number.as_deref(), Some("12-A"));
+
+        let RepoReference::Inline(archive) = &citations[1].repository else {
+            panic!("expected an inline repository");
+        };
+        assert_eq!(archive.name.as_deref(), Some("County Archive"));
+        assert!(archive.address.is_some());
+
+        let RepoReference::Inline(bible) = &citations[2].repository else {
+            panic!("expected an inline repository");
+        };
+        assert_eq!(bible.name.as_deref(), Some("Family Bible"));
+    }
+
+    #[test]
+    fn parses_structured_citation_pages() {
+        let individual = Individual::parse_str(
+            "0 @I1@ INDI\n1 BIRT\n2 SOUR @S1@\n3 PAGE Database: 1880 Census; Page: 12; Record #: 345; Url: https://example.com/r\n",
+        );
+        let citation = &individual.events()[0].citations[0];
+        assert_eq!(
+            citation.page.as_deref(),
+            Some("Database: 1880 Census; Page: 12; Record #: 345; Url: https://example.com/r")
+        );
+        assert_eq!(
+            citation.page_fields(),
+            vec![
+                ("Database", "1880 Census"),
+                ("Page", "12"),
+                ("Record #", "345"),
+                ("Url", "https://example.com/r"),
+            ]
+        );
+
+        let individual = Individual::parse_str("0 @I1@ INDI\n1 BIRT\n2 SOUR @S1@\n3 PAGE p. 42\n");
+        assert!(individual.events()[0].citations[0].page_fields().is_empty());
+    }
+
+    #[test]
+    fn finds_partners_of_family_parents() {
+        let family = Family::parse_str("0 @F1@ FAM\n1 WIFE @I2@\n1 HUSB @I1@\n1 CHIL @I3@\n");
+        assert_eq!(
+            family.parents(),
+            vec![("@I1@", ParentTag::Husband), ("@I2@", ParentTag::Wife)]
+        );
+        assert_eq!(family.partner_of("@I1@"), Some("@I2@"));
+        assert_eq!(family.partner_of("@I2@"), Some("@I1@"));
+        assert_eq!(family.partner_of("@I3@"), None);
+
+        let mut family = Family::new(None);
+        assert_eq!(
+            family.add_parent("@I1@".to_string(), ParentTag::Wife),
+            Ok(())
+        );
+        assert_eq!(
+            family.add_parent("@I2@".to_string(), ParentTag::Wife),
+            Ok(())
+        );
+        assert_eq!(
+            family.add_parent("@I3@".to_string(), ParentTag::Husband),
+            Err("@I3@".to_string())
+        );
+        assert_eq!(family.individual1.as_deref(), Some("@I2@"));
+        assert_eq!(family.partner_of("@I2@"), Some("@I1@"));
+    }
+
+    #[test]
+    fn computes_marriage_durations() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 BIRT\n2 DATE 10 MAR 1900\n1 DEAT\n2 DATE 5 JUN 1960\n\
+            0 @I2@ INDI\n1 BIRT\n2 DATE ABT 1904\n\
+            0 @I3@ INDI\n1 BIRT\n2 DATE 1 JAN 1910\n\
+            0 @F1@ FAM\n1 HUSB @I1@\n1 WIFE @I2@\n1 MARR\n2 DATE 1 JUN 1925\n1 DIV\n2 DATE 2 MAY 1931\n\
+            0 @F2@ FAM\n1 HUSB @I1@\n1 WIFE @I3@\n1 MARR\n2 DATE 12 AUG 1935\n\
+            0 @F3@ FAM\n1 HUSB @I3@\n0 TRLR\n";
+        let data = Parser::new(content.chars()).parse_record();
+
+        let durations = marriage_durations(&data);
+        assert_eq!(durations.len(), 2);
+        assert_eq!(durations[0].family, "@F1@");
+        assert_eq!(durations[0].end, Some(MarriageEnd::Divorce));
+        assert_eq!(durations[0].ended.as_deref(), Some("2 MAY 1931"));
+        assert_eq!(durations[0].years, Some(5));
+        assert_eq!(
+            durations[1].end,
+            Some(MarriageEnd::Death("@I1@".to_string()))
+        );
+        assert_eq!(durations[1].years, Some(24));
+
+        let stats = data.statistics();
+        assert_eq!(stats.families, 3);
+        // ages 25 & 21 at the first marriage, 35 & 25 at the second
+        assert_eq!(stats.median_age_at_marriage, Some(25.0));
+        assert_eq!(stats.remarriage_rate, Some(1.0 / 3.0));
+    }
+
+    #[test]
+    fn compares_gedcom_versions() {
+        let header = Header::parse_str("0 HEAD\n1 GEDC\n2 VERS 7.0.14\n2 FORM LINEAGE-LINKED\n");
+        assert_eq!(header.version(), Some(GedcomVersion::V7_0));
+
+        assert!(GedcomVersion::V5_5 < GedcomVersion::V5_5_1);
+        assert!(GedcomVersion::V5_5_1 < GedcomVersion::V7_0);
+        assert_eq!(
+            GedcomVersion::from("5.5.5"),
+            GedcomVersion::Other("5.5.5".to_string())
+        );
+        assert!(GedcomVersion::from("5.5.5") > GedcomVersion::V5_5_1);
+        assert!(GedcomVersion::from("5.5.5") < GedcomVersion::V7_0);
+        assert_eq!(
+            GedcomVersion::from("draft").partial_cmp(&GedcomVersion::V7_0),
+            None
+        );
+        assert_eq!(GedcomVersion::V5_5_1.to_string(), "5.5.1");
+    }
+
+    #[test]
+    fn displays_names_in_order() {
+        let individual = Individual::parse_str("0 @I1@ INDI\n1 NAME John Paul /Doe/ Jr.\n");
+        assert_eq!(
+            individual.display_name(NameOrder::Western).as_deref(),
+            Some("John Paul Doe Jr.")
+        );
+        assert_eq!(
+            individual.display_name(NameOrder::Eastern).as_deref(),
+            Some("Doe John Paul Jr.")
+        );
+        assert_eq!(
+            individual
+                .display_name(NameOrder::SurnameFirstComma)
+                .as_deref(),
+            Some("Doe, John Paul Jr.")
+        );
+
+        // the language of the name overrides the configured order
+        let individual = Individual::parse_str(
+            "0 @I2@ INDI\n1 NAME Ferenc /Molnár/\n2 GIVN Ferenc\n2 SURN Molnár\n2 LANG hu\n",
+        );
+        let name = individual.primary_name().unwrap();
+        assert_eq!(name.language_order(), Some(NameOrder::Eastern));
+        assert_eq!(name.display(NameOrder::Western), "Molnár Ferenc");
+        assert_eq!(name.display(NameOrder::SurnameFirstComma), "Molnár, Ferenc");
+
+        // but not that of a name taken on immigration
+        let individual = Individual::parse_str(
+            "0 @I3@ INDI\n1 NAME Frank /Miller/\n2 TYPE IMMIGRANT\n2 LANG hu\n",
+        );
+        let name = individual.primary_name().unwrap();
+        assert_eq!(name.language_order(), None);
+        assert_eq!(name.display(NameOrder::Western), "Frank Miller");
+
+        let name = Name {
+            surname: Some("Gogh".to_string()),
+            surname_prefix: Some("van".to_string()),
+            given: Some("Vincent".to_string()),
+            ..Name::default()
+        };
+        assert_eq!(name.display(NameOrder::Western), "Vincent van Gogh");
+    }
+
+    #[test]
+    fn extracts_branch_around_individual() {
+        let data =
+            Parser::new(read_relative("./tests/fixtures/lineage.ged").chars()).parse_record();
+
+        let branch = extract_branch(&data, "@I2@", 1, 1);
+        let xrefs: Vec<&str> = branch
+            .individuals
+            .iter()
+            .filter_map(|i| i.xref.as_deref())
+            .collect();
+        // the root, its partner, its child & its father
+        assert_eq!(xrefs, vec!["@I1@", "@I2@", "@I3@", "@I4@"]);
+        let families: Vec<&str> = branch
+            .families
+            .iter()
+            .filter_map(|f| f.xref.as_deref())
+            .collect();
+        assert_eq!(families, vec!["@F1@", "@F2@"]);
+        // links to the families left out are removed
+        assert_eq!(branch.individuals[2].families.len(), 1);
+        assert_eq!(branch.individuals[3].families.len(), 1);
+
+        assert!(extract_branch(&data, "@I9@", 1, 1).individuals.is_empty());
+    }
+
+    #[test]
+    fn privatizes_living_individuals() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 NAME Ada /Smith/\n1 BIRT\n2 DATE 4 JUL 1990\n\
+            0 @I2@ INDI\n1 NAME John /Smith/\n1 BIRT\n2 DATE 1950\n1 DEAT\n2 DATE 2010\n\
+            0 @I3@ INDI\n1 NAME Mary /Jones/\n1 BIRT\n2 DATE ABT 1890\n0 TRLR\n";
+        let mut data = Parser::new(content.chars()).parse_record();
+
+        let filter = LivingFilter {
+            current_year: 2026,
+            max_age: 110,
+        };
+        assert_eq!(filter.privatize(&mut data), 1);
+        let living = &data.individuals[0];
+        assert_eq!(living.xref.as_deref(), Some("@I1@"));
+        assert_eq!(
+            living.primary_name().unwrap().value.as_deref(),
+            Some("Living /Smith/")
+        );
+        assert!(living.events().is_empty());
+        assert_eq!(data.individuals[1].events().len(), 2);
+        assert_eq!(data.individuals[2].events().len(), 1);
+    }
+
+    #[test]
+    fn matches_gedcom_errors() {
+        use std::error::Error;
+
+        let error = gedcom::GedcomError::UnexpectedTag {
+            line: 12,
+            tag: "_FOO".to_string(),
+            context: "Individual".to_string(),
+        };
+        assert_eq!(error.line(), Some(12));
+        assert_eq!(error.to_string(), "line 12: Unhandled Individual Tag: _FOO");
+
+        let missing = std::fs::read("./tests/fixtures/missing.ged").unwrap_err();
+        let error = gedcom::GedcomError::from(missing);
+        assert!(matches!(error, gedcom::GedcomError::Io(_)));
+        assert!(error.source().is_some());
+        assert_eq!(error.line(), None);
+    }
+
+    #[test]
+    fn updates_change_dates_automatically() {
+        use gedcom::{ChangeDatePolicy, GedcomData};
+
+        let mut data = GedcomData::default();
+        data.add_individual(Individual::new(Some("@I1@".to_string())));
+        assert!(data.individuals[0].last_updated.is_none());
+
+        data.change_dates = ChangeDatePolicy::Automatic;
+        data.add_individual(Individual::new(Some("@I2@".to_string())));
+        let added = &data.individuals[1];
+        assert!(added.created.is_some());
+        assert_eq!(added.created, added.last_updated);
+
+        let edited = data.edit_individual("@I1@", |individual| individual.sex = Gender::Female);
+        assert_eq!(edited, Some(()));
+        let individual = &data.individuals[0];
+        assert!(individual.created.is_none());
+        // ie. "16 OCT 2026 08:30:05"
+        assert!(individual.last_updated.as_ref().unwrap().contains(':'));
+        assert!(data.edit_family("@F1@", |_| ()).is_none());
+    }
+
+    #[test]
+    fn builds_place_tree() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n1 PLAC\n2 FORM City, County, State, Country\n\
+            0 @I1@ INDI\n1 BIRT\n2 PLAC Boston, Suffolk, Massachusetts, USA\n\
+            1 DEAT\n2 PLAC Salem, , Massachusetts, USA\n\
+            0 @I2@ INDI\n1 BIRT\n2 PLAC Massachusetts, USA\n\
+            1 RESI\n2 PLAC Ontario, Canada\n3 FORM Province, Country\n0 TRLR\n";
+        let data = Parser::new(content.chars()).parse_record();
+        assert_eq!(
+            data.header.place_form.as_deref(),
+            Some("City, County, State, Country")
+        );
+
+        let tree = data.place_tree();
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree[0].name, "Canada");
+        assert_eq!(
+            tree[0].children[0].jurisdiction.as_deref(),
+            Some("Province")
+        );
+
+        let usa = &tree[1];
+        assert_eq!(usa.jurisdiction.as_deref(), Some("Country"));
+        assert_eq!(usa.total_events(), 3);
+        let massachusetts = &usa.children[0];
+        assert_eq!(massachusetts.jurisdiction.as_deref(), Some("State"));
+        assert_eq!(massachusetts.events, 1);
+        let names: Vec<&str> = massachusetts
+            .children
+            .iter()
+            .map(|node| node.name.as_str())
+            .collect();
+        // the empty county of Salem is skipped
+        assert_eq!(names, vec!["Salem", "Suffolk"]);
+        assert_eq!(
+            massachusetts.children[0].jurisdiction.as_deref(),
+            Some("City")
+        );
+        assert_eq!(massachusetts.children[1].children[0].name, "Boston");
+    }
+
+    #[test]
+    fn gathers_source_text_of_events() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 BIRT\n2 SOUR @S1@\n3 PAGE p. 12\n3 DATA\n4 DATE 3 APR 1881\n\
+            4 TEXT John Smith, son, aged 4\n5 CONT born Boston\n2 SOUR @S1@\n3 PAGE p. 13\n\
+            2 SOUR @S2@\n\
+            0 @S1@ SOUR\n1 TITL 1881 Census\n1 TEXT Enumeration district 7\n\
+            0 @S2@ SOUR\n1 ABBR Parish register\n0 TRLR\n";
+        let data = Parser::new(content.chars()).parse_record();
+        let birth = &data.individuals[0].events()[0];
+
+        let excerpts = source_excerpts(&data, birth);
+        assert_eq!(excerpts.len(), 2);
+        assert_eq!(excerpts[0].origin, ExcerptOrigin::Citation);
+        assert_eq!(excerpts[0].label(), "1881 Census, p. 12");
+        assert_eq!(excerpts[1].origin, ExcerptOrigin::Source);
+        assert_eq!(birth.citations[0].date.as_deref(), Some("3 APR 1881"));
+        assert_eq!(
+            source_text(&data, birth),
+            "[1881 Census, p. 12] John Smith, son, aged 4\nborn Boston\n\n\
+             [1881 Census] Enumeration district 7"
+        );
+    }
+
+    #[test]
+    fn replaces_place_names() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 BIRT\n2 PLAC NY, USA\n1 DEAT\n2 PLAC Boston, USA\n\
+            1 BAPL\n2 PLAC NY, USA\n\
+            0 @F1@ FAM\n1 MARR\n2 PLAC NY, USA\n1 SLGS\n2 PLAC NY, USA\n\
+            0 @S1@ SOUR\n1 DATA\n2 EVEN CENS\n3 PLAC NY, USA\n0 TRLR\n";
+        let mut data = Parser::new(content.chars()).parse_record();
+
+        let mapping = [
+            ("NY, USA", "New York, United States"),
+            ("Paris, France", "Paris, Île-de-France, France"),
+        ]
+        .iter()
+        .map(|(from, to)| (from.to_string(), to.to_string()))
+        .collect();
+        let counts = data.replace_places(&mapping);
+        assert_eq!(counts.len(), 1);
+        assert_eq!(counts["NY, USA"], 5);
+        assert_eq!(
+            data.individuals[0].places(),
+            vec!["New York, United States", "Boston, USA"]
+        );
+        assert_eq!(
+            data.families[0].events()[0].place_name(),
+            Some("New York, United States")
+        );
+        let ordinances = data.individuals[0]
+            .lds_ordinances
+            .iter()
+            .chain(&data.families[0].lds_ordinances);
+        for ordinance in ordinances {
+            let place = ordinance.place.as_ref().map(|place| place.value.as_str());
+            assert_eq!(place, Some("New York, United States"));
+        }
+    }
+
+    #[test]
+    fn merges_duplicate_individuals() {
+        use gedcom::merge::{MergeConflict, MergeError};
+
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 NAME John /Smith/\n1 SEX M\n1 BIRT\n2 DATE 1 JAN 1900\n\
+            1 DEAT\n2 DATE 1970\n2 SOUR @S1@\n1 FAMS @F1@\n\
+            0 @I2@ INDI\n1 NAME Johnny /Smith/\n1 BIRT\n2 DATE 2 JAN 1900\n\
+            1 DEAT\n2 DATE 1970\n2 SOUR @S2@\n1 FAMC @F2@\n\
+            0 @F1@ FAM\n1 HUSB @I1@\n0 @F2@ FAM\n1 CHIL @I2@\n0 TRLR\n";
+        let mut data = Parser::new(content.chars()).parse_record();
+
+        assert_eq!(
+            data.merge_individuals("@I1@", "@I1@").unwrap_err(),
+            MergeError::SameIndividual
+        );
+        assert_eq!(
+            data.merge_individuals("@I1@", "@I9@").unwrap_err(),
+            MergeError::NotFound("@I9@".to_string())
+        );
+
+        let report = data.merge_individuals("@I1@", "@I2@").unwrap();
+        assert_eq!(data.individuals.len(), 1);
+        assert_eq!(report.moved_events, 1);
+        assert_eq!(report.moved_links, 1);
+        assert_eq!(report.conflicts.len(), 2);
+        assert!(matches!(report.conflicts[0], MergeConflict::Name { .. }));
+        assert!(matches!(report.conflicts[1], MergeConflict::Event { .. }));
+
+        let individual = &data.individuals[0];
+        // both births are kept, the deaths are the same event cited twice
+        assert_eq!(individual.events().len(), 3);
+        assert_eq!(individual.events()[1].citations.len(), 2);
+        assert_eq!(individual.families.len(), 2);
+        assert_eq!(data.families[1].children, vec!["@I1@".to_string()]);
+    }
+
+    #[test]
+    fn merges_every_fact_of_individuals() {
+        use gedcom::merge::MergeConflict;
+
+        let content = "0 HEAD\n\
+            0 @I1@ INDI\n1 NAME John /Doe/\n1 RESN privacy\n1 RIN 11\n\
+            0 @I2@ INDI\n1 NAME John /Doe/\n1 NCHI 3\n1 RESN locked\n1 OBJE @M1@\n\
+            1 ASSO @I3@\n2 RELA Godson\n\
+            1 BAPL\n2 DATE 1 JAN 1900\n2 TEMP SLAKE\n\
+            1 NOTE Emigrated in 1921\n\
+            1 RFN 1:42\n1 AFN 9X1-2\n1 REFN 1842\n2 TYPE census\n1 RIN 12\n\
+            0 @I3@ INDI\n1 NAME Jane /Roe/\n1 ASSO @I2@\n2 RELA Godmother\n\
+            1 CHR\n2 ASSO @I2@\n3 RELA Witness\n0 TRLR\n";
+        let mut data = gedcom::parse_document(content).unwrap();
+
+        let report = data.merge_individuals("@I1@", "@I2@").unwrap();
+        let kept = &data.individuals[0];
+        assert_eq!(kept.num_children, Some(3));
+        assert_eq!(kept.multimedia[0].xref, "@M1@");
+        assert_eq!(kept.associations[0].relation.as_deref(), Some("Godson"));
+        assert_eq!(kept.lds_ordinances[0].temple.as_deref(), Some("SLAKE"));
+        assert_eq!(kept.notes[0].text(), "Emigrated in 1921");
+        assert_eq!(kept.permanent_record_file_number.as_deref(), Some("1:42"));
+        assert_eq!(kept.ancestral_file_number.as_deref(), Some("9X1-2"));
+        assert_eq!(kept.user_reference_numbers[0].number, "1842");
+        assert_eq!(kept.automated_record_id.as_deref(), Some("11"));
+        assert_eq!(kept.restriction_notice.as_deref(), Some("privacy"));
+        assert!(report.conflicts.iter().any(|conflict| matches!(
+            conflict,
+            MergeConflict::Field { tag: "RESN", removed, .. } if removed == "locked"
+        )));
+        assert!(report.conflicts.iter().any(|conflict| matches!(
+            conflict,
+            MergeConflict::Field { tag: "RIN", removed, .. } if removed == "12"
+        )));
+        // pointers from other individuals follow the merge
+        let godmother = &data.individuals[1];
+        assert_eq!(godmother.associations[0].xref, "@I1@");
+        assert_eq!(godmother.events()[0].associations[0].xref, "@I1@");
+    }
+
+    #[test]
+    fn merges_an_individual_without_losing_any_field() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n\
+            0 @I2@ INDI\n1 RESN locked\n1 NAME Ann /Doe/\n2 NOTE Maiden name\n\
+            1 NAME Annie /Doe/\n1 SEX F\n1 NCHI 2\n1 BIRT\n2 DATE 1 JAN 1900\n1 DEAT Y\n\
+            1 FAMS @F1@\n1 FAMC @F2@\n2 PEDI birth\n1 OBJE @M1@\n\
+            1 ASSO @I3@\n2 RELA Godmother\n1 BAPL\n2 TEMP SLAKE\n1 NOTE Emigrated\n\
+            1 RFN 1:42\n1 AFN 9X1-2\n1 REFN 1842\n2 TYPE census\n1 RIN 12\n\
+            1 _MILT Navy\n1 CHAN\n2 DATE 2 FEB 2020\n1 CREA\n2 DATE 1 JAN 2019\n\
+            0 @I3@ INDI\n0 @F1@ FAM\n1 WIFE @I2@\n0 @F2@ FAM\n1 CHIL @I2@\n\
+            0 @M1@ OBJE\n1 FILE photo.jpg\n0 TRLR\n";
+        let mut data = gedcom::parse_document(content).unwrap();
+        // the lines of the individual `xref` as written
+        let lines = |data: &gedcom::GedcomData, xref: &str| {
+            let written = data.to_gedcom_string();
+            let start = written.find(&format!("0 {xref} INDI\n")).unwrap();
+            let record = &written[start..];
+            let end = record[1..].find("\n0 ").map_or(record.len(), |end| end + 2);
+            record[..end].to_string()
+        };
+        let removed = lines(&data, "@I2@").replace("@I2@", "@I1@");
+
+        let report = data.merge_individuals("@I1@", "@I2@").unwrap();
+        assert!(report.conflicts.is_empty());
+        assert_eq!(lines(&data, "@I1@"), removed);
+        let kept = &data.individuals[0];
+        assert_eq!(kept.created.as_deref(), Some("1 JAN 2019"));
+        assert_eq!(kept.last_updated.as_deref(), Some("2 FEB 2020"));
+
+        // the first creation & the last change are kept
+        let content = "0 HEAD\n\
+            0 @I1@ INDI\n1 CHAN\n2 DATE 1 JAN 2021\n1 CREA\n2 DATE 1 JAN 2020\n\
+            0 @I2@ INDI\n1 CHAN\n2 DATE 1 JAN 2020\n1 CREA\n2 DATE 1 JAN 2019\n0 TRLR\n";
+        let mut data = gedcom::parse_document(content).unwrap();
+        data.merge_individuals("@I1@", "@I2@").unwrap();
+        let kept = &data.individuals[0];
+        assert_eq!(kept.created.as_deref(), Some("1 JAN 2019"));
+        assert_eq!(kept.last_updated.as_deref(), Some("1 JAN 2021"));
+    }
+
+    #[test]
+    fn numbers_descendants_and_ancestors() {
+        let data =
+            Parser::new(read_relative("./tests/fixtures/lineage.ged").chars()).parse_record();
+
+        let numbers = number_descendants(&data, "@I6@", NumberingSystem::DAboville);
+        assert_eq!(numbers.len(), 4);
+        assert_eq!(numbers["@I6@"], "1");
+        assert_eq!(numbers["@I1@"], "1.1.1.1");
+        let numbers = number_descendants(&data, "@I6@", NumberingSystem::Generation);
+        assert_eq!(numbers["@I2@"], "3");
+
+        let children: String = (1..=10).map(|i| format!("1 CHIL @C{i}@\n")).collect();
+        let content =
+            format!("0 HEAD\n0 @P@ INDI\n1 FAMS @F@\n0 @F@ FAM\n1 HUSB @P@\n{children}0 TRLR\n");
+        let family = Parser::new(content.chars()).parse_record();
+        let numbers = number_descendants(&family, "@P@", NumberingSystem::Henry);
+        assert_eq!(numbers["@C9@"], "19");
+        assert_eq!(numbers["@C10@"], "1(10)");
+
+        let sosa = sosa_numbers(&data, "@I1@");
+        assert_eq!(sosa.len(), 6);
+        assert_eq!(sosa["@I2@"], 2);
+        assert_eq!(sosa["@I3@"], 3);
+        assert_eq!(sosa["@I5@"], 7);
+        assert_eq!(sosa["@I6@"], 8);
+    }
+
+    #[test]
+    fn writes_narrative_report() {
+        use gedcom::reports::{narrative, NarrativeOptions, ReportFormat};
+
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 NAME John /Doe/\n1 SEX M\n1 BIRT\n2 DATE 1 JAN 1899\n2 PLAC Boston\n\
+            1 DEAT\n2 DATE 1970\n1 FAMS @F1@\n\
+            0 @I2@ INDI\n1 NAME Jane /Roe/\n1 SEX F\n1 FAMS @F1@\n\
+            0 @I3@ INDI\n1 NAME Ann /Doe/\n1 SEX F\n1 FAMC @F1@\n\
+            0 @I4@ INDI\n1 NAME Paul /Doe/\n1 FAMC @F1@\n\
+            0 @F1@ FAM\n1 HUSB @I1@\n1 WIFE @I2@\n1 CHIL @I3@\n1 CHIL @I4@\n\
+            1 MARR\n2 DATE ABT 1920\n0 TRLR\n";
+        let data = Parser::new(content.chars()).parse_record();
+
+        let options = NarrativeOptions {
+            format: ReportFormat::Markdown,
+            ..NarrativeOptions::default()
+        };
+        assert_eq!(
+            narrative(&data, "@I1@", &options),
+            "### 1. John Doe\n\n\
+             John Doe was born on 1 JAN 1899 in Boston. He married Jane Roe about 1920. \
+             They had 2 children: Ann Doe and Paul Doe. He died in 1970.\n\n\
+             ### 1.1. Ann Doe\n\n### 1.2. Paul Doe"
+        );
+
+        let options = NarrativeOptions {
+            generations: 0,
+            ..NarrativeOptions::default()
+        };
+        assert!(narrative(&data, "@I1@", &options).starts_with("1. John Doe\n\nJohn Doe was born"));
+        assert!(!narrative(&data, "@I1@", &options).contains("1.1."));
+    }
+
+    #[test]
+    fn exports_records_as_markdown() {
+        use gedcom::export::markdown;
+
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 NAME John /Doe/\n1 SEX M\n1 BIRT\n2 DATE 1 JAN 1899\n2 PLAC Boston | MA\n\
+            2 SOUR @S1@\n3 PAGE p. 12\n1 DEAT\n2 DATE 1970\n2 SOUR @S1@\n3 PAGE p. 12\n1 FAMS @F1@\n\
+            0 @I2@ INDI\n1 NAME Jane /Roe/\n1 FAMS @F1@\n\
+            0 @I3@ INDI\n1 NAME Ann /Doe/\n1 FAMC @F1@\n\
+            0 @F1@ FAM\n1 HUSB @I1@\n1 WIFE @I2@\n1 CHIL @I3@\n1 MARR\n2 DATE 1920\n\
+            0 @S1@ SOUR\n1 TITL 1900 Census\n0 TRLR\n";
+        let data = Parser::new(content.chars()).parse_record();
+
+        assert_eq!(
+            markdown::individual(&data, &data.individuals[0]),
+            "# John Doe\n\n**Sex:** Male\n\n\
+             | Fact | Date | Place |\n| --- | --- | --- |\n\
+             | Birth[^1] | 1 JAN 1899 | Boston \\| MA |\n| Death[^1] | 1970 |  |\n\n\
+             ## Families\n\n- Spouse of Jane Roe (@F1@)\n\n[^1]: 1900 Census, p. 12\n"
+        );
+        assert_eq!(
+            markdown::family(&data, &data.families[0]),
+            "# Family of John Doe & Jane Roe\n\n\
+             | Fact | Date | Place |\n| --- | --- | --- |\n| Marriage | 1920 |  |\n\n\
+             ## Children\n\n- Ann Doe (@I3@)\n"
+        );
+    }
+
+    #[test]
+    fn checks_entered_field_values() {
+        use gedcom::entry::{
+            check_date, check_email, check_sex, check_text, check_xref, FieldError,
+        };
+
+        assert!(check_xref("@I1@").is_ok());
+        assert!(matches!(check_xref("I1"), Err(FieldError::Xref(_))));
+        assert!(check_xref("@I 1@").is_err());
+        assert!(check_xref("@@").is_err());
+        assert!(check_xref("@_X@").is_err());
+        assert!(check_xref("@I12345678901234567890@").is_err());
+
+        for date in [
+            "1899",
+            "JAN 1899",
+            "29 FEB 1904",
+            "ABT 1850",
+            "BET 1850 AND 1860",
+            "FROM 3 MAR 1900 TO 1910",
+            "TO 1920",
+        ] {
+            assert!(check_date(date).is_ok(), "{}", date);
+        }
+        for date in [
+            "",
+            "29 FEB 1900",
+            "31 APR 1900",
+            "1 Foo 1900",
+            "BET 1850",
+            "ABT",
+            "next year",
+        ] {
+            assert!(
+                matches!(check_date(date), Err(FieldError::Date(_))),
+                "{}",
+                date
+            );
+        }
+
+        assert!(matches!(check_sex("F"), Ok(Gender::Female)));
+        assert!(matches!(check_sex("X"), Ok(Gender::Nonbinary)));
+        assert_eq!(
+            check_sex("female").unwrap_err(),
+            FieldError::Sex("female".to_string())
+        );
+
+        assert!(check_email("ada@example.com").is_ok());
+        for email in [
+            "ada",
+            "@example.com",
+            "ada@example",
+            "ada@@example.com",
+            "a da@example.com",
+            "ada@example.",
+        ] {
+            assert!(
+                matches!(check_email(email), Err(FieldError::Email(_))),
+                "{}",
+                email
+            );
+        }
+
+        assert!(check_text("Salt Lake City").is_ok());
+        assert!(matches!(check_text("two\nlines"), Err(FieldError::Line(_))));
+        assert_eq!(
+            check_sex("Q").unwrap_err().to_string(),
+            "Invalid sex \"Q\", expected M, F, X or U"
+        );
+    }
+
+    #[test]
+    fn extracts_media_only_below_allowed_roots() {
+        use gedcom::media::{safe_relative_path, MediaError, MediaExtractor};
+        use std::path::{Path, PathBuf};
+
+        assert_eq!(
+            safe_relative_path("photos\\./john.jpg").unwrap(),
+            Path::new("photos").join("john.jpg")
+        );
+        for reference in [
+            "/etc/passwd",
+            "C:\\photo.jpg",
+            "file:///etc/passwd",
+            "\\\\server\\share",
+        ] {
+            assert!(
+                matches!(safe_relative_path(reference), Err(MediaError::Absolute(_))),
+                "{}",
+                reference
+            );
+        }
+        assert!(matches!(
+            safe_relative_path("photos/../../etc/passwd"),
+            Err(MediaError::Traversal(_))
+        ));
+        assert!(matches!(safe_relative_path("./"), Err(MediaError::Empty)));
+
+        let root = std::env::temp_dir().join(format!("gedcom-media-{}", std::process::id()));
+        let extractor = MediaExtractor::new(vec![root.clone()]);
+        let written = extractor
+            .extract(&root.join("tree"), "photos/john.jpg", b"jpeg")
+            .unwrap();
+        assert_eq!(written, root.join("tree").join("photos").join("john.jpg"));
+        assert_eq!(std::fs::read(&written).unwrap(), b"jpeg");
+
+        let elsewhere = PathBuf::from("/tmp/elsewhere");
+        assert!(matches!(
+            extractor.destination(&elsewhere, "john.jpg"),
+            Err(MediaError::RootNotAllowed(_))
+        ));
+        assert!(matches!(
+            extractor.destination(&root.join(".."), "john.jpg"),
+            Err(MediaError::RootNotAllowed(_))
+        ));
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(std::env::temp_dir(), root.join("tree").join("link"))
+                .unwrap();
+            assert!(matches!(
+                extractor.extract(&root.join("tree"), "link/john.jpg", b"jpeg"),
+                Err(MediaError::OutsideRoot(_))
+            ));
+        }
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn repairs_one_sided_family_links() {
+        use gedcom::repair::{LinkIssue, RepairMode};
+
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 NAME John /Doe/\n1 SEX M\n1 FAMS @F1@\n\
+            0 @I2@ INDI\n1 NAME Jane /Roe/\n1 SEX F\n\
+            0 @I3@ INDI\n1 NAME Jim /Doe/\n1 FAMC @F1@\n1 FAMS @F9@\n\
+            0 @F1@ FAM\n1 WIFE @I2@\n0 TRLR\n";
+        let mut data = Parser::new(content.chars()).parse_record();
+
+        let issues = |repairs: Vec<gedcom::repair::LinkRepair>| {
+            repairs
+                .into_iter()
+                .map(|repair| (repair.issue.to_string(), repair.repaired))
+                .collect::<Vec<_>>()
+        };
+        let expected = vec![
+            (
+                "@I1@ is a spouse in @F1@, which lacks them as a parent".to_string(),
+                false,
+            ),
+            (
+                "@I3@ is a child in @F1@, which lacks them as a child".to_string(),
+                false,
+            ),
+            ("@I3@ links to missing record @F9@".to_string(), false),
+            (
+                "@F1@ lists @I2@ as a parent, who lacks a FAMS link".to_string(),
+                false,
+            ),
+        ];
+        assert_eq!(issues(data.repair_links(RepairMode::Report)), expected);
+        assert!(data.families[0].children.is_empty());
+
+        let repairs = data.repair_links(RepairMode::Fix);
+        assert_eq!(
+            repairs
+                .iter()
+                .map(|repair| repair.repaired)
+                .collect::<Vec<_>>(),
+            vec![true, true, false, true]
+        );
+        let family = &data.families[0];
+        assert_eq!(family.individual1.as_deref(), Some("@I1@"));
+        assert_eq!(family.children, vec!["@I3@".to_string()]);
+        assert!(data.individuals[1]
+            .families
+            .iter()
+            .any(|link| link.xref == "@F1@"));
+
+        let remaining = data.repair_links(RepairMode::Fix);
+        assert_eq!(remaining.len(), 1);
+        assert!(matches!(remaining[0].issue, LinkIssue::Dangling { .. }));
+    }
+
+    #[test]
+    fn qualifies_and_formats_date_values() {
+        use gedcom::types::{DateStyle, DateValue, Qualifier};
+
+        let about = DateValue::parse("ABT 1832");
+        assert_eq!(about.qualifier(), Some(Qualifier::About));
+        assert!(about.is_estimated());
+        assert!(!about.is_range());
+        assert_eq!(about.format(DateStyle::Abbreviated), "abt 1832");
+        assert_eq!(about.format(DateStyle::Circa), "c. 1832");
+        assert_eq!(about.format(DateStyle::Words), "about 1832");
+        assert_eq!(about.to_string(), "ABT 1832");
+
+        let between = DateValue::parse("BET 1830 AND 1835");
+        assert_eq!(between.qualifier(), Some(Qualifier::Between));
+        assert!(between.is_range());
+        assert_eq!(between.format(DateStyle::Circa), "1830–1835");
+        assert_eq!(between.format(DateStyle::Words), "between 1830 and 1835");
+        assert_eq!(between.to_string(), "BET 1830 AND 1835");
+
+        let period = DateValue::parse("FROM 3 MAR 1900 TO 1910");
+        assert_eq!(period.qualifier(), Some(Qualifier::From));
+        assert!(period.is_period() && !period.is_range());
+        assert_eq!(period.format(DateStyle::Abbreviated), "3 MAR 1900–1910");
+        assert_eq!(DateValue::parse("TO 1910").qualifier(), Some(Qualifier::To));
+
+        assert_eq!(
+            DateValue::parse("bef 1 jan 1900").format(DateStyle::Circa),
+            "bef. 1 JAN 1900"
+        );
+        let exact = DateValue::parse("1 JAN 1900");
+        assert_eq!(exact.qualifier(), None);
+        assert_eq!(exact.date().unwrap().month, Some(1));
+        let interpreted = DateValue::parse("INT 1900 (turn of the century)");
+        assert_eq!(interpreted.qualifier(), Some(Qualifier::Interpreted));
+        assert_eq!(interpreted.to_string(), "INT 1900 (turn of the century)");
+        assert_eq!(
+            DateValue::parse("(stillborn)"),
+            DateValue::Phrase("(stillborn)".to_string())
+        );
+    }
+
+    #[test]
+    fn tokenizes_content_losslessly() {
+        use gedcom::syntax::{folding_ranges, syntax_tokens, FoldRange, SyntaxKind};
+
+        let content = "0 HEAD\r\n1 GEDC\r\n2 VERS 5.5.1\r\n\
+            0 @I1@ INDI\n1 NAME  John /Doe/\n1 FAMS @F1@\n1 _MILT Army\nbroken line\n0 TRLR";
+        let tokens = syntax_tokens(content);
+        let text: String = tokens.iter().map(|token| token.text).collect();
+        assert_eq!(text, content);
+
+        let line = |number: u32| {
+            tokens
+                .iter()
+                .filter(|token| token.line == number)
+                .map(|token| (token.kind, token.text))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            line(4),
+            vec![
+                (SyntaxKind::Level, "0"),
+                (SyntaxKind::Whitespace, " "),
+                (SyntaxKind::Xref, "@I1@"),
+                (SyntaxKind::Whitespace, " "),
+                (SyntaxKind::Tag, "INDI"),
+                (SyntaxKind::Newline, "\n"),
+            ]
+        );
+        assert_eq!(line(5)[4], (SyntaxKind::Value, " John /Doe/"));
+        assert_eq!(line(6)[4], (SyntaxKind::Pointer, "@F1@"));
+        assert_eq!(line(7)[2], (SyntaxKind::CustomTag, "_MILT"));
+        assert_eq!(line(8)[0], (SyntaxKind::Error, "broken line"));
+        assert_eq!(line(1)[3], (SyntaxKind::Newline, "\r\n"));
+        assert_eq!(tokens[3].span, 6..8);
+
+        let range = |start_line, end_line| FoldRange {
+            start_line,
+            end_line,
+        };
+        assert_eq!(
+            folding_ranges(&tokens),
+            vec![range(1, 3), range(2, 3), range(4, 8), range(7, 8)]
+        );
+    }
+
+    #[test]
+    fn finds_custom_data_across_records() {
+        use gedcom::types::HasCustomData;
+        use gedcom::writer::GedcomWriter;
+
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5\n\
+            0 @I1@ INDI\n1 NAME John /Doe/\n1 BIRT\n2 DATE 1900\n2 _UID 1234\n1 _UID 5678\n\
+            0 @F1@ FAM\n1 HUSB @I1@\n1 _STAT\n2 _UID 9012\n\
+            0 @S1@ SOUR\n1 TITL Census\n1 _UID 3456\n\
+            0 @R1@ REPO\n1 NAME Archives\n1 _URL example.org\n\
+            0 TRLR";
+        let data = Parser::new(content.chars()).parse_record();
+
+        assert_eq!(data.individuals[0].find_by_tag("_UID").len(), 1);
+        assert_eq!(data.families[0].custom_data()[0].tag, "_STAT");
+        assert_eq!(data.families[0].find_by_tag("_UID")[0].value, "9012");
+        assert_eq!(data.repositories[0].custom_data().len(), 1);
+
+        let found: Vec<(Option<&str>, &str)> = data
+            .find_custom("_UID")
+            .into_iter()
+            .map(|(xref, found)| (xref, found.value.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (Some("@I1@"), "5678"),
+                (Some("@I1@"), "1234"),
+                (Some("@F1@"), "9012"),
+                (Some("@S1@"), "3456"),
+            ]
+        );
+
+        let written = GedcomWriter::default().write_string(&data);
+        assert!(written.contains("1 BIRT\n2 DATE 1900\n2 _UID 1234\n"));
+        assert!(written.contains("1 _STAT\n2 _UID 9012\n"));
+        assert!(written.contains("1 _URL example.org\n"));
+    }
+
+    #[test]
+    fn finds_records_changed_since_a_date() {
+        use gedcom::types::ChangeDate;
+        use std::time::{Duration, UNIX_EPOCH};
+
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 CHAN\n2 DATE 3 MAR 2021\n\
+            0 @I2@ INDI\n1 CHAN\n2 DATE 16 OCT 2026\n3 TIME 08:30:05\n\
+            0 @I3@ INDI\n1 NAME No /Change/\n\
+            0 @F1@ FAM\n1 CHAN\n2 DATE 16 OCT 2026\n3 TIME 07:00\n\
+            0 @R1@ REPO\n1 NAME Archives\n1 CHAN\n2 DATE 16 OCT 2026\n3 TIME 08:15\n\
+            0 @M1@ OBJE\n1 FILE photo.jpg\n1 CHAN\n2 DATE 1 JAN 2027\n\
+            0 @N1@ NOTE Changed\n1 CHAN\n2 DATE 2 FEB 2022\n\
+            0 TRLR";
+        let data = Parser::new(content.chars()).parse_record();
+
+        let changed = |since: &str| -> Vec<&str> {
+            data.changed_since(&ChangeDate::parse(since).unwrap())
+                .iter()
+                .filter_map(|record| record.xref())
+                .collect()
+        };
+        assert_eq!(
+            changed("1 JAN 2021"),
+            vec!["@I1@", "@I2@", "@F1@", "@R1@", "@M1@", "@N1@"]
+        );
+        assert_eq!(
+            changed("1 FEB 2022"),
+            vec!["@I2@", "@F1@", "@R1@", "@M1@", "@N1@"]
+        );
+        assert_eq!(changed("16 OCT 2026"), vec!["@I2@", "@F1@", "@R1@", "@M1@"]);
+        assert_eq!(
+            changed("16 OCT 2026 08:00:00"),
+            vec!["@I2@", "@R1@", "@M1@"]
+        );
+        assert_eq!(changed("1 JAN 2027"), Vec::<&str>::new());
+
+        // 16 OCT 2026 08:30:05 UTC
+        let since = ChangeDate::from(UNIX_EPOCH + Duration::from_secs(1_792_139_405));
+        assert_eq!(since.to_string(), "16 OCT 2026 08:30:05");
+        assert_eq!(
+            data.changed_since(&since)[0].last_updated(),
+            Some("1 JAN 2027")
+        );
+        assert_eq!(ChangeDate::parse("16 OCT 2026 25:00"), None);
+    }
+
+    #[test]
+    fn runs_pipeline_passes_in_order() {
+        use gedcom::diagnostics::Diagnostic;
+        use gedcom::pipeline::{
+            Pipeline, NORMALIZE_MULTIMEDIA, PLACE_MULTIMEDIA, REPAIR_LINKS, VALIDATE,
+        };
+
+        let mut pipeline = Pipeline::standard();
+        assert_eq!(
+            pipeline.names(),
+            vec![
+                PLACE_MULTIMEDIA,
+                NORMALIZE_MULTIMEDIA,
+                REPAIR_LINKS,
+                VALIDATE
+            ]
+        );
+        pipeline.add("count-families", |data, diagnostics| {
+            let message = format!("{} families", data.families.len());
+            diagnostics.push(Diagnostic::warning(0, message));
+        });
+        pipeline.add_before(REPAIR_LINKS, "drop-families", |data, _| {
+            data.families
+                .retain(|family| family.xref.as_deref() != Some("@F2@"));
+        });
+        assert!(pipeline.remove(NORMALIZE_MULTIMEDIA));
+        assert!(!pipeline.remove("missing"));
+        assert_eq!(
+            pipeline.names(),
+            vec![
+                PLACE_MULTIMEDIA,
+                "drop-families",
+                REPAIR_LINKS,
+                VALIDATE,
+                "count-families"
+            ]
+        );
+
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 FAMS @F1@\n0 @F1@ FAM\n0 @F2@ FAM\n0 TRLR";
+        let mut parser = Parser::new(content.chars());
+        let data = parser.parse_record_with(&pipeline);
+        assert_eq!(data.families.len(), 1);
+        assert_eq!(data.families[0].individual1.as_deref(), Some("@I1@"));
+        let messages: Vec<&str> = parser
+            .diagnostics()
+            .iter()
+            .map(|diagnostic| diagnostic.message.as_str())
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "@I1@ is a spouse in @F1@, which lacks them as a parent, repaired",
+                "HEAD: The header lacks the required SOUR field",
+                "HEAD: The header lacks the required SUBM field",
+                "HEAD: The header lacks the required CHAR field",
+                "1 families",
+            ]
+        );
+
+        // GEDCOM 7.0 has no objects embedded in links
+        let content = "0 HEAD\n1 GEDC\n2 VERS 7.0\n\
+            0 @I1@ INDI\n1 OBJE\n2 FILE photo.jpg\n3 FORM image/jpeg\n0 TRLR";
+        let mut parser = Parser::new(content.chars());
+        let data = parser.parse_record_with(&Pipeline::standard());
+        assert_eq!(data.multimedia.len(), 1);
+        assert_eq!(
+            data.individuals[0].multimedia[0].xref,
+            data.multimedia[0].xref.clone().unwrap()
+        );
+        assert!(parser
+            .diagnostics()
+            .iter()
+            .any(|diagnostic| diagnostic.message
+                == "Moved the objects of 1 multimedia links into records"));
+    }
+
+    #[test]
+    fn joins_conc_lines_by_policy() {
+        use gedcom::parser::{ConcJoin, ParseOptions};
+
+        let parse = |source: &str, conc_join| {
+            let content = format!(
+                "0 HEAD\n1 SOUR {}\n1 GEDC\n2 VERS 5.5.1\n\
+                 0 @S1@ SOUR\n1 TITL Parish register of\n2 CONC the church \n2 CONC of Salem\n\
+                 0 TRLR",
+                source
+            );
+            let options = ParseOptions {
+                conc_join,
+                ..ParseOptions::default()
+            };
+            let data = Parser::with_options(content.chars(), options).parse_record();
+            data.sources[0].title.clone().unwrap()
+        };
+
+        let exact = "Parish register ofthe church of Salem";
+        let spaced = "Parish register of the church of Salem";
+        assert_eq!(parse("GRAMPS", ConcJoin::Exact), exact);
+        assert_eq!(parse("FTM", ConcJoin::Exact), exact);
+        assert_eq!(parse("GRAMPS", ConcJoin::InsertSpace), spaced);
+        assert_eq!(parse("GRAMPS", ConcJoin::Auto), exact);
+        assert_eq!(parse("FTM", ConcJoin::Auto), spaced);
+    }
+
+    #[test]
+    fn audits_suspicious_conc_lines() {
+        use gedcom::parser::{ConcAudit, ParseOptions};
+
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @S1@ SOUR\n1 TITL Parish register of the\n2 CONC church.Baptisms and burials\n\
+            2 CONC Of the parish, 1750-\n2 CONC 1812\n2 CONT\n2 CONC Copied 1907\n\
+            0 TRLR";
+        let parse = |conc_audit| {
+            let options = ParseOptions {
+                conc_audit,
+                ..ParseOptions::default()
+            };
+            let mut parser = Parser::with_options(content.chars(), options);
+            let data = parser.parse_record();
+            let findings: Vec<String> = parser
+                .diagnostics()
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            (data.sources[0].title.clone().unwrap(), findings)
+        };
+
+        let (title, findings) = parse(ConcAudit::Off);
+        assert_eq!(
+            title,
+            "Parish register of thechurch.Baptisms and burialsOf the parish, 1750-1812\nCopied 1907"
+        );
+        assert!(findings.is_empty());
+
+        let (report_title, findings) = parse(ConcAudit::Report);
+        assert_eq!(report_title, title);
+        assert_eq!(
+            findings,
+            vec![
+                "line 7: warning: CONC joins words without a space: sO",
+                "line 10: warning: CONC continues an empty line",
+            ]
+        );
+
+        let (title, findings) = parse(ConcAudit::Repair);
+        assert_eq!(
+            title,
+            "Parish register of thechurch.Baptisms and burials Of the parish, 1750-1812\nCopied 1907"
+        );
+        assert_eq!(findings.len(), 2);
+    }
+
+    #[test]
+    fn reads_preferred_photos() {
+        let content = "0 @I1@ INDI\n\
+            1 OBJE @M1@\n\
+            1 OBJE @M2@\n\
+            2 _PRIMARY Y\n\
+            1 OBJE\n\
+            2 FILE photo.jpg\n\
+            0 TRLR";
+        let individual = Individual::parse_str(content);
+        assert_eq!(individual.multimedia.len(), 3);
+        let embedded = individual.multimedia[2].embedded.as_ref().unwrap();
+        assert_eq!(embedded.files[0].value, "photo.jpg");
+        assert!(!individual.multimedia[0].primary);
+        assert!(individual.multimedia[1].primary);
+        assert_eq!(individual.multimedia[1].custom_data[0].tag, "_PRIMARY");
+        assert_eq!(individual.primary_photo().unwrap().xref, "@M2@");
+
+        let mut individual = Individual::parse_str("0 @I1@ INDI\n1 OBJE @M1@\n0 TRLR");
+        assert_eq!(individual.primary_photo().unwrap().xref, "@M1@");
+        individual.multimedia.clear();
+        assert!(individual.primary_photo().is_none());
+    }
+
+    #[test]
+    fn reads_dates_of_other_calendars() {
+        use gedcom::types::{Calendar, DateValue};
+
+        let julian = DateValue::parse("@#DJULIAN@ 11 FEB 1731/32");
+        let date = julian.date().unwrap();
+        assert_eq!(date.calendar, Calendar::Julian);
+        assert_eq!((date.year, date.month, date.day), (1731, Some(2), Some(11)));
+        assert_eq!(julian.to_string(), "@#DJULIAN@ 11 FEB 1731");
+
+        let hebrew = DateValue::parse("ABT @#DHEBREW@ NSN 5600");
+        assert!(hebrew.is_estimated());
+        assert_eq!(hebrew.date().unwrap().calendar, Calendar::Hebrew);
+        assert_eq!(hebrew.date().unwrap().month, Some(8));
+        assert_eq!(hebrew.year(), Some(5600));
+        assert!(Calendar::Julian.is_gregorian_like());
+        assert!(!Calendar::Hebrew.is_gregorian_like());
+
+        let born = DateValue::parse("ABT 12 MAR 1850").date().unwrap();
+        let married = DateValue::parse("BET 1 MAR 1875 AND 1876").date().unwrap();
+        assert_eq!(born.years_until(married), 24);
+        // without a month, the years are compared
+        let year = DateValue::parse("1875").date().unwrap();
+        assert_eq!(born.years_until(year), 25);
+
+        let french = DateValue::parse("BET @#DFRENCH R@ 1 VEND 3 AND FRENCH_R 1 COMP 3");
+        assert!(french.is_range());
+        assert_eq!(french.date().unwrap().calendar, Calendar::FrenchRepublican);
+        assert_eq!(
+            french.to_string(),
+            "BET @#DFRENCH R@ 1 VEND 3 AND @#DFRENCH R@ 1 COMP 3"
+        );
+
+        assert_eq!(DateValue::parse("44 B.C.").year(), Some(-44));
+        assert_eq!(DateValue::parse("44 B.C.").to_string(), "44 B.C.");
+        assert_eq!(DateValue::parse("@#DGREGORIAN@ 1900").to_string(), "1900");
+        assert!(matches!(
+            DateValue::parse("@#DROMAN@ 1900"),
+            DateValue::Phrase(_)
+        ));
+        assert!(matches!(
+            DateValue::parse("@#DHEBREW@ 1 JAN 5600"),
+            DateValue::Phrase(_)
+        ));
+    }
+
+    #[test]
+    fn gathers_census_households() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 CENS\n2 DATE 1880\n2 PLAC Boston\n\
+            2 SOUR @S1@\n3 PAGE ED 12, sheet 4\n3 EVEN CENS\n4 ROLE HEAD\n\
+            0 @I2@ INDI\n1 CENS\n2 DATE 1880\n\
+            2 SOUR @S1@\n3 PAGE ED 12, sheet 4\n3 EVEN CENS\n4 ROLE (boarder)\n\
+            0 @I3@ INDI\n1 EVEN\n2 TYPE Census\n2 DATE 1900\n2 ASSO @I4@\n3 ROLE WITN\n\
+            0 @I4@ INDI\n0 @S1@ SOUR\n1 TITL 1880 US Census\n0 TRLR\n";
+        let data = Parser::new(content.chars()).parse_record();
+
+        let households = census_households(&data);
+        assert_eq!(households.len(), 2);
+        assert_eq!(households[0].source.as_deref(), Some("@S1@"));
+        assert_eq!(households[0].place.as_deref(), Some("Boston"));
+        assert_eq!(households[0].head(), Some("@I1@"));
+        assert_eq!(households[0].members[1].individual, "@I2@");
+        assert_eq!(households[0].members[1].role, Some(HouseholdRole::Boarder));
+
+        assert_eq!(households[1].date.as_deref(), Some("1900"));
+        assert_eq!(households[1].head(), None);
+        assert_eq!(households[1].members.len(), 2);
+        assert_eq!(households[1].members[1].role, Some(HouseholdRole::Witness));
+    }
+
+    #[test]
+    fn decodes_ansel_and_utf16_content() {
+        // ANSEL writes the acute accent (0xE2) before its letter
+        let mut ansel = b"0 HEAD\n1 CHAR ANSEL\n0 @I1@ INDI\n1 NAME Ren".to_vec();
+        ansel.extend([0xE2, b'e', b' ', 0xB1, b'o', b'd', b'z', b' ', b'/']);
+        ansel.extend(b"M\xE8uller/\n0 TRLR\n");
+        let data = gedcom::parse_bytes(&ansel).unwrap();
+        let name = data.individuals[0].primary_name().unwrap();
+        assert_eq!(
+            name.value.as_deref(),
+            Some("Ren\u{e9} \u{142}odz /M\u{fc}ller/")
+        );
+        assert_eq!(data.header.encoding.as_deref(), Some("ANSEL"));
+
+        let content = "0 HEAD\n1 CHAR UNICODE\n0 @I1@ INDI\n1 NAME Zo\u{eb} /\u{5f20}/\n0 TRLR\n";
+        for bom in [[0xFF, 0xFE], [0xFE, 0xFF]] {
+            let mut utf16 = bom.to_vec();
+            for unit in content.encode_utf16() {
+                if bom[0] == 0xFF {
+                    utf16.extend(unit.to_le_bytes());
+                } else {
+                    utf16.extend(unit.to_be_bytes());
+                }
+            }
+            let data = gedcom::parse_bytes(&utf16).unwrap();
+            let name = data.individuals[0].primary_name().unwrap();
+            assert_eq!(name.value.as_deref(), Some("Zo\u{eb} /\u{5f20}/"));
+        }
+    }
+
+    #[test]
+    fn reports_parse_statistics() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n0 @I1@ INDI\n1 NAME Ann /Doe/\n\
+            0 @I2@ INDI\n0 @F1@ FAM\n1 CHIL @I1@\n0 @X1@ FOO\n1 BAR baz\n0 TRLR\n";
+        let (data, report) = gedcom::parse_bytes_with_report(content.as_bytes()).unwrap();
+        assert_eq!(data.individuals.len(), 2);
+        assert_eq!(report.bytes, content.len());
+        assert_eq!(report.lines, 11);
+        let records: Vec<(&str, usize)> = report
+            .records
+            .iter()
+            .map(|(tag, count)| (tag.as_str(), *count))
+            .collect();
+        assert_eq!(records, [("FAM", 1), ("HEAD", 1), ("INDI", 2)]);
+        assert_eq!((report.errors, report.warnings), (0, 1));
+        assert_eq!(report.elapsed(), report.decoding + report.parsing);
+
+        // Text given as is takes no decoding
+        let mut parser = Parser::new(content.chars());
+        let (_, report) = parser.parse_document_with_report().unwrap();
+        assert_eq!(report.decoding, std::time::Duration::ZERO);
+        assert_eq!(report.bytes, content.len());
+    }
+
+    #[test]
+    fn exports_cypher_statements() {
+        use gedcom::export::cypher;
+
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 NAME John /O'Brien/\n1 SEX M\n1 BIRT\n2 DATE 1 JAN 1899\n1 FAMS @F1@\n\
+            0 @I2@ INDI\n1 NAME Ann /O'Brien/\n1 FAMC @F1@\n2 PEDI adopted\n\
+            0 @F1@ FAM\n1 HUSB @I1@\n1 CHIL @I2@\n1 MARR\n2 DATE 1920\n2 PLAC Cork\n0 TRLR\n";
+        let data = Parser::new(content.chars()).parse_record();
+
+        assert_eq!(
+            cypher::create_statements(&data),
+            "CREATE (:Person {xref: '@I1@', name: 'John O\\'Brien', sex: 'M', birth_date: '1 JAN 1899'});\n\
+             CREATE (:Person {xref: '@I2@', name: 'Ann O\\'Brien'});\n\
+             CREATE (:Family {xref: '@F1@', marriage_date: '1920', marriage_place: 'Cork'});\n\
+             MATCH (p:Person {xref: '@I1@'}), (f:Family {xref: '@F1@'}) \
+             CREATE (p)-[:SPOUSE_OF {role: 'HUSB', marriage_date: '1920', marriage_place: 'Cork'}]->(f);\n\
+             MATCH (p:Person {xref: '@I2@'}), (f:Family {xref: '@F1@'}) \
+             CREATE (p)-[:CHILD_OF {pedigree: 'adopted'}]->(f);\n"
+        );
+    }
+
+    #[test]
+    fn exports_graphml() {
+        use gedcom::export::graphml::graphml;
+
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 NAME John /O'Brien/\n1 SEX M\n1 BIRT\n2 DATE 1 JAN 1899\n1 FAMS @F1@\n\
+            0 @I2@ INDI\n1 NAME Mary /Smith & Sons/\n1 SEX F\n1 FAMS @F1@\n\
+            0 @I3@ INDI\n1 NAME Ann /O'Brien/\n1 FAMC @F1@\n\
+            0 @F1@ FAM\n1 HUSB @I1@\n1 WIFE @I2@\n1 CHIL @I3@\n1 CHIL @I4@\n0 TRLR\n";
+        let data = Parser::new(content.chars()).parse_record();
+
+        let xml = graphml(&data);
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<graphml"));
+        assert!(xml
+            .contains("<key id=\"d3\" for=\"node\" attr.name=\"birth_year\" attr.type=\"int\"/>"));
+        assert!(xml.contains(
+            "<node id=\"@I1@\"><data key=\"d0\">John O&apos;Brien</data>\
+             <data key=\"d1\">O&apos;Brien</data><data key=\"d2\">M</data>\
+             <data key=\"d3\">1899</data></node>"
+        ));
+        assert!(xml.contains("<data key=\"d1\">Smith &amp; Sons</data>"));
+        assert!(xml.contains(
+            "<edge id=\"e0\" source=\"@I1@\" target=\"@I2@\">\
+             <data key=\"d4\">spouse</data><data key=\"d5\">@F1@</data></edge>"
+        ));
+        assert!(xml.contains("source=\"@I2@\" target=\"@I3@\"><data key=\"d4\">parent</data>"));
+        // The missing child @I4@ gets no edges
+        assert_eq!(xml.matches("<edge ").count(), 3);
+        assert!(xml.ends_with("</graph>\n</graphml>\n"));
+    }
+
+    #[test]
+    fn iterates_over_records() {
+        use gedcom::parser::Record;
+
+        let simple_ged: String = read_relative("./tests/fixtures/simple.ged");
+        let mut parser = Parser::new(simple_ged.chars());
+        let records: Vec<Record> = parser.records().map(Result::unwrap).collect();
+        assert!(matches!(records[0], Record::Header(_)));
+        let individuals = records
+            .iter()
+            .filter(|record| matches!(record, Record::Individual(_)))
+            .count();
+        assert_eq!(individuals, 3);
+
+        let mut data = gedcom::GedcomData::default();
+        records
+            .into_iter()
+            .for_each(|record| data.add_record(record));
+        assert_eq!(data.families.len(), 1);
+        assert_eq!(data.header.encoding.as_deref(), Some("ASCII"));
+
+        // the iteration stops at the first error
+        let content = "0 HEAD\n0 @I1@ INDI\n1 BOGUS x\n0 @I2@ INDI\n0 TRLR\n";
+        let mut parser = Parser::new(content.chars());
+        let results: Vec<_> = parser.records().collect();
+        assert_eq!(results.len(), 2);
+        assert!(results[1].is_err());
+    }
+
+    #[test]
+    fn navigates_between_relatives() {
+        use gedcom::store::RecordStore;
+
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 FAMS @F1@\n1 FAMS @F2@\n0 @I2@ INDI\n1 FAMS @F1@\n\
+            0 @I3@ INDI\n1 FAMS @F2@\n0 @I4@ INDI\n1 FAMC @F1@\n\
+            0 @I5@ INDI\n1 FAMC @F1@\n0 @I6@ INDI\n1 FAMC @F2@\n\
+            0 @F1@ FAM\n1 HUSB @I1@\n1 WIFE @I2@\n1 CHIL @I4@\n1 CHIL @I5@\n\
+            0 @F2@ FAM\n1 HUSB @I1@\n1 WIFE @I3@\n1 CHIL @I6@\n0 TRLR\n";
+        let data = Parser::new(content.chars()).parse_record();
+        let xrefs = |individuals: Vec<std::borrow::Cow<'_, Individual>>| {
+            individuals
+                .iter()
+                .map(|individual| individual.xref.clone().unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(xrefs(data.parents_of("@I4@")), ["@I1@", "@I2@"]);
+        assert_eq!(xrefs(data.children_of("@I1@")), ["@I4@", "@I5@", "@I6@"]);
+        assert_eq!(xrefs(data.spouses_of("@I1@")), ["@I2@", "@I3@"]);
+        assert_eq!(xrefs(data.siblings_of("@I4@")), ["@I5@"]);
+        assert!(data.parents_of("@I1@").is_empty());
+        assert!(data.children_of("@MISSING@").is_empty());
+    }
+
+    #[test]
+    fn reports_repository_coverage() {
+        use gedcom::analysis::repository_coverage;
+
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @R1@ REPO\n1 NAME State Archives\n0 @R2@ REPO\n1 NAME Library\n\
+            0 @S1@ SOUR\n1 REPO @R1@\n2 CALN MS 12\n\
+            0 @S2@ SOUR\n1 REPO @R1@\n1 REPO @R9@\n2 CALN B 7\n\
+            0 @S3@ SOUR\n1 TITL Family Bible\n0 TRLR\n";
+        let data = Parser::new(content.chars()).parse_record();
+
+        let coverage = repository_coverage(&data);
+        assert_eq!(coverage.repositories.len(), 2);
+        let archives = &coverage.repositories[0];
+        assert_eq!(archives.sources.len(), 2);
+        assert_eq!(archives.sources[0].call_number.as_deref(), Some("MS 12"));
+        assert!(coverage.repositories[1].sources.is_empty());
+        assert_eq!(coverage.unlocated, ["@S3@"]);
+        assert_eq!(
+            coverage.dangling,
+            [("@S2@".to_string(), "@R9@".to_string())]
+        );
+        assert_eq!(
+            coverage.missing_call_numbers,
+            [("@S2@".to_string(), Some("@R1@".to_string()))]
+        );
+        assert_eq!(coverage.call_number_completeness(), Some(2.0 / 3.0));
+    }
+
+    #[test]
+    fn finds_conflicting_events() {
+        use gedcom::analysis::event_conflicts;
+        use gedcom::types::EventType;
+
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 BIRT\n2 DATE 1 JAN 1900\n2 SOUR @S1@\n3 QUAY 1\n\
+            1 BIRT\n2 DATE 3 JAN 1900\n2 SOUR @S2@\n3 QUAY 3\n\
+            1 DEAT\n2 DATE 1970\n1 DEAT\n2 PLAC Boston\n\
+            0 @S1@ SOUR\n0 @S2@ SOUR\n0 TRLR\n";
+        let data = Parser::new(content.chars()).parse_record();
+
+        // the deaths don't disagree, one only lacks the date the other has
+        let conflicts = event_conflicts(&data);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].individual, "@I1@");
+        assert_eq!(conflicts[0].event_type, EventType::Birth);
+        assert_eq!(conflicts[0].facts[0].quality, Some(1));
+        let preferred = conflicts[0].preferred().unwrap();
+        assert_eq!(preferred.event.date.as_deref(), Some("3 JAN 1900"));
+    }
+
+    #[test]
+    fn parses_individual_associations() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 NAME Anna /Berg/\n\
+            1 ASSO @I2@\n2 RELA Godfather\n2 NOTE Named in the\n3 CONT baptism record\n\
+            2 SOUR @S1@\n3 PAGE folio 12\n\
+            0 @I2@ INDI\n0 @S1@ SOUR\n0 TRLR\n";
+        let mut parser = Parser::new(content.chars());
+        let data = parser.parse_record();
+        assert!(parser.diagnostics().is_empty());
+
+        let association = &data.individuals[0].associations[0];
+        assert_eq!(association.xref, "@I2@");
+        assert_eq!(association.relation.as_deref(), Some("Godfather"));
+        assert_eq!(
+            association.notes.first().map(|note| note.text()).as_deref(),
+            Some("Named in the\nbaptism record")
+        );
+        assert_eq!(association.citations[0].page.as_deref(), Some("folio 12"));
+
+        let written = data.to_gedcom_string();
+        assert!(written.contains(
+            "1 ASSO @I2@\n2 RELA Godfather\n2 NOTE Named in the\n3 CONT baptism record\n\
+            2 SOUR @S1@\n3 PAGE folio 12\n"
+        ));
+    }
+
+    #[test]
+    fn iterates_over_notes_with_their_owner() {
+        let content = "0 HEAD\n1 NOTE Exported for review\n\
+            0 @U1@ SUBM\n1 NAME Ann\n1 NOTE Sent by mail\n\
+            0 @I1@ INDI\n1 NAME Anna /Berg/\n2 NOTE Bergh in 1900\n\
+            2 SOUR @S1@\n3 NOTE Census spelling\n2 FONE Ana Berk\n3 NOTE Phonetic\n\
+            1 BIRT\n2 PLAC Oslo\n3 NOTE Then Christiania\n2 NOTE Date from family bible\n\
+            2 ASSO @I2@\n3 RELA Witness\n3 NOTE Midwife\n1 NOTE Emigrated young\n\
+            0 @I2@ INDI\n0 @F1@ FAM\n1 MARR\n2 NOTE Church wedding\n1 NOTE Married twice\n\
+            0 @S1@ SOUR\n1 NOTE Microfilm only\n\
+            0 @M1@ OBJE\n1 FILE photo.jpg\n1 NOTE Scanned in 2020\n0 TRLR\n";
+        let data = Parser::new(content.chars()).parse_record();
+
+        let notes: Vec<_> = data
+            .iter_notes()
+            .map(|(note, owner)| {
+                (
+                    note.text().into_owned(),
+                    owner.record,
+                    owner.xref,
+                    owner.path,
+                )
+            })
+            .collect();
+        assert_eq!(
+            notes,
+            vec![
+                ("Exported for review".to_string(), "HEAD", None, vec![]),
+                ("Sent by mail".to_string(), "SUBM", Some("@U1@"), vec![]),
+                (
+                    "Bergh in 1900".to_string(),
+                    "INDI",
+                    Some("@I1@"),
+                    vec!["NAME"]
+                ),
+                (
+                    "Census spelling".to_string(),
+                    "INDI",
+                    Some("@I1@"),
+                    vec!["NAME", "SOUR"]
+                ),
+                (
+                    "Phonetic".to_string(),
+                    "INDI",
+                    Some("@I1@"),
+                    vec!["NAME", "FONE"]
+                ),
+                (
+                    "Then Christiania".to_string(),
+                    "INDI",
+                    Some("@I1@"),
+                    vec!["BIRT", "PLAC"]
+                ),
+                (
+                    "Date from family bible".to_string(),
+                    "INDI",
+                    Some("@I1@"),
+                    vec!["BIRT"]
+                ),
+                (
+                    "Midwife".to_string(),
+                    "INDI",
+                    Some("@I1@"),
+                    vec!["BIRT", "ASSO"]
+                ),
+                ("Emigrated young".to_string(), "INDI", Some("@I1@"), vec![]),
+                (
+                    "Church wedding".to_string(),
+                    "FAM",
+                    Some("@F1@"),
+                    vec!["MARR"]
+                ),
+                ("Married twice".to_string(), "FAM", Some("@F1@"), vec![]),
+                ("Microfilm only".to_string(), "SOUR", Some("@S1@"), vec![]),
+                ("Scanned in 2020".to_string(), "OBJE", Some("@M1@"), vec![]),
+            ]
+        );
+
+        let reparsed = Parser::new(data.to_gedcom_string().chars()).parse_record();
+        assert_eq!(reparsed.iter_notes().count(), notes.len());
+    }
+
+    #[test]
+    fn parses_lds_ordinances() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 NAME Anna /Berg/\n\
+            1 BAPL\n2 DATE 12 MAR 1905\n2 TEMP SLAKE\n2 STAT COMPLETED\n3 DATE 1 APR 1905\n\
+            1 SLGC\n2 FAMC @F1@\n2 STAT BIC\n2 SOUR @S1@\n\
+            0 @F1@ FAM\n1 HUSB @I2@\n1 SLGS\n2 DATE 3 JUN 1890\n2 PLAC Salt Lake City\n\
+            2 NOTE Sealed by proxy\n0 @I2@ INDI\n0 @S1@ SOUR\n0 TRLR\n";
+        let mut parser = Parser::new(content.chars());
+        let data = parser.parse_record();
+        assert!(parser.diagnostics().is_empty());
+
+        let ordinances = &data.individuals[0].lds_ordinances;
+        assert_eq!(ordinances.len(), 2);
+        assert_eq!(ordinances[0].ordinance, LdsOrdinanceType::Baptism);
+        assert_eq!(ordinances[0].temple.as_deref(), Some("SLAKE"));
+        assert_eq!(ordinances[0].status.as_deref(), Some("COMPLETED"));
+        assert_eq!(ordinances[0].status_date.as_deref(), Some("1 APR 1905"));
+        assert_eq!(ordinances[1].ordinance, LdsOrdinanceType::ChildSealing);
+        assert_eq!(ordinances[1].family.as_deref(), Some("@F1@"));
+        assert_eq!(ordinances[1].citations[0].xref, "@S1@");
+
+        let sealing = &data.families[0].lds_ordinances[0];
+        assert_eq!(sealing.ordinance, LdsOrdinanceType::SpouseSealing);
+        assert_eq!(
+            sealing.place.as_ref().map(|place| place.value.as_str()),
+            Some("Salt Lake City")
+        );
+
+        let written = data.to_gedcom_string();
+        assert!(written.contains(
+            "1 BAPL\n2 DATE 12 MAR 1905\n2 TEMP SLAKE\n2 STAT COMPLETED\n3 DATE 1 APR 1905\n\
+            1 SLGC\n2 FAMC @F1@\n2 STAT BIC\n2 SOUR @S1@\n"
+        ));
+        assert!(written.contains(
+            "1 SLGS\n2 DATE 3 JUN 1890\n2 PLAC Salt Lake City\n2 NOTE Sealed by proxy\n"
+        ));
+    }
+
+    #[test]
+    fn parses_note_records() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 NAME Anna /Berg/\n1 NOTE @N1@\n\
+            0 @N1@ NOTE Letters ke\n1 CONC pt by the family\n1 CONT in Oslo\n\
+            1 REFN 42\n2 TYPE archive box\n1 RIN 7\n1 SOUR @S1@\n1 CHAN\n2 DATE 1 JAN 2020\n\
+            0 @S1@ SOUR\n0 TRLR\n";
+        let mut parser = Parser::new(content.chars());
+        let data = parser.parse_record();
+        assert!(parser.diagnostics().is_empty());
+
+        let record = &data.notes[0];
+        assert_eq!(record.text.text(), "Letters kept by the family\nin Oslo");
+        assert_eq!(record.user_reference_numbers[0].number, "42");
+        assert_eq!(
+            record.user_reference_numbers[0].reference_type.as_deref(),
+            Some("archive box")
+        );
+        assert_eq!(record.automated_record_id.as_deref(), Some("7"));
+        assert_eq!(record.citations[0].xref, "@S1@");
+        assert_eq!(record.last_updated.as_deref(), Some("1 JAN 2020"));
+
+        let note = &data.individuals[0].notes[0];
+        let resolved = data.resolve_note(note).unwrap();
+        assert_eq!(resolved.xref.as_deref(), Some("@N1@"));
+
+        let written = data.to_gedcom_string();
+        assert!(written.contains("1 NOTE @N1@\n"));
+        assert!(written.contains(
+            "0 @N1@ NOTE Letters kept by the family\n1 CONT in Oslo\n\
+            1 REFN 42\n2 TYPE archive box\n1 RIN 7\n1 SOUR @S1@\n1 CHAN\n2 DATE 1 JAN 2020\n"
+        ));
+    }
+
+    #[test]
+    fn decodes_content_with_warnings() {
+        use gedcom::encoding::{decode, DetectedEncoding, EncodingWarning};
+
+        let (content, encoding, warnings) = decode(b"\xEF\xBB\xBF0 HEAD\n0 TRLR\n");
+        assert_eq!(content, "0 HEAD\n0 TRLR\n");
+        assert_eq!(encoding, DetectedEncoding::Utf8);
+        assert!(warnings.is_empty());
+
+        let (content, encoding, warnings) =
+            decode(b"0 HEAD\n1 CHAR ANSI\n0 @I1@ INDI\n1 NAME Ren\xE9\n0 TRLR\n");
+        assert_eq!(encoding, DetectedEncoding::Utf8);
+        assert!(content.contains("1 NAME Ren\u{fffd}\n"));
+        assert_eq!(
+            warnings,
+            vec![
+                EncodingWarning::UnsupportedEncoding("ANSI".to_string()),
+                EncodingWarning::InvalidBytes(41),
+            ]
+        );
+
+        let utf16: Vec<u8> = "0 HEAD\n"
+            .encode_utf16()
+            .flat_map(u16::to_be_bytes)
+            .collect();
+        let (content, encoding, warnings) = decode(&utf16[..utf16.len() - 1]);
+        assert_eq!(encoding, DetectedEncoding::Utf16Be);
+        assert_eq!(content, "0 HEAD");
+        assert_eq!(warnings, vec![EncodingWarning::TruncatedUtf16]);
+    }
+
+    #[test]
+    fn parses_multiple_names() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 NAME \u{5c71}\u{7530} /\u{592a}\u{90ce}/\n\
+            2 FONE \u{3084}\u{307e}\u{3060} /\u{305f}\u{308d}\u{3046}/\n3 TYPE kana\n\
+            2 ROMN Yamada /Taro/\n3 TYPE romaji\n\
+            1 NAME Tom /Yamada/\n2 TYPE aka\n0 TRLR\n";
+        let mut parser = Parser::new(content.chars());
+        let data = parser.parse_record();
+        assert!(parser.diagnostics().is_empty());
+
+        let individual = &data.individuals[0];
+        assert_eq!(individual.names.len(), 2);
+        let primary = individual.primary_name().unwrap();
+        assert_eq!(primary.phonetic[0].variation_type.as_deref(), Some("kana"));
+        assert_eq!(primary.romanized[0].value, "Yamada /Taro/");
+        assert_eq!(individual.names[1].name_type.as_deref(), Some("aka"));
+        assert_eq!(
+            individual.display_name(NameOrder::Western).as_deref(),
+            Some("\u{5c71}\u{7530} \u{592a}\u{90ce}")
+        );
+
+        let written = data.to_gedcom_string();
+        assert!(written
+            .contains("2 ROMN Yamada /Taro/\n3 TYPE romaji\n1 NAME Tom /Yamada/\n2 TYPE aka\n"));
+    }
+
+    #[test]
+    fn keeps_repeated_notes() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n1 NOTE First header note\n\
+            1 NOTE Second header note\n\
+            0 @I1@ INDI\n1 NAME John /Doe/\n1 BIRT\n2 DATE 1900\n2 NOTE Born at home\n\
+            2 NOTE Registered late\n2 SOUR @S1@\n3 PAGE p. 4\n3 NOTE Hard to read\n\
+            1 NOTE First note\n1 NOTE Second note\n\
+            0 @S1@ SOUR\n1 TITL Parish register\n1 NOTE Microfilm\n1 NOTE Original lost\n\
+            0 TRLR\n";
+        let data = Parser::new(content.chars()).parse_record();
+
+        let texts = |notes: &[gedcom::types::Note]| -> Vec<String> {
+            notes.iter().map(|note| note.text().into_owned()).collect()
+        };
+        assert_eq!(
+            texts(&data.header.notes),
+            ["First header note", "Second header note"]
+        );
+        let individual = &data.individuals[0];
+        assert_eq!(texts(&individual.notes), ["First note", "Second note"]);
+        let birth = &individual.events()[0];
+        assert_eq!(texts(&birth.notes), ["Born at home", "Registered late"]);
+        assert_eq!(texts(&birth.citations[0].notes), ["Hard to read"]);
+        assert_eq!(
+            texts(&data.sources[0].notes),
+            ["Microfilm", "Original lost"]
+        );
+        assert_eq!(data.iter_notes().count(), 9);
+
+        let reparsed = Parser::new(data.to_gedcom_string().chars()).parse_record();
+        assert_eq!(
+            texts(&reparsed.individuals[0].notes),
+            ["First note", "Second note"]
+        );
+        assert_eq!(
+            texts(&reparsed.individuals[0].events()[0].citations[0].notes),
+            ["Hard to read"]
+        );
+        assert_eq!(reparsed.iter_notes().count(), 9);
+    }
+
+    #[test]
+    fn merges_documents_with_their_provenance() {
+        use gedcom::merge::PROVENANCE_TAG;
+
+        let first = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 NAME John /Smith/\n1 FAMS @F1@\n\
+            0 @F1@ FAM\n1 HUSB @I1@\n0 @S1@ SOUR\n1 TITL Census\n0 TRLR\n";
+        let second = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 NAME Mary /Jones/\n1 BIRT\n2 SOUR @S1@\n1 FAMS @F1@\n\
+            1 NOTE @N1@\n0 @I2@ INDI\n1 NAME Ann /Jones/\n1 FAMC @F1@\n\
+            0 @F1@ FAM\n1 WIFE @I1@\n1 CHIL @I2@\n0 @S1@ SOUR\n1 TITL Parish register\n\
+            0 @N1@ NOTE Researched by Mary\n0 TRLR\n";
+        let mut data = Parser::new(first.chars()).parse_record();
+        assert_eq!(data.tag_provenance("smith.ged"), 3);
+        let merge = data.merge_document(Parser::new(second.chars()).parse_record(), "jones.ged");
+
+        assert_eq!(merge.records, 5);
+        assert_eq!(
+            merge.renamed,
+            [
+                ("@I1@".to_string(), "@I1_2@".to_string()),
+                ("@F1@".to_string(), "@F1_2@".to_string()),
+                ("@S1@".to_string(), "@S1_2@".to_string()),
+            ]
+        );
+        let mary = &data.individuals[1];
+        assert_eq!(mary.xref.as_deref(), Some("@I1_2@"));
+        assert_eq!(mary.families[0].xref, "@F1_2@");
+        assert_eq!(mary.events()[0].citations[0].xref, "@S1_2@");
+        assert_eq!(mary.notes[0].text(), "@N1@");
+        let family = &data.families[1];
+        assert_eq!(family.individual2.as_deref(), Some("@I1_2@"));
+        assert_eq!(family.children, ["@I2@"]);
+
+        assert_eq!(data.provenance("@I1@"), Some("smith.ged"));
+        assert_eq!(data.provenance("@I2@"), Some("jones.ged"));
+        assert_eq!(data.origins(), ["smith.ged", "jones.ged"]);
+        assert_eq!(
+            data.records_from("jones.ged"),
+            ["@I1_2@", "@I2@", "@F1_2@", "@S1_2@", "@N1@"]
+        );
+
+        let written = data.to_gedcom_string();
+        assert!(written.contains(&format!("1 {PROVENANCE_TAG} jones.ged\n")));
+        let reparsed = Parser::new(written.chars()).parse_record();
+        assert_eq!(reparsed.records_from("smith.ged"), ["@I1@", "@F1@", "@S1@"]);
+    }
+
+    #[test]
+    fn parses_source_data() {
+        use gedcom::types::DateValue;
+
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @S1@ SOUR\n1 DATA\n2 EVEN BIRT, DEAT\n3 DATE FROM 1820 TO 1870\n\
+            3 PLAC Salem, Essex, Massachusetts\n2 EVEN MARR\n2 AGNC Salem town clerk\n\
+            2 NOTE Indexed by volunteers\n1 TITL Vital records of Salem\n\
+            1 NOTE Bought in 1990\n0 TRLR\n";
+        let data = Parser::new(content.chars()).parse_record();
+
+        let source = &data.sources[0];
+        let events = source.data.events();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].events_recorded(), ["BIRT", "DEAT"]);
+        assert!(matches!(
+            events[0].date_value(),
+            Some(DateValue::Period {
+                from: Some(_),
+                to: Some(_)
+            })
+        ));
+        assert_eq!(events[0].place_name(), Some("Salem, Essex, Massachusetts"));
+        assert_eq!(events[1].events_recorded(), ["MARR"]);
+        assert_eq!(source.data.agency.as_deref(), Some("Salem town clerk"));
+        assert_eq!(source.data.notes[0].text(), "Indexed by volunteers");
+        assert_eq!(source.notes.len(), 1);
+
+        let written = data.to_gedcom_string();
+        assert!(written.contains(
+            "0 @S1@ SOUR\n1 DATA\n2 EVEN BIRT, DEAT\n3 DATE FROM 1820 TO 1870\n\
+             3 PLAC Salem, Essex, Massachusetts\n2 EVEN MARR\n2 AGNC Salem town clerk\n\
+             2 NOTE Indexed by volunteers\n1 TITL Vital records of Salem\n"
+        ));
+    }
+
+    #[test]
+    fn parses_repository_records() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @R1@ REPO\n1 NAME Family History Library\n1 ADDR 35 N West Temple\n\
+            2 CITY Salt Lake City\n1 PHON +1 801 240 6996\n1 PHON +1 866 406 1830\n\
+            1 EMAIL fhl@example.org\n1 FAX +1 801 240 1234\n1 WWW https://example.org\n\
+            1 NOTE Closed on Sundays\n1 REFN 42\n2 TYPE archive\n1 RIN 7\n\
+            1 CHAN\n2 DATE 1 MAR 2020\n0 TRLR\n";
+        let data = Parser::new(content.chars()).parse_record();
+
+        let repository = &data.repositories[0];
+        assert_eq!(repository.phones, ["+1 801 240 6996", "+1 866 406 1830"]);
+        assert_eq!(repository.emails, ["fhl@example.org"]);
+        assert_eq!(repository.faxes, ["+1 801 240 1234"]);
+        assert_eq!(repository.websites, ["https://example.org"]);
+        assert_eq!(repository.notes[0].text(), "Closed on Sundays");
+        assert_eq!(repository.user_reference_numbers[0].number, "42");
+        assert_eq!(
+            repository.user_reference_numbers[0]
+                .reference_type
+                .as_deref(),
+            Some("archive")
+        );
+        assert_eq!(repository.automated_record_id.as_deref(), Some("7"));
+        assert_eq!(repository.last_updated.as_deref(), Some("1 MAR 2020"));
+
+        let written = data.to_gedcom_string();
+        assert!(written.contains(
+            "2 CITY Salt Lake City\n1 PHON +1 801 240 6996\n1 PHON +1 866 406 1830\n\
+             1 EMAIL fhl@@example.org\n1 FAX +1 801 240 1234\n1 WWW https://example.org\n\
+             1 NOTE Closed on Sundays\n1 REFN 42\n2 TYPE archive\n1 RIN 7\n\
+             1 CHAN\n2 DATE 1 MAR 2020\n"
+        ));
+        let reparsed = Parser::new(written.chars()).parse_record();
+        assert_eq!(reparsed.repositories[0].emails, ["fhl@example.org"]);
+    }
+
+    #[test]
+    fn distinguishes_birth_and_adoptive_parents() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 NAME Ann /Doe/\n1 FAMC @F1@\n2 PEDI birth\n1 FAMC @F2@\n\
+            1 FAMC @F3@\n2 PEDI foster\n1 ADOP\n2 FAMC @F2@\n3 ADOP HUSB\n\
+            0 @I2@ INDI\n0 @I3@ INDI\n0 @I4@ INDI\n0 @I5@ INDI\n0 @I6@ INDI\n\
+            0 @F1@ FAM\n1 HUSB @I2@\n1 WIFE @I3@\n1 CHIL @I1@\n\
+            0 @F2@ FAM\n1 HUSB @I4@\n1 WIFE @I3@\n1 CHIL @I1@\n\
+            0 @F3@ FAM\n1 WIFE @I6@\n1 CHIL @I1@\n0 TRLR\n";
+        let data = Parser::new(content.chars()).parse_record();
+        let xrefs = |parents: Vec<&Individual>| -> Vec<String> {
+            parents
+                .iter()
+                .filter_map(|parent| parent.xref.clone())
+                .collect()
+        };
+
+        let child = &data.individuals[0];
+        let adoption = &child.events()[0];
+        assert_eq!(adoption.family.as_deref(), Some("@F2@"));
+        assert_eq!(adoption.adopted_by.as_deref(), Some("HUSB"));
+        assert_eq!(xrefs(child.birth_parents(&data)), ["@I2@", "@I3@"]);
+        assert_eq!(xrefs(child.adoptive_parents(&data)), ["@I4@"]);
+
+        let written = data.to_gedcom_string();
+        assert!(written.contains("1 ADOP\n2 FAMC @F2@\n3 ADOP HUSB\n"));
+    }
+
+    #[test]
+    fn parses_family_link_notes_and_citations() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 7.0\n\
+            0 @I1@ INDI\n1 FAMC @F1@\n2 PEDI adopted\n2 NOTE Raised by her aunt\n\
+            2 NOTE Adoption papers lost\n2 SOUR @S1@\n3 PAGE p. 12\n\
+            0 @F1@ FAM\n1 CHIL @I1@\n0 @S1@ SOUR\n1 TITL Court records\n0 TRLR\n";
+        let data = Parser::new(content.chars()).parse_record();
+
+        let link = &data.individuals[0].families[0];
+        assert_eq!(link.xref, "@F1@");
+        assert_eq!(link.notes.len(), 2);
+        assert_eq!(link.notes[1].text(), "Adoption papers lost");
+        assert_eq!(link.citations[0].xref, "@S1@");
+        assert_eq!(link.citations[0].page.as_deref(), Some("p. 12"));
+        assert_eq!(data.iter_notes().count(), 2);
+
+        let written = data.to_gedcom_string();
+        assert!(written.contains(
+            "1 FAMC @F1@\n2 PEDI adopted\n2 NOTE Raised by her aunt\n\
+             2 NOTE Adoption papers lost\n2 SOUR @S1@\n3 PAGE p. 12\n"
+        ));
+    }
+
+    #[test]
+    fn splits_name_pieces_listing_several_names() {
+        let individual = Individual::parse_str(
+            "0 @I1@ INDI\n1 NAME Mary Ann /Garcia Lopez/\n2 GIVN Mary, Ann\n\
+             2 SURN Garcia,Lopez\n",
+        );
+        let name = individual.primary_name().unwrap();
+        assert_eq!(name.given.as_deref(), Some("Mary, Ann"));
+        assert_eq!(name.given_names(), ["Mary", "Ann"]);
+        assert_eq!(name.surnames(), ["Garcia", "Lopez"]);
+        assert!(Name::default().given_names().is_empty());
+    }
+
+    #[test]
+    fn validates_the_structure_of_a_tree() {
+        use gedcom::diagnostics::Severity;
+        use gedcom::validate::{validate, Check};
+
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n1 CHAR UTF-8\n\
+            0 @I1@ INDI\n1 SEX F\n1 DEAT\n2 DATE 3 MAR 1900\n1 FAMS @F1@\n\
+            0 @I2@ INDI\n1 BIRT\n2 DATE 8 MAY 1900\n2 SOUR @S9@\n1 FAMC @F1@\n\
+            0 @I3@ INDI\n1 BIRT\n2 DATE 1901\n0 @I4@ INDI\n0 @I4@ INDI\n\
+            0 @I5@ INDI\n1 BIRT\n2 DATE @#DHEBREW@ 1 TSH 5700\n1 FAMC @F1@\n\
+            0 @F1@ FAM\n1 WIFE @I1@\n1 CHIL @I2@\n1 CHIL @I3@\n1 CHIL @I5@\n0 TRLR\n";
+        let data = Parser::new(content.chars()).parse_record();
+
+        let issues = validate(&data, &Check::ALL);
+        let found: Vec<(Check, Option<&str>)> = issues
+            .iter()
+            .map(|issue| (issue.check, issue.record.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (Check::DanglingPointer, Some("@I2@")),
+                (Check::DuplicateXref, Some("@I4@")),
+                (Check::OneSidedLink, Some("@F1@")),
+                (Check::BirthAfterParentDeath, Some("@I2@")),
+                (Check::BirthAfterParentDeath, Some("@I3@")),
+                (Check::MissingHeaderField, None),
+                (Check::MissingHeaderField, None),
+            ]
+        );
+        assert_eq!(issues[0].severity, Severity::Error);
+        assert_eq!(issues[0].message, "@I2@ links to missing record @S9@");
+        assert_eq!(
+            issues[5].message,
+            "The header lacks the required SOUR field"
+        );
+
+        let header_only = validate(&data, &[Check::MissingHeaderField]);
+        assert_eq!(header_only.len(), 2);
+    }
+
+    #[test]
+    fn checks_numbers_of_children() {
+        use gedcom::validate::{validate, Check};
+
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 NCHI 3\n2 DATE 1900\n1 FAMS @F1@\n1 FAMS @F2@\n\
+            0 @I2@ INDI\n1 NCHI 2\n1 FAMS @F1@\n0 @I3@ INDI\n1 NCHI many\n\
+            0 @F1@ FAM\n1 HUSB @I1@\n1 WIFE @I2@\n1 CHIL @I4@\n1 NCHI 2\n\
+            0 @F2@ FAM\n1 HUSB @I1@\n1 CHIL @I5@\n1 CHIL @I6@\n1 NCHI 2\n\
+            0 @I4@ INDI\n0 @I5@ INDI\n0 @I6@ INDI\n0 TRLR\n";
+        let mut parser = Parser::new(content.chars());
+        let data = parser.parse_record();
+        assert_eq!(data.individuals[0].num_children, Some(3));
+        assert_eq!(data.individuals[2].num_children, None);
+        assert_eq!(
+            parser.diagnostics()[0].message,
+            "Invalid number of children: many"
+        );
+        assert_eq!(data.families[0].num_children, Some(2));
+
+        let issues = validate(&data, &[Check::ChildCount]);
+        let messages: Vec<&str> = issues.iter().map(|issue| issue.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "@F1@ has 2 children by NCHI, but lists 1",
+                "@I2@ has 2 children by NCHI, but 1 are listed in their families",
+            ]
+        );
+        assert_eq!(Check::ChildCount.to_string(), "child-count");
+
+        let written = data.to_gedcom_string();
+        assert!(written.contains("0 @I1@ INDI\n1 NCHI 3\n"));
+        assert!(written.contains("1 CHIL @I4@\n1 NCHI 2\n"));
+    }
+
+    #[test]
+    fn builds_a_tree() {
+        use gedcom::types::{Event, EventType};
+        use gedcom::GedcomData;
+
+        let mut data = GedcomData::default();
+        let mut birth = Event::new(EventType::Birth);
+        birth.date = Some("2 FEB 1990".to_string());
+        let jane = data.add_individual_with_xref(
+            Individual::builder()
+                .name("Jane /Doe/")
+                .sex(Gender::Female)
+                .event(birth)
+                .spouse_in("@F1@")
+                .build()
+                .unwrap(),
+        );
+        let john = data.add_individual_with_xref(
+            Individual::builder()
+                .xref("@JOHN@")
+                .name("John /Smith/")
+                .sex(Gender::Male)
+                .spouse_in("@F1@")
+                .build()
+                .unwrap(),
+        );
+        let family = data.add_family_with_xref(
+            Family::builder()
+                .husband(&john)
+                .wife(&jane)
+                .build()
+                .unwrap(),
+        );
+        assert_eq!((jane.as_str(), john.as_str()), ("@I1@", "@JOHN@"));
+        assert_eq!(family, "@F1@");
+
+        let written = data.to_gedcom_string();
+        let reparsed = Parser::new(written.chars()).parse_record();
+        assert_eq!(reparsed.individuals.len(), 2);
+        let reparsed_jane = &reparsed.individuals[0];
+        assert_eq!(
+            reparsed_jane.display_name(NameOrder::Western).as_deref(),
+            Some("Jane Doe")
+        );
+        assert_eq!(
+            reparsed_jane.events()[0].date.as_deref(),
+            Some("2 FEB 1990")
+        );
+        assert_eq!(
+            reparsed.families[0].parents(),
+            [("@JOHN@", ParentTag::Husband), ("@I1@", ParentTag::Wife)]
+        );
+    }
+
+    #[test]
+    fn builders_refuse_invalid_values() {
+        use gedcom::entry::FieldError;
+
+        let error = Individual::builder()
+            .xref("I 1")
+            .name("Jane /Doe/")
+            .build()
+            .unwrap_err();
+        assert!(matches!(error, FieldError::Xref(_)));
+        let error = Individual::builder().name("Jane\nDoe").build();
+        assert!(matches!(error, Err(FieldError::Line(_))));
+        let error = Family::builder().husband("@I1@").child("@I 2@").build();
+        assert!(matches!(error, Err(FieldError::Xref(_))));
+    }
+
+    #[test]
+    fn parses_times() {
+        use gedcom::types::{ChangeDate, Time};
+
+        let time = Time::parse("08:30:05.25Z").unwrap();
+        assert_eq!(
+            (
+                time.hours,
+                time.minutes,
+                time.seconds,
+                time.fraction,
+                time.utc
+            ),
+            (8, 30, Some(5), Some(250_000_000), true)
+        );
+        assert_eq!(time.to_string(), "08:30:05.25Z");
+        assert_eq!(Time::parse("8:30").unwrap().to_string(), "08:30");
+        assert_eq!(Time::parse("08:60"), None);
+        assert_eq!(Time::parse("08:30:05.1234567890"), None);
+        assert_eq!(Time::parse("08"), None);
+
+        let earlier = ChangeDate::parse("16 OCT 2026 08:30:05.2").unwrap();
+        let later = ChangeDate::parse("16 OCT 2026 08:30:05.25").unwrap();
+        assert!(earlier < later);
+        assert_eq!(later.to_string(), "16 OCT 2026 08:30:05.25");
+
+        let content = "0 HEAD\n1 GEDC\n2 VERS 7.0\n\
+            0 @I1@ INDI\n1 CHAN\n2 DATE 16 OCT 2026\n3 TIME 8h30\n0 TRLR\n";
+        let mut parser = Parser::new(content.chars());
+        parser.parse_record();
+        assert_eq!(parser.diagnostics()[0].message, "Invalid change time: 8h30");
+    }
+
+    #[test]
+    fn guards_the_referential_integrity_of_edits() {
+        use gedcom::integrity::{IntegrityError, IntegrityMode};
+
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 FAMS @F1@\n0 @I2@ INDI\n1 FAMC @F1@\n1 ASSO @I1@\n\
+            0 @F1@ FAM\n1 HUSB @I1@\n1 CHIL @I2@\n0 TRLR\n";
+        let mut data = Parser::new(content.chars()).parse_record();
+
+        let refused = data
+            .guarded(IntegrityMode::Strict)
+            .remove_individual("@I1@");
+        assert_eq!(
+            refused.unwrap_err(),
+            IntegrityError::Referenced {
+                xref: "@I1@".to_string(),
+                by: vec!["@F1@".to_string(), "@I2@".to_string()],
+            }
+        );
+        assert_eq!(data.individuals.len(), 2);
+
+        let dangling = Family::builder()
+            .husband("@I9@")
+            .child("@I2@")
+            .build()
+            .unwrap();
+        let refused = data
+            .guarded(IntegrityMode::Strict)
+            .add_family(dangling.clone());
+        assert_eq!(
+            refused.unwrap_err().to_string(),
+            "The record links to missing records @I9@"
+        );
+        let edited = data
+            .guarded(IntegrityMode::Strict)
+            .edit_individual("@I2@", |individual| individual.families.clear());
+        assert!(matches!(edited, Ok(Some(()))));
+
+        let mut guard = data.guarded(IntegrityMode::Fix);
+        let removed = guard.remove_individual("@I1@").unwrap().unwrap();
+        assert_eq!(removed.xref.as_deref(), Some("@I1@"));
+        guard.add_family(dangling).unwrap();
+        assert_eq!(data.families[0].parents(), []);
+        assert!(data.individuals[0].associations.is_empty());
+        assert_eq!(data.families[1].children, ["@I2@"]);
+
+        let removed = data.guarded(IntegrityMode::Ignore).remove_family("@F1@");
+        assert!(removed.unwrap().is_some());
+    }
+
+    #[test]
+    fn diffs_two_exports_of_a_tree() {
+        use gedcom::diff::{EventDiff, FieldChange, RecordDiff};
+
+        let old = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 NAME Jane /Doe/\n1 SEX F\n1 BIRT\n2 DATE 2 FEB 1990\n\
+            0 @I2@ INDI\n1 NAME John /Smith/\n1 BIRT\n2 DATE 1988\n\
+            0 @I3@ INDI\n1 NAME Gone /Away/\n\
+            0 @F1@ FAM\n1 HUSB @I2@\n1 WIFE @I1@\n0 TRLR\n";
+        // John was renumbered, Jane's birth place was added & a child was born
+        let new = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 NAME Jane /Doe/\n1 SEX F\n1 BIRT\n2 DATE 2 FEB 1990\n\
+            2 PLAC Boston\n\
+            0 @P7@ INDI\n1 NAME John  /Smith/\n1 BIRT\n2 DATE 4 MAY 1988\n1 DEAT\n\
+            0 @I4@ INDI\n1 NAME Baby /Smith/\n\
+            0 @F9@ FAM\n1 HUSB @P7@\n1 WIFE @I1@\n1 CHIL @I4@\n0 TRLR\n";
+        let old = Parser::new(old.chars()).parse_record();
+        let new = Parser::new(new.chars()).parse_record();
+
+        let diff = gedcom::diff(&old, &new);
+        assert_eq!(diff.individuals.len(), 4);
+        let RecordDiff::Changed(jane) = &diff.individuals[0] else {
+            panic!("Jane changed");
+        };
+        assert!(jane.fields.is_empty());
+        assert!(matches!(&jane.events[..], [EventDiff::Changed { new, .. }]
+            if new.place_name() == Some("Boston")));
+        let RecordDiff::Changed(john) = &diff.individuals[1] else {
+            panic!("John changed");
+        };
+        assert_eq!(
+            (john.old_xref.as_str(), john.new_xref.as_str()),
+            ("@I2@", "@P7@")
+        );
+        assert_eq!(
+            john.fields,
+            [FieldChange {
+                tag: "NAME",
+                old: Some("John /Smith/".to_string()),
+                new: Some("John  /Smith/".to_string()),
+            }]
+        );
+        assert_eq!(john.events.len(), 2);
+        assert!(matches!(&diff.individuals[2], RecordDiff::Removed(xref) if xref == "@I3@"));
+        assert!(matches!(&diff.individuals[3], RecordDiff::Added(xref) if xref == "@I4@"));
+
+        let RecordDiff::Changed(family) = &diff.families[0] else {
+            panic!("The family changed");
+        };
+        assert_eq!(family.new_xref, "@F9@");
+        assert_eq!(
+            family.fields,
+            [FieldChange {
+                tag: "CHIL",
+                old: None,
+                new: Some("@I4@".to_string()),
+            }]
+        );
+        assert!(gedcom::diff(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn falls_back_to_the_default_language_of_notes() {
+        use gedcom::writer::{GedcomWriter, WriteOptions};
+
+        let content = "0 HEAD\n1 GEDC\n2 VERS 7.0\n1 LANG en\n1 LANG fr\n\
+            0 @U1@ SUBM\n1 NAME Ann\n1 LANG English\n1 LANG German\n\
+            0 @I1@ INDI\n1 NOTE Bonjour\n2 LANG fr\n1 NOTE Hello\n1 NOTE @N1@\n\
+            0 @N1@ SNOTE Guten Tag\n1 LANG de\n0 TRLR\n";
+        let data = gedcom::parse_document(content).unwrap();
+        assert_eq!(data.header.languages, ["en", "fr"]);
+        assert_eq!(data.header.language(), Some("en"));
+        assert_eq!(data.submitters[0].languages, ["English", "German"]);
+
+        let notes = &data.individuals[0].notes;
+        assert_eq!(notes[0].language(), Some("fr"));
+        assert_eq!(notes[0].language_in(&data), Some("fr"));
+        assert_eq!(notes[1].language(), None);
+        assert_eq!(notes[1].language_in(&data), Some("en"));
+        // a pointer takes the language of the note record
+        assert_eq!(notes[2].language_in(&data), Some("de"));
+
+        let written = GedcomWriter::new(WriteOptions::default()).write_string(&data);
+        assert!(written.contains("1 LANG en\n1 LANG fr\n"));
+        assert!(written.contains("1 LANG English\n1 LANG German\n"));
+        assert!(written.contains("1 NOTE Bonjour\n2 LANG fr\n1 NOTE Hello\n"));
+        assert!(written.contains("0 @N1@ NOTE Guten Tag\n1 LANG de\n"));
+    }
+
+    #[test]
+    fn iterates_over_lines() {
+        use gedcom::tokenizer::{GedcomLine, LineIter};
+
+        let content = "0 HEAD\r\n0 @I1@ INDI\r\n1 NAME Ann /Doe/\r\n1 _UID 42\r\n\
+            1 NOTE Mail ann@@example.org\r\n2 CONC  today\r\n0 TRLR\r\n";
+        let lines: Vec<GedcomLine> = LineIter::new(content.chars()).map(Result::unwrap).collect();
+        assert_eq!(lines.len(), 7);
+        assert_eq!(
+            lines[1],
+            GedcomLine {
+                line: 2,
+                level: 0,
+                xref: Some("@I1@".to_string()),
+                tag: "INDI".to_string(),
+                value: None,
+            }
+        );
+        assert_eq!(lines[3].tag, "_UID");
+        assert_eq!(lines[4].value.as_deref(), Some("Mail ann@example.org"));
+        assert_eq!((lines[6].line, lines[6].tag.as_str()), (7, "TRLR"));
+
+        let mut lines = LineIter::new("0 HEAD\n1 CHAR UTF-8\nJUNK\n0 TRLR\n".chars());
+        assert_eq!(lines.next().unwrap().unwrap().tag, "HEAD");
+        // the line after a value is read ahead
+        let error = lines.next().unwrap().unwrap_err();
+        assert!(matches!(error, gedcom::GedcomError::Tokenize { line: 3 }));
+        assert!(lines.next().is_none());
+    }
+
+    #[test]
+    fn indexes_a_tree() {
+        use gedcom::index::GedcomIndex;
+
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 NAME Anna Mary /Doe/\n1 BIRT\n2 PLAC Salem\n2 SOUR @S1@\n\
+            0 @I2@ INDI\n1 NAME John /Doe/\n2 GIVN John\n2 SURN Doe, Smith\n1 FAMS @F1@\n\
+            0 @I3@ INDI\n1 NAME Mary /Roe/\n\
+            0 @F1@ FAM\n1 HUSB @I2@\n1 MARR\n2 PLAC Salem\n2 SOUR @S1@\n\
+            0 @S1@ SOUR\n1 TITL Parish register\n0 TRLR\n";
+        let data = gedcom::parse_document(content).unwrap();
+        let index = GedcomIndex::build(&data);
+
+        assert_eq!(index.stats().records, 5);
+        assert_eq!(index.individual("@I2@").unwrap().names.len(), 1);
+        assert!(index.family("@F1@").is_some());
+        assert!(index.source("@S1@").is_some());
+        assert!(index.individual("@I9@").is_none());
+
+        assert_eq!(index.with_surname("doe"), ["@I1@", "@I2@"]);
+        assert_eq!(index.with_surname("Smith"), ["@I2@"]);
+        assert_eq!(index.with_given_name("Mary"), ["@I1@", "@I3@"]);
+        assert_eq!(index.at_place("Salem"), ["@I1@", "@F1@"]);
+        assert_eq!(index.citing("@S1@"), ["@I1@", "@F1@"]);
+        assert!(index.citing("@S2@").is_empty());
+    }
+
+    #[test]
+    fn parses_individual_record_numbers() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 RESN privacy\n1 NAME Ann /Doe/\n1 RFN 1234:5678\n1 AFN 9ABC-DE\n\
+            1 REFN 42\n2 TYPE archive box\n1 REFN 43\n1 RIN 7\n0 TRLR\n";
+        let data = gedcom::parse_document(content).unwrap();
+        let individual = &data.individuals[0];
+        assert_eq!(individual.restriction_notice.as_deref(), Some("privacy"));
+        assert_eq!(
+            individual.permanent_record_file_number.as_deref(),
+            Some("1234:5678")
+        );
+        assert_eq!(individual.ancestral_file_number.as_deref(), Some("9ABC-DE"));
+        assert_eq!(individual.user_reference_numbers.len(), 2);
+        assert_eq!(
+            individual.user_reference_numbers[0]
+                .reference_type
+                .as_deref(),
+            Some("archive box")
+        );
+        assert_eq!(individual.automated_record_id.as_deref(), Some("7"));
+
+        let written = data.to_gedcom_string();
+        assert!(written.contains(
+            "0 @I1@ INDI\n1 RESN privacy\n1 NAME Ann /Doe/\n1 RFN 1234:5678\n1 AFN 9ABC-DE\n\
+             1 REFN 42\n2 TYPE archive box\n1 REFN 43\n1 RIN 7\n"
+        ));
+    }
+
+    #[test]
+    fn parses_family_record_numbers() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @SUBMITTER@ SUBM\n1 NAME John Doe\n\
+            0 @FAMILY1@ FAM\n1 RESN locked\n1 HUSB @PERSON1@\n1 WIFE @PERSON2@\n\
+            1 SUBM @SUBMITTER@\n1 REFN User Reference Number\n2 TYPE User Reference Type\n\
+            1 RIN 12345\n0 TRLR\n";
+        let data = gedcom::parse_document(content).unwrap();
+        let family = &data.families[0];
+        assert_eq!(family.restriction_notice.as_deref(), Some("locked"));
+        assert_eq!(family.submitters, vec!["@SUBMITTER@"]);
+        assert_eq!(family.user_reference_numbers.len(), 1);
+        assert_eq!(
+            family.user_reference_numbers[0].reference_type.as_deref(),
+            Some("User Reference Type")
+        );
+        assert_eq!(family.automated_record_id.as_deref(), Some("12345"));
+
+        let written = data.to_gedcom_string();
+        assert!(written.contains(
+            "0 @FAMILY1@ FAM\n1 RESN locked\n1 HUSB @PERSON1@\n1 WIFE @PERSON2@\n\
+             1 SUBM @SUBMITTER@\n1 REFN User Reference Number\n2 TYPE User Reference Type\n\
+             1 RIN 12345\n"
+        ));
+    }
+
+    #[test]
+    fn searches_texts_across_continuation_lines() {
+        use gedcom::index::{GedcomIndex, TextField};
+
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 NAME Anna /Doe/\n1 NOTE Emigra\n2 CONC ted to\n2 CONT Ohio in 1850\n\
+            1 BIRT\n2 SOUR @S1@\n3 DATA\n4 TEXT Born at the parish of St. Mary\n\
+            0 @S1@ SOUR\n1 TITL Parish register\n1 TEXT Baptisms of the parish of St. Ma\n2 CONC ry\n\
+            0 @N1@ NOTE Moved to ohio\n0 TRLR\n";
+        let data = gedcom::parse_document(content).unwrap();
+        let index = GedcomIndex::build(&data);
+
+        let matches = index.search("emigrated to ohio");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].record, "@I1@");
+        assert_eq!(matches[0].field, TextField::Note);
+        assert_eq!(matches[0].text, "Emigrated to\nOhio in 1850");
+
+        let matches = index.search("parish of  St. Mary");
+        let found: Vec<_> = matches.iter().map(|m| (m.record, m.field)).collect();
+        assert_eq!(
+            found,
+            [
+                ("@I1@", TextField::CitationText),
+                ("@S1@", TextField::SourceText)
+            ]
+        );
+        assert_eq!(index.search("REGISTER")[0].field, TextField::Title);
+        assert_eq!(index.search("ohio").len(), 2);
+        assert!(index.search(" ").is_empty());
+    }
+
+    #[test]
+    fn parses_name_pieces_of_variations() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 NAME William /Lee/\n2 GIVN William\n2 NICK Bill\n2 SURN Lee\n\
+            2 NOTE Anglicized on arrival\n2 SOUR @S1@\n3 PAGE p. 12\n\
+            2 FONE Wiriamu /Ri/\n3 TYPE kana\n3 GIVN Wiriamu\n3 SURN Ri\n\
+            2 ROMN Uiriamu /Rii/\n3 TYPE romaji\n3 NICK Biru\n3 NOTE Hepburn\n3 SOUR @S1@\n\
+            0 @S1@ SOUR\n1 TITL Passenger list\n0 TRLR\n";
+        let data = gedcom::parse_document(content).unwrap();
+        let name = &data.individuals[0].names[0];
+        assert_eq!(name.nickname.as_deref(), Some("Bill"));
+        assert_eq!(name.notes[0].text(), "Anglicized on arrival");
+        assert_eq!(name.citations[0].page.as_deref(), Some("p. 12"));
+
+        let phonetic = &name.phonetic[0];
+        assert_eq!(phonetic.variation_type.as_deref(), Some("kana"));
+        assert_eq!(phonetic.given.as_deref(), Some("Wiriamu"));
+        assert_eq!(phonetic.surname.as_deref(), Some("Ri"));
+        let romanized = &name.romanized[0];
+        assert_eq!(romanized.nickname.as_deref(), Some("Biru"));
+        assert_eq!(romanized.notes[0].text(), "Hepburn");
+        assert_eq!(romanized.citations[0].xref, "@S1@");
+
+        let written = data.to_gedcom_string();
+        assert!(written.contains(
+            "1 NAME William /Lee/\n2 GIVN William\n2 NICK Bill\n2 SURN Lee\n\
+             2 NOTE Anglicized on arrival\n2 SOUR @S1@\n3 PAGE p. 12\n\
+             2 FONE Wiriamu /Ri/\n3 TYPE kana\n3 GIVN Wiriamu\n3 SURN Ri\n\
+             2 ROMN Uiriamu /Rii/\n3 TYPE romaji\n3 NICK Biru\n3 NOTE Hepburn\n3 SOUR @S1@\n"
+        ));
+    }
+
+    #[test]
+    fn parses_event_details() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 NAME Ann /Doe/\n\
+            1 CENS\n2 DATE 1880\n2 PLAC Salem\n2 ADDR 12 Main Street\n2 PHON 555-0100\n\
+            2 EMAIL office@@salem.gov\n2 AGNC Enumeration district 42\n2 RELI Quaker\n\
+            2 OBJE @M1@\n\
+            1 DEAT\n2 DATE 3 MAR 1901\n2 AGNC County coroner\n2 CAUS Pneumonia\n\
+            2 RESN privacy\n2 AGE 63y\n0 @M1@ OBJE\n1 FILE census.jpg\n0 TRLR\n";
+        let data = gedcom::parse_document(content).unwrap();
+        let events = data.individuals[0].events();
+
+        let census = &events[0];
+        assert_eq!(census.phones, ["555-0100"]);
+        assert_eq!(census.emails, ["office@salem.gov"]);
+        assert_eq!(census.agency.as_deref(), Some("Enumeration district 42"));
+        assert_eq!(census.religion.as_deref(), Some("Quaker"));
+        assert_eq!(census.multimedia[0].xref, "@M1@");
+        let death = &events[1];
+        assert_eq!(death.agency.as_deref(), Some("County coroner"));
+        assert_eq!(death.cause.as_deref(), Some("Pneumonia"));
+        assert_eq!(death.restriction_notice.as_deref(), Some("privacy"));
+        assert_eq!(death.age.as_deref(), Some("63y"));
+
+        let written = data.to_gedcom_string();
+        assert!(written.contains(
+            "2 ADDR 12 Main Street\n2 PHON 555-0100\n2 EMAIL office@@salem.gov\n\
+             2 AGNC Enumeration district 42\n2 RELI Quaker\n2 OBJE @M1@\n"
+        ));
+        assert!(written.contains(
+            "1 DEAT\n2 DATE 3 MAR 1901\n2 AGNC County coroner\n2 CAUS Pneumonia\n\
+             2 RESN privacy\n2 AGE 63y\n"
+        ));
+    }
+
+    #[test]
+    fn parses_individual_events_known_to_have_happened() {
+        use gedcom::types::EventType;
+
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 BAPM\n2 DATE 1900\n1 EMIG Y\n1 CREM Y\n\
+            0 @F1@ FAM\n1 MARR Y\n0 TRLR\n";
+        let data = gedcom::parse_document(content).unwrap();
+        let events = data.individuals[0].events();
+        let types: Vec<_> = events.iter().map(|event| event.event.clone()).collect();
+        assert_eq!(
+            types,
+            [
+                EventType::Baptism,
+                EventType::Emigration,
+                EventType::Cremation
+            ]
+        );
+        assert!(!events[0].occurred);
+        assert!(events[2].occurred);
+        assert!(data.families[0].events()[0].occurred);
+
+        let written = data.to_gedcom_string();
+        assert!(written.contains("1 BAPM\n2 DATE 1900\n1 EMIG Y\n1 CREM Y\n"));
+        assert!(written.contains("1 MARR Y\n"));
+    }
+
+    #[test]
+    fn surveys_records_without_parsing() {
+        let sample = read_relative("./tests/fixtures/sample.ged");
+        let data = Parser::new(sample.chars()).parse_record();
+        let survey = gedcom::survey(sample.as_bytes()).unwrap();
+        assert_eq!(survey.count("INDI"), data.individuals.len());
+        assert_eq!(survey.count("FAM"), data.families.len());
+        assert_eq!(survey.count("SOUR"), data.sources.len());
+        let xrefs: Vec<&str> = data
+            .individuals
+            .iter()
+            .filter_map(|individual| individual.xref.as_deref())
+            .collect();
+        assert_eq!(survey.xrefs("INDI"), xrefs.as_slice());
+        let bytes: usize = survey.records.values().map(|records| records.bytes).sum();
+        assert_eq!(bytes, sample.len());
+
+        let content = b"0 HEAD\r\n1 CHAR ANSEL\r\n0 @N1@ NOTE Caf\xe2e\r\n\r\n0 TRLR\r\n";
+        let survey = gedcom::survey(content).unwrap();
+        assert_eq!(survey.lines, 4);
+        assert_eq!(survey.xrefs("NOTE"), ["@N1@"]);
+        assert_eq!(survey.records["HEAD"].bytes, 22);
+        assert_eq!(survey.count("TRLR"), 1);
+        assert!(gedcom::survey(b"\xff\xfe0\x00 \x00").is_err());
+    }
+
+    /// Run with `cargo test --release --test lib -- --ignored surveys_faster`
+    #[test]
+    #[ignore = "timing, meaningful in release builds only"]
+    fn surveys_faster_than_parsing() {
+        use std::time::{Duration, Instant};
+
+        let bytes = std::fs::read("./tests/fixtures/washington.ged").unwrap();
+        let time = |run: &dyn Fn()| {
+            (0..20)
+                .map(|_| {
+                    let start = Instant::now();
+                    run();
+                    start.elapsed()
+                })
+                .min()
+                .unwrap_or(Duration::ZERO)
+        };
+        let surveying = time(&|| {
+            gedcom::survey(&bytes).unwrap();
+        });
+        let parsing = time(&|| {
+            gedcom::parse_bytes(&bytes).unwrap();
+        });
+        let ratio = parsing.as_secs_f64() / surveying.as_secs_f64();
+        println!("survey {surveying:?}, parse {parsing:?}, {ratio:.1}x faster");
+        assert!(
+            ratio >= 10.0,
+            "survey only {:.1}x faster than parsing",
+            ratio
+        );
+    }
+
+    #[test]
+    fn places_embedded_multimedia() {
+        use gedcom::MultimediaPlacement;
+
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 NAME Ann /Doe/\n1 OBJE\n2 FORM gif\n2 TITL A gif picture\n\
+            2 FILE portrait.gif\n2 _PRIM Y\n1 BIRT\n2 OBJE\n3 FILE birth.jpg\n4 FORM jpg\n\
+            5 MEDI photo\n3 TITL Certificate\n1 OBJE @M1@\n\
+            0 @I2@ INDI\n1 OBJE @M1@\n1 OBJE @M2@\n\
+            0 @M1@ OBJE\n1 FILE shared.jpg\n0 @M2@ OBJE\n1 FILE single.jpg\n0 TRLR\n";
+        let mut data = gedcom::parse_document(content).unwrap();
+        let link = &data.individuals[0].multimedia[0];
+        assert!(link.primary);
+        let embedded = link.embedded.as_ref().unwrap();
+        assert_eq!(embedded.files[0].value, "portrait.gif");
+        assert_eq!(embedded.title.as_deref(), Some("A gif picture"));
+        let checks = [gedcom::validate::Check::DanglingPointer];
+        assert!(gedcom::validate::validate(&data, &checks).is_empty());
+
+        let written = data.to_gedcom_string();
+        assert!(written.contains(
+            "1 OBJE\n2 FILE portrait.gif\n3 FORM gif\n2 TITL A gif picture\n2 _PRIM Y\n"
+        ));
+        assert!(written
+            .contains("2 OBJE\n3 FILE birth.jpg\n4 FORM jpg\n5 MEDI photo\n3 TITL Certificate\n"));
+
+        assert_eq!(
+            MultimediaPlacement::required_by(&GedcomVersion::V7_0),
+            Some(MultimediaPlacement::Records)
+        );
+        assert_eq!(data.place_multimedia(MultimediaPlacement::Records), 2);
+        let links: Vec<&str> = data.individuals[0]
+            .multimedia
+            .iter()
+            .map(|link| link.xref.as_str())
+            .collect();
+        assert_eq!(links, ["@M3@", "@M1@"]);
+        assert_eq!(data.multimedia.len(), 4);
+        assert_eq!(data.multimedia[3].files[0].value, "birth.jpg");
+        assert!(data.individuals[0].multimedia[0].primary);
+        let written = data.to_gedcom_string();
+        assert!(written.contains("1 OBJE @M3@\n2 _PRIM Y\n"));
+        assert!(written
+            .contains("0 @M3@ OBJE\n1 FILE portrait.gif\n2 FORM gif\n2 TITL A gif picture\n"));
+
+        // the records used by a single link go back into it, @M1@ is shared
+        assert_eq!(data.place_multimedia(MultimediaPlacement::Inline), 3);
+        let xrefs: Vec<_> = data.multimedia.iter().map(|m| m.xref.as_deref()).collect();
+        assert_eq!(xrefs, [Some("@M1@")]);
+        assert!(data.individuals[1].multimedia[1].embedded.is_some());
+    }
+
+    #[test]
+    fn parses_place_coordinates() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 NAME Taro /Yamada/\n\
+            1 BIRT\n2 PLAC \u{6771}\u{4eac}\n3 FONE \u{3068}\u{3046}\u{304d}\u{3087}\u{3046}\n4 TYPE kana\n\
+            3 ROMN Tokyo\n4 TYPE romaji\n3 MAP\n4 LATI N35.6895\n4 LONG E139.6917\n\
+            1 DEAT\n2 PLAC Lima\n3 MAP\n4 LATI S12.0464\n4 LONG W77.0428\n0 TRLR\n";
+        let data = gedcom::parse_document(content).unwrap();
+        let events = data.individuals[0].events();
+
+        let birth = &events[0];
+        let place = birth.place.as_ref().unwrap();
+        assert_eq!(place.phonetic[0].variation_type.as_deref(), Some("kana"));
+        assert_eq!(place.romanized[0].value, "Tokyo");
+        let map = place.map.unwrap();
+        assert!((map.latitude() - 35.6895).abs() < 1e-9);
+        assert_eq!(map.latitude_value(), "N35.6895");
+        let (latitude, longitude) = events[1].coordinates().unwrap();
+        assert!((latitude + 12.0464).abs() < 1e-9 && (longitude + 77.0428).abs() < 1e-9);
+
+        let written = data.to_gedcom_string();
+        assert!(written
+            .contains("3 ROMN Tokyo\n4 TYPE romaji\n3 MAP\n4 LATI N35.6895\n4 LONG E139.6917\n"));
+        let reparsed = gedcom::parse_document(&written).unwrap();
+        assert_eq!(
+            reparsed.individuals[0].events()[0].coordinates(),
+            birth.coordinates()
+        );
+    }
+
+    #[test]
+    fn ignores_out_of_range_coordinates() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 NAME Taro /Yamada/\n\
+            1 BIRT\n2 PLAC Nowhere\n3 MAP\n4 LATI N200\n4 LONG E10\n\
+            1 BAPL\n2 PLAC Salt Lake City\n3 MAP\n4 LATI N40.7608\n4 LONG W111.891\n0 TRLR\n";
+        let mut parser = Parser::new(content.chars());
+        let data = parser.parse_document().unwrap();
+        let diagnostics = parser.diagnostics();
+        let individual = &data.individuals[0];
+
+        let events = individual.events();
+        let place = events[0].place.as_ref().unwrap();
+        assert_eq!(place.value, "Nowhere");
+        assert!(place.map.is_none());
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("ignoring MAP"));
+
+        let temple = individual.lds_ordinances[0].place.as_ref().unwrap();
+        assert_eq!(temple.coordinates(), Some((40.7608, -111.891)));
+        assert!(data
+            .to_gedcom_string()
+            .contains("3 MAP\n4 LATI N40.7608\n4 LONG W111.891\n"));
+    }
 }
@@ -0,0 +1,63 @@
+#[cfg(test)]
+#[cfg(feature = "watch")]
+mod watch_feature_tests {
+    use gedcom::diff::RecordDiff;
+    use gedcom::parser::ParseOptions;
+    use gedcom::watch::{RecordChange, Watcher};
+    use std::fs;
+
+    #[test]
+    fn reports_changed_records() {
+        let path = std::env::temp_dir().join(format!("gedcom-watch-{}.ged", std::process::id()));
+        fs::write(
+            &path,
+            "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n0 @I1@ INDI\n1 NAME John /Doe/\n\
+             0 @I2@ INDI\n1 NAME Jane /Doe/\n0 TRLR\n",
+        )
+        .unwrap();
+
+        let mut watcher = Watcher::new(&path, ParseOptions::default()).unwrap();
+        assert_eq!(watcher.data().individuals.len(), 2);
+        assert!(watcher.poll().unwrap().is_none());
+
+        fs::write(
+            &path,
+            "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n0 @I1@ INDI\n1 NAME Johnny /Doe/\n\
+             0 @I3@ INDI\n1 NAME Jim /Doe/\n0 TRLR\n",
+        )
+        .unwrap();
+        let reload = watcher.poll().unwrap().unwrap();
+        assert_eq!(
+            reload.records,
+            vec![
+                RecordChange::Modified("@I1@".to_string()),
+                RecordChange::Removed("@I2@".to_string()),
+                RecordChange::Added("@I3@".to_string()),
+            ]
+        );
+        let changed: Vec<_> = reload
+            .diff
+            .individuals
+            .iter()
+            .map(|change| match change {
+                RecordDiff::Added(xref) => format!("+{xref}"),
+                RecordDiff::Removed(xref) => format!("-{xref}"),
+                RecordDiff::Changed(changes) => format!("~{}", changes.old_xref),
+            })
+            .collect();
+        assert_eq!(changed, vec!["~@I1@", "-@I2@", "+@I3@"]);
+        let name = watcher.data().individuals[0].primary_name().unwrap();
+        assert_eq!(name.value.as_deref(), Some("Johnny /Doe/"));
+
+        // a malformed file keeps the previous data
+        fs::write(
+            &path,
+            "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n0 @I1@ INDI\n1 SEX Q\n",
+        )
+        .unwrap();
+        assert!(watcher.poll().is_err());
+        assert_eq!(watcher.data().individuals.len(), 2);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
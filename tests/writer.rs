@@ -0,0 +1,521 @@
+#![allow(special_module_name)]
+
+#[cfg(test)]
+mod lib;
+
+#[cfg(test)]
+mod writer {
+    use super::lib::util::read_relative;
+    use gedcom::parser::{ParseOptions, Parser};
+    use gedcom::types::{event::HasEvents, Association, Individual, Note};
+    use gedcom::writer::{
+        check_line_value, CustomTagPlacement, CustomTagPolicy, GedcomWriter, InvalidValue,
+        TagFilter, WriteOptions, LDS_ORDINANCE_TAGS, MAX_LINE_LENGTH,
+    };
+    use gedcom::GedcomData;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn round_trips_records() {
+        let simple = read_relative("./tests/fixtures/simple.ged");
+        let data = Parser::new(simple.chars()).parse_record();
+
+        let written = GedcomWriter::default().write_string(&data);
+        let reparsed = Parser::new(written.chars()).parse_record();
+
+        assert_eq!(reparsed.submitters.len(), 1);
+        assert_eq!(reparsed.individuals.len(), 3);
+        assert_eq!(reparsed.families.len(), 1);
+        assert_eq!(
+            reparsed.submitters[0]
+                .address
+                .as_ref()
+                .unwrap()
+                .value
+                .as_deref(),
+            Some("Submitters address\naddress continued here")
+        );
+        assert_eq!(reparsed.families[0].children, vec!["@CHILD@".to_string()]);
+    }
+
+    #[test]
+    fn rewrites_header_for_written_file() {
+        let simple = read_relative("./tests/fixtures/simple.ged");
+        let data = Parser::new(simple.chars()).parse_record();
+
+        let options = WriteOptions {
+            filename: Some("export.ged".to_string()),
+            // 16 OCT 2026 08:30:05 UTC
+            timestamp: Some(UNIX_EPOCH + Duration::from_secs(1_792_139_405)),
+            ..WriteOptions::default()
+        };
+        let written = GedcomWriter::new(options).write_string(&data);
+        assert!(written.starts_with(
+            "0 HEAD\n1 SOUR ID_OF_CREATING_FILE\n1 DATE 16 OCT 2026\n2 TIME 08:30:05\n\
+             1 SUBM @SUBMITTER@\n1 FILE export.ged\n1 GEDC\n2 VERS 5.5.1\n2 FORM LINEAGE-LINKED\n\
+             1 CHAR UTF-8\n"
+        ));
+        assert!(written.ends_with("0 TRLR\n"));
+
+        let header = Parser::new(written.chars()).parse_record().header;
+        assert_eq!(header.date.as_deref(), Some("16 OCT 2026 08:30:05"));
+        assert_eq!(header.gedcom_version.as_deref(), Some("5.5.1"));
+    }
+
+    #[test]
+    fn preserves_original_header() {
+        let content = "0 HEAD\n1 SOUR APP\n1 DATE 1 JAN 1998\n2 TIME 13:57:24\n1 FILE family.ged\n\
+                       1 GEDC\n2 VERS 5.5\n2 FORM LINEAGE-LINKED\n0 @I1@ INDI\n1 SEX X\n0 TRLR\n";
+        let data = Parser::new(content.chars()).parse_record();
+
+        let options = WriteOptions {
+            preserve_header: true,
+            filename: Some("ignored.ged".to_string()),
+            ..WriteOptions::default()
+        };
+        let written = GedcomWriter::new(options).write_string(&data);
+        assert!(written.contains("1 DATE 1 JAN 1998\n2 TIME 13:57:24\n1 FILE family.ged\n"));
+        assert!(written.contains("2 VERS 5.5\n"));
+        // nonbinary sex degrades for the preserved version
+        assert!(written.contains("1 SEX U\n1 _SEX X\n"));
+    }
+
+    #[test]
+    fn reports_sex_degraded_for_the_version_written() {
+        use gedcom::types::GedcomVersion;
+
+        let content =
+            "0 HEAD\n1 GEDC\n2 VERS 7.0\n0 @I1@ INDI\n1 NAME Sam /Doe/\n1 SEX X\n0 TRLR\n";
+        let data = Parser::new(content.chars()).parse_record();
+
+        let mut out = Vec::new();
+        let diagnostics = GedcomWriter::default()
+            .write_with_diagnostics(&data, &mut out)
+            .unwrap();
+        let written = String::from_utf8(out).unwrap();
+        assert!(written.contains("1 SEX U\n1 _SEX X\n"));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "GEDCOM 5.5.1 does not define a nonbinary SEX value, writing U"
+        );
+        let line = written.lines().nth(diagnostics[0].line as usize - 1);
+        assert_eq!(line, Some("1 SEX U"));
+
+        let options = WriteOptions {
+            version: Some(GedcomVersion::V7_0),
+            ..WriteOptions::default()
+        };
+        let mut out = Vec::new();
+        let diagnostics = GedcomWriter::new(options)
+            .write_with_diagnostics(&data, &mut out)
+            .unwrap();
+        let written = String::from_utf8(out).unwrap();
+        assert!(written.contains("2 VERS 7.0\n"));
+        assert!(written.contains("1 SEX X\n"));
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn keeps_header_character_set_and_custom_tags() {
+        let content = "0 HEAD\n1 CHAR ASCII\n2 VERS 1963\n1 _MYOWNTAG Allowed\n0 TRLR\n";
+        let mut parser = Parser::new(content.chars());
+        let data = parser.parse_document().unwrap();
+        assert!(parser.diagnostics().is_empty());
+        assert_eq!(data.header.encoding.as_deref(), Some("ASCII"));
+        assert_eq!(data.header.encoding_version.as_deref(), Some("1963"));
+        assert_eq!(data.header.custom_data[0].value, "Allowed");
+
+        let written = GedcomWriter::default().write_string(&data);
+        assert!(written.contains("1 _MYOWNTAG Allowed\n"));
+    }
+
+    #[test]
+    fn keeps_submitter_change_date() {
+        let content = "0 HEAD\n0 @U1@ SUBM\n1 NAME Jane /Doe/\n1 CHAN\n2 DATE 19 JUN 2000\n\
+                       3 TIME 12:34:56\n2 NOTE A note\n0 TRLR\n";
+        let mut parser = Parser::new(content.chars());
+        let data = parser.parse_document().unwrap();
+        assert!(parser.diagnostics().is_empty());
+        assert_eq!(
+            data.submitters[0].last_updated.as_deref(),
+            Some("19 JUN 2000 12:34:56")
+        );
+
+        let written = GedcomWriter::default().write_string(&data);
+        assert!(written.contains("1 CHAN\n2 DATE 19 JUN 2000\n3 TIME 12:34:56\n0 TRLR\n"));
+    }
+
+    #[test]
+    fn terminates_partial_trees() {
+        let mut data = GedcomData::default();
+        data.add_individual(Individual::parse_str("0 @I1@ INDI\n1 NAME John /Doe/\n"));
+
+        let written = GedcomWriter::default().write_string(&data);
+        assert!(written.starts_with("0 HEAD\n"));
+        assert!(written.ends_with("0 @I1@ INDI\n1 NAME John /Doe/\n0 TRLR\n"));
+
+        let reparsed = Parser::new(written.chars()).parse_record();
+        assert_eq!(reparsed.individuals.len(), 1);
+    }
+
+    #[test]
+    fn encodes_text_values() {
+        let long_line = "word ".repeat(150);
+        let note = format!("Contact @doe on forums\n{long_line}\nlast line");
+        let mut data = GedcomData::default();
+        data.header.notes.push(Note::new(note.clone()));
+        let mut individual = Individual::parse_str("0 @I1@ INDI\n1 NAME John /Doe/\n");
+        individual.names.first_mut().unwrap().given = Some("John\nJack".to_string());
+        data.add_individual(individual);
+
+        let written = GedcomWriter::default().write_string(&data);
+        assert!(written
+            .lines()
+            .all(|line| line.chars().count() <= MAX_LINE_LENGTH));
+        assert!(written.contains("1 NOTE Contact @@doe on forums\n2 CONT word word"));
+        assert!(written.contains("\n2 CONC "));
+        assert!(written.contains("2 GIVN John Jack\n"));
+
+        let reparsed = Parser::new(written.chars()).parse_record();
+        assert_eq!(reparsed.header.notes[0].text(), note);
+    }
+
+    #[test]
+    fn checks_line_values() {
+        assert_eq!(check_line_value("John /Doe/"), Ok(()));
+        assert_eq!(check_line_value("a\nb"), Err(InvalidValue::LineBreak));
+        assert_eq!(
+            check_line_value(&"@".repeat(130)),
+            Err(InvalidValue::TooLong(260))
+        );
+    }
+
+    #[test]
+    fn writes_multimedia_files() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @M1@ OBJE\n1 FILE census.jpg\n2 FORM jpg\n3 MEDI photo\n2 TITL 1881 census\n\
+            1 FILE census.txt\n2 FORM txt\n2 TITL Transcript\n0 TRLR\n";
+        let data = Parser::new(content.chars()).parse_record();
+        assert_eq!(data.multimedia[0].files.len(), 2);
+
+        let written = GedcomWriter::default().write_string(&data);
+        assert!(written.contains(
+            "0 @M1@ OBJE\n1 FILE census.jpg\n2 FORM jpg\n3 MEDI photo\n2 TITL 1881 census\n\
+             1 FILE census.txt\n2 FORM txt\n2 TITL Transcript\n0 TRLR\n"
+        ));
+    }
+
+    #[test]
+    fn writes_change_dates() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 7.0\n\
+            0 @F1@ FAM\n1 CREA\n2 DATE 1 JAN 2020\n1 CHAN\n2 DATE 2 FEB 2021\n3 TIME 10:15:00\n\
+            0 TRLR\n";
+        let data = Parser::new(content.chars()).parse_record();
+        assert_eq!(
+            data.families[0].last_updated.as_deref(),
+            Some("2 FEB 2021 10:15:00")
+        );
+
+        let family = "0 @F1@ FAM\n1 CHAN\n2 DATE 2 FEB 2021\n3 TIME 10:15:00\n";
+        let written = GedcomWriter::default().write_string(&data);
+        assert!(written.contains(family));
+
+        // CREA only exists in GEDCOM 7.0
+        let options = WriteOptions {
+            preserve_header: true,
+            ..WriteOptions::default()
+        };
+        let written = GedcomWriter::new(options).write_string(&data);
+        assert!(written.contains("0 @F1@ FAM\n1 CREA\n2 DATE 1 JAN 2020\n1 CHAN\n"));
+    }
+
+    #[test]
+    fn writes_multimedia_form_title_under_file() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @M1@ OBJE\n1 FILE photo.jpg\n1 FORM jpg\n1 TITL Portrait\n0 TRLR\n";
+        let data = Parser::new(content.chars()).parse_record();
+
+        let written = GedcomWriter::default().write_string(&data);
+        assert!(written
+            .contains("0 @M1@ OBJE\n1 FILE photo.jpg\n2 FORM jpg\n2 TITL Portrait\n0 TRLR\n"));
+    }
+
+    #[test]
+    fn filters_written_structures_by_tag() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n1 NOTE Header note\n\
+            0 @I1@ INDI\n1 NAME John /Doe/\n1 BIRT\n2 DATE 1900\n2 PLAC Salem\n\
+            1 _MILT Army\n2 DATE 1918\n2 NOTE Served\n3 CONT in France\n0 TRLR\n";
+        let data = Parser::new(content.chars()).parse_record();
+
+        let options = WriteOptions {
+            tags: TagFilter::Except(vec!["PLAC".to_string(), "_*".to_string()]),
+            ..WriteOptions::default()
+        };
+        let written = GedcomWriter::new(options).write_string(&data);
+        assert!(written.contains("1 NOTE Header note\n"));
+        assert!(written.contains("0 @I1@ INDI\n1 NAME John /Doe/\n1 BIRT\n2 DATE 1900\n0 TRLR\n"));
+
+        let only = ["INDI", "NAME", "BIRT", "DATE"].map(String::from);
+        let options = WriteOptions {
+            tags: TagFilter::Only(only.to_vec()),
+            ..WriteOptions::default()
+        };
+        let written = GedcomWriter::new(options).write_string(&data);
+        assert!(written.contains("0 @I1@ INDI\n1 NAME John /Doe/\n1 BIRT\n2 DATE 1900\n0 TRLR\n"));
+        assert!(TagFilter::Except(LDS_ORDINANCE_TAGS.map(String::from).to_vec()).writes("BIRT"));
+    }
+
+    #[test]
+    fn shares_repeated_notes() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 7.0\n0 @N1@ SUBM\n1 NAME Submitter\n\
+            0 @I1@ INDI\n1 NOTE Imported from\n2 CONT a family tree\n\
+            1 BIRT\n2 NOTE Imported from\n3 CONT a family tree\n\
+            0 @I2@ INDI\n1 NOTE Imported from\n2 CONT a family tree\n\
+            0 @S1@ SOUR\n1 NOTE Written once\n0 TRLR";
+        let options = ParseOptions {
+            deduplicate_notes: true,
+            ..ParseOptions::default()
+        };
+        let data = Parser::with_options(content.chars(), options).parse_record();
+
+        assert_eq!(data.shared_notes.len(), 1);
+        let shared = &data.shared_notes[0];
+        // @N1@ is taken by the submitter
+        assert_eq!(shared.shared_xref(), Some("@N2@"));
+        assert_eq!(shared.text(), "Imported from\na family tree");
+        let note = &data.individuals[1].notes[0];
+        assert_eq!(note.shared_xref(), Some("@N2@"));
+        assert_eq!(note, shared);
+        let note = &data.sources[0].notes[0];
+        assert_eq!(
+            (note.shared_xref(), note.text().as_ref()),
+            (None, "Written once")
+        );
+
+        let options = WriteOptions {
+            preserve_header: true,
+            ..WriteOptions::default()
+        };
+        let written = GedcomWriter::new(options).write_string(&data);
+        assert!(written.contains("0 @I1@ INDI\n1 BIRT\n2 SNOTE @N2@\n1 SNOTE @N2@\n"));
+        assert!(written.contains("0 @S1@ SOUR\n1 NOTE Written once\n"));
+        assert!(written.ends_with("0 @N2@ SNOTE Imported from\n1 CONT a family tree\n0 TRLR\n"));
+
+        let written = GedcomWriter::default().write_string(&data);
+        assert!(written.contains("0 @I2@ INDI\n1 NOTE @N2@\n"));
+        assert!(written.contains("0 @N2@ NOTE Imported from\n"));
+
+        let data = Parser::new(content.chars()).parse_record();
+        assert!(data.shared_notes.is_empty());
+        assert_eq!(data.individuals[0].notes[0].shared_xref(), None);
+    }
+
+    #[test]
+    fn round_trips_through_gedcom_data() {
+        let sample = read_relative("./tests/fixtures/sample.ged");
+        let mut data = Parser::new(sample.chars()).parse_record();
+        let long_text = "A source text long enough to be split across lines. ".repeat(8);
+        data.sources[0].text = Some(format!("{}\nSecond line", long_text.trim_end()));
+
+        let written = data.to_gedcom_string();
+        assert!(written.contains("2 VERS 5.5.1\n"));
+        assert!(written.lines().all(|line| line.len() <= MAX_LINE_LENGTH));
+        assert!(written.contains("\n2 CONC "));
+        assert!(written.contains("\n2 CONT Second line\n"));
+
+        let mut out = Vec::new();
+        data.write(&mut out).unwrap();
+        let reparsed = Parser::new(String::from_utf8(out).unwrap().chars()).parse_record();
+        assert_eq!(reparsed.individuals.len(), data.individuals.len());
+        assert_eq!(reparsed.families.len(), data.families.len());
+        assert_eq!(reparsed.sources[0].text, data.sources[0].text);
+    }
+
+    #[test]
+    fn writes_preferred_photo_flags() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n0 @I1@ INDI\n1 OBJE @M1@\n2 _PRIMARY Y\n1 OBJE @M2@\n0 TRLR\n";
+        let mut data = Parser::new(content.chars()).parse_record();
+        let output = GedcomWriter::default().write_string(&data);
+        assert!(output.contains("1 OBJE @M1@\n2 _PRIMARY Y\n1 OBJE @M2@\n0 TRLR"));
+
+        let photos = &mut data.individuals[0].multimedia;
+        photos[0].primary = false;
+        photos[1].primary = true;
+        let output = GedcomWriter::default().write_string(&data);
+        assert!(output.contains("1 OBJE @M1@\n1 OBJE @M2@\n2 _PRIM Y\n0 TRLR"));
+    }
+
+    #[test]
+    fn keeps_extension_records() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 7.0\n\
+            0 @I1@ INDI\n1 _TODO @T1@\n\
+            0 @T1@ _TODO\n1 DESC Find baptism\n2 DATE FROM 1630\n\
+            0 @N1@ SNOTE Shared text\n\
+            0 TRLR\n";
+        let mut parser = Parser::new(content.chars());
+        let data = parser.parse_record();
+        assert!(data.extension_records.is_empty());
+        assert_eq!(parser.diagnostics().len(), 1);
+        // shared notes are note records
+        assert_eq!(data.notes[0].text.text(), "Shared text");
+
+        let options = ParseOptions {
+            extension_records: true,
+            ..ParseOptions::default()
+        };
+        let mut parser = Parser::with_options(content.chars(), options);
+        let data = parser.parse_record();
+        assert!(parser.diagnostics().is_empty());
+        assert_eq!(data.extension_records.len(), 1);
+        let pointer = &data.individuals[0].custom_data[0].value;
+        let task = data.extension_record(pointer).unwrap();
+        assert_eq!(task.data.tag, "_TODO");
+        assert_eq!(task.data.children[0].value, "Find baptism");
+        assert_eq!(data.find_custom("DATE").len(), 1);
+
+        let output = GedcomWriter::default().write_string(&data);
+        assert!(output.contains(
+            "0 @N1@ NOTE Shared text\n0 @T1@ _TODO\n1 DESC Find baptism\n2 DATE FROM 1630\n0 TRLR"
+        ));
+    }
+
+    #[test]
+    fn reads_and_writes_shared_locations() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 BIRT\n2 DATE 1 JAN 1800\n2 PLAC Boston, Suffolk\n3 _LOC @L1@\n\
+            0 @L1@ _LOC\n1 NAME Boston\n2 DATE FROM 1630\n1 TYPE City\n\
+            1 MAP\n2 LATI N42.3601\n2 LONG W71.0589\n1 _LOC @L2@\n1 _GOV BOSTON_123\n\
+            0 @L2@ _LOC\n1 NAME Suffolk\n1 _LOC @L1@\n\
+            0 TRLR\n";
+        let mut parser = Parser::new(content.chars());
+        let data = parser.parse_record();
+        assert!(
+            parser.diagnostics().is_empty(),
+            "{:?}",
+            parser.diagnostics()
+        );
+        assert_eq!(data.locations.len(), 2);
+
+        let birth = &data.individuals[0].events()[0];
+        let place = birth.place.as_ref().unwrap();
+        let location = data.location(place.location.as_deref().unwrap()).unwrap();
+        assert_eq!(location.name(), Some("Boston"));
+        assert_eq!(location.names[0].date.as_deref(), Some("FROM 1630"));
+        assert_eq!(location.location_type.as_deref(), Some("City"));
+        let (latitude, longitude) = location.map.unwrap().coordinates();
+        assert!((latitude - 42.3601).abs() < 1e-9 && (longitude + 71.0589).abs() < 1e-9);
+        assert_eq!(location.custom_data[0].tag, "_GOV");
+        // the cycle back to Boston ends the hierarchy
+        let hierarchy: Vec<_> = data
+            .location_hierarchy("@L1@")
+            .iter()
+            .filter_map(|location| location.name())
+            .collect();
+        assert_eq!(hierarchy, vec!["Boston", "Suffolk"]);
+
+        let output = GedcomWriter::default().write_string(&data);
+        assert!(output.contains("2 PLAC Boston, Suffolk\n3 _LOC @L1@\n"));
+        assert!(output.contains(
+            "0 @L1@ _LOC\n1 NAME Boston\n2 DATE FROM 1630\n1 TYPE City\n\
+            1 MAP\n2 LATI N42.3601\n2 LONG W71.0589\n1 _LOC @L2@\n1 _GOV BOSTON_123\n"
+        ));
+    }
+
+    #[test]
+    fn declares_custom_tags_in_schema() {
+        let content =
+            "0 HEAD\n1 GEDC\n2 VERS 7.0\n1 SCHMA\n2 TAG _MILT https://example.org/military\n\
+            0 @I1@ INDI\n1 NAME John /Doe/\n1 _MILT Army\n2 DATE 1917\n2 _RANK Private\n\
+            1 BIRT\n2 PLAC Boston\n3 _GOV BOSTON_123\n0 TRLR\n";
+        let mut parser = Parser::new(content.chars());
+        let data = parser.parse_record();
+        assert!(parser.diagnostics().is_empty());
+        assert_eq!(
+            data.header.schema_uri("_MILT"),
+            Some("https://example.org/military")
+        );
+        assert_eq!(data.custom_tags(), vec!["_MILT", "_RANK", "_GOV"]);
+
+        let output = GedcomWriter::new(WriteOptions {
+            preserve_header: true,
+            schema_namespace: Some("https://example.com/tags/".to_string()),
+            ..WriteOptions::default()
+        })
+        .write_string(&data);
+        assert!(output.contains(
+            "1 SCHMA\n2 TAG _MILT https://example.org/military\n\
+            2 TAG _RANK https://example.com/tags/_RANK\n2 TAG _GOV https://example.com/tags/_GOV\n"
+        ));
+        assert!(output.contains("1 _MILT Army\n2 DATE 1917\n2 _RANK Private\n"));
+        assert!(output.contains("2 PLAC Boston\n3 _GOV BOSTON_123\n"));
+
+        // 5.5.1 files have no schema
+        let output = GedcomWriter::new(WriteOptions {
+            schema_namespace: Some("https://example.com/tags/".to_string()),
+            ..WriteOptions::default()
+        })
+        .write_string(&data);
+        assert!(!output.contains("SCHMA"));
+    }
+
+    #[test]
+    fn places_custom_tags_by_policy() {
+        let content = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n\
+            0 @I1@ INDI\n1 _uid 42\n1 NAME Ann /Doe/\n1 _MILT Army\n2 DATE 1917\n\
+            1 BIRT\n2 _FLAG Y\n1 CHAN\n2 DATE 1 JAN 2000\n0 @F1@ FAM\n1 HUSB @I1@\n0 TRLR\n";
+        let data = Parser::new(content.chars()).parse_record();
+        let write = |custom_tags| {
+            GedcomWriter::new(WriteOptions {
+                custom_tags,
+                ..WriteOptions::default()
+            })
+            .write_string(&data)
+        };
+
+        let written = write(CustomTagPolicy::default());
+        assert!(written.contains("1 _uid 42\n1 _MILT Army\n2 DATE 1917\n1 CHAN\n"));
+
+        let written = write(CustomTagPolicy {
+            placement: CustomTagPlacement::Last,
+            overrides: Vec::new(),
+        });
+        assert!(written.contains(
+            "1 BIRT\n2 _FLAG Y\n1 CHAN\n2 DATE 1 JAN 2000\n\
+             1 _MILT Army\n2 DATE 1917\n1 _UID 42\n0 @F1@ FAM\n"
+        ));
+
+        let written = write(CustomTagPolicy {
+            placement: CustomTagPlacement::Last,
+            overrides: vec![
+                ("_U*".to_string(), CustomTagPlacement::Drop),
+                ("_FLAG".to_string(), CustomTagPlacement::Drop),
+            ],
+        });
+        assert!(!written.contains("_UID") && !written.contains("_uid"));
+        assert!(written.contains("1 BIRT\n1 CHAN\n2 DATE 1 JAN 2000\n1 _MILT Army\n"));
+    }
+
+    #[test]
+    fn refuses_invalid_xrefs() {
+        let mut data = GedcomData::default();
+        let mut individual = Individual::new(Some("@I1@".to_string()));
+        data.add_individual(individual.clone());
+        let written = data.to_gedcom_string();
+        let reparsed = gedcom::parse_document(&written).unwrap();
+        assert_eq!(reparsed.individuals[0].xref.as_deref(), Some("@I1@"));
+
+        // `0 I 1 INDI` would be read back as no individual at all
+        individual.xref = Some("I 1".to_string());
+        data.individuals = vec![individual];
+        let error = data.write(&mut Vec::new()).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+
+        let mut data = GedcomData::default();
+        data.add_individual(Individual::new(Some("@I1@".to_string())));
+        data.individuals[0]
+            .associations
+            .push(Association::new("@I 2@".to_string()));
+        assert!(data.write(&mut Vec::new()).is_err());
+    }
+}